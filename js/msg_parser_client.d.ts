@@ -0,0 +1,16 @@
+import type * as MsgParser from "../pkg/web/msg_parser_wasm.js";
+
+type Exports = Omit<typeof MsgParser, "default" | "initSync">;
+
+/** 与 msg-parser-wasm 导出函数同名、返回 Promise 的方法集合 */
+export type MsgParserWorker = {
+  [K in keyof Exports]: Exports[K] extends (...args: infer A) => infer R
+    ? (...args: A) => Promise<R>
+    : never;
+} & {
+  /** 结束 Worker，未完成的调用不会再返回 */
+  terminate(): void;
+};
+
+/** 创建专用 Worker 并返回代理对象，`workerUrl` 默认为同目录下的 msg_parser_worker.js */
+export function createMsgParserWorker(workerUrl?: string | URL): MsgParserWorker;
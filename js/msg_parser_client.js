@@ -0,0 +1,51 @@
+// 把 msg-parser-wasm 的全部导出函数代理到专用 Worker 中执行，主线程不会被大文件解析阻塞
+//
+//   const parser = createMsgParserWorker();
+//   const emailData = await parser.parse_msg_file(uint8Array);
+//
+// 每个导出函数都变为返回 Promise 的同名方法，参数和返回值与直接调用相同。
+// 参数以结构化克隆传给 Worker，因此不支持传入函数（如 parse_msg_file_with_attachment_sink 的 sink）。
+
+export function createMsgParserWorker(
+  workerUrl = new URL("./msg_parser_worker.js", import.meta.url),
+) {
+  const worker = new Worker(workerUrl, { type: "module" });
+  const pending = new Map();
+  let nextId = 0;
+
+  worker.onmessage = ({ data: { id, result, error, code } }) => {
+    const call = pending.get(id);
+    pending.delete(id);
+    if (error === undefined) {
+      call.resolve(result);
+    } else {
+      call.reject(Object.assign(new Error(error), { code }));
+    }
+  };
+  // Worker 加载失败时，所有未完成的调用一起失败
+  worker.onerror = (event) => {
+    for (const call of pending.values()) {
+      call.reject(event.error ?? new Error(event.message));
+    }
+    pending.clear();
+  };
+
+  const call = (name, args) =>
+    new Promise((resolve, reject) => {
+      const id = nextId++;
+      pending.set(id, { resolve, reject });
+      worker.postMessage({ id, name, args });
+    });
+
+  return new Proxy(
+    {},
+    {
+      get(_, name) {
+        if (name === "terminate") return () => worker.terminate();
+        // 避免代理对象被 await 时当作 thenable
+        if (typeof name !== "string" || name === "then") return undefined;
+        return (...args) => call(name, args);
+      },
+    },
+  );
+}
@@ -0,0 +1,36 @@
+// 在专用 Worker 中运行 msg-parser-wasm，由 msg_parser_client.js 创建，无需直接引用
+// 先执行 wasm-pack build --target web --out-dir pkg/web；复制到其他位置时请同步修改下面的路径
+import init, * as wasm from "../pkg/web/msg_parser_wasm.js";
+
+const ready = init();
+
+// 初始化函数不对外暴露
+const HIDDEN = new Set(["default", "initSync"]);
+
+self.onmessage = async ({ data: { id, name, args } }) => {
+  try {
+    await ready;
+    const fn = wasm[name];
+    if (typeof fn !== "function" || HIDDEN.has(name)) {
+      throw new Error(`未知的函数: ${name}`);
+    }
+    const result = fn(...args);
+    // 结果中的 Uint8Array 都是独立的拷贝，直接转移给主线程，不再复制一次
+    self.postMessage({ id, result }, collectTransferables(result, new Set()));
+  } catch (err) {
+    // 解析错误是 { code, message } 对象，其余为 Error 或字符串
+    const message = typeof err?.message === "string" ? err.message : String(err);
+    self.postMessage({ id, error: message, code: err?.code });
+  }
+};
+
+function collectTransferables(value, buffers) {
+  if (ArrayBuffer.isView(value)) {
+    if (value.buffer instanceof ArrayBuffer) buffers.add(value.buffer);
+  } else if (value instanceof Map) {
+    for (const item of value.values()) collectTransferables(item, buffers);
+  } else if (value !== null && typeof value === "object") {
+    for (const item of Object.values(value)) collectTransferables(item, buffers);
+  }
+  return [...buffers];
+}
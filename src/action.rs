@@ -0,0 +1,88 @@
+//! 回复/转发状态（Outlook 邮件列表中的图标）
+
+use crate::props::PropertyStore;
+use serde::Serialize;
+
+const PROP_ICON_INDEX: u16 = 0x1080;
+const PROP_LAST_VERB_EXECUTED: u16 = 0x1081;
+const PROP_LAST_VERB_EXECUTION_TIME: u16 = 0x1082;
+
+// PR_LAST_VERB_EXECUTED 取值
+const EXCHIVERB_REPLYTOSENDER: i32 = 102;
+const EXCHIVERB_REPLYTOALL: i32 = 103;
+const EXCHIVERB_FORWARD: i32 = 104;
+
+// PR_ICON_INDEX 取值
+const ICON_MAIL_REPLIED: i32 = 0x105;
+const ICON_MAIL_FORWARDED: i32 = 0x106;
+
+/// 对邮件执行过的最后一个操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LastAction {
+    Replied,
+    RepliedAll,
+    Forwarded,
+}
+
+/// 读取最后操作及其时间
+///
+/// 优先使用 PR_LAST_VERB_EXECUTED；部分客户端只更新 PR_ICON_INDEX，此时无法区分
+/// 回复和全部回复，按回复处理。
+pub(crate) fn read_last_action(store: &PropertyStore) -> (Option<LastAction>, Option<String>) {
+    let verb = store.get(PROP_LAST_VERB_EXECUTED).and_then(|v| v.as_i32());
+    let action = match verb {
+        Some(EXCHIVERB_REPLYTOSENDER) => Some(LastAction::Replied),
+        Some(EXCHIVERB_REPLYTOALL) => Some(LastAction::RepliedAll),
+        Some(EXCHIVERB_FORWARD) => Some(LastAction::Forwarded),
+        _ => match store.get(PROP_ICON_INDEX).and_then(|v| v.as_i32()) {
+            Some(ICON_MAIL_REPLIED) => Some(LastAction::Replied),
+            Some(ICON_MAIL_FORWARDED) => Some(LastAction::Forwarded),
+            _ => None,
+        },
+    };
+    let time = action.and_then(|_| {
+        store
+            .get(PROP_LAST_VERB_EXECUTION_TIME)
+            .and_then(|v| v.as_str().map(str::to_string))
+    });
+    (action, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::props::parse_property_stream;
+
+    fn store(props: &[(u32, u64)]) -> PropertyStore {
+        let data: Vec<u8> = props
+            .iter()
+            .flat_map(|(tag, value)| {
+                [
+                    &tag.to_le_bytes()[..],
+                    &6u32.to_le_bytes(),
+                    &value.to_le_bytes(),
+                ]
+                .concat()
+            })
+            .collect();
+        let mut store = PropertyStore::default();
+        store.set_fixed(parse_property_stream(&data, 0));
+        store
+    }
+
+    #[test]
+    fn test_read_last_action() {
+        let (action, time) = read_last_action(&store(&[
+            (0x1081_0003, 103),
+            (0x1082_0040, 133428698600000000),
+        ]));
+        assert_eq!(action, Some(LastAction::RepliedAll));
+        assert!(time.is_some());
+
+        let (action, _) = read_last_action(&store(&[(0x1080_0003, 0x106)]));
+        assert_eq!(action, Some(LastAction::Forwarded));
+
+        assert_eq!(read_last_action(&store(&[])), (None, None));
+    }
+}
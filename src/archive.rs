@@ -0,0 +1,86 @@
+//! ZIP 附件内容列表（`archive` feature）
+//!
+//! 只读取中央目录，不解压任何条目，安全类界面可以直接展示压缩包里有什么。
+
+use serde::Serialize;
+use std::io::Cursor;
+
+/// 压缩包中的一个条目
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArchiveEntry {
+    pub name: String,
+    /// 解压后大小（字节）
+    pub size: u64,
+    /// 压缩后大小（字节）
+    pub compressed_size: u64,
+    /// 是否加密（加密的压缩包常被用来绕过网关扫描）
+    pub encrypted: bool,
+    pub is_dir: bool,
+}
+
+/// 列出 ZIP 数据中的条目，不是 ZIP 或中央目录损坏时返回 `None`
+pub(crate) fn list_zip(data: &[u8]) -> Option<Vec<ArchiveEntry>> {
+    if !data.starts_with(b"PK\x03\x04") && !data.starts_with(b"PK\x05\x06") {
+        return None;
+    }
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).ok()?;
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let file = archive.by_index_raw(index).ok()?;
+        entries.push(ArchiveEntry {
+            name: file.name().to_string(),
+            size: file.size(),
+            compressed_size: file.compressed_size(),
+            encrypted: file.encrypted(),
+            is_dir: file.is_dir(),
+        });
+    }
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_zip() {
+        // 仅含一个未压缩条目 "a.txt"（内容 "hi"）的最小 ZIP
+        let mut data = Vec::new();
+        let crc = 0xD8932AACu32;
+        data.extend_from_slice(b"PK\x03\x04\x0A\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        data.extend_from_slice(&crc.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(b"a.txthi");
+        let central = data.len() as u32;
+        data.extend_from_slice(b"PK\x01\x02\x14\x00\x0A\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        data.extend_from_slice(&crc.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes());
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(b"a.txt");
+        let central_len = data.len() as u32 - central;
+        data.extend_from_slice(b"PK\x05\x06\x00\x00\x00\x00\x01\x00\x01\x00");
+        data.extend_from_slice(&central_len.to_le_bytes());
+        data.extend_from_slice(&central.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+
+        let entries = list_zip(&data).unwrap();
+        assert_eq!(
+            entries,
+            vec![ArchiveEntry {
+                name: "a.txt".to_string(),
+                size: 2,
+                compressed_size: 2,
+                encrypted: false,
+                is_dir: false,
+            }]
+        );
+        assert_eq!(list_zip(b"not a zip"), None);
+    }
+}
@@ -0,0 +1,345 @@
+//! 发件人验证结果 (Authentication-Results / Received-SPF)
+//!
+//! 收件方服务器会把 SPF、DKIM、DMARC 的验证结果写进传输头（RFC 8601 Authentication-Results，
+//! RFC 7208 Received-SPF）。这里把它们解析成结构化数据，浏览器里的分诊工具不必再自己解析头部。
+//! 传输头按从新到旧的顺序添加在顶部，最上面一条由最终收件服务器写入，可信度最高，
+//! 汇总字段取自这一条。
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 发件人验证结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SecurityInfo {
+    /// 全部 Authentication-Results 头中的各项结果，按出现顺序
+    pub authentication_results: Vec<AuthResult>,
+    /// 最上面一条 Received-SPF 头
+    pub received_spf: Option<ReceivedSpf>,
+    /// SPF 结果，如 `pass`、`fail`、`softfail`；没有 Authentication-Results 时取自 Received-SPF
+    pub spf: Option<String>,
+    /// 各个 DKIM 签名的验证结果
+    pub dkim: Vec<DkimResult>,
+    /// DMARC 结果
+    pub dmarc: Option<String>,
+    /// DMARC 处置，如 `none`、`quarantine`、`reject`
+    pub dmarc_disposition: Option<String>,
+}
+
+/// Authentication-Results 中的一项验证结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthResult {
+    /// 写入该头的验证服务器 (authserv-id)
+    pub authserv_id: Option<String>,
+    /// 验证方法，如 `spf`、`dkim`、`dmarc`、`arc`，小写
+    pub method: String,
+    /// 验证结果，如 `pass`、`fail`、`none`，小写
+    pub result: String,
+    /// `reason=` 说明
+    pub reason: Option<String>,
+    /// 其余属性，如 `smtp.mailfrom`、`header.d`、`header.s`，按出现顺序
+    pub properties: Vec<(String, String)>,
+    /// 括号中的注释
+    pub comment: Option<String>,
+}
+
+/// 一个 DKIM 签名的验证结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DkimResult {
+    pub result: String,
+    /// 签名域 (`header.d`，缺失时取 `header.i` 的域名部分)
+    pub domain: Option<String>,
+    /// 选择器 (`header.s`)
+    pub selector: Option<String>,
+}
+
+/// Received-SPF 头
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceivedSpf {
+    /// SPF 结果，小写
+    pub result: String,
+    pub client_ip: Option<String>,
+    pub envelope_from: Option<String>,
+    pub helo: Option<String>,
+    /// 括号中的说明
+    pub comment: Option<String>,
+}
+
+/// 从传输头中读取验证结果，没有相关头时返回 `None`。`headers` 为 [`parse_header_block`] 的结果
+///
+/// [`parse_header_block`]: crate::headers::parse_header_block
+pub(crate) fn read_security(headers: &BTreeMap<String, Vec<String>>) -> Option<SecurityInfo> {
+    let results: Vec<Vec<AuthResult>> = headers
+        .get("authentication-results")
+        .into_iter()
+        .flatten()
+        .map(|value| parse_authentication_results(value))
+        .collect();
+    let received_spf = headers
+        .get("received-spf")
+        .and_then(|values| values.first())
+        .and_then(|value| parse_received_spf(value));
+    if results.is_empty() && received_spf.is_none() {
+        return None;
+    }
+
+    let latest = results.first().map(Vec::as_slice).unwrap_or_default();
+    let find = |method: &str| latest.iter().find(|r| r.method == method);
+    let dmarc = find("dmarc");
+    Some(SecurityInfo {
+        spf: find("spf")
+            .map(|r| r.result.clone())
+            .or_else(|| received_spf.as_ref().map(|spf| spf.result.clone())),
+        dkim: latest
+            .iter()
+            .filter(|r| r.method == "dkim")
+            .map(|r| DkimResult {
+                result: r.result.clone(),
+                domain: r.property("header.d").map(str::to_string).or_else(|| {
+                    r.property("header.i")
+                        .map(|i| i.rsplit('@').next().unwrap_or(i).to_string())
+                }),
+                selector: r.property("header.s").map(str::to_string),
+            })
+            .collect(),
+        dmarc: dmarc.map(|r| r.result.clone()),
+        dmarc_disposition: dmarc.and_then(dmarc_disposition),
+        authentication_results: results.into_iter().flatten().collect(),
+        received_spf,
+    })
+}
+
+impl AuthResult {
+    fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// DMARC 处置：Exchange Online 写在 `action=`，部分服务器写在 `policy.dmarc=`，
+/// Gmail 写在注释中的 `dis=`
+fn dmarc_disposition(result: &AuthResult) -> Option<String> {
+    result
+        .property("action")
+        .or_else(|| result.property("policy.dmarc"))
+        .or_else(|| {
+            result
+                .comment
+                .as_deref()?
+                .split_whitespace()
+                .find_map(|token| token.strip_prefix("dis="))
+        })
+        .map(str::to_ascii_lowercase)
+}
+
+/// 解析一个 Authentication-Results 头：`authserv-id; method=result prop=value ...; ...`。
+/// Exchange Online 写入的头省略了 authserv-id，直接以 `spf=...` 开头
+fn parse_authentication_results(value: &str) -> Vec<AuthResult> {
+    let mut parts = split_outside(value, ';').into_iter().peekable();
+    let first_token = parts.peek().and_then(|part| {
+        let (text, _) = strip_comments(part);
+        text.split_whitespace().next().map(str::to_string)
+    });
+    let authserv_id = match first_token {
+        Some(token) if !token.contains('=') => {
+            parts.next();
+            Some(token)
+        }
+        _ => None,
+    };
+
+    parts
+        .filter_map(|part| {
+            let (text, comments) = strip_comments(part);
+            let mut tokens = tokens(&text).into_iter();
+            let (method, result) = tokens.next()?.split_once('=')?;
+            let mut auth = AuthResult {
+                authserv_id: authserv_id.clone(),
+                // 去掉方法版本号，如 "dkim/1"
+                method: method.split('/').next()?.trim().to_ascii_lowercase(),
+                result: unquote(result).to_ascii_lowercase(),
+                reason: None,
+                properties: Vec::new(),
+                comment: Some(comments.join(" ")).filter(|c| !c.is_empty()),
+            };
+            for token in tokens {
+                let Some((key, value)) = token.split_once('=') else {
+                    continue;
+                };
+                let value = unquote(value).to_string();
+                match key.to_ascii_lowercase().as_str() {
+                    "reason" => auth.reason = Some(value),
+                    key => auth.properties.push((key.to_string(), value)),
+                }
+            }
+            Some(auth)
+        })
+        .collect()
+}
+
+/// 解析 Received-SPF 头：`result (comment) key=value; key=value`
+fn parse_received_spf(value: &str) -> Option<ReceivedSpf> {
+    let (text, comments) = strip_comments(value);
+    let mut parts = text.trim().splitn(2, char::is_whitespace);
+    let mut spf = ReceivedSpf {
+        result: parts.next().filter(|r| !r.is_empty())?.to_ascii_lowercase(),
+        client_ip: None,
+        envelope_from: None,
+        helo: None,
+        comment: Some(comments.join(" ")).filter(|c| !c.is_empty()),
+    };
+    for pair in split_outside(parts.next().unwrap_or_default(), ';') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let value = Some(unquote(value.trim()).to_string());
+        match key.trim().to_ascii_lowercase().as_str() {
+            "client-ip" => spf.client_ip = value,
+            "envelope-from" => spf.envelope_from = value,
+            "helo" => spf.helo = value,
+            _ => {}
+        }
+    }
+    Some(spf)
+}
+
+/// 按分隔符拆分，引号和括号（注释）内的分隔符不拆分
+fn split_outside(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' if depth == 0 => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 && !in_quotes => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts.retain(|part| !part.trim().is_empty());
+    parts
+}
+
+/// 去掉括号注释（可嵌套），返回剩余文本和各个注释
+fn strip_comments(text: &str) -> (String, Vec<String>) {
+    let mut rest = String::new();
+    let mut comments = Vec::new();
+    let mut comment = String::new();
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    for c in text.chars() {
+        match c {
+            '"' if depth == 0 => {
+                in_quotes = !in_quotes;
+                rest.push(c);
+            }
+            '(' if !in_quotes => {
+                if depth > 0 {
+                    comment.push(c);
+                }
+                depth += 1;
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    comments.push(std::mem::take(&mut comment).trim().to_string());
+                    rest.push(' ');
+                } else {
+                    comment.push(c);
+                }
+            }
+            c if depth > 0 => comment.push(c),
+            c => rest.push(c),
+        }
+    }
+    (rest, comments)
+}
+
+/// 按空白拆分，引号内的空白不拆分
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut in_quotes = false;
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        if c.is_whitespace() && !in_quotes {
+            if let Some(s) = start.take() {
+                tokens.push(&text[s..i]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::parse_header_block;
+
+    #[test]
+    fn test_read_security() {
+        let headers = parse_header_block(concat!(
+            "Authentication-Results: spf=pass (sender IP is 192.0.2.1)\r\n",
+            " smtp.mailfrom=example.com; dkim=pass (signature was verified)\r\n",
+            " header.d=example.com;dmarc=pass action=none\r\n",
+            " header.from=example.com;compauth=pass reason=100\r\n",
+            "Received-SPF: Pass (protection.outlook.com: domain of example.com designates\r\n",
+            " 192.0.2.1 as permitted sender) receiver=protection.outlook.com;\r\n",
+            " client-ip=192.0.2.1; helo=mail.example.com;\r\n",
+            "Authentication-Results: mx.google.com;\r\n",
+            "       dkim=pass header.i=@example.com header.s=s1 header.b=abc;\r\n",
+            "       dkim=fail (bad signature) header.d=other.org header.s=k2;\r\n",
+            "       dmarc=pass (p=QUARANTINE sp=NONE dis=NONE) header.from=example.com\r\n",
+        ));
+
+        let security = read_security(&headers).unwrap();
+        assert_eq!(security.spf.as_deref(), Some("pass"));
+        assert_eq!(security.dmarc.as_deref(), Some("pass"));
+        assert_eq!(security.dmarc_disposition.as_deref(), Some("none"));
+        assert_eq!(security.dkim.len(), 1);
+        assert_eq!(security.dkim[0].domain.as_deref(), Some("example.com"));
+        assert_eq!(security.authentication_results.len(), 7);
+        assert_eq!(security.authentication_results[0].authserv_id, None);
+        assert_eq!(
+            security.authentication_results[3].reason.as_deref(),
+            Some("100")
+        );
+
+        let spf = security.received_spf.unwrap();
+        assert_eq!(spf.result, "pass");
+        assert_eq!(spf.client_ip.as_deref(), Some("192.0.2.1"));
+        assert_eq!(spf.helo.as_deref(), Some("mail.example.com"));
+
+        let google = &security.authentication_results[4..];
+        assert_eq!(google[0].authserv_id.as_deref(), Some("mx.google.com"));
+        assert_eq!(
+            google[0].properties[0],
+            ("header.i".to_string(), "@example.com".to_string())
+        );
+        assert_eq!(google[1].comment.as_deref(), Some("bad signature"));
+        assert_eq!(dmarc_disposition(&google[2]).as_deref(), Some("none"));
+
+        assert_eq!(read_security(&BTreeMap::new()), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! 批量 MSG → EML 并打包为 ZIP（`eml-zip` feature）
+//!
+//! 覆盖"把一整个文件夹的 Outlook 邮件转成 .eml"这类需求：一次调用完成解析、
+//! 生成 MIME 邮件和压缩，全部在 WASM 内进行，无需上传到服务端。
+
+use crate::{eml, parse_msg_to_struct, MsgParseError};
+use serde_bytes::ByteBuf;
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use wasm_bindgen::prelude::*;
+use zip::write::SimpleFileOptions;
+
+/// WASM 导出接口
+/// 把多个 MSG 文件（`Uint8Array[]`）转为 EML 并打包为 ZIP 返回
+///
+/// `names` 为可选的原文件名数组，与 `files` 一一对应，扩展名会被替换为 `.eml`；
+/// 未提供时按邮件主题命名。任意一个文件解析失败都会报错并指明是第几个。
+#[wasm_bindgen]
+pub fn convert_msgs_to_eml_zip(files: JsValue, names: JsValue) -> Result<Vec<u8>, JsValue> {
+    let files: Vec<ByteBuf> = serde_wasm_bindgen::from_value(files)
+        .map_err(|e| MsgParseError::invalid_argument(e).to_js())?;
+    let names: Option<Vec<String>> = serde_wasm_bindgen::from_value(names)
+        .map_err(|e| MsgParseError::invalid_argument(e).to_js())?;
+
+    let files: Vec<&[u8]> = files.iter().map(|f| f.as_slice()).collect();
+    msgs_to_eml_zip(&files, names.as_deref().unwrap_or_default()).map_err(|e| e.to_js())
+}
+
+/// 把多个 MSG 转为 EML 并打包为 ZIP，见 [`convert_msgs_to_eml_zip`]
+pub fn msgs_to_eml_zip(files: &[&[u8]], names: &[String]) -> Result<Vec<u8>, MsgParseError> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut used = HashSet::new();
+
+    for (index, data) in files.iter().enumerate() {
+        let email = parse_msg_to_struct(data).map_err(|e| MsgParseError::InFile {
+            index,
+            error: Box::new(e),
+        })?;
+
+        let stem = match names.get(index) {
+            Some(name) => name
+                .rsplit_once('.')
+                .map_or(name.as_str(), |(stem, _)| stem)
+                .to_string(),
+            None => email.subject.clone().unwrap_or_default(),
+        };
+        let stem = sanitize_file_name(&stem).unwrap_or_else(|| format!("message-{}", index + 1));
+        let name = unique_name(&stem, &mut used);
+
+        zip.start_file(name, options).map_err(write_error)?;
+        zip.write_all(&eml::build_eml(&email))
+            .map_err(write_error)?;
+    }
+
+    Ok(zip.finish().map_err(write_error)?.into_inner())
+}
+
+fn write_error(e: impl std::fmt::Display) -> MsgParseError {
+    MsgParseError::WriteError {
+        reason: e.to_string(),
+    }
+}
+
+/// 去掉 Windows/macOS 文件名中不允许的字符，结果为空时返回 `None`
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(120)
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').to_string();
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// 同名文件追加 " (2)"、" (3)" …，比较时不区分大小写
+fn unique_name(stem: &str, used: &mut HashSet<String>) -> String {
+    let mut name = format!("{}.eml", stem);
+    let mut n = 2;
+    while !used.insert(name.to_lowercase()) {
+        name = format!("{} ({}).eml", stem, n);
+        n += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_msgs_to_eml_zip() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let zip = msgs_to_eml_zip(&[data, data], &[]).unwrap();
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(zip)).unwrap();
+        let names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"Quarterly report.eml".to_string()));
+        assert!(names.contains(&"Quarterly report (2).eml".to_string()));
+
+        let err = msgs_to_eml_zip(&[data, b"not a msg"], &[]).unwrap_err();
+        assert_eq!(err.code(), "not_cfb_file");
+        assert!(err.to_string().starts_with("第 2 个文件"));
+
+        let mut eml = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("Quarterly report.eml").unwrap(),
+            &mut eml,
+        )
+        .unwrap();
+        assert!(eml.contains("Subject: Quarterly report"));
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("RE: a/b?").as_deref(), Some("RE_ a_b_"));
+        assert_eq!(sanitize_file_name(" .. "), None);
+    }
+}
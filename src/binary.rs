@@ -0,0 +1,68 @@
+//! MessagePack / CBOR 二进制输出
+//!
+//! 附件数据通过 `serde_bytes` 序列化为原生二进制类型，不需要 JSON 的 base64 开销，
+//! 适合直接 POST 给后端或持久化存储。
+
+use crate::{parse_msg_to_struct, MsgEmail};
+use wasm_bindgen::prelude::*;
+
+/// 将解析结果编码为 MessagePack（字段名以 map 形式保留）
+#[cfg(feature = "msgpack")]
+pub fn to_msgpack(email: &MsgEmail) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(email)
+}
+
+/// 将解析结果编码为 CBOR
+#[cfg(feature = "cbor")]
+pub fn to_cbor(email: &MsgEmail) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(email, &mut buf)?;
+    Ok(buf)
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件并以 MessagePack 字节返回
+#[cfg(feature = "msgpack")]
+#[wasm_bindgen]
+pub fn parse_msg_to_msgpack(file_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+    to_msgpack(&email).map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件并以 CBOR 字节返回
+#[cfg(feature = "cbor")]
+#[wasm_bindgen]
+pub fn parse_msg_to_cbor(file_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+    to_cbor(&email).map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MsgEmail {
+        MsgEmail {
+            subject: Some("Hello".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_to_msgpack() {
+        let bytes = to_msgpack(&sample()).unwrap();
+        // to_vec_named 会保留字段名
+        assert!(bytes.windows(7).any(|w| w == b"subject"));
+        assert!(bytes.windows(5).any(|w| w == b"Hello"));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_to_cbor() {
+        let bytes = to_cbor(&sample()).unwrap();
+        let value: ciborium::Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert!(value.is_map());
+    }
+}
@@ -0,0 +1,155 @@
+//! 日历项（IPM.Appointment）及会议请求中的约会属性
+//!
+//! 约会信息几乎都是 PSETID_Appointment / PSETID_Common 下的命名属性，
+//! 需要先通过命名属性映射查到属性 ID 再取值。
+
+use crate::named::{NameIdMap, PSETID_APPOINTMENT, PSETID_COMMON, PSETID_MEETING};
+use crate::props::{to_hex, PropertyStore, PropertyValue};
+use crate::recurrence::{parse_recurrence, RecurrencePattern};
+use serde::Serialize;
+
+// PSETID_Appointment 下的 LID
+const LID_BUSY_STATUS: u32 = 0x8205;
+const LID_LOCATION: u32 = 0x8208;
+const LID_APPOINTMENT_START_WHOLE: u32 = 0x820D;
+const LID_APPOINTMENT_END_WHOLE: u32 = 0x820E;
+const LID_APPOINTMENT_SUB_TYPE: u32 = 0x8215;
+const LID_APPOINTMENT_RECUR: u32 = 0x8216;
+const LID_TO_ATTENDEES_STRING: u32 = 0x823B;
+const LID_CC_ATTENDEES_STRING: u32 = 0x823C;
+
+// PSETID_Common 下的 LID
+const LID_REMINDER_DELTA: u32 = 0x8501;
+const LID_REMINDER_SET: u32 = 0x8503;
+const LID_PRIVATE: u32 = 0x8506;
+
+// PSETID_Meeting 下的 LID
+const LID_GLOBAL_OBJECT_ID: u32 = 0x0003;
+
+/// PR_SENSITIVITY，值为 2 表示私人
+const PROP_SENSITIVITY: u16 = 0x0036;
+const SENSITIVITY_PRIVATE: i32 = 2;
+
+/// 组织者取“代表发送”的身份，没有时取发件人
+const PROP_SENT_REPRESENTING_NAME: u16 = 0x0042;
+const PROP_SENDER_NAME: u16 = 0x0C1A;
+
+/// 忙闲状态 (PidLidBusyStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BusyStatus {
+    Free,
+    Tentative,
+    Busy,
+    OutOfOffice,
+    WorkingElsewhere,
+}
+
+impl BusyStatus {
+    fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(BusyStatus::Free),
+            1 => Some(BusyStatus::Tentative),
+            2 => Some(BusyStatus::Busy),
+            3 => Some(BusyStatus::OutOfOffice),
+            4 => Some(BusyStatus::WorkingElsewhere),
+            _ => None,
+        }
+    }
+}
+
+/// 约会信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Appointment {
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub location: Option<String>,
+    pub busy_status: Option<BusyStatus>,
+    /// 全天事件
+    pub all_day: bool,
+    /// 提前多少分钟提醒，未设置提醒时为 `None`
+    pub reminder_minutes: Option<i32>,
+    /// 私人约会，共享日历中不应显示详情
+    pub is_private: bool,
+    /// 组织者显示名
+    pub organizer: Option<String>,
+    /// 必选与会者显示名 (PidLidToAttendeesString)
+    pub required_attendees: Vec<String>,
+    /// 可选与会者显示名 (PidLidCcAttendeesString)
+    pub optional_attendees: Vec<String>,
+    /// 会议的全局对象 ID (PidLidGlobalObjectId)，十六进制；同一会议的请求、响应和日历项相同
+    pub global_object_id: Option<String>,
+    /// 周期约会的重复模式 (PidLidAppointmentRecur)
+    pub recurrence: Option<RecurrencePattern>,
+}
+
+/// 是否为携带约会属性的消息类型（约会本身及会议请求/响应）
+pub(crate) fn is_appointment_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class.starts_with("IPM.APPOINTMENT") || class.starts_with("IPM.SCHEDULE.MEETING.")
+}
+
+/// 从顶层属性中读取约会信息
+pub(crate) fn read_appointment(store: &PropertyStore, names: &NameIdMap) -> Appointment {
+    let appointment = |lid| names.get_lid(store, PSETID_APPOINTMENT, lid);
+    let common = |lid| names.get_lid(store, PSETID_COMMON, lid);
+    let text = |value: Option<PropertyValue>| value.and_then(|v| v.as_str().map(str::to_string));
+    let top_level = |id| text(store.get(id)).filter(|s| !s.is_empty());
+    // Outlook 把与会者显示名用分号连接保存
+    let attendees = |lid| {
+        text(appointment(lid))
+            .map(|list| {
+                list.split(';')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let reminder_set = common(LID_REMINDER_SET)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let is_private = common(LID_PRIVATE).and_then(|v| v.as_bool()) == Some(true)
+        || store.get(PROP_SENSITIVITY).and_then(|v| v.as_i32()) == Some(SENSITIVITY_PRIVATE);
+
+    Appointment {
+        start_time: text(appointment(LID_APPOINTMENT_START_WHOLE)),
+        end_time: text(appointment(LID_APPOINTMENT_END_WHOLE)),
+        location: text(appointment(LID_LOCATION)).filter(|s| !s.is_empty()),
+        busy_status: appointment(LID_BUSY_STATUS)
+            .and_then(|v| v.as_i32())
+            .and_then(BusyStatus::from_i32),
+        all_day: appointment(LID_APPOINTMENT_SUB_TYPE)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        reminder_minutes: reminder_set
+            .then(|| common(LID_REMINDER_DELTA).and_then(|v| v.as_i32()))
+            .flatten(),
+        is_private,
+        organizer: top_level(PROP_SENT_REPRESENTING_NAME).or_else(|| top_level(PROP_SENDER_NAME)),
+        required_attendees: attendees(LID_TO_ATTENDEES_STRING),
+        optional_attendees: attendees(LID_CC_ATTENDEES_STRING),
+        global_object_id: match names.get_lid(store, PSETID_MEETING, LID_GLOBAL_OBJECT_ID) {
+            Some(PropertyValue::Binary(data)) if !data.is_empty() => Some(to_hex(&data)),
+            _ => None,
+        },
+        recurrence: match appointment(LID_APPOINTMENT_RECUR) {
+            Some(PropertyValue::Binary(data)) => parse_recurrence(&data),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_appointment_class() {
+        assert!(is_appointment_class("IPM.Appointment"));
+        assert!(is_appointment_class("ipm.schedule.meeting.request"));
+        assert!(!is_appointment_class("IPM.Note"));
+    }
+}
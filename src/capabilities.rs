@@ -0,0 +1,86 @@
+//! 版本及能力查询
+//!
+//! 同一个 npm 包可能由不同 feature 组合构建，宿主应用在运行时据此判断可用功能，
+//! 而不是假设某个导出一定存在。
+
+use wasm_bindgen::prelude::*;
+
+/// 始终可用的能力
+const BUILTIN: &[&str] = &[
+    "rtf",
+    "html",
+    "encodings",
+    "calendar",
+    "contact",
+    "task",
+    "journal",
+    "rss",
+    "post",
+    "smime",
+    "named-properties",
+    "headers",
+    "events",
+    "attachment-sink",
+    "csp-html",
+    "eml",
+    "ical",
+    "recurrence",
+    "vcard",
+    "lazy-attachments",
+];
+
+/// WASM 导出接口
+/// 返回 crate 版本号，如 `"0.0.4-beta"`
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// WASM 导出接口
+/// 返回当前构建启用的能力列表，包括内置能力和已启用的 Cargo feature
+#[wasm_bindgen]
+pub fn supported_features() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&enabled_features())
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 当前构建启用的能力，见 [`supported_features`]
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = BUILTIN.to_vec();
+    let optional = [
+        ("archive", cfg!(feature = "archive")),
+        ("eml-zip", cfg!(feature = "eml-zip")),
+        ("ndjson", cfg!(feature = "ndjson")),
+        ("zip-input", cfg!(feature = "zip-input")),
+        ("smime-verify", cfg!(feature = "smime-verify")),
+        ("wmf-preview", cfg!(feature = "wmf-preview")),
+        ("msgpack", cfg!(feature = "msgpack")),
+        ("cbor", cfg!(feature = "cbor")),
+        ("protobuf", cfg!(feature = "protobuf")),
+        ("mail-parser", cfg!(feature = "mail-parser")),
+        ("lettre", cfg!(feature = "lettre")),
+        ("tokio", cfg!(feature = "tokio")),
+        ("mmap", cfg!(feature = "mmap")),
+        ("perf-marks", cfg!(feature = "perf-marks")),
+    ];
+    features.extend(
+        optional
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name),
+    );
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_features() {
+        let features = enabled_features();
+        assert!(features.contains(&"rtf"));
+        assert_eq!(features.contains(&"archive"), cfg!(feature = "archive"));
+        assert!(!version().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+//! 联系人（IPM.Contact）
+
+use crate::named::{NameIdMap, PSETID_ADDRESS};
+use crate::props::{self, PropertyStore, PropertyValue};
+use crate::{quirks, Attachment};
+use cfb::CompoundFile;
+use serde::Serialize;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+
+// PSETID_Address 下的 LID
+const LID_HAS_PICTURE: u32 = 0x8015;
+const LID_EMAIL_ADDRESSES: [u32; 3] = [0x8083, 0x8093, 0x80A3];
+const LID_INSTANT_MESSAGING_ADDRESS: u32 = 0x8062;
+const LID_WORK_ADDRESS: [u32; 5] = [0x8045, 0x8046, 0x8047, 0x8048, 0x8049];
+
+// 联系人的普通属性
+const PROP_DISPLAY_NAME: u16 = 0x3001;
+const PROP_GIVEN_NAME: u16 = 0x3A06;
+const PROP_SURNAME: u16 = 0x3A11;
+const PROP_MIDDLE_NAME: u16 = 0x3A44;
+const PROP_DISPLAY_NAME_PREFIX: u16 = 0x3A45;
+const PROP_GENERATION: u16 = 0x3A05;
+const PROP_NICKNAME: u16 = 0x3A4F;
+const PROP_COMPANY_NAME: u16 = 0x3A16;
+const PROP_TITLE: u16 = 0x3A17;
+const PROP_DEPARTMENT_NAME: u16 = 0x3A18;
+const PROP_BUSINESS_TELEPHONE_NUMBER: u16 = 0x3A08;
+const PROP_HOME_TELEPHONE_NUMBER: u16 = 0x3A09;
+const PROP_MOBILE_TELEPHONE_NUMBER: u16 = 0x3A1C;
+const PROP_BUSINESS_FAX_NUMBER: u16 = 0x3A24;
+const PROP_BIRTHDAY: u16 = 0x3A42;
+const PROP_WEDDING_ANNIVERSARY: u16 = 0x3A41;
+/// 住宅地址：街道、城市、省/州、邮编、国家
+const PROP_HOME_ADDRESS: [u16; 5] = [0x3A5D, 0x3A59, 0x3A5C, 0x3A5B, 0x3A5A];
+/// 其他地址：街道、城市、省/州、邮编、国家
+const PROP_OTHER_ADDRESS: [u16; 5] = [0x3A63, 0x3A5F, 0x3A62, 0x3A61, 0x3A60];
+/// PR_ATTACHMENT_CONTACTPHOTO，附件存储中标记联系人照片的布尔属性
+const PROP_ATTACHMENT_CONTACTPHOTO: u16 = 0x7FFF;
+
+/// 邮政地址
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PostalAddress {
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+/// 联系人信息
+#[derive(Debug, Default, Serialize)]
+pub struct Contact {
+    pub display_name: Option<String>,
+    pub given_name: Option<String>,
+    pub surname: Option<String>,
+    pub middle_name: Option<String>,
+    /// 称谓前缀，如 `Dr.`
+    pub name_prefix: Option<String>,
+    /// 称谓后缀，如 `Jr.`
+    pub name_suffix: Option<String>,
+    pub nickname: Option<String>,
+    /// 电子邮件 1~3 中非空的地址，按槽位顺序排列
+    pub emails: Vec<String>,
+    pub im_address: Option<String>,
+    pub business_phone: Option<String>,
+    pub home_phone: Option<String>,
+    pub mobile_phone: Option<String>,
+    pub business_fax: Option<String>,
+    pub company: Option<String>,
+    pub job_title: Option<String>,
+    pub department: Option<String>,
+    pub work_address: Option<PostalAddress>,
+    pub home_address: Option<PostalAddress>,
+    pub other_address: Option<PostalAddress>,
+    pub birthday: Option<String>,
+    pub anniversary: Option<String>,
+    /// 联系人照片，不会再出现在 `MsgEmail::attachments` 中
+    pub photo: Option<Attachment>,
+}
+
+/// 是否为联系人
+pub(crate) fn is_contact_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class == "IPM.CONTACT" || class.starts_with("IPM.CONTACT.")
+}
+
+/// 从顶层属性中读取联系人信息（不含照片）
+pub(crate) fn read_contact(store: &PropertyStore, names: &NameIdMap) -> Contact {
+    let text = |value: Option<PropertyValue>| {
+        value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+
+    let prop = |id| text(store.get(id));
+    let named = |lid| text(names.get_lid(store, PSETID_ADDRESS, lid));
+    let address = |parts: [Option<String>; 5]| {
+        let [street, city, state, postal_code, country] = parts;
+        let address = PostalAddress {
+            street,
+            city,
+            state,
+            postal_code,
+            country,
+        };
+        (address != PostalAddress::default()).then_some(address)
+    };
+
+    Contact {
+        display_name: prop(PROP_DISPLAY_NAME),
+        given_name: prop(PROP_GIVEN_NAME),
+        surname: prop(PROP_SURNAME),
+        middle_name: prop(PROP_MIDDLE_NAME),
+        name_prefix: prop(PROP_DISPLAY_NAME_PREFIX),
+        name_suffix: prop(PROP_GENERATION),
+        nickname: prop(PROP_NICKNAME),
+        emails: LID_EMAIL_ADDRESSES.into_iter().filter_map(named).collect(),
+        im_address: named(LID_INSTANT_MESSAGING_ADDRESS),
+        business_phone: prop(PROP_BUSINESS_TELEPHONE_NUMBER),
+        home_phone: prop(PROP_HOME_TELEPHONE_NUMBER),
+        mobile_phone: prop(PROP_MOBILE_TELEPHONE_NUMBER),
+        business_fax: prop(PROP_BUSINESS_FAX_NUMBER),
+        company: prop(PROP_COMPANY_NAME),
+        job_title: prop(PROP_TITLE),
+        department: prop(PROP_DEPARTMENT_NAME),
+        work_address: address(LID_WORK_ADDRESS.map(named)),
+        home_address: address(PROP_HOME_ADDRESS.map(prop)),
+        other_address: address(PROP_OTHER_ADDRESS.map(prop)),
+        birthday: prop(PROP_BIRTHDAY),
+        anniversary: prop(PROP_WEDDING_ANNIVERSARY),
+        photo: None,
+    }
+}
+
+/// 找到标记为联系人照片的附件存储
+///
+/// 消息上的 PidLidHasPicture 为假时不查找。
+pub(crate) fn find_photo_attachment<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    store: &PropertyStore,
+    names: &NameIdMap,
+    attachment_paths: &[&Path],
+    quirks: &mut quirks::Quirks,
+) -> Option<PathBuf> {
+    let has_picture = names
+        .get_lid(store, PSETID_ADDRESS, LID_HAS_PICTURE)
+        .and_then(|v| v.as_bool());
+    if has_picture == Some(false) {
+        return None;
+    }
+
+    attachment_paths.iter().find_map(|path| {
+        let data = props::read_stream(comp, path.join(props::PROPERTIES_STREAM)).ok()?;
+        let header_len = quirks.property_header_len(data.len(), props::CHILD_HEADER_LEN);
+        props::parse_property_stream(&data, header_len)
+            .iter()
+            .any(|prop| {
+                prop.id() == PROP_ATTACHMENT_CONTACTPHOTO
+                    && props::decode_fixed(prop) == Some(PropertyValue::Boolean(true))
+            })
+            .then(|| path.to_path_buf())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_contact_class() {
+        assert!(is_contact_class("IPM.Contact"));
+        assert!(is_contact_class("IPM.Contact.Custom"));
+        assert!(!is_contact_class("IPM.DistList"));
+    }
+}
@@ -0,0 +1,185 @@
+//! 会话索引 (PR_CONVERSATION_INDEX) 解码
+//!
+//! 索引由 22 字节的头部块和若干 5 字节的子块组成：头部块记录会话开始时间和会话 GUID，
+//! 每回复或转发一次追加一个子块，记录相对上一次的时间差。Date 头缺失或被伪造时，
+//! 按子块重建的时间线仍能给出正确的先后顺序。
+//!
+//! 另外读取 Message-ID、In-Reply-To 和 References，供按 RFC 5322 的方式串联会话和去重。
+
+use crate::datetime::filetime_to_string;
+use crate::props::{self, PropertyStore, PropertyValue};
+use crate::MsgEmail;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const PROP_CONVERSATION_TOPIC: u16 = 0x0070;
+const PROP_CONVERSATION_INDEX: u16 = 0x0071;
+const PROP_INTERNET_MESSAGE_ID: u16 = 0x1035;
+const PROP_INTERNET_REFERENCES: u16 = 0x1039;
+const PROP_IN_REPLY_TO_ID: u16 = 0x1042;
+
+const HEADER_LEN: usize = 22;
+const CHILD_LEN: usize = 5;
+
+/// 解码后的会话索引
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationIndex {
+    /// 会话 GUID，同一会话中的邮件相同
+    pub guid: String,
+    /// 会话开始时间（第一封邮件的时间）
+    pub start_time: Option<String>,
+    /// 之后每次回复/转发，按先后顺序排列；长度即该邮件在会话中的深度
+    pub replies: Vec<ConversationReply>,
+}
+
+/// 会话中的一次回复或转发
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConversationReply {
+    /// 由时间差累加得到的时间
+    pub time: Option<String>,
+    /// 距上一条的时间差（秒），精度约 0.03 秒或 0.8 秒
+    pub delta_seconds: f64,
+}
+
+/// 读取 PR_CONVERSATION_TOPIC：会话最初的主题，不带 `RE:`、`FW:` 等前缀
+pub(crate) fn read_conversation_topic(store: &PropertyStore) -> Option<String> {
+    store
+        .get(PROP_CONVERSATION_TOPIC)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|s| !s.is_empty())
+}
+
+/// 读取 PR_INTERNET_MESSAGE_ID、PR_IN_REPLY_TO_ID 和 PR_INTERNET_REFERENCES
+pub(crate) fn read_message_ids(email: &mut MsgEmail, store: &PropertyStore) {
+    let text = |id| {
+        store
+            .get(id)
+            .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+            .filter(|s| !s.is_empty())
+    };
+    email.message_id = text(PROP_INTERNET_MESSAGE_ID);
+    email.in_reply_to = text(PROP_IN_REPLY_TO_ID);
+    email.references = text(PROP_INTERNET_REFERENCES)
+        .map(|value| split_message_ids(&value))
+        .unwrap_or_default();
+}
+
+/// 属性缺失时改用传输头中的 Message-ID、In-Reply-To 和 References
+pub(crate) fn read_message_id_headers(
+    email: &mut MsgEmail,
+    headers: &BTreeMap<String, Vec<String>>,
+) {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|values| values.first())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if email.message_id.is_none() {
+        email.message_id = header("message-id");
+    }
+    if email.in_reply_to.is_none() {
+        email.in_reply_to = header("in-reply-to");
+    }
+    if email.references.is_empty() {
+        email.references = header("references")
+            .map(|value| split_message_ids(&value))
+            .unwrap_or_default();
+    }
+}
+
+/// 拆分 References 中的各个 `<id>`；没有尖括号的旧式写法按空白拆分
+fn split_message_ids(value: &str) -> Vec<String> {
+    let ids: Vec<String> = value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| format!("<{}>", id.trim()))
+        .collect();
+    if !ids.is_empty() {
+        return ids;
+    }
+    value.split_whitespace().map(str::to_string).collect()
+}
+
+/// 读取并解码顶层的 PR_CONVERSATION_INDEX
+pub(crate) fn read_conversation_index(store: &PropertyStore) -> Option<ConversationIndex> {
+    match store.get(PROP_CONVERSATION_INDEX) {
+        Some(PropertyValue::Binary(data)) => decode(&data),
+        _ => None,
+    }
+}
+
+/// 解码会话索引，长度不足头部块时返回 `None`，末尾不完整的子块忽略
+pub(crate) fn decode(data: &[u8]) -> Option<ConversationIndex> {
+    let header = data.get(..HEADER_LEN)?;
+
+    // 头部前 6 字节是 FILETIME 的高 48 位
+    let mut time = header[..6]
+        .iter()
+        .fold(0u64, |acc, &b| (acc << 8) | b as u64)
+        << 16;
+    let start_time = filetime_to_string(time);
+
+    let replies = data[HEADER_LEN..]
+        .chunks_exact(CHILD_LEN)
+        .map(|block| {
+            let value = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+            // 最高位为 0 时时间差取 FILETIME 的第 18~48 位，为 1 时取第 23~53 位
+            let delta_code = value >> 31;
+            let time_delta = (value & 0x7FFF_FFFF) as u64;
+            let delta = if delta_code == 0 {
+                time_delta << 18
+            } else {
+                time_delta << 23
+            };
+            time = time.saturating_add(delta);
+            ConversationReply {
+                time: filetime_to_string(time),
+                delta_seconds: delta as f64 / 10_000_000.0,
+            }
+        })
+        .collect();
+
+    Some(ConversationIndex {
+        guid: props::format_guid(&header[6..]),
+        start_time,
+        replies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let mut data = vec![0x01, 0xD2, 0x00, 0x00, 0x00, 0x00];
+        data.extend_from_slice(&[0xAB; 16]);
+        // 时间差 1 << 18 个 100ns 单位
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0x00]);
+        // DeltaCode 为 1：1 << 23
+        data.extend_from_slice(&[0x80, 0x00, 0x00, 0x01, 0x05]);
+        data.push(0xFF);
+
+        let index = decode(&data).unwrap();
+        assert_eq!(index.guid, "ABABABAB-ABAB-ABAB-ABAB-ABABABABABAB");
+        assert!(index.start_time.is_some());
+        assert_eq!(index.replies.len(), 2);
+        assert_eq!(index.replies[0].delta_seconds, 0.0262144);
+        assert_eq!(index.replies[1].delta_seconds, 0.8388608);
+
+        assert_eq!(decode(&data[..10]), None);
+    }
+
+    #[test]
+    fn test_split_message_ids() {
+        assert_eq!(
+            split_message_ids("<a@example.com>\r\n <b@example.com><c@example.com>"),
+            ["<a@example.com>", "<b@example.com>", "<c@example.com>"]
+        );
+        assert_eq!(split_message_ids("a@example.com"), ["a@example.com"]);
+        assert!(split_message_ids("  ").is_empty());
+    }
+}
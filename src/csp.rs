@@ -0,0 +1,670 @@
+//! 适合严格 CSP 的 HTML 正文
+//!
+//! 邮件 HTML 经常带有脚本、内联事件、外部样式表和远程图片，放进沙箱 iframe 并配上
+//! `default-src 'none'; img-src data:; style-src 'unsafe-inline'` 这样的策略时，
+//! 要么被拦截要么渲染不全。这里做一次改写：
+//!
+//! - 删除 `<script>`、`<iframe>`、`<object>` 等可执行或嵌入内容，以及 `<link>`、`<base>`、
+//!   `<meta http-equiv>`；
+//! - 删除全部 `on*` 事件属性和 `javascript:` 链接；
+//! - `<textarea>`、`<title>` 等纯文本元素的内容原样复制，与浏览器一样不解析其中的标签；
+//! - `cid:` 引用的内嵌图片换成 data URI，其他远程资源直接去掉；
+//! - `<style>` 中的选择器统一加上 `.msg-body` 前缀，正文包在 `<div class="msg-body">` 中，
+//!   避免样式影响宿主页面。
+
+use crate::{eml, Attachment};
+
+/// 正文外层容器的类名，`<style>` 中的选择器都会限定在它下面
+const SCOPE_CLASS: &str = "msg-body";
+
+/// 连同内容一起删除的元素
+const REMOVED_ELEMENTS: &[&str] = &[
+    "script", "iframe", "object", "applet", "frameset", "frame", "template",
+];
+/// 只删除标签本身的元素（空元素或外壳）
+const REMOVED_TAGS: &[&str] = &[
+    "link", "base", "embed", "noscript", "param", "animate", "set",
+];
+/// 内容按纯文本解析的元素 (RCDATA/RAWTEXT)：浏览器直到结束标签才结束，内容中的标签
+/// 不生效，这里同样原样复制，否则属性值中的结束标签会让后面的内容逃出过滤
+const RAW_TEXT_ELEMENTS: &[&str] = &["textarea", "title", "xmp", "noembed", "noframes"];
+/// 会触发资源加载的属性
+const RESOURCE_ATTRS: &[&str] = &["src", "background", "poster", "lowsrc", "dynsrc"];
+/// 样式中可执行代码或加载外部内容的写法
+const DANGEROUS_CSS: &[&str] = &["expression(", "behavior", "-moz-binding", "@import"];
+/// 无法安全改写、直接删除的属性
+const DROPPED_ATTRS: &[&str] = &["srcset", "action", "formaction", "xmlns:xlink", "ping"];
+
+/// 把 HTML 正文改写为可在严格 CSP 下渲染的形式
+pub(crate) fn make_csp_safe(html: &str, attachments: &[Attachment]) -> String {
+    rewrite(html, Some(attachments))
+}
+
+/// 同 [`make_csp_safe`]，但保留 `cid:` 引用不内联，留给后续步骤处理
+pub(crate) fn sanitize_keep_cid(html: &str) -> String {
+    rewrite(html, None)
+}
+
+/// `attachments` 为 `None` 时 `cid:` 引用原样保留
+fn rewrite(html: &str, attachments: Option<&[Attachment]>) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut has_body = false;
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        out.push_str(&html[pos..start]);
+
+        if html[start..].starts_with("<!--") {
+            pos = html[start..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        // <!DOCTYPE> 原样保留，<?xml ...?> 之类的处理指令删除
+        if let Some(kind @ (b'!' | b'?')) = html.as_bytes().get(start + 1) {
+            let end = html[start..]
+                .find('>')
+                .map_or(html.len(), |end| start + end + 1);
+            if *kind == b'!' {
+                out.push_str(&html[start..end]);
+            }
+            pos = end;
+            continue;
+        }
+        let Some((tag, end)) = parse_tag(html, start) else {
+            // 不是标签（如 "a < b"），按文本转义输出
+            out.push_str("&lt;");
+            pos = start + 1;
+            continue;
+        };
+        pos = end;
+
+        if tag.closing {
+            match tag.name.as_str() {
+                "body" => out.push_str("</div></body>"),
+                name if REMOVED_TAGS.contains(&name) => {}
+                name => {
+                    out.push_str("</");
+                    out.push_str(name);
+                    out.push('>');
+                }
+            }
+            continue;
+        }
+
+        match tag.name.as_str() {
+            name if REMOVED_ELEMENTS.contains(&name) => {
+                if !tag.self_closing {
+                    pos = skip_element(html, pos, name);
+                }
+            }
+            name if REMOVED_TAGS.contains(&name) => {}
+            name if RAW_TEXT_ELEMENTS.contains(&name) => {
+                let content_end = find_close(html, pos, name).unwrap_or(html.len());
+                write_tag(&mut out, &tag, attachments);
+                out.push_str(&html[pos..content_end]);
+                out.push_str("</");
+                out.push_str(name);
+                out.push('>');
+                pos = skip_element(html, pos, name);
+            }
+            "meta" if tag.attr("http-equiv").is_some() => {}
+            "style" => {
+                let content_end = find_close(html, pos, "style").unwrap_or(html.len());
+                out.push_str("<style>");
+                out.push_str(&scope_css(&html[pos..content_end], attachments));
+                out.push_str("</style>");
+                pos = skip_element(html, pos, "style");
+            }
+            "body" => {
+                has_body = true;
+                out.push_str("<body>");
+                let mut div = tag;
+                div.name = "div".to_string();
+                div.attrs.retain(|(name, _)| name != "class");
+                div.attrs
+                    .insert(0, ("class".to_string(), Some(SCOPE_CLASS.to_string())));
+                write_tag(&mut out, &div, attachments);
+            }
+            _ => write_tag(&mut out, &tag, attachments),
+        }
+    }
+    out.push_str(&html[pos..]);
+
+    if has_body {
+        out
+    } else {
+        format!("<div class=\"{}\">{}</div>", SCOPE_CLASS, out)
+    }
+}
+
+pub(crate) struct Tag {
+    pub name: String,
+    pub closing: bool,
+    pub self_closing: bool,
+    pub attrs: Vec<(String, Option<String>)>,
+}
+
+impl Tag {
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_deref().unwrap_or_default())
+    }
+}
+
+/// 解析 `start` 处的标签，返回标签和 `>` 之后的位置
+pub(crate) fn parse_tag(html: &str, start: usize) -> Option<(Tag, usize)> {
+    let bytes = html.as_bytes();
+    let mut i = start + 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+    let name_start = i;
+    if !bytes.get(i)?.is_ascii_alphabetic() {
+        return None;
+    }
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'-' | b':')) {
+        i += 1;
+    }
+    let name = html[name_start..i].to_ascii_lowercase();
+
+    let mut tag = Tag {
+        name,
+        closing,
+        self_closing: false,
+        attrs: Vec::new(),
+    };
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            tag.self_closing = bytes[i] == b'/';
+            i += 1;
+        }
+        match bytes.get(i)? {
+            b'>' => return Some((tag, i + 1)),
+            _ => tag.self_closing = false,
+        }
+
+        let attr_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'=' | b'>')
+        {
+            i += 1;
+        }
+        let attr_name = html[attr_start..i].to_ascii_lowercase();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = None;
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let value_start;
+            match bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    value_start = i + 1;
+                    i = html[value_start..].find(*quote as char)? + value_start;
+                    value = Some(html[value_start..i].to_string());
+                    i += 1;
+                }
+                _ => {
+                    value_start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    value = Some(html[value_start..i].to_string());
+                }
+            }
+        }
+        tag.attrs.push((attr_name, value));
+    }
+}
+
+/// 在 `from` 之后找到 `</name` 的位置（不区分大小写）
+pub(crate) fn find_close(html: &str, from: usize, name: &str) -> Option<usize> {
+    let needle = format!("</{}", name);
+    let bytes = html.as_bytes();
+    (from..bytes.len().saturating_sub(needle.len() - 1)).find(|&i| {
+        bytes[i..i + needle.len()].eq_ignore_ascii_case(needle.as_bytes())
+            && !bytes
+                .get(i + needle.len())
+                .is_some_and(|b| b.is_ascii_alphanumeric())
+    })
+}
+
+/// 跳过元素内容及其结束标签，返回结束标签之后的位置；没有结束标签时跳到末尾
+fn skip_element(html: &str, from: usize, name: &str) -> usize {
+    find_close(html, from, name)
+        .and_then(|close| html[close..].find('>').map(|end| close + end + 1))
+        .unwrap_or(html.len())
+}
+
+fn write_tag(out: &mut String, tag: &Tag, attachments: Option<&[Attachment]>) {
+    out.push('<');
+    out.push_str(&tag.name);
+    for (name, value) in &tag.attrs {
+        let value = match sanitize_attr(name, value.as_deref(), attachments) {
+            AttrAction::Keep => value.clone(),
+            AttrAction::Replace(value) => Some(value),
+            AttrAction::Drop => continue,
+        };
+        out.push(' ');
+        out.push_str(name);
+        if let Some(value) = value {
+            out.push_str("=\"");
+            out.push_str(&value.replace('"', "&quot;"));
+            out.push('"');
+        }
+    }
+    if tag.self_closing {
+        out.push_str(" /");
+    }
+    out.push('>');
+}
+
+enum AttrAction {
+    Keep,
+    Replace(String),
+    Drop,
+}
+
+fn sanitize_attr(
+    name: &str,
+    value: Option<&str>,
+    attachments: Option<&[Attachment]>,
+) -> AttrAction {
+    if name.starts_with("on") || DROPPED_ATTRS.contains(&name) {
+        return AttrAction::Drop;
+    }
+    let Some(value) = value else {
+        return AttrAction::Keep;
+    };
+    let url = value.trim();
+
+    if RESOURCE_ATTRS.contains(&name) {
+        return match resolve_resource(url, attachments) {
+            Some(data_uri) => AttrAction::Replace(data_uri),
+            None => AttrAction::Drop,
+        };
+    }
+    match name {
+        "href" | "xlink:href" | "formaction" => {
+            if is_script_url(url) || starts_with_ignore_case(url, "data:") {
+                AttrAction::Drop
+            } else {
+                AttrAction::Keep
+            }
+        }
+        "style" => AttrAction::Replace(sanitize_css(value, attachments)),
+        _ => AttrAction::Keep,
+    }
+}
+
+/// 把资源地址转换为 data URI：`cid:` 查找内嵌附件（`attachments` 为 `None` 时原样保留），
+/// `data:` 原样保留，其余返回 `None`
+fn resolve_resource(url: &str, attachments: Option<&[Attachment]>) -> Option<String> {
+    if starts_with_ignore_case(url, "data:") {
+        return (!url.contains(['"', '\'', '<', '>'])).then(|| url.to_string());
+    }
+    let cid = url
+        .get(..4)?
+        .eq_ignore_ascii_case("cid:")
+        .then(|| &url[4..])?;
+    let Some(attachments) = attachments else {
+        return (!url.contains(['"', '\'', '<', '>', '(', ')'])).then(|| url.to_string());
+    };
+    let attachment = attachments.iter().find(|a| {
+        a.content_id
+            .as_deref()
+            .map(|id| id.trim_matches(['<', '>']))
+            .is_some_and(|id| id.eq_ignore_ascii_case(cid))
+    })?;
+    if attachment.data.is_empty() {
+        return None;
+    }
+    let mime = attachment
+        .content_type
+        .as_deref()
+        .filter(|t| t.contains('/') && !t.contains(['"', ';', ' ']))
+        .unwrap_or("application/octet-stream");
+    Some(format!(
+        "data:{};base64,{}",
+        mime,
+        eml::base64(&attachment.data)
+    ))
+}
+
+/// 只把 `src`、`background` 等属性中的 `cid:` 引用换成 data URI，其余内容原样保留
+pub(crate) fn inline_cid_images(html: &str, attachments: &[Attachment]) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        let Some((mut tag, end)) = parse_tag(html, start) else {
+            out.push_str(&html[pos..start + 1]);
+            pos = start + 1;
+            continue;
+        };
+        out.push_str(&html[pos..start]);
+        pos = end;
+
+        let mut changed = false;
+        for (name, value) in &mut tag.attrs {
+            let Some(url) = value
+                .as_deref()
+                .filter(|_| RESOURCE_ATTRS.contains(&name.as_str()))
+            else {
+                continue;
+            };
+            if starts_with_ignore_case(url.trim(), "cid:") {
+                if let Some(data_uri) = resolve_resource(url.trim(), Some(attachments)) {
+                    *value = Some(data_uri);
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            write_raw_tag(&mut out, &tag);
+        } else {
+            out.push_str(&html[start..end]);
+        }
+    }
+    out.push_str(&html[pos..]);
+    out
+}
+
+/// 按原样写出标签，不做任何过滤
+fn write_raw_tag(out: &mut String, tag: &Tag) {
+    out.push('<');
+    out.push_str(&tag.name);
+    for (name, value) in &tag.attrs {
+        out.push(' ');
+        out.push_str(name);
+        if let Some(value) = value {
+            out.push_str("=\"");
+            out.push_str(&value.replace('"', "&quot;"));
+            out.push('"');
+        }
+    }
+    if tag.self_closing {
+        out.push_str(" /");
+    }
+    out.push('>');
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+fn is_script_url(url: &str) -> bool {
+    // 属性值中的字符引用会先被解码，浏览器还会忽略协议名中的空白和控制字符
+    let scheme: String = decode_char_refs(url)
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take(11)
+        .collect();
+    starts_with_ignore_case(&scheme, "javascript:") || starts_with_ignore_case(&scheme, "vbscript:")
+}
+
+/// 解码数字字符引用及 `&colon;`、`&Tab;`、`&NewLine;`，其余原样保留。
+/// 与浏览器一致，数字引用末尾的 `;` 可以省略
+fn decode_char_refs(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        match decode_char_ref(rest) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 解码以 `&` 开头的一个字符引用，返回字符和引用长度
+fn decode_char_ref(text: &str) -> Option<(char, usize)> {
+    if let Some(code) = text.strip_prefix("&#") {
+        let (radix, digits_start) = match code.strip_prefix(['x', 'X']) {
+            Some(_) => (16, 3),
+            None => (10, 2),
+        };
+        let digits = text[digits_start..]
+            .bytes()
+            .take_while(|b| (*b as char).is_digit(radix))
+            .count();
+        if digits == 0 {
+            return None;
+        }
+        // 超出范围的码点按浏览器的做法替换为 U+FFFD
+        let code = text[digits_start..digits_start + digits]
+            .chars()
+            .try_fold(0u32, |code, c| {
+                code.checked_mul(radix)?.checked_add(c.to_digit(radix)?)
+            })
+            .and_then(char::from_u32)
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        let end = digits_start + digits;
+        let len = if text[end..].starts_with(';') {
+            end + 1
+        } else {
+            end
+        };
+        return Some((code, len));
+    }
+    let end = text.find(';').filter(|&end| end <= 10)?;
+    let c = match &text[1..end] {
+        "colon" => ':',
+        "Tab" => '\t',
+        "NewLine" => '\n',
+        _ => return None,
+    };
+    Some((c, end + 1))
+}
+
+/// 处理 CSS 中的危险写法：去掉 `expression()`、`behavior`、`-moz-binding`、`@import`，
+/// `url()` 按资源规则改写，无法改写的替换为 `none`
+fn sanitize_css(css: &str, attachments: Option<&[Attachment]>) -> String {
+    let lower = css.to_ascii_lowercase();
+    if DANGEROUS_CSS.iter().any(|bad| lower.contains(bad)) {
+        return css
+            .split(';')
+            .filter(|decl| {
+                let decl = decl.to_ascii_lowercase();
+                !DANGEROUS_CSS.iter().any(|bad| decl.contains(bad))
+            })
+            .map(|decl| sanitize_css(decl, attachments))
+            .collect::<Vec<_>>()
+            .join(";");
+    }
+
+    let mut out = String::with_capacity(css.len());
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find("url(") {
+        let start = pos + offset;
+        let Some(len) = css[start..].find(')') else {
+            break;
+        };
+        let end = start + len;
+        let url = css[start + 4..end].trim().trim_matches(['"', '\'']);
+        out.push_str(&css[pos..start]);
+        match resolve_resource(url, attachments) {
+            Some(data_uri) => {
+                out.push_str("url(\"");
+                out.push_str(&data_uri);
+                out.push_str("\")");
+            }
+            None => out.push_str("none"),
+        }
+        pos = end + 1;
+    }
+    out.push_str(&css[pos..]);
+    out
+}
+
+/// 给样式表中的选择器加上 `.msg-body` 前缀，`body`/`html` 选择器改为容器本身
+fn scope_css(css: &str, attachments: Option<&[Attachment]>) -> String {
+    // Outlook 常把样式包在 <!-- --> 中，CSS 里它们没有意义；顺便去掉注释
+    let mut cleaned = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        cleaned.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("*/")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    cleaned.push_str(rest);
+    let cleaned = cleaned.replace("<!--", "").replace("-->", "");
+
+    let mut out = String::with_capacity(cleaned.len());
+    scope_rules(&cleaned, attachments, &mut out);
+    out
+}
+
+fn scope_rules(css: &str, attachments: Option<&[Attachment]>, out: &mut String) {
+    let mut rest = css;
+    while let Some(open) = rest.find('{') {
+        let prelude = rest[..open].trim();
+        let Some(close) = matching_brace(rest, open) else {
+            break;
+        };
+        let block = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        // 块前可能残留以分号结尾的 @charset / @import 语句，一律丢弃
+        let prelude = prelude.rsplit(';').next().unwrap_or_default().trim();
+        let lower = prelude.to_ascii_lowercase();
+        if lower.starts_with("@media") || lower.starts_with("@supports") {
+            out.push_str(prelude);
+            out.push('{');
+            scope_rules(block, attachments, out);
+            out.push('}');
+        } else if lower.starts_with('@') {
+            // @font-face、@page、@keyframes 等不含元素选择器
+            out.push_str(prelude);
+            out.push('{');
+            out.push_str(&sanitize_css(block, attachments));
+            out.push('}');
+        } else if !prelude.is_empty() {
+            let selectors: Vec<String> = prelude.split(',').map(scope_selector).collect();
+            out.push_str(&selectors.join(", "));
+            out.push('{');
+            out.push_str(&sanitize_css(block, attachments));
+            out.push('}');
+        }
+    }
+}
+
+fn scope_selector(selector: &str) -> String {
+    let selector = selector.trim();
+    let lower = selector.to_ascii_lowercase();
+    for root in ["html body", "html", "body"] {
+        if let Some(rest) = lower.strip_prefix(root) {
+            if rest.is_empty() || !rest.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+                return format!(".{}{}", SCOPE_CLASS, &selector[root.len()..]);
+            }
+        }
+    }
+    format!(".{} {}", SCOPE_CLASS, selector)
+}
+
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_csp_safe() {
+        let logo = Attachment {
+            content_type: Some("image/png".to_string()),
+            content_id: Some("<logo@x>".to_string()),
+            data: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let html = concat!(
+            "<html><head><script>alert(1)</script>",
+            "<link rel=stylesheet href=\"https://x/a.css\">",
+            "<style><!-- body { color: red } p.a, td { margin: 0 } --></style></head>",
+            "<body class=x onload=\"go()\"><p onclick='x()' style=\"background:url(http://t/p.gif)\">",
+            "<img src=\"cid:logo@x\"><img src=\"http://t/track.gif\">",
+            "<a href=\"javascript:alert(1)\">a</a><a href=\"https://ok\">b</a></p></body></html>"
+        );
+
+        let safe = make_csp_safe(html, &[logo]);
+        assert_eq!(
+            safe,
+            concat!(
+                "<html><head>",
+                "<style>.msg-body{ color: red }.msg-body p.a, .msg-body td{ margin: 0 }</style></head>",
+                "<body><div class=\"msg-body\"><p style=\"background:none\">",
+                "<img src=\"data:image/png;base64,AQID\"><img>",
+                "<a>a</a><a href=\"https://ok\">b</a></p></div></body></html>"
+            )
+        );
+    }
+
+    #[test]
+    fn test_raw_text_elements() {
+        let html = "<textarea><img title=\"</textarea><img src=x onerror=alert(1)>\"></textarea>";
+        let safe = make_csp_safe(html, &[]);
+        assert_eq!(
+            safe,
+            "<div class=\"msg-body\"><textarea><img title=\"</textarea><img>\"></textarea></div>"
+        );
+        assert!(!safe.contains("onerror"));
+
+        let safe = make_csp_safe("<TITLE>a <b onclick=x()> </Title><p>b</p>", &[]);
+        assert!(safe.contains("<title>a <b onclick=x()> </title><p>b</p>"));
+    }
+
+    #[test]
+    fn test_is_script_url() {
+        assert!(is_script_url("javascript:alert(1)"));
+        assert!(is_script_url(" java\tscript:x"));
+        assert!(is_script_url("jav&#x61;script&colon;x"));
+        assert!(is_script_url("javascript&#58alert(1)"));
+        assert!(is_script_url("javascript&#x3a//%0aalert(1)"));
+        assert!(is_script_url("javascript&#000058;alert(1)"));
+        assert_eq!(
+            decode_char_refs("a&#xZ&#;b&#9999999999"),
+            "a&#xZ&#;b\u{FFFD}"
+        );
+        assert!(!is_script_url("https://example.com/?a=1&b=2"));
+    }
+
+    #[test]
+    fn test_fragment_is_wrapped() {
+        assert_eq!(
+            make_csp_safe("<p>1 < 2</p>", &[]),
+            "<div class=\"msg-body\"><p>1 &lt; 2</p></div>"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! 时间转换
+//!
+//! MSG 中的时间都是 FILETIME（自 1601-01-01 起的 100 纳秒数，UTC）。这里按公历换算成
+//! RFC 3339 字符串（如 `2023-10-27T08:44:20Z`）和 Unix 时间戳，另外解析传输头中
+//! RFC 2822 格式的 Date 行。
+
+use crate::timezone;
+
+/// 1601-01-01 到 1970-01-01 的 100 纳秒数
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+/// 4500-08-31 00:00 UTC。Outlook 用 4500-08-31 23:59 或 4501-01-01 表示“无”日期
+const FILETIME_NONE: u64 = 915_045_120_000_000_000;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// FILETIME 转为 Unix 时间戳（秒），早于 1970 年时为负数；0 和“无”日期占位值返回 `None`
+pub(crate) fn filetime_to_unix(filetime: u64) -> Option<i64> {
+    if filetime == 0 || filetime >= FILETIME_NONE {
+        return None;
+    }
+    Some((filetime as i64 - FILETIME_UNIX_EPOCH as i64).div_euclid(10_000_000))
+}
+
+/// 全天日期 `YYYYMMDD`：Outlook 把本地零点换算为 UTC 保存，取最近的 UTC 零点即得本地日期
+/// （时区偏移在 ±12 小时以内）
+pub(crate) fn all_day_date(rfc3339: &str) -> Option<String> {
+    let unix = parse_rfc3339(rfc3339)?;
+    let midnight = (unix + 43200).div_euclid(86400) * 86400;
+    Some(format_rfc3339(midnight)[..10].replace('-', ""))
+}
+
+/// FILETIME 转为 RFC 3339 字符串
+pub(crate) fn filetime_to_string(filetime: u64) -> Option<String> {
+    filetime_to_unix(filetime).map(format_rfc3339)
+}
+
+/// Unix 时间戳转为 `YYYY-MM-DDTHH:MM:SSZ`
+pub(crate) fn format_rfc3339(unix: i64) -> String {
+    let (year, month, day) = civil_from_days(unix.div_euclid(86400));
+    let seconds = unix.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// 解析 [`format_rfc3339`] 输出的 UTC 时间
+pub(crate) fn parse_rfc3339(text: &str) -> Option<i64> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let mut ymd = date.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (ymd.next()??, ymd.next()??, ymd.next()??);
+    let mut hms = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    to_unix(year, month, day, hour, minute, second)
+}
+
+/// Unix 时间戳转为 RFC 2822 日期，如 `Fri, 27 Oct 2023 08:44:20 +0000`
+pub(crate) fn format_rfc2822(unix: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    let days = unix.div_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    let seconds = unix.rem_euclid(86400);
+    format!(
+        "{}, {} {} {} {:02}:{:02}:{:02} +0000",
+        WEEKDAYS[days.rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// 解析 RFC 2822 日期（如 Date 头），返回 Unix 时间戳；星期可省略，时区未知时按 UTC 计算
+pub(crate) fn parse_rfc2822(date: &str) -> Option<i64> {
+    let date = date.split('(').next()?;
+    let date = date.split_once(',').map_or(date, |(_, rest)| rest);
+    let mut parts = date.split_whitespace();
+
+    let day: i64 = parts.next()?.parse().ok()?;
+    let month_name = parts.next()?;
+    let month = MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(month_name))? as i64
+        + 1;
+    let mut year: i64 = parts.next()?.parse().ok()?;
+    // RFC 5322 已废弃的两位数年份
+    if year < 50 {
+        year += 2000;
+    } else if year < 1000 {
+        year += 1900;
+    }
+    let mut hms = parts.next()?.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute) = (hms.next()??, hms.next()??);
+    let second = hms.next().flatten().unwrap_or(0);
+    let offset = parts.next().and_then(timezone::zone_offset).unwrap_or(0) as i64;
+
+    Some(to_unix(year, month, day, hour, minute, second)? - offset * 60)
+}
+
+fn to_unix(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: i64) -> Option<i64> {
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || !(0..24).contains(&hour)
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+    {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// 公历日期到 1970-01-01 的天数
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// 1970-01-01 起的天数到公历日期，[`days_from_civil`] 的逆运算
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filetime_conversion() {
+        let unix = filetime_to_unix(133_428_698_600_000_000).unwrap();
+        assert_eq!(unix, 1_698_396_260);
+        assert_eq!(format_rfc3339(unix), "2023-10-27T08:44:20Z");
+        assert_eq!(parse_rfc3339("2023-10-27T08:44:20Z"), Some(unix));
+        assert_eq!(format_rfc2822(unix), "Fri, 27 Oct 2023 08:44:20 +0000");
+        // 闰日和世纪闰年
+        assert_eq!(format_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(filetime_to_unix(0), None);
+        assert_eq!(filetime_to_unix(915_151_392_000_000_000), None);
+        // 早于 1970 年的生日
+        let birthday = filetime_to_unix(114_972_480_000_000_000).unwrap();
+        assert_eq!(format_rfc3339(birthday), "1965-05-03T00:00:00Z");
+        assert_eq!(format_rfc3339(-1), "1969-12-31T23:59:59Z");
+
+        assert_eq!(
+            parse_rfc2822("Fri, 27 Oct 2023 16:44:20 +0800 (CST)"),
+            Some(unix)
+        );
+        assert_eq!(parse_rfc2822("27 Oct 23 08:44 GMT"), Some(unix - 20));
+        assert_eq!(parse_rfc2822("not a date"), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! BER/DER 编码的最小解析
+//!
+//! S/MIME 的 CMS 结构和 X.509 证书都是 ASN.1 编码。这里只提供按 TLV 逐层取值所需的
+//! 最少功能，不做完整的 ASN.1 模式校验。
+
+use crate::eml;
+
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_CONTEXT_0: u8 = 0xA0;
+/// 构造型 OCTET STRING（BER 分段编码）
+pub(crate) const TAG_OCTET_STRING_CONSTRUCTED: u8 = 0x24;
+
+/// 不定长编码和分段 OCTET STRING 的最大嵌套层数。解析需要递归，
+/// 恶意构造的深层嵌套会耗尽栈（wasm32 默认只有 1 MB）
+const MAX_DEPTH: usize = 64;
+
+/// 一个 BER 编码的 TLV，支持不定长编码
+#[derive(Clone, Copy)]
+pub(crate) struct Tlv<'a> {
+    pub tag: u8,
+    /// 内容字节，不含不定长编码末尾的 00 00
+    pub body: &'a [u8],
+    indefinite: bool,
+    /// 整个 TLV 的长度
+    len: usize,
+    /// 整个 TLV 的编码
+    encoded: &'a [u8],
+}
+
+impl<'a> Tlv<'a> {
+    pub(crate) fn read(data: &'a [u8]) -> Option<Self> {
+        Self::read_nested(data, 0)
+    }
+
+    fn read_nested(data: &'a [u8], depth: usize) -> Option<Self> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let tag = *data.first()?;
+        let first = *data.get(1)?;
+        let (body_start, body_len, indefinite) = match first {
+            0x80 => {
+                // 不定长：逐个跳过子项直到 00 00
+                let mut offset = 2;
+                while data.get(offset..offset + 2)? != [0, 0] {
+                    offset += Tlv::read_nested(&data[offset..], depth + 1)?.len;
+                }
+                (2, offset - 2, true)
+            }
+            n if n < 0x80 => (2, n as usize, false),
+            n => {
+                let count = (n & 0x7F) as usize;
+                if count > 4 {
+                    return None;
+                }
+                let len = data
+                    .get(2..2 + count)?
+                    .iter()
+                    .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+                (2 + count, len, false)
+            }
+        };
+        let body_end = body_start.checked_add(body_len)?;
+        let body = data.get(body_start..body_end)?;
+        let len = body_end + if indefinite { 2 } else { 0 };
+        Some(Tlv {
+            tag,
+            body,
+            indefinite,
+            len,
+            encoded: &data[..len],
+        })
+    }
+
+    /// 含标签和长度的完整编码，签名校验时需要原样的字节
+    #[cfg_attr(not(feature = "smime-verify"), allow(dead_code))]
+    pub(crate) fn encoded(&self) -> &'a [u8] {
+        self.encoded
+    }
+
+    pub(crate) fn expect(self, tag: u8) -> Option<Self> {
+        (self.tag == tag).then_some(self)
+    }
+
+    pub(crate) fn children(&self) -> impl Iterator<Item = Tlv<'a>> {
+        let mut rest = self.body;
+        std::iter::from_fn(move || {
+            let tlv = Tlv::read(rest)?;
+            rest = &rest[tlv.len..];
+            Some(tlv)
+        })
+    }
+
+    /// OCTET STRING 的内容，分段编码时拼接各段
+    pub(crate) fn octets(&self) -> Option<Vec<u8>> {
+        self.octets_nested(0)
+    }
+
+    fn octets_nested(&self, depth: usize) -> Option<Vec<u8>> {
+        match self.tag {
+            TAG_OCTET_STRING if !self.indefinite => Some(self.body.to_vec()),
+            TAG_OCTET_STRING_CONSTRUCTED if depth < MAX_DEPTH => self
+                .children()
+                .map(|part| part.octets_nested(depth + 1))
+                .collect::<Option<Vec<_>>>()
+                .map(|parts| parts.concat()),
+            _ => None,
+        }
+    }
+}
+
+/// 把 OID 编码转换为点分形式
+pub(crate) fn format_oid(data: &[u8]) -> String {
+    let mut parts = Vec::new();
+    let mut value: u64 = 0;
+    for &b in data {
+        value = (value << 7) | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            if parts.is_empty() {
+                let first = (value / 40).min(2);
+                parts.push(first);
+                parts.push(value - first * 40);
+            } else {
+                parts.push(value);
+            }
+            value = 0;
+        }
+    }
+    parts
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 跳过 MIME 头，把正文按 base64 解码
+pub(crate) fn decode_mime_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(data).ok()?;
+    let body = text
+        .split_once("\r\n\r\n")
+        .or_else(|| text.split_once("\n\n"))
+        .map_or(text, |(_, body)| body);
+
+    eml::decode_base64(body).filter(|out| !out.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indefinite_length() {
+        let data = [0x30, 0x80, 0x04, 0x01, 0xAA, 0x00, 0x00];
+        let tlv = Tlv::read(&data).unwrap();
+        assert_eq!(tlv.body, [0x04, 0x01, 0xAA]);
+        assert_eq!(tlv.children().next().unwrap().octets().unwrap(), [0xAA]);
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let nested = |open: &[u8], depth: usize| {
+            let mut data = open.repeat(depth);
+            data.extend([0, 0].repeat(depth));
+            data
+        };
+        assert!(Tlv::read(&nested(&[0x30, 0x80], 100_000)).is_none());
+        assert!(Tlv::read(&nested(&[0x30, 0x80], 10)).is_some());
+
+        let octets = nested(&[TAG_OCTET_STRING_CONSTRUCTED, 0x80], 100_000);
+        assert!(Tlv::read(&octets).is_none());
+    }
+}
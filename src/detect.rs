@@ -0,0 +1,121 @@
+//! 复合文档格式识别
+//!
+//! .doc/.xls/.ppt/.vsd/.msi 与 .msg 一样都是 CFB 复合文档，`CompoundFile::open` 能成功打开，
+//! 但解析结果会是一封空邮件。这里根据根存储下的特征流和 CLSID 判断文件的真实格式，
+//! 以便给用户一个明确的错误提示。
+
+use crate::MsgParseError;
+use cfb::CompoundFile;
+use std::io::{Cursor, Read, Seek};
+
+/// CFB 复合文档的文件头签名
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Outlook 模板 (.oft) 的根 CLSID，普通 .msg 为 00020d0b-0000-0000-c000-000000000046
+const CLSID_OUTLOOK_TEMPLATE: &str = "0006f046-0000-0000-c000-000000000046";
+/// Windows Installer 包的根 CLSID
+const CLSID_MSI: &str = "000c1084-0000-0000-c000-000000000046";
+/// Word 97-2003 文档的根 CLSID
+const CLSID_WORD: &str = "00020906-0000-0000-c000-000000000046";
+/// Excel 97-2003 工作簿的根 CLSID
+const CLSID_EXCEL: &str = "00020820-0000-0000-c000-000000000046";
+/// PowerPoint 97-2003 演示文稿的根 CLSID
+const CLSID_POWERPOINT: &str = "64818d10-4f9b-11cf-86ea-00aa00b929e8";
+
+/// 打开 MSG 文件，区分"不是复合文档"、"复合文档已损坏"和"不是 Outlook 邮件"三种情况
+pub(crate) fn open_msg(file_data: &[u8]) -> Result<CompoundFile<Cursor<&[u8]>>, MsgParseError> {
+    if !file_data.starts_with(&CFB_SIGNATURE) {
+        return Err(MsgParseError::NotCfbFile);
+    }
+    let comp =
+        CompoundFile::open(Cursor::new(file_data)).map_err(|e| MsgParseError::CorruptFile {
+            reason: e.to_string(),
+        })?;
+    if !is_msg(&comp) {
+        return Err(MsgParseError::NotMsgFile {
+            format: identify_format(&comp),
+        });
+    }
+    Ok(comp)
+}
+
+/// 根存储下是否存在 MSG 特有的属性流
+pub(crate) fn is_msg<R: Read + Seek>(comp: &CompoundFile<R>) -> bool {
+    comp.read_root_storage().any(|entry| {
+        // 第三方生成器可能写出大小写不规范的名称，这里统一按小写比较
+        let name = entry.name().to_ascii_lowercase();
+        name.starts_with("__substg1.0_")
+            || name == "__properties_version1.0"
+            || name.starts_with("__attach_version1.0_")
+            || name.starts_with("__recip_version1.0_")
+    })
+}
+
+/// 是否为 Outlook 模板 (.oft) 文件
+pub(crate) fn is_template<R: Read + Seek>(comp: &CompoundFile<R>) -> bool {
+    comp.root_entry().clsid().to_string() == CLSID_OUTLOOK_TEMPLATE
+}
+
+/// 识别非 MSG 复合文档的实际格式，返回便于展示的格式名称
+pub(crate) fn identify_format<R: Read + Seek>(comp: &CompoundFile<R>) -> &'static str {
+    let names: Vec<String> = comp
+        .read_root_storage()
+        .map(|entry| entry.name().to_string())
+        .collect();
+    let has = |stream: &str| names.iter().any(|n| n == stream);
+
+    if has("EncryptedPackage") {
+        return "加密的 Office 文档";
+    }
+    if has("WordDocument") {
+        return "Word 文档";
+    }
+    if has("Workbook") || has("Book") {
+        return "Excel 工作簿";
+    }
+    if has("PowerPoint Document") {
+        return "PowerPoint 演示文稿";
+    }
+    if has("VisioDocument") {
+        return "Visio 绘图";
+    }
+    if has("Catalog") {
+        return "Thumbs.db 缩略图缓存";
+    }
+
+    match comp.root_entry().clsid().to_string().as_str() {
+        CLSID_MSI => "Windows Installer 安装包",
+        CLSID_WORD => "Word 文档",
+        CLSID_EXCEL => "Excel 工作簿",
+        CLSID_POWERPOINT => "PowerPoint 演示文稿",
+        _ => "未知格式的复合文档",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn build(streams: &[&str]) -> CompoundFile<Cursor<Vec<u8>>> {
+        let mut comp = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        for name in streams {
+            let mut stream = comp.create_stream(format!("/{}", name)).unwrap();
+            stream.write_all(b"data").unwrap();
+        }
+        comp
+    }
+
+    #[test]
+    fn test_identify_word_document() {
+        let comp = build(&["WordDocument", "1Table"]);
+        assert!(!is_msg(&comp));
+        assert_eq!(identify_format(&comp), "Word 文档");
+    }
+
+    #[test]
+    fn test_msg_is_recognized() {
+        let comp = build(&["__substg1.0_0037001F", "__properties_version1.0"]);
+        assert!(is_msg(&comp));
+    }
+}
@@ -0,0 +1,59 @@
+//! 正文书写方向检测
+
+use serde::Serialize;
+
+/// 正文的主要书写方向，渲染时可据此设置 `dir` 属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+/// 是否为从右到左书写的字符（希伯来文、阿拉伯文及其表现形式）
+fn is_rtl_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+/// 统计有方向的字母，从右到左的字母占多数时返回 `Rtl`；HTML 标签内的文本不计入
+pub(crate) fn detect_direction(text: &str) -> Option<TextDirection> {
+    let (mut rtl, mut ltr) = (0usize, 0usize);
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if in_tag || !c.is_alphabetic() => {}
+            _ if is_rtl_char(c) => rtl += 1,
+            _ => ltr += 1,
+        }
+    }
+
+    match (rtl, ltr) {
+        (0, 0) => None,
+        (rtl, ltr) if rtl > ltr => Some(TextDirection::Rtl),
+        _ => Some(TextDirection::Ltr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_direction() {
+        assert_eq!(
+            detect_direction("שלום, מה שלומך? OK"),
+            Some(TextDirection::Rtl)
+        );
+        assert_eq!(
+            detect_direction("<p class=\"x\">مرحبا بالعالم</p>"),
+            Some(TextDirection::Rtl)
+        );
+        assert_eq!(detect_direction("Hello שלום"), Some(TextDirection::Ltr));
+        assert_eq!(detect_direction("12345 !!"), None);
+    }
+}
@@ -0,0 +1,672 @@
+//! RFC 5322 / MIME 邮件生成
+//!
+//! 根据解析结果拼出一封标准 MIME 邮件：文本和 HTML 正文放在 multipart/alternative 中，
+//! 附件以 base64 编码附在 multipart/mixed 之后；HTML 中以 `cid:` 引用的内嵌图片带上
+//! Content-ID，与 HTML 一起放在 multipart/related 中。所有正文统一转为 UTF-8。
+
+use crate::{datetime, parse_msg_to_struct, Address, Attachment, MsgEmail, MsgParseError};
+use wasm_bindgen::prelude::*;
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// WASM 导出接口
+/// 把 MSG 文件转为 .eml（RFC 5322 / MIME 邮件）字节
+#[wasm_bindgen]
+pub fn convert_msg_to_eml(file_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    msg_to_eml(file_data).map_err(|e| e.to_js())
+}
+
+/// 把 MSG 文件转为 .eml 字节，见 [`convert_msg_to_eml`]
+pub fn msg_to_eml(file_data: &[u8]) -> Result<Vec<u8>, MsgParseError> {
+    Ok(build_eml(&parse_msg_to_struct(file_data)?))
+}
+
+/// 生成完整的 .eml 字节
+pub(crate) fn build_eml(email: &MsgEmail) -> Vec<u8> {
+    build_message(email, 0)
+}
+
+/// 生成一封邮件，`depth` 为作为 message/rfc822 嵌套的层数，用于区分各层的边界
+fn build_message(email: &MsgEmail, depth: usize) -> Vec<u8> {
+    let mut out = String::new();
+
+    let from = format_sender(email);
+    // 代理发送 (RFC 5322 §3.6.2)：From 为被代表者，Sender 为实际发送的代理人
+    match (&email.on_behalf_of, &email.sender) {
+        (Some(principal), Some(sender)) => {
+            push_header(
+                &mut out,
+                "From",
+                &format_address_list(std::slice::from_ref(principal)),
+            );
+            push_header(
+                &mut out,
+                "Sender",
+                &format_address_list(std::slice::from_ref(sender)),
+            );
+        }
+        _ => {
+            if let Some(from) = &from {
+                push_header(&mut out, "From", from);
+            }
+        }
+    }
+    if !email.recipients.is_empty() {
+        push_header(&mut out, "To", &format_address_list(&email.recipients));
+    }
+    if !email.cc_recipients.is_empty() {
+        push_header(&mut out, "Cc", &format_address_list(&email.cc_recipients));
+    }
+    // 草稿转出的 .eml 重新发送时仍需保留密送人
+    if !email.bcc_recipients.is_empty() {
+        push_header(&mut out, "Bcc", &format_address_list(&email.bcc_recipients));
+    }
+    if !email.reply_to.is_empty() {
+        push_header(&mut out, "Reply-To", &email.reply_to.join(", "));
+    }
+    if let Some(subject) = &email.subject {
+        push_header(&mut out, "Subject", &encode_word(subject));
+    }
+    if let Some(date) = email.sent_time.as_deref().and_then(rfc2822_date) {
+        push_header(&mut out, "Date", &date);
+    }
+    if let Some(id) = &email.message_id {
+        push_header(&mut out, "Message-ID", id);
+    }
+    if let Some(id) = &email.in_reply_to {
+        push_header(&mut out, "In-Reply-To", id);
+    }
+    if !email.references.is_empty() {
+        push_header(&mut out, "References", &email.references.join(" "));
+    }
+    // 已读回执请求 (RFC 8098)：沿用原传输头中的地址，只有 MAPI 标志时回执发给发件人
+    if !email.disposition_notification_to.is_empty() {
+        push_header(
+            &mut out,
+            "Disposition-Notification-To",
+            &format_address_list(&email.disposition_notification_to),
+        );
+    } else if let Some(from) = from.as_deref().filter(|_| email.read_receipt_requested) {
+        push_header(&mut out, "Disposition-Notification-To", from);
+    }
+    if !email.return_receipt_to.is_empty() {
+        push_header(
+            &mut out,
+            "Return-Receipt-To",
+            &format_address_list(&email.return_receipt_to),
+        );
+    }
+    push_header(&mut out, "MIME-Version", "1.0");
+
+    let body = build_body(email, depth);
+    out.push_str(&body);
+    out.into_bytes()
+}
+
+/// 生成正文部分（含顶层 Content-Type 头）
+fn build_body(email: &MsgEmail, depth: usize) -> String {
+    // 每层邮件最多三层 multipart，嵌入消息的边界不能与外层相同 (RFC 2046 §5.1.1)
+    let level = |n: usize| depth * 3 + n;
+    // HTML 中以 cid: 引用的附件作为内嵌部分，与 HTML 一起放进 multipart/related
+    let (inline, attached): (Vec<&Attachment>, Vec<&Attachment>) =
+        email.attachments.iter().partition(|attachment| {
+            attachment.embedded_message.is_none()
+                && email
+                    .body_html
+                    .as_deref()
+                    .is_some_and(|html| is_referenced(html, attachment))
+        });
+
+    let plain_part = email
+        .body_text
+        .as_deref()
+        .map(|text| text_part("text/plain", text));
+    let html_part = email.body_html.as_deref().map(|html| {
+        let html = text_part("text/html", html);
+        if inline.is_empty() {
+            return html;
+        }
+        let mut parts = vec![html];
+        parts.extend(inline.iter().map(|a| attachment_part(a, "inline")));
+        multipart("related", &parts, level(2))
+    });
+
+    let content = match (plain_part, html_part) {
+        (Some(text), Some(html)) => multipart("alternative", &[text, html], level(1)),
+        (Some(part), None) | (None, Some(part)) => part,
+        (None, None) => text_part("text/plain", ""),
+    };
+
+    if attached.is_empty() {
+        return content;
+    }
+
+    let mut parts = vec![content];
+    for attachment in attached {
+        // 嵌入消息转为 message/rfc822 部分；生成的邮件全为 ASCII，按 RFC 2046 不再做 base64
+        if let Some(embedded) = &attachment.embedded_message {
+            let mut part = String::new();
+            push_header(&mut part, "Content-Type", "message/rfc822");
+            push_header(
+                &mut part,
+                "Content-Disposition",
+                &format!(
+                    "attachment; {}",
+                    mime_param("filename", &format!("{}.eml", attachment.filename))
+                ),
+            );
+            part.push_str("\r\n");
+            part.push_str(&String::from_utf8_lossy(&build_message(
+                embedded,
+                depth + 1,
+            )));
+            parts.push(part);
+            continue;
+        }
+        parts.push(attachment_part(attachment, "attachment"));
+    }
+    multipart("mixed", &parts, level(0))
+}
+
+/// 附件部分，`disposition` 为 `attachment` 或 `inline`
+fn attachment_part(attachment: &Attachment, disposition: &str) -> String {
+    let content_type = attachment
+        .content_type
+        .as_deref()
+        .filter(|content_type| is_mime_type(content_type))
+        .unwrap_or("application/octet-stream");
+    let mut part = String::new();
+    push_header(
+        &mut part,
+        "Content-Type",
+        &format!(
+            "{}; {}",
+            content_type,
+            mime_param("name", &attachment.filename)
+        ),
+    );
+    push_header(&mut part, "Content-Transfer-Encoding", "base64");
+    if let Some(id) = content_id(attachment) {
+        push_header(&mut part, "Content-ID", &format!("<{}>", id));
+    }
+    push_header(
+        &mut part,
+        "Content-Disposition",
+        &format!(
+            "{}; {}",
+            disposition,
+            mime_param("filename", &attachment.filename)
+        ),
+    );
+    part.push_str("\r\n");
+    part.push_str(&base64_lines(&attachment.data));
+    part
+}
+
+/// 形如 `type/subtype` 且不含参数、空白和控制字符的 MIME 类型
+fn is_mime_type(value: &str) -> bool {
+    value.split_once('/').is_some_and(|(kind, subtype)| {
+        !kind.is_empty()
+            && !subtype.is_empty()
+            && value
+                .bytes()
+                .all(|b| b.is_ascii_graphic() && !b"\"\\;()<>@,:[]?=".contains(&b))
+    })
+}
+
+/// MIME 参数：可打印 ASCII 放在引号中并转义，否则按 RFC 2231 编码为 `name*=UTF-8''...`
+fn mime_param(name: &str, value: &str) -> String {
+    if value.bytes().all(|b| b == b' ' || b.is_ascii_graphic()) {
+        return format!(
+            "{}=\"{}\"",
+            name,
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+    }
+    let mut encoded = String::new();
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    format!("{}*=UTF-8''{}", name, encoded)
+}
+
+/// 去掉尖括号后的 Content-ID
+fn content_id(attachment: &Attachment) -> Option<&str> {
+    attachment
+        .content_id
+        .as_deref()
+        .map(|id| id.trim().trim_matches(['<', '>']))
+        .filter(|id| !id.is_empty())
+}
+
+/// HTML 中是否有 `cid:` 引用该附件
+fn is_referenced(html: &str, attachment: &Attachment) -> bool {
+    let Some(id) = content_id(attachment) else {
+        return false;
+    };
+    let needle = format!("cid:{}", id.to_ascii_lowercase());
+    html.to_ascii_lowercase().contains(&needle)
+}
+
+/// 单个 UTF-8 文本部分，使用 base64 以避免长行和 8bit 传输问题
+fn text_part(mime: &str, text: &str) -> String {
+    let mut part = String::new();
+    push_header(
+        &mut part,
+        "Content-Type",
+        &format!("{}; charset=utf-8", mime),
+    );
+    push_header(&mut part, "Content-Transfer-Encoding", "base64");
+    part.push_str("\r\n");
+    part.push_str(&base64_lines(text.as_bytes()));
+    part
+}
+
+/// 组装 multipart，`level` 用于生成互不相同的边界
+fn multipart(subtype: &str, parts: &[String], level: usize) -> String {
+    let boundary = format!("----=_MsgParser_{}_{}", subtype, level);
+    let mut out = String::new();
+    push_header(
+        &mut out,
+        "Content-Type",
+        &format!("multipart/{}; boundary=\"{}\"", subtype, boundary),
+    );
+    out.push_str("\r\n");
+    for part in parts {
+        out.push_str(&format!("--{}\r\n", boundary));
+        out.push_str(part);
+        if !part.ends_with("\r\n") {
+            out.push_str("\r\n");
+        }
+    }
+    out.push_str(&format!("--{}--\r\n", boundary));
+    out
+}
+
+/// 写入一行头部。值中的 CR/LF 会开启新的头部行，替换为空格
+fn push_header(out: &mut String, name: &str, value: &str) {
+    out.push_str(name);
+    out.push_str(": ");
+    out.extend(
+        value
+            .chars()
+            .map(|c| if matches!(c, '\r' | '\n') { ' ' } else { c }),
+    );
+    out.push_str("\r\n");
+}
+
+/// 由发件人显示名和地址组成 From 头的值
+pub(crate) fn format_sender(email: &MsgEmail) -> Option<String> {
+    match (&email.sender_name, &email.sender_email) {
+        (Some(name), Some(addr)) if name != addr => {
+            Some(format!("{} <{}>", format_display_name(name), addr))
+        }
+        (_, Some(addr)) => Some(format!("<{}>", addr)),
+        (Some(name), None) => Some(format_display_name(name)),
+        (None, None) => None,
+    }
+}
+
+/// 地址部分原样保留，仅对显示名编码
+fn format_address_list(list: &[Address]) -> String {
+    list.iter()
+        .filter_map(|address| match (&address.name, &address.email) {
+            (Some(name), Some(addr)) if name != addr => {
+                Some(format!("{} <{}>", format_display_name(name), addr))
+            }
+            (_, Some(addr)) => Some(addr.clone()),
+            (Some(name), None) => Some(format_display_name(name)),
+            (None, None) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 显示名：非 ASCII 或含控制字符时使用 RFC 2047 编码，含特殊字符时加引号
+fn format_display_name(name: &str) -> String {
+    if !name.is_ascii() || has_control(name) {
+        return encode_word(name);
+    }
+    if name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c)) {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        name.to_string()
+    }
+}
+
+/// RFC 2047 B 编码，不含控制字符的纯 ASCII 原样返回
+pub(crate) fn encode_word(text: &str) -> String {
+    if text.is_ascii() && !text.contains("=?") && !has_control(text) {
+        return text.to_string();
+    }
+    format!("=?UTF-8?B?{}?=", base64(text.as_bytes()))
+}
+
+fn has_control(text: &str) -> bool {
+    text.chars().any(char::is_control)
+}
+
+pub(crate) fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        out.push(BASE64_CHARS[(n >> 18) as usize & 63] as char);
+        out.push(BASE64_CHARS[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            BASE64_CHARS[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            BASE64_CHARS[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// base64 解码，忽略空白，遇到 `=` 结束；含非法字符时返回 `None`
+pub(crate) fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            c if c.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// base64 编码并按 76 字符换行
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = base64(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 76 * 2 + 2);
+    for line in encoded.as_bytes().chunks(76) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// 把解析结果中的时间转换为 RFC 2822 日期
+///
+/// `sent_time` 通常是 RFC 3339 格式的 UTC 时间；Date 头无法识别时为原文，含逗号的原样使用。
+fn rfc2822_date(sent_time: &str) -> Option<String> {
+    match datetime::parse_rfc3339(sent_time) {
+        Some(unix) => Some(datetime::format_rfc2822(unix)),
+        None => sent_time.contains(',').then(|| sent_time.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(
+            decode_base64("Zm9v\r\nYmE=").as_deref(),
+            Some(&b"fooba"[..])
+        );
+        assert_eq!(decode_base64("Zm9v!"), None);
+    }
+
+    #[test]
+    fn test_rfc2822_date() {
+        assert_eq!(
+            rfc2822_date("2023-10-27T08:44:20Z").as_deref(),
+            Some("Fri, 27 Oct 2023 08:44:20 +0000")
+        );
+        assert_eq!(
+            rfc2822_date("Fri, 27 Oct 2023 08:44:20 +0800").as_deref(),
+            Some("Fri, 27 Oct 2023 08:44:20 +0800")
+        );
+    }
+
+    #[test]
+    fn test_build_eml_receipt_headers() {
+        let email = MsgEmail {
+            sender_email: Some("alice@example.com".to_string()),
+            read_receipt_requested: true,
+            disposition_notification_to: vec![Address::parse("receipts@example.com").unwrap()],
+            return_receipt_to: vec![Address::parse("postmaster@example.com").unwrap()],
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        assert!(eml.contains("Disposition-Notification-To: receipts@example.com\r\n"));
+        assert!(eml.contains("Return-Receipt-To: postmaster@example.com\r\n"));
+    }
+
+    #[test]
+    fn test_build_eml_on_behalf_of() {
+        let address = |name: &str, email: &str| Address {
+            name: Some(name.to_string()),
+            email: Some(email.to_string()),
+        };
+        let email = MsgEmail {
+            sender_name: Some("Assistant".to_string()),
+            sender_email: Some("assistant@example.com".to_string()),
+            sender: Some(address("Assistant", "assistant@example.com")),
+            on_behalf_of: Some(address("Boss", "boss@example.com")),
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        assert!(eml.contains("From: Boss <boss@example.com>\r\n"));
+        assert!(eml.contains("Sender: Assistant <assistant@example.com>\r\n"));
+    }
+
+    #[test]
+    fn test_build_eml_embedded_message() {
+        let email = MsgEmail {
+            subject: Some("Fwd: plan".to_string()),
+            attachments: vec![Attachment {
+                filename: "plan".to_string(),
+                embedded_message: Some(Box::new(MsgEmail {
+                    subject: Some("Plan".to_string()),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        assert!(eml.contains("Content-Type: message/rfc822\r\n"));
+        assert!(eml.contains("filename=\"plan.eml\""));
+        assert!(eml.contains("Subject: Plan\r\n"));
+    }
+
+    #[test]
+    fn test_build_eml_nested_boundaries() {
+        let attachment = |name: &str| Attachment {
+            filename: name.to_string(),
+            data: b"abc".to_vec(),
+            ..Default::default()
+        };
+        let email = MsgEmail {
+            body_text: Some("outer".to_string()),
+            body_html: Some("<p>outer</p>".to_string()),
+            attachments: vec![
+                attachment("a.txt"),
+                Attachment {
+                    filename: "inner".to_string(),
+                    embedded_message: Some(Box::new(MsgEmail {
+                        body_text: Some("inner".to_string()),
+                        body_html: Some("<p>inner</p>".to_string()),
+                        attachments: vec![attachment("b.txt")],
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        let boundaries: Vec<&str> = eml
+            .split("boundary=\"")
+            .skip(1)
+            .map(|rest| rest.split('"').next().unwrap())
+            .collect();
+        assert_eq!(boundaries.len(), 4);
+        for (i, boundary) in boundaries.iter().enumerate() {
+            assert!(!boundaries[i + 1..].contains(boundary));
+        }
+        // 外层 mixed 边界只出现在外层的分隔行上：开头、两个附件和结尾
+        assert_eq!(eml.matches(boundaries[0]).count(), 5);
+    }
+
+    #[test]
+    fn test_build_eml_structure() {
+        let email = MsgEmail {
+            subject: Some("周报".to_string()),
+            sender_name: Some("Alice".to_string()),
+            sender_email: Some("alice@example.com".to_string()),
+            recipients: vec![Address::parse("bob@example.com").unwrap()],
+            body_text: Some("hi".to_string()),
+            body_html: Some("<p>hi</p>".to_string()),
+            read_receipt_requested: true,
+            attachments: vec![Attachment {
+                filename: "a.txt".to_string(),
+                data: b"abc".to_vec(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+
+        assert!(eml.contains("From: Alice <alice@example.com>\r\n"));
+        assert!(eml.contains("Subject: =?UTF-8?B?5ZGo5oql?=\r\n"));
+        assert!(eml.contains("Disposition-Notification-To: Alice <alice@example.com>\r\n"));
+        assert!(eml.contains("multipart/mixed"));
+        assert!(eml.contains("multipart/alternative"));
+        assert!(eml.contains("filename=\"a.txt\""));
+        assert!(eml.contains("YWJj\r\n"));
+    }
+
+    #[test]
+    fn test_build_eml_header_injection() {
+        let email = MsgEmail {
+            subject: Some("Outer\r\nBcc: attacker@evil.example".to_string()),
+            sender_name: Some("Eve\r\nX-Injected: 1".to_string()),
+            sender_email: Some("eve@example.com".to_string()),
+            message_id: Some("<id@x>\r\nX-Injected: 2".to_string()),
+            reply_to: vec!["a@x\nX-Injected: 3".to_string()],
+            attachments: vec![Attachment {
+                filename: "a\".txt\r\nX-Injected: 4".to_string(),
+                content_type: Some("text/plain\r\nX-Injected: 5".to_string()),
+                data: b"abc".to_vec(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        // 换行只出现在 CRLF 行尾
+        assert!(eml.split("\r\n").all(|line| !line.contains(['\r', '\n'])));
+        assert!(!eml.contains("\r\nBcc:"));
+        assert!(!eml.contains("\r\nX-Injected"));
+        assert!(eml.contains("Subject: =?UTF-8?B?"));
+        assert!(eml.contains("Content-Type: application/octet-stream; name*=UTF-8''a%22.txt%0D%0AX-Injected%3A%204\r\n"));
+
+        let email = MsgEmail {
+            attachments: vec![Attachment {
+                filename: "say \"hi\".txt".to_string(),
+                data: b"abc".to_vec(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        assert!(eml.contains("filename=\"say \\\"hi\\\".txt\""));
+    }
+
+    #[test]
+    fn test_build_eml_inline_images() {
+        let image = |id: &str| Attachment {
+            filename: format!("{}.png", id),
+            content_type: Some("image/png".to_string()),
+            content_id: Some(format!("<{}@01D9>", id)),
+            data: vec![1, 2, 3],
+            ..Default::default()
+        };
+        let email = MsgEmail {
+            body_text: Some("hi".to_string()),
+            body_html: Some("<img src=\"CID:logo@01D9\">".to_string()),
+            attachments: vec![image("logo"), image("photo")],
+            ..Default::default()
+        };
+
+        let eml = String::from_utf8(build_eml(&email)).unwrap();
+        // 被引用的 logo 在 multipart/related 内，photo 作为普通附件在其后
+        let related = eml.find("multipart/related").unwrap();
+        let logo = eml.find("Content-ID: <logo@01D9>\r\n").unwrap();
+        let related_end = eml.find("------=_MsgParser_related_2--").unwrap();
+        let photo = eml.find("Content-ID: <photo@01D9>\r\n").unwrap();
+        assert!(related < logo && logo < related_end && related_end < photo);
+        assert!(eml.contains("Content-Disposition: inline; filename=\"logo.png\""));
+        assert!(eml.contains("Content-Disposition: attachment; filename=\"photo.png\""));
+    }
+
+    #[test]
+    fn test_msg_to_eml() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let eml = String::from_utf8(msg_to_eml(data).unwrap()).unwrap();
+        assert!(eml.contains("Subject: Quarterly report\r\n"));
+        assert!(msg_to_eml(b"not a msg").is_err());
+    }
+
+    #[test]
+    fn test_msg_to_eml_header_injection() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let data = crate::tests::build_msg(&[
+            (
+                "/__substg1.0_0037001F",
+                utf16("Outer\r\nBcc: attacker@evil.example"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3707001F",
+                utf16("a.txt\r\nX-Injected: 1"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_37010102",
+                b"abc".to_vec(),
+            ),
+        ]);
+        let eml = String::from_utf8(msg_to_eml(&data).unwrap()).unwrap();
+        let headers: Vec<&str> = eml
+            .split("\r\n")
+            .filter(|line| line.contains(": "))
+            .map(|line| line.split(": ").next().unwrap())
+            .collect();
+        assert!(!headers.contains(&"Bcc"));
+        assert!(!headers.contains(&"X-Injected"));
+        assert!(eml.contains("filename*=UTF-8''a.txt%0D%0AX-Injected%3A%201"));
+    }
+}
@@ -0,0 +1,146 @@
+//! 条目标识 (ENTRYID) 解析，参见 [MS-OXCDATA] 2.2.4
+//!
+//! 迁移工具需要通过 PR_ENTRYID / PR_STORE_ENTRYID / PR_PARENT_ENTRYID 把 .msg 对应回
+//! Exchange 中的原始邮箱、文件夹和邮件。所有 ENTRYID 都以 4 字节标志和 16 字节提供程序 UID
+//! 开头，其后的内容由提供程序决定，这里识别 Exchange 的文件夹、消息和存储三种格式。
+
+use crate::props::{format_guid, to_hex};
+use serde::Serialize;
+
+/// 存储 ENTRYID 外层包装使用的提供程序 UID (MUIDStoreWrap)
+const MUID_STORE_WRAP: [u8; 16] = [
+    0x38, 0xA1, 0xBB, 0x10, 0x05, 0xE5, 0x10, 0x1A, 0xA1, 0xBB, 0x08, 0x00, 0x2B, 0x2A, 0x56, 0xC2,
+];
+
+/// 解析后的 ENTRYID
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EntryId {
+    /// 原始字节的十六进制表示
+    pub raw: String,
+    pub flags: u32,
+    /// 提供程序 UID（十六进制），对 Exchange 文件夹/消息而言即邮箱存储的 GUID
+    pub provider_uid: String,
+    /// 条目类型：private_folder、public_folder、private_message、public_message、store 或 unknown
+    pub kind: String,
+    /// 文件夹所在数据库 GUID
+    pub folder_database_guid: Option<String>,
+    /// 文件夹全局计数器（6 字节，十六进制）
+    pub folder_global_counter: Option<String>,
+    /// 消息所在数据库 GUID
+    pub message_database_guid: Option<String>,
+    /// 消息全局计数器（6 字节，十六进制）
+    pub message_global_counter: Option<String>,
+    /// 存储 ENTRYID 中的服务器短名
+    pub server: Option<String>,
+    /// 存储 ENTRYID 中的邮箱 DN
+    pub mailbox_dn: Option<String>,
+}
+
+/// 解析 ENTRYID，长度不足 20 字节时返回 `None`
+pub(crate) fn parse_entry_id(data: &[u8]) -> Option<EntryId> {
+    if data.len() < 20 {
+        return None;
+    }
+
+    let mut entry = EntryId {
+        raw: to_hex(data),
+        flags: u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        provider_uid: to_hex(&data[4..20]),
+        kind: "unknown".to_string(),
+        ..Default::default()
+    };
+
+    if data[4..20] == MUID_STORE_WRAP {
+        parse_store_entry_id(&mut entry, &data[20..]);
+        return Some(entry);
+    }
+
+    let object_type = data.get(20..22).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    match (object_type, data.len()) {
+        (Some(t @ (0x0001 | 0x0003)), 46) => {
+            entry.kind = if t == 0x0001 {
+                "private_folder"
+            } else {
+                "public_folder"
+            }
+            .to_string();
+            entry.folder_database_guid = Some(format_guid(&data[22..38]));
+            entry.folder_global_counter = Some(to_hex(&data[38..44]));
+        }
+        (Some(t @ (0x0007 | 0x0009)), 70) => {
+            entry.kind = if t == 0x0007 {
+                "private_message"
+            } else {
+                "public_message"
+            }
+            .to_string();
+            entry.folder_database_guid = Some(format_guid(&data[22..38]));
+            entry.folder_global_counter = Some(to_hex(&data[38..44]));
+            entry.message_database_guid = Some(format_guid(&data[46..62]));
+            entry.message_global_counter = Some(to_hex(&data[62..68]));
+        }
+        _ => {}
+    }
+
+    Some(entry)
+}
+
+/// 存储 ENTRYID：版本(1) + 标志(1) + DLL 名(14) + 包装标志(4) + 包装 UID(16) + 包装类型(4)
+/// + 服务器短名 + 邮箱 DN（均为以 NUL 结尾的 ASCII）
+fn parse_store_entry_id(entry: &mut EntryId, rest: &[u8]) {
+    entry.kind = "store".to_string();
+    let Some(strings) = rest.get(2 + 14 + 4 + 16 + 4..) else {
+        return;
+    };
+    let mut parts = strings.split(|&b| b == 0);
+    entry.server = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned());
+    entry.mailbox_dn = parts
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_message_entry_id() {
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(&[0x11; 16]);
+        data.extend_from_slice(&0x0007u16.to_le_bytes());
+        data.extend_from_slice(&[0x22; 16]);
+        data.extend_from_slice(&[0, 0, 0, 0, 0x01, 0x02, 0, 0]);
+        data.extend_from_slice(&[0x33; 16]);
+        data.extend_from_slice(&[0, 0, 0, 0, 0x0A, 0x0B, 0, 0]);
+
+        let entry = parse_entry_id(&data).unwrap();
+
+        assert_eq!(entry.kind, "private_message");
+        assert_eq!(entry.provider_uid, "11".repeat(16));
+        assert_eq!(entry.folder_global_counter.as_deref(), Some("000000000102"));
+        assert_eq!(
+            entry.message_global_counter.as_deref(),
+            Some("000000000A0B")
+        );
+    }
+
+    #[test]
+    fn test_parse_store_entry_id() {
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(&MUID_STORE_WRAP);
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(b"emsmdb.dll\0\0\0\0");
+        data.extend_from_slice(&[0; 4 + 16 + 4]);
+        data.extend_from_slice(b"mail01\0/o=Contoso/cn=alice\0");
+
+        let entry = parse_entry_id(&data).unwrap();
+
+        assert_eq!(entry.kind, "store");
+        assert_eq!(entry.server.as_deref(), Some("mail01"));
+        assert_eq!(entry.mailbox_dn.as_deref(), Some("/o=Contoso/cn=alice"));
+    }
+}
@@ -0,0 +1,269 @@
+//! SAX 风格的事件解析器
+//!
+//! [`MsgEventReader`] 按需逐个产出事件，不会构造完整的 [`MsgEmail`]：附件数据按固定大小
+//! 分块读取，每次只持有一个块，适合内存受限的环境处理超大邮件。
+
+use crate::{
+    apply_property, detect, locale, parse_property, props, quirks, read_attachment_entry, MsgEmail,
+    MsgParseError,
+};
+use cfb::CompoundFile;
+use std::collections::VecDeque;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+/// 附件数据分块大小
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 解析过程中产生的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum MsgEvent {
+    Subject(String),
+    SenderName(String),
+    SenderEmail(String),
+    /// PR_DISPLAY_TO 等显示字符串按分号拆分后的一项
+    Recipient(String),
+    CcRecipient(String),
+    SentTime(String),
+    BodyText(String),
+    BodyHtml(String),
+    BodyRtf(String),
+    /// 附件开始，`size` 为二进制数据总长度
+    AttachmentStart {
+        index: usize,
+        filename: String,
+        content_type: Option<String>,
+        content_id: Option<String>,
+        size: u64,
+    },
+    /// 附件数据块，按顺序拼接即为完整数据
+    AttachmentChunk {
+        index: usize,
+        data: Vec<u8>,
+    },
+    AttachmentEnd {
+        index: usize,
+    },
+}
+
+/// 待处理的工作项
+enum Step {
+    Stream(String, PathBuf),
+    Attachment(PathBuf),
+    Chunk {
+        index: usize,
+        path: PathBuf,
+        offset: u64,
+        len: u64,
+    },
+}
+
+/// 拉取式事件解析器
+pub struct MsgEventReader<'a> {
+    comp: CompoundFile<Cursor<&'a [u8]>>,
+    quirks: quirks::Quirks,
+    steps: VecDeque<Step>,
+    pending: VecDeque<MsgEvent>,
+    attachment_index: usize,
+    /// 消息声明的字符集，用于解码文本属性流
+    charsets: locale::Charsets,
+}
+
+impl<'a> MsgEventReader<'a> {
+    /// 打开 MSG 文件并规划遍历顺序：先顶层属性，再逐个附件
+    pub fn new(file_data: &'a [u8]) -> Result<Self, MsgParseError> {
+        let mut comp = detect::open_msg(file_data)?;
+
+        let mut quirks = quirks::Quirks::new(true);
+        let mut steps = VecDeque::new();
+        let mut attachments = Vec::new();
+        for entry in comp.read_root_storage() {
+            let name = quirks.canonical_name(entry.name());
+            if name.starts_with("__substg1.0_") {
+                steps.push_back(Step::Stream(name, entry.path().to_path_buf()));
+            } else if name.starts_with("__attach_version1.0_") {
+                attachments.push(Step::Attachment(entry.path().to_path_buf()));
+            }
+        }
+        steps.extend(attachments);
+
+        // 定长属性（时间等）一次性转换为事件
+        let mut pending = VecDeque::new();
+        let data = props::read_stream(&mut comp, format!("/{}", props::PROPERTIES_STREAM))
+            .unwrap_or_default();
+        let header_len = quirks.property_header_len(data.len(), props::TOP_LEVEL_HEADER_LEN);
+        let mut scratch = MsgEmail::default();
+        let fixed = props::parse_property_stream(&data, header_len);
+        for prop in fixed.iter().filter(|prop| !prop.is_error()) {
+            if let Some(value) = props::decode_fixed(prop) {
+                apply_property(&mut scratch, &format!("{:04X}", prop.id()), &value);
+            }
+        }
+        drain_fields(scratch, &mut pending);
+        let mut store = props::PropertyStore::default();
+        store.set_fixed(fixed);
+
+        Ok(MsgEventReader {
+            comp,
+            quirks,
+            steps,
+            pending,
+            attachment_index: 0,
+            charsets: locale::Charsets::declared(&store),
+        })
+    }
+
+    /// 取下一个事件，全部处理完后返回 `None`
+    pub fn next_event(&mut self) -> Option<MsgEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let step = self.steps.pop_front()?;
+            self.run(step);
+        }
+    }
+
+    fn run(&mut self, step: Step) {
+        match step {
+            Step::Stream(name, path) => {
+                let data = props::read_stream(&mut self.comp, &path).unwrap_or_default();
+                if data.is_empty() {
+                    return;
+                }
+                let mut scratch = MsgEmail::default();
+                parse_property(&mut scratch, &name, &data, self.charsets);
+                drain_fields(scratch, &mut self.pending);
+            }
+            Step::Attachment(path) => {
+                let Ok(entry) = read_attachment_entry(&mut self.comp, &path, &mut self.quirks)
+                else {
+                    return;
+                };
+                let index = self.attachment_index;
+                self.attachment_index += 1;
+
+                self.pending.push_back(MsgEvent::AttachmentStart {
+                    index,
+                    filename: entry.attachment.filename,
+                    content_type: entry.attachment.content_type,
+                    content_id: entry.attachment.content_id,
+                    size: entry.data_len,
+                });
+                // OLE 对象只能整体转换为文件，转换后再分块产出
+                if let Some((storage, object)) = &entry.ole_object {
+                    if let Ok(data) = object.read(&mut self.comp, storage) {
+                        self.pending.extend(data.chunks(CHUNK_SIZE).map(|chunk| {
+                            MsgEvent::AttachmentChunk {
+                                index,
+                                data: chunk.to_vec(),
+                            }
+                        }));
+                    }
+                    self.pending.push_back(MsgEvent::AttachmentEnd { index });
+                    return;
+                }
+                match entry.data_path {
+                    Some(path) if entry.data_len > 0 => self.steps.push_front(Step::Chunk {
+                        index,
+                        path,
+                        offset: 0,
+                        len: entry.data_len,
+                    }),
+                    _ => self.pending.push_back(MsgEvent::AttachmentEnd { index }),
+                }
+            }
+            Step::Chunk {
+                index,
+                path,
+                offset,
+                len,
+            } => {
+                let size = (len - offset).min(CHUNK_SIZE as u64) as usize;
+                let mut data = vec![0u8; size];
+                let read = self.comp.open_stream(&path).and_then(|mut stream| {
+                    stream.seek(SeekFrom::Start(offset))?;
+                    stream.read_exact(&mut data)
+                });
+                if read.is_err() {
+                    self.pending.push_back(MsgEvent::AttachmentEnd { index });
+                    return;
+                }
+
+                self.pending
+                    .push_back(MsgEvent::AttachmentChunk { index, data });
+                let offset = offset + size as u64;
+                if offset < len {
+                    self.steps.push_front(Step::Chunk {
+                        index,
+                        path,
+                        offset,
+                        len,
+                    });
+                } else {
+                    self.pending.push_back(MsgEvent::AttachmentEnd { index });
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for MsgEventReader<'_> {
+    type Item = MsgEvent;
+
+    fn next(&mut self) -> Option<MsgEvent> {
+        self.next_event()
+    }
+}
+
+/// 把单个属性解码出的字段转换为事件
+fn drain_fields(email: MsgEmail, out: &mut VecDeque<MsgEvent>) {
+    out.extend(email.subject.map(MsgEvent::Subject));
+    out.extend(email.sender_name.map(MsgEvent::SenderName));
+    out.extend(email.sender_email.map(MsgEvent::SenderEmail));
+    out.extend(email.recipients_flat.into_iter().map(MsgEvent::Recipient));
+    out.extend(
+        email
+            .cc_recipients_flat
+            .into_iter()
+            .map(MsgEvent::CcRecipient),
+    );
+    out.extend(email.sent_time.map(MsgEvent::SentTime));
+    out.extend(email.body_text.map(MsgEvent::BodyText));
+    out.extend(email.body_html.map(MsgEvent::BodyHtml));
+    out.extend(email.body_rtf.map(MsgEvent::BodyRtf));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_stream() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let events: Vec<MsgEvent> = MsgEventReader::new(file_data).unwrap().collect();
+
+        assert!(events.iter().any(|e| matches!(e, MsgEvent::Subject(_))));
+
+        let start = events
+            .iter()
+            .position(|e| matches!(e, MsgEvent::AttachmentStart { .. }))
+            .unwrap();
+        let MsgEvent::AttachmentStart { size, .. } = &events[start] else {
+            unreachable!()
+        };
+        let data: Vec<u8> = events[start..]
+            .iter()
+            .filter_map(|e| match e {
+                MsgEvent::AttachmentChunk { data, .. } => Some(data.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(data.len() as u64, *size);
+        assert!(matches!(
+            events.last(),
+            Some(MsgEvent::AttachmentEnd { index: 0 })
+        ));
+    }
+}
@@ -0,0 +1,71 @@
+//! 按扩展名或 MIME 类型筛选附件
+//!
+//! 规则写法与 HTML `<input accept>` 一致：`.pdf` 匹配扩展名，`image/png` 匹配 MIME 类型，
+//! `image/*` 匹配整个大类，均不区分大小写。筛选在读取附件数据之前进行，
+//! 被排除的附件不会读取二进制数据。
+
+use crate::Attachment;
+
+/// 附件是否通过白名单和黑名单
+///
+/// 白名单为空表示不限制；同时命中两者时以黑名单为准。
+pub(crate) fn accepts(attachment: &Attachment, allow: &[String], deny: &[String]) -> bool {
+    let matches = |rule: &String| matches_rule(attachment, rule);
+    (allow.is_empty() || allow.iter().any(matches)) && !deny.iter().any(matches)
+}
+
+fn matches_rule(attachment: &Attachment, rule: &str) -> bool {
+    let rule = rule.trim().to_ascii_lowercase();
+    if let Some(ext) = rule.strip_prefix('.') {
+        return attachment
+            .filename
+            .rsplit_once('.')
+            .is_some_and(|(_, e)| e.eq_ignore_ascii_case(ext));
+    }
+
+    let Some(content_type) = &attachment.content_type else {
+        return false;
+    };
+    // 去掉 "; name=..." 之类的参数
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match rule.strip_suffix("/*") {
+        Some(major) => mime.split_once('/').is_some_and(|(m, _)| m == major),
+        None => mime == rule,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(filename: &str, content_type: Option<&str>) -> Attachment {
+        Attachment {
+            filename: filename.to_string(),
+            content_type: content_type.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accepts() {
+        let rules = |r: &[&str]| r.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let pdf = attachment("Report.PDF", Some("application/pdf"));
+        let logo = attachment("image001.png", Some("image/png"));
+
+        assert!(accepts(&pdf, &[], &[]));
+        assert!(accepts(&pdf, &rules(&[".pdf", ".docx"]), &[]));
+        assert!(!accepts(&logo, &rules(&[".pdf", ".docx"]), &[]));
+        assert!(!accepts(&logo, &[], &rules(&["image/*"])));
+        assert!(accepts(&pdf, &[], &rules(&["image/*"])));
+        assert!(!accepts(
+            &pdf,
+            &rules(&["application/pdf"]),
+            &rules(&[".pdf"])
+        ));
+    }
+}
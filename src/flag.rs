@@ -0,0 +1,102 @@
+//! 后续标记 (Follow-up flag)
+//!
+//! 标记状态和颜色是普通属性，标记文本、开始/截止日期和提醒则是命名属性。
+//! 截止日期与任务共用 PidLidTaskDueDate 等属性，邮件被标记后在 Outlook 的待办栏中显示为任务。
+
+use crate::named::{NameIdMap, PSETID_COMMON, PSETID_TASK};
+use crate::props::{PropertyStore, PropertyValue};
+use serde::Serialize;
+
+const PROP_FLAG_STATUS: u16 = 0x1090;
+const PROP_FLAG_COMPLETE_TIME: u16 = 0x1091;
+const PROP_FOLLOWUP_ICON: u16 = 0x1095;
+
+// PSETID_Task 下的 LID
+const LID_TASK_START_DATE: u32 = 0x8104;
+const LID_TASK_DUE_DATE: u32 = 0x8105;
+
+// PSETID_Common 下的 LID
+const LID_REMINDER_TIME: u32 = 0x8502;
+const LID_REMINDER_SET: u32 = 0x8503;
+
+/// 标记状态 (PR_FLAG_STATUS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagStatus {
+    Flagged,
+    Complete,
+}
+
+/// 标记颜色 (PR_FOLLOWUP_ICON)，Outlook 2003 之前的版本使用，新版本只有红色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagColor {
+    Purple,
+    Orange,
+    Green,
+    Yellow,
+    Blue,
+    Red,
+}
+
+/// 后续标记
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FollowUpFlag {
+    /// 标记状态，只有标记文本时为 `None`
+    pub status: Option<FlagStatus>,
+    /// 标记文本 (PidLidFlagRequest)，如 "Follow up"
+    pub request: Option<String>,
+    pub color: Option<FlagColor>,
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    /// 标记完成时间 (PR_FLAG_COMPLETE_TIME)
+    pub complete_time: Option<String>,
+    /// 提醒时间，未设置提醒时为 `None`
+    pub reminder_time: Option<String>,
+}
+
+/// 读取后续标记，`request` 为已读出的 PidLidFlagRequest；未标记时返回 `None`
+pub(crate) fn read_follow_up_flag(
+    store: &PropertyStore,
+    names: &NameIdMap,
+    request: Option<String>,
+) -> Option<FollowUpFlag> {
+    let status = match store.get(PROP_FLAG_STATUS).and_then(|v| v.as_i32()) {
+        Some(1) => Some(FlagStatus::Complete),
+        Some(2) => Some(FlagStatus::Flagged),
+        _ => None,
+    };
+    if status.is_none() && request.is_none() {
+        return None;
+    }
+    let text = |value: Option<PropertyValue>| {
+        value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+    let task = |lid| text(names.get_lid(store, PSETID_TASK, lid));
+    let reminder_set = names
+        .get_lid(store, PSETID_COMMON, LID_REMINDER_SET)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    Some(FollowUpFlag {
+        status,
+        request,
+        color: match store.get(PROP_FOLLOWUP_ICON).and_then(|v| v.as_i32()) {
+            Some(1) => Some(FlagColor::Purple),
+            Some(2) => Some(FlagColor::Orange),
+            Some(3) => Some(FlagColor::Green),
+            Some(4) => Some(FlagColor::Yellow),
+            Some(5) => Some(FlagColor::Blue),
+            Some(6) => Some(FlagColor::Red),
+            _ => None,
+        },
+        start_date: task(LID_TASK_START_DATE),
+        due_date: task(LID_TASK_DUE_DATE),
+        complete_time: text(store.get(PROP_FLAG_COMPLETE_TIME)),
+        reminder_time: reminder_set
+            .then(|| text(names.get_lid(store, PSETID_COMMON, LID_REMINDER_TIME)))
+            .flatten(),
+    })
+}
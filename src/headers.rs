@@ -0,0 +1,220 @@
+//! 只解析传输头
+//!
+//! 邮件安全类工具通常只关心 Received、Authentication-Results 等头部，
+//! 这里只读取根存储下的少数几个流，不碰正文、收件人和附件。
+
+use crate::{
+    apply_property, detect, locale, parse_property, props, quirks, stream_type, Address, MsgEmail,
+    MsgParseError, PROP_CLIENT_SUBMIT_TIME, TAG_CLIENT_SUBMIT_TIME, TAG_SENDER_EMAIL_1,
+    TAG_SENDER_EMAIL_2, TAG_SENDER_EMAIL_3, TAG_SENDER_NAME, TAG_SUBJECT, TAG_TRANSPORT_HEADERS,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+/// 传输头及基本信封信息
+#[derive(Debug, Default, Serialize)]
+pub struct MsgHeaders {
+    /// 传输头，键为小写头名，同名头（如 Received）按出现顺序保留全部值
+    pub headers: BTreeMap<String, Vec<String>>,
+    pub subject: Option<String>,
+    pub sender_name: Option<String>,
+    pub sender_email: Option<String>,
+    pub sent_time: Option<String>,
+}
+
+/// WASM 导出接口
+/// 只解析传输头和基本信封信息，开销远小于完整解析
+#[wasm_bindgen]
+pub fn parse_msg_headers(file_data: &[u8]) -> Result<JsValue, JsValue> {
+    let headers = parse_msg_headers_to_struct(file_data).map_err(|e| e.to_js())?;
+
+    // 头部映射序列化为普通对象而不是 Map，方便按 headers["received"] 访问
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    headers
+        .serialize(&serializer)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 只解析传输头和基本信封信息，见 [`parse_msg_headers`]
+pub fn parse_msg_headers_to_struct(file_data: &[u8]) -> Result<MsgHeaders, MsgParseError> {
+    let mut comp = detect::open_msg(file_data)?;
+
+    let mut quirks = quirks::Quirks::new(true);
+    let wanted = [
+        TAG_SUBJECT,
+        TAG_SENDER_NAME,
+        TAG_SENDER_EMAIL_1,
+        TAG_SENDER_EMAIL_2,
+        TAG_SENDER_EMAIL_3,
+        TAG_TRANSPORT_HEADERS,
+    ];
+    let streams: Vec<_> = comp
+        .read_root_storage()
+        .filter(|entry| entry.is_stream())
+        .filter_map(|entry| {
+            let name = quirks.canonical_name(entry.name());
+            let tag = name.strip_prefix("__substg1.0_")?.get(..4)?;
+            wanted
+                .contains(&tag)
+                .then(|| (name.clone(), entry.path().to_path_buf()))
+        })
+        .collect();
+
+    let mut email = MsgEmail::default();
+
+    // 提交时间只存放在属性流中
+    let data =
+        props::read_stream(&mut comp, format!("/{}", props::PROPERTIES_STREAM)).unwrap_or_default();
+    let header_len = quirks.property_header_len(data.len(), props::TOP_LEVEL_HEADER_LEN);
+    let mut store = props::PropertyStore::default();
+    store.set_fixed(props::parse_property_stream(&data, header_len));
+    if let Some(value) = store.get(PROP_CLIENT_SUBMIT_TIME) {
+        apply_property(&mut email, TAG_CLIENT_SUBMIT_TIME, &value);
+    }
+    let charsets = locale::Charsets::declared(&store);
+
+    let mut headers = BTreeMap::new();
+    for (name, path) in streams {
+        let data = props::read_stream(&mut comp, &path)
+            .map_err(|e| MsgParseError::corrupt_stream(&path, e))?;
+        if name.get(12..16) == Some(TAG_TRANSPORT_HEADERS) {
+            let value = stream_type(&name)
+                .and_then(|prop_type| props::decode_stream(prop_type, &data, charsets.string8));
+            if let Some(text) = value.as_ref().and_then(|v| v.as_str()) {
+                headers = parse_header_block(text);
+            }
+        }
+        parse_property(&mut email, &name, &data, charsets);
+    }
+
+    Ok(MsgHeaders {
+        headers,
+        subject: email.subject,
+        sender_name: email.sender_name,
+        sender_email: email.sender_email,
+        sent_time: email.sent_time,
+    })
+}
+
+/// 解析 RFC 5322 头部块，键为小写头名，同名头按出现顺序保留全部值
+pub(crate) fn parse_header_block(text: &str) -> BTreeMap<String, Vec<String>> {
+    let mut headers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in header_fields(text) {
+        headers
+            .entry(name.to_ascii_lowercase())
+            .or_default()
+            .push(value);
+    }
+    headers
+}
+
+/// 按出现顺序列出头部块中的各个头：展开折行，遇到空行（正文开始）即停止
+pub(crate) fn header_fields(text: &str) -> Vec<(String, String)> {
+    let mut headers = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = &mut current {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        headers.extend(current.take());
+        if let Some((name, value)) = line.split_once(':') {
+            current = Some((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    headers.extend(current);
+
+    headers
+}
+
+/// 从传输头中读取回执请求：Disposition-Notification-To（RFC 8098 已读回执）和
+/// 非标准但仍常见的 Return-Receipt-To（投递回执）
+pub(crate) fn read_receipt_headers(email: &mut MsgEmail, headers: &BTreeMap<String, Vec<String>>) {
+    let addresses = |name: &str| -> Vec<Address> {
+        headers
+            .get(name)
+            .into_iter()
+            .flatten()
+            .flat_map(|value| parse_address_list(value))
+            .collect()
+    };
+    email.disposition_notification_to = addresses("disposition-notification-to");
+    email.return_receipt_to = addresses("return-receipt-to");
+}
+
+/// 按逗号拆分地址列表，引号内和尖括号内的逗号不拆分
+pub(crate) fn parse_address_list(value: &str) -> Vec<Address> {
+    let mut addresses = Vec::new();
+    let mut in_quotes = false;
+    let mut in_angle = false;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '<' if !in_quotes => in_angle = true,
+            '>' if !in_quotes => in_angle = false,
+            ',' if !in_quotes && !in_angle => {
+                addresses.extend(Address::parse(&value[start..i]));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    addresses.extend(Address::parse(&value[start..]));
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_block() {
+        let headers = parse_header_block(
+            "Received: from a\r\n\tby b\r\nReceived: from c\r\nSubject: Hi\r\n\r\nbody: no\r\n",
+        );
+        assert_eq!(headers["received"], vec!["from a by b", "from c"]);
+        assert_eq!(headers["subject"], vec!["Hi"]);
+        assert!(!headers.contains_key("body"));
+    }
+
+    #[test]
+    fn test_read_receipt_headers() {
+        let mut email = MsgEmail::default();
+        read_receipt_headers(
+            &mut email,
+            &parse_header_block("Subject: Hi\r\nDisposition-Notification-To: \"Smith, Alice\" <alice@example.com>,\r\n bob@example.com\r\nReturn-Receipt-To: <postmaster@example.com>\r\n"),
+        );
+        assert_eq!(email.disposition_notification_to.len(), 2);
+        assert_eq!(
+            email.disposition_notification_to[0].name.as_deref(),
+            Some("Smith, Alice")
+        );
+        assert_eq!(
+            email.disposition_notification_to[1].email.as_deref(),
+            Some("bob@example.com")
+        );
+        assert_eq!(
+            email.return_receipt_to[0].email.as_deref(),
+            Some("postmaster@example.com")
+        );
+    }
+
+    #[test]
+    fn test_parse_msg_headers() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let headers = parse_msg_headers_to_struct(data).unwrap();
+
+        assert_eq!(headers.subject.as_deref(), Some("Quarterly report"));
+        assert_eq!(headers.sender_email.as_deref(), Some("alice@example.com"));
+        assert!(!headers.headers.is_empty());
+    }
+}
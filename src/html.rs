@@ -0,0 +1,85 @@
+//! HTML 正文处理
+
+/// 把 HTML 中声明的字符集统一改为 UTF-8，没有声明时注入 `<meta charset="utf-8">`
+///
+/// 正文在解析时已经转码为 UTF-8，若仍保留原来的 `charset=gb2312` 之类的声明，
+/// 放进 iframe（尤其是以 Blob 形式加载）时浏览器会按旧字符集再解码一次，出现乱码。
+pub(crate) fn force_utf8_charset(html: &str) -> String {
+    // 只做 ASCII 小写转换，字节偏移与原文一致
+    let lower = html.to_ascii_lowercase();
+    let mut result = String::with_capacity(html.len() + 32);
+    let mut copied = 0;
+    let mut found = false;
+
+    let mut search = 0;
+    while let Some(offset) = lower[search..].find("<meta") {
+        let tag_start = search + offset;
+        let Some(tag_len) = lower[tag_start..].find('>') else {
+            break;
+        };
+        let tag_end = tag_start + tag_len;
+        search = tag_end;
+
+        let Some(charset) = lower[tag_start..tag_end].find("charset") else {
+            continue;
+        };
+        let mut value_start = tag_start + charset + "charset".len();
+        let bytes = lower.as_bytes();
+        while value_start < tag_end && matches!(bytes[value_start], b' ' | b'=' | b'"' | b'\'') {
+            value_start += 1;
+        }
+        let value_end = lower[value_start..tag_end]
+            .find(|c: char| matches!(c, '"' | '\'' | ';' | '/' | '>') || c.is_whitespace())
+            .map_or(tag_end, |len| value_start + len);
+
+        result.push_str(&html[copied..value_start]);
+        result.push_str("utf-8");
+        copied = value_end;
+        found = true;
+    }
+
+    if found {
+        result.push_str(&html[copied..]);
+        return result;
+    }
+
+    // 没有字符集声明：依次尝试放在 <head>、<html> 之后，否则放在最前面
+    let insert_at = ["<head", "<html"]
+        .iter()
+        .find_map(|open| {
+            let start = lower.find(open)?;
+            lower[start..].find('>').map(|end| start + end + 1)
+        })
+        .unwrap_or(0);
+    result.push_str(&html[..insert_at]);
+    result.push_str("<meta charset=\"utf-8\">");
+    result.push_str(&html[insert_at..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_utf8_charset() {
+        assert_eq!(
+            force_utf8_charset("<html><head><meta charset=\"gb2312\"></head></html>"),
+            "<html><head><meta charset=\"utf-8\"></head></html>"
+        );
+        assert_eq!(
+            force_utf8_charset(
+                "<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=iso-8859-1\">"
+            ),
+            "<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=utf-8\">"
+        );
+        assert_eq!(
+            force_utf8_charset("<html><HEAD><title>t</title></HEAD></html>"),
+            "<html><HEAD><meta charset=\"utf-8\"><title>t</title></HEAD></html>"
+        );
+        assert_eq!(
+            force_utf8_charset("<p>你好</p>"),
+            "<meta charset=\"utf-8\"><p>你好</p>"
+        );
+    }
+}
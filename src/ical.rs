@@ -0,0 +1,353 @@
+//! iCalendar (RFC 5545) 导出
+//!
+//! 把约会或会议请求转为只含一个 VEVENT 的 VCALENDAR，便于导入其他日历。
+//! 时间统一输出为 UTC；UID 取会议的全局对象 ID，这样同一会议的请求和更新
+//! 在目标日历中会合并为同一个事件。
+
+use crate::{
+    datetime, parse_msg_to_struct, Appointment, BusyStatus, MsgEmail, MsgParseError, RecipientType,
+};
+use wasm_bindgen::prelude::*;
+
+/// WASM 导出接口
+/// 把约会或会议请求 MSG 文件转为 .ics 文本
+#[wasm_bindgen]
+pub fn convert_msg_to_ical(file_data: &[u8]) -> Result<String, JsValue> {
+    msg_to_ical(file_data).map_err(|e| e.to_js())
+}
+
+/// 把约会或会议请求 MSG 文件转为 .ics 文本，见 [`convert_msg_to_ical`]
+pub fn msg_to_ical(file_data: &[u8]) -> Result<String, MsgParseError> {
+    let email = parse_msg_to_struct(file_data)?;
+    build_ical(&email).ok_or(MsgParseError::WrongItemKind {
+        expected: "约会或会议消息",
+    })
+}
+
+/// 生成 VCALENDAR 文本，没有约会信息时返回 `None`
+pub(crate) fn build_ical(email: &MsgEmail) -> Option<String> {
+    let appointment = email.appointment.as_ref()?;
+    let class = email
+        .message_class
+        .as_deref()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    let method = if class.starts_with("IPM.SCHEDULE.MEETING.REQUEST") {
+        "REQUEST"
+    } else if class.starts_with("IPM.SCHEDULE.MEETING.CANCELED") {
+        "CANCEL"
+    } else if class.starts_with("IPM.SCHEDULE.MEETING.RESP.") {
+        "REPLY"
+    } else {
+        "PUBLISH"
+    };
+
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(
+        &mut out,
+        &format!(
+            "PRODID:-//msg-parser-wasm//{}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+    );
+    push_line(&mut out, &format!("METHOD:{}", method));
+    push_line(&mut out, "BEGIN:VEVENT");
+    push_line(&mut out, &format!("UID:{}", uid(email, appointment)));
+
+    let start = appointment.start_time.as_deref().and_then(utc_date_time);
+    let stamp = [
+        &email.client_submit_time,
+        &email.creation_time,
+        &email.last_modification_time,
+    ]
+    .into_iter()
+    .find_map(|time| time.as_deref().and_then(utc_date_time))
+    .or_else(|| start.clone());
+    if let Some(stamp) = stamp {
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+    }
+    // 全天事件写成日期值，避免在其他时区的日历中跨天显示
+    let (date_time, value_type): (fn(&str) -> Option<String>, _) = if appointment.all_day {
+        (datetime::all_day_date, ";VALUE=DATE")
+    } else {
+        (utc_date_time, "")
+    };
+    let time = |time: &Option<String>| time.as_deref().and_then(date_time);
+    if let Some(start) = time(&appointment.start_time) {
+        push_line(&mut out, &format!("DTSTART{}:{}", value_type, start));
+    }
+    if let Some(end) = time(&appointment.end_time) {
+        push_line(&mut out, &format!("DTEND{}:{}", value_type, end));
+    }
+    if let Some(subject) = email.subject.as_deref().filter(|s| !s.is_empty()) {
+        push_line(&mut out, &format!("SUMMARY:{}", escape_text(subject)));
+    }
+    if let Some(location) = &appointment.location {
+        push_line(&mut out, &format!("LOCATION:{}", escape_text(location)));
+    }
+    if let Some(body) = email
+        .body_text
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape_text(body)));
+    }
+    if method == "CANCEL" {
+        push_line(&mut out, "STATUS:CANCELLED");
+    }
+    if appointment.is_private {
+        push_line(&mut out, "CLASS:PRIVATE");
+    }
+    if let Some(status) = appointment.busy_status {
+        let (transp, busy) = match status {
+            BusyStatus::Free => ("TRANSPARENT", "FREE"),
+            BusyStatus::Tentative => ("OPAQUE", "TENTATIVE"),
+            BusyStatus::Busy => ("OPAQUE", "BUSY"),
+            BusyStatus::OutOfOffice => ("OPAQUE", "OOF"),
+            BusyStatus::WorkingElsewhere => ("OPAQUE", "WORKINGELSEWHERE"),
+        };
+        push_line(&mut out, &format!("TRANSP:{}", transp));
+        push_line(&mut out, &format!("X-MICROSOFT-CDO-BUSYSTATUS:{}", busy));
+    }
+
+    // 组织者优先取收件人表中带组织者标志的一行，其次取发件人
+    let organizer = email.recipient_table.iter().find(|r| r.organizer);
+    let organizer_email = organizer
+        .and_then(|r| r.email.as_deref())
+        .or(email.sender_email.as_deref())
+        .filter(|address| address.contains('@'));
+    if let Some(address) = organizer_email {
+        let name = organizer
+            .and_then(|r| r.display_name.as_deref())
+            .or(appointment.organizer.as_deref());
+        push_line(
+            &mut out,
+            &format!(
+                "ORGANIZER{}:mailto:{}",
+                common_name(name),
+                address.replace(char::is_control, "")
+            ),
+        );
+    }
+    // 会议请求中收件人即与会者：收件人为必选，抄送为可选，密送为会议室等资源
+    for recipient in email.recipient_table.iter().filter(|r| !r.organizer) {
+        let Some(address) = recipient.email.as_deref().filter(|a| a.contains('@')) else {
+            continue;
+        };
+        let (role, cutype) = match recipient.recipient_type {
+            Some(RecipientType::Cc) => ("OPT-PARTICIPANT", ""),
+            Some(RecipientType::Bcc) => ("NON-PARTICIPANT", ";CUTYPE=RESOURCE"),
+            _ => ("REQ-PARTICIPANT", ""),
+        };
+        push_line(
+            &mut out,
+            &format!(
+                "ATTENDEE{}{};ROLE={};PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:{}",
+                common_name(recipient.display_name.as_deref()),
+                cutype,
+                role,
+                address.replace(char::is_control, "")
+            ),
+        );
+    }
+
+    if let Some(minutes) = appointment.reminder_minutes {
+        push_line(&mut out, "BEGIN:VALARM");
+        push_line(&mut out, "ACTION:DISPLAY");
+        push_line(&mut out, "DESCRIPTION:Reminder");
+        push_line(&mut out, &format!("TRIGGER:-PT{}M", minutes));
+        push_line(&mut out, "END:VALARM");
+    }
+    push_line(&mut out, "END:VEVENT");
+    push_line(&mut out, "END:VCALENDAR");
+    Some(out)
+}
+
+/// 会议的全局对象 ID，没有时退回搜索键，再退回开始时间和主题
+fn uid(email: &MsgEmail, appointment: &Appointment) -> String {
+    if let Some(id) = appointment
+        .global_object_id
+        .as_ref()
+        .or(email.search_key.as_ref())
+    {
+        return id.clone();
+    }
+    let seed = format!(
+        "{}{}",
+        appointment.start_time.as_deref().unwrap_or_default(),
+        email.subject.as_deref().unwrap_or_default()
+    );
+    // FNV-1a，只求稳定，不要求抗碰撞
+    let hash = seed.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016X}@msg-parser-wasm", hash)
+}
+
+/// `2024-01-01T02:00:00Z` 转为 `20240101T020000Z`
+fn utc_date_time(rfc3339: &str) -> Option<String> {
+    crate::datetime::parse_rfc3339(rfc3339)?;
+    Some(rfc3339.replace(['-', ':'], ""))
+}
+
+/// `;CN="名称"` 参数，名称为空时返回空串
+fn common_name(name: Option<&str>) -> String {
+    match name.map(param_value).filter(|n| !n.is_empty()) {
+        Some(name) => format!(";CN=\"{}\"", name),
+        None => String::new(),
+    }
+}
+
+/// 参数值转义 (RFC 6868)：`^`、换行和双引号写成 `^^`、`^n`、`^'`，其余控制字符丢弃
+fn param_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '^' => out.push_str("^^"),
+            '\n' => out.push_str("^n"),
+            '"' => out.push_str("^'"),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// TEXT 值转义 (RFC 5545 §3.3.11)
+pub(crate) fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 写入一行内容，超过 75 字节时按 RFC 5545 §3.1 折行，不拆开多字节字符
+pub(crate) fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Recipient;
+
+    #[test]
+    fn test_build_ical() {
+        let recipient = |name: &str, email: &str, recipient_type, organizer| Recipient {
+            display_name: Some(name.to_string()),
+            email: Some(email.to_string()),
+            recipient_type: Some(recipient_type),
+            organizer,
+            ..Default::default()
+        };
+        let email = MsgEmail {
+            message_class: Some("IPM.Schedule.Meeting.Request".to_string()),
+            subject: Some("Plan, review; sync".to_string()),
+            body_text: Some("Agenda:\r\n1. Roadmap".to_string()),
+            recipient_table: vec![
+                recipient("Carol", "carol@example.com", RecipientType::To, true),
+                recipient("Alice", "alice@example.com", RecipientType::To, false),
+                recipient("Room 1", "room1@example.com", RecipientType::Bcc, false),
+            ],
+            appointment: Some(Appointment {
+                start_time: Some("2024-01-01T02:00:00Z".to_string()),
+                end_time: Some("2024-01-01T03:00:00Z".to_string()),
+                busy_status: Some(BusyStatus::Busy),
+                reminder_minutes: Some(15),
+                global_object_id: Some("040000008200E000".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let ical = build_ical(&email).unwrap();
+        let lines: Vec<&str> = ical.split("\r\n").collect();
+        for expected in [
+            "METHOD:REQUEST",
+            "UID:040000008200E000",
+            "DTSTAMP:20240101T020000Z",
+            "DTSTART:20240101T020000Z",
+            "DTEND:20240101T030000Z",
+            "SUMMARY:Plan\\, review\\; sync",
+            "DESCRIPTION:Agenda:\\n1. Roadmap",
+            "X-MICROSOFT-CDO-BUSYSTATUS:BUSY",
+            "ORGANIZER;CN=\"Carol\":mailto:carol@example.com",
+            "TRIGGER:-PT15M",
+        ] {
+            assert!(lines.contains(&expected), "{}", expected);
+        }
+        // 超过 75 字节的行被折行，续行以空格开头
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "ATTENDEE;CN=\"Alice\";ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:alice@example.com\r\n"
+        ));
+        assert!(unfolded.contains("CUTYPE=RESOURCE;ROLE=NON-PARTICIPANT"));
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n") && ical.ends_with("END:VCALENDAR\r\n"));
+
+        assert!(build_ical(&MsgEmail::default()).is_none());
+    }
+
+    #[test]
+    fn test_all_day_event() {
+        // UTC+8 的 2024-03-10 全天
+        let email = MsgEmail {
+            subject: Some("Holiday".to_string()),
+            appointment: Some(Appointment {
+                start_time: Some("2024-03-09T16:00:00Z".to_string()),
+                end_time: Some("2024-03-10T16:00:00Z".to_string()),
+                all_day: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let ical = build_ical(&email).unwrap();
+        assert!(ical.contains("\r\nDTSTART;VALUE=DATE:20240310\r\n"));
+        assert!(ical.contains("\r\nDTEND;VALUE=DATE:20240311\r\n"));
+        assert!(ical.contains("\r\nDTSTAMP:20240309T160000Z\r\n"));
+    }
+
+    #[test]
+    fn test_param_injection() {
+        let email = MsgEmail {
+            recipient_table: vec![Recipient {
+                display_name: Some("Eve \"^\"\r\nATTENDEE:mailto:x@evil.example\n".to_string()),
+                email: Some("eve@example.com\r\nX-INJECTED:1".to_string()),
+                organizer: true,
+                ..Default::default()
+            }],
+            appointment: Some(Appointment::default()),
+            ..Default::default()
+        };
+
+        let ical = build_ical(&email).unwrap();
+        let unfolded = ical.replace("\r\n ", "");
+        assert!(unfolded.contains(
+            "\r\nORGANIZER;CN=\"Eve ^'^^^'^nATTENDEE:mailto:x@evil.example^n\":mailto:eve@example.comX-INJECTED:1\r\n"
+        ));
+        assert!(!unfolded.contains("\r\nATTENDEE"));
+        assert!(!unfolded.contains("\r\nX-INJECTED"));
+        assert_eq!(param_value("a\u{7}b"), "ab");
+    }
+}
@@ -0,0 +1,180 @@
+//! 重要性、敏感度及消息状态
+//!
+//! 这些属性都是定长属性，只存放在 `__properties_version1.0` 属性流中，没有对应的
+//! `__substg1.0_` 流。
+
+use crate::props::PropertyStore;
+use serde::Serialize;
+
+const PROP_IMPORTANCE: u16 = 0x0017;
+const PROP_PRIORITY: u16 = 0x0026;
+const PROP_SENSITIVITY: u16 = 0x0036;
+const PROP_MESSAGE_FLAGS: u16 = 0x0E07;
+const PROP_MESSAGE_SIZE: u16 = 0x0E08;
+
+// PR_MESSAGE_FLAGS 标志位
+const MSGFLAG_READ: u32 = 0x0001;
+const MSGFLAG_UNMODIFIED: u32 = 0x0002;
+const MSGFLAG_SUBMIT: u32 = 0x0004;
+const MSGFLAG_UNSENT: u32 = 0x0008;
+const MSGFLAG_HASATTACH: u32 = 0x0010;
+const MSGFLAG_FROMME: u32 = 0x0020;
+const MSGFLAG_ASSOCIATED: u32 = 0x0040;
+const MSGFLAG_RESEND: u32 = 0x0080;
+
+/// 重要性 (PR_IMPORTANCE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Importance {
+    Low,
+    Normal,
+    High,
+}
+
+/// 优先级 (PR_PRIORITY)，对应 X-Priority / Priority 头
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    NonUrgent,
+    Normal,
+    Urgent,
+}
+
+/// 敏感度 (PR_SENSITIVITY)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sensitivity {
+    Normal,
+    Personal,
+    Private,
+    Confidential,
+}
+
+/// PR_MESSAGE_FLAGS 中的各个标志位
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MessageFlags {
+    /// 已读 (MSGFLAG_READ)
+    pub read: bool,
+    /// 保存后未被修改过 (MSGFLAG_UNMODIFIED)
+    pub unmodified: bool,
+    /// 已提交发送、尚在发件箱中 (MSGFLAG_SUBMIT)
+    pub submitted: bool,
+    /// 未发送，即草稿 (MSGFLAG_UNSENT)
+    pub unsent: bool,
+    /// 带有附件 (MSGFLAG_HASATTACH)
+    pub has_attachments: bool,
+    /// 由当前邮箱用户发出 (MSGFLAG_FROMME)
+    pub from_me: bool,
+    /// 文件夹关联信息，如视图设置、规则 (MSGFLAG_ASSOCIATED)
+    pub associated: bool,
+    /// 重新发送的邮件 (MSGFLAG_RESEND)
+    pub resent: bool,
+}
+
+/// 消息状态，取自属性流中的定长属性
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MessageStatus {
+    pub importance: Option<Importance>,
+    pub priority: Option<Priority>,
+    pub sensitivity: Option<Sensitivity>,
+    /// 原始 PR_MESSAGE_FLAGS
+    pub message_flags: Option<u32>,
+    /// 拆开的 PR_MESSAGE_FLAGS 标志位
+    pub flags: MessageFlags,
+    /// PR_MESSAGE_SIZE，消息在存储中的大小（字节）
+    pub message_size: Option<u32>,
+}
+
+pub(crate) fn read_status(store: &PropertyStore) -> MessageStatus {
+    let value = |id| store.get(id).and_then(|v| v.as_i32());
+    let flags = value(PROP_MESSAGE_FLAGS).map(|v| v as u32);
+    let flag = |bit| flags.is_some_and(|flags| flags & bit != 0);
+
+    MessageStatus {
+        importance: value(PROP_IMPORTANCE).and_then(|v| match v {
+            0 => Some(Importance::Low),
+            1 => Some(Importance::Normal),
+            2 => Some(Importance::High),
+            _ => None,
+        }),
+        priority: value(PROP_PRIORITY).and_then(|v| match v {
+            -1 => Some(Priority::NonUrgent),
+            0 => Some(Priority::Normal),
+            1 => Some(Priority::Urgent),
+            _ => None,
+        }),
+        sensitivity: value(PROP_SENSITIVITY).and_then(|v| match v {
+            0 => Some(Sensitivity::Normal),
+            1 => Some(Sensitivity::Personal),
+            2 => Some(Sensitivity::Private),
+            3 => Some(Sensitivity::Confidential),
+            _ => None,
+        }),
+        message_flags: flags,
+        flags: MessageFlags {
+            read: flag(MSGFLAG_READ),
+            unmodified: flag(MSGFLAG_UNMODIFIED),
+            submitted: flag(MSGFLAG_SUBMIT),
+            unsent: flag(MSGFLAG_UNSENT),
+            has_attachments: flag(MSGFLAG_HASATTACH),
+            from_me: flag(MSGFLAG_FROMME),
+            associated: flag(MSGFLAG_ASSOCIATED),
+            resent: flag(MSGFLAG_RESEND),
+        },
+        message_size: value(PROP_MESSAGE_SIZE).map(|v| v as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::props;
+
+    #[test]
+    fn test_read_status() {
+        let record = |tag: u32, value: u64| {
+            [
+                &tag.to_le_bytes()[..],
+                &6u32.to_le_bytes(),
+                &value.to_le_bytes(),
+            ]
+            .concat()
+        };
+        let data = [
+            vec![0u8; props::TOP_LEVEL_HEADER_LEN],
+            record(0x0017_0003, 2),
+            record(0x0026_0003, 0xFFFF_FFFF),
+            record(0x0036_0003, 3),
+            record(0x0E07_0003, 0x39),
+            record(0x0E08_0003, 40960),
+        ]
+        .concat();
+        let mut store = PropertyStore::default();
+        store.set_fixed(props::parse_property_stream(
+            &data,
+            props::TOP_LEVEL_HEADER_LEN,
+        ));
+
+        assert_eq!(
+            read_status(&store),
+            MessageStatus {
+                importance: Some(Importance::High),
+                priority: Some(Priority::NonUrgent),
+                sensitivity: Some(Sensitivity::Confidential),
+                message_flags: Some(0x39),
+                flags: MessageFlags {
+                    read: true,
+                    unsent: true,
+                    has_attachments: true,
+                    from_me: true,
+                    ..Default::default()
+                },
+                message_size: Some(40960),
+            }
+        );
+        assert_eq!(
+            read_status(&PropertyStore::default()),
+            MessageStatus::default()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! 与其他 Rust 邮件生态 crate 的互操作
+//!
+//! mail-parser 方向先把解析结果转换为标准 MIME 邮件再交给它解析，保证得到的结构与
+//! 直接处理 .eml 文件完全一致；lettre 方向则直接用其 builder 组装，便于重新发送。
+
+#[cfg(feature = "mail-parser")]
+use crate::eml;
+use crate::MsgEmail;
+
+/// 转换为 `mail_parser::Message`，便于复用基于 MIME 的处理代码
+#[cfg(feature = "mail-parser")]
+pub fn to_mail_parser_message(email: &MsgEmail) -> Option<mail_parser::Message<'static>> {
+    let raw = eml::build_eml(email);
+    mail_parser::MessageParser::default()
+        .parse(&raw)
+        .map(|message| message.into_owned())
+}
+
+/// 转换为可通过 SMTP 发送的 `lettre::Message`
+///
+/// 正文为 multipart/alternative（文本 + HTML），带 Content-ID 的附件作为内嵌附件，
+/// 其余作为普通附件。无法解析为邮箱地址的收件人（只有显示名的）会被跳过；
+/// 没有有效发件人或收件人时返回 lettre 的构建错误。
+#[cfg(feature = "lettre")]
+pub fn to_lettre_message(email: &MsgEmail) -> Result<lettre::Message, lettre::error::Error> {
+    use lettre::message::header::ContentType;
+    use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+
+    let mut builder = lettre::Message::builder();
+
+    let from = email.sender_email.as_deref().and_then(|addr| {
+        let mailbox = match &email.sender_name {
+            Some(name) => format!("\"{}\" <{}>", name.replace('"', ""), addr),
+            None => addr.to_string(),
+        };
+        mailbox.parse::<Mailbox>().ok()
+    });
+    if let Some(from) = from {
+        builder = builder.from(from);
+    }
+    for to in email
+        .recipients
+        .iter()
+        .filter_map(|r| r.to_string().parse::<Mailbox>().ok())
+    {
+        builder = builder.to(to);
+    }
+    for cc in email
+        .cc_recipients
+        .iter()
+        .filter_map(|r| r.to_string().parse::<Mailbox>().ok())
+    {
+        builder = builder.cc(cc);
+    }
+    for bcc in email
+        .bcc_recipients
+        .iter()
+        .filter_map(|r| r.to_string().parse::<Mailbox>().ok())
+    {
+        builder = builder.bcc(bcc);
+    }
+    for reply_to in email
+        .reply_to
+        .iter()
+        .filter_map(|address| address.parse::<Mailbox>().ok())
+    {
+        builder = builder.reply_to(reply_to);
+    }
+    if let Some(subject) = &email.subject {
+        builder = builder.subject(subject);
+    }
+
+    let text = email.body_text.clone();
+    let html = email.body_html.clone();
+    let body = match (text, html) {
+        (Some(text), Some(html)) => MultiPart::alternative_plain_html(text, html),
+        (None, Some(html)) => MultiPart::alternative().singlepart(SinglePart::html(html)),
+        (text, None) => {
+            MultiPart::alternative().singlepart(SinglePart::plain(text.unwrap_or_default()))
+        }
+    };
+
+    let mut mixed = MultiPart::mixed().multipart(body);
+    for attachment in &email.attachments {
+        let content_type = attachment
+            .content_type
+            .as_deref()
+            .and_then(|ct| ContentType::parse(ct).ok())
+            .unwrap_or_else(|| ContentType::parse("application/octet-stream").unwrap());
+        let part = match &attachment.content_id {
+            Some(cid) => Attachment::new_inline(cid.clone()),
+            None => Attachment::new(attachment.filename.clone()),
+        };
+        mixed = mixed.singlepart(part.body(attachment.data.clone(), content_type));
+    }
+
+    builder.multipart(mixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "lettre")]
+    use crate::Address;
+
+    #[cfg(feature = "mail-parser")]
+    #[test]
+    fn test_to_mail_parser_message() {
+        let email = MsgEmail {
+            subject: Some("季度报告".to_string()),
+            sender_name: Some("Alice".to_string()),
+            sender_email: Some("alice@example.com".to_string()),
+            body_text: Some("正文".to_string()),
+            attachments: vec![crate::Attachment {
+                filename: "a.txt".to_string(),
+                data: b"abc".to_vec(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let message = to_mail_parser_message(&email).unwrap();
+
+        assert_eq!(message.subject(), Some("季度报告"));
+        assert_eq!(
+            message
+                .from()
+                .and_then(|f| f.first())
+                .and_then(|a| a.address()),
+            Some("alice@example.com")
+        );
+        assert_eq!(message.body_text(0).as_deref(), Some("正文"));
+        assert_eq!(message.attachment_count(), 1);
+    }
+
+    #[cfg(feature = "lettre")]
+    #[test]
+    fn test_to_lettre_message() {
+        let email = MsgEmail {
+            subject: Some("Report".to_string()),
+            sender_name: Some("Alice".to_string()),
+            sender_email: Some("alice@example.com".to_string()),
+            recipients: vec![
+                Address::parse("bob@example.com").unwrap(),
+                Address::parse("Carol").unwrap(),
+            ],
+            body_text: Some("hi".to_string()),
+            body_html: Some("<p>hi</p>".to_string()),
+            ..Default::default()
+        };
+
+        let message = to_lettre_message(&email).unwrap();
+        let raw = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(raw.contains("To: bob@example.com"));
+        assert!(!raw.contains("Carol"));
+        assert!(raw.contains("multipart/alternative"));
+    }
+
+    #[cfg(feature = "lettre")]
+    #[test]
+    fn test_to_lettre_message_requires_sender() {
+        let email = MsgEmail {
+            recipients: vec![Address::parse("bob@example.com").unwrap()],
+            ..Default::default()
+        };
+        assert!(to_lettre_message(&email).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! 按条目类型区分的输出结构
+//!
+//! `MsgEmail` 把所有类型都塞进同一个邮件形状，联系人、任务等条目只能通过
+//! 零散的可选字段去猜。[`MsgItem`] 把通用信封和各类型特有的内容分开，
+//! 调用方对 [`MessageKind`] 做 match 即可显式处理非邮件条目。
+
+use crate::{
+    parse_msg_to_struct, Address, Appointment, Attachment, Contact, ConversationIndex, EntryId,
+    FollowUpFlag, ItemKind, JournalEntry, LastAction, Locale, MessageStatus, MsgEmail,
+    MsgParseError, NamedProperty, OriginalMessage, Post, PropertyValue, ReceivedHop, Recipient,
+    RemoteResource, Report, RssItem, SecurityInfo, SenderSource, SenderTimezone, SmimeInfo,
+    SmimeReceipt, Task, TaskRequest, TextDirection, Voting,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// 各类条目共有的信息
+#[derive(Debug, Default, Serialize)]
+pub struct Envelope {
+    pub message_class: Option<String>,
+    pub kind: ItemKind,
+    pub subject: Option<String>,
+    pub sender_name: Option<String>,
+    pub sender_email: Option<String>,
+    pub sender_email_source: Option<SenderSource>,
+    pub from: Option<String>,
+    pub sender: Option<Address>,
+    pub on_behalf_of: Option<Address>,
+    pub recipients: Vec<Address>,
+    pub cc_recipients: Vec<Address>,
+    pub bcc_recipients: Vec<Address>,
+    pub reply_to: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients_flat: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cc_recipients_flat: Vec<String>,
+    pub recipient_table: Vec<Recipient>,
+    pub sent_time: Option<String>,
+    pub sent_time_unix: Option<i64>,
+    pub client_submit_time: Option<String>,
+    pub message_delivery_time: Option<String>,
+    pub creation_time: Option<String>,
+    pub last_modification_time: Option<String>,
+    pub body_text: Option<String>,
+    pub body_html: Option<String>,
+    pub body_rtf: Option<String>,
+    pub attachments: Vec<Attachment>,
+    pub is_template: bool,
+    pub truncated: bool,
+    pub warnings: Vec<String>,
+    pub named_properties: Vec<NamedProperty>,
+    pub categories: Vec<String>,
+    pub flag_request: Option<String>,
+    pub flag: Option<FollowUpFlag>,
+    pub voting: Option<Voting>,
+    #[serde(serialize_with = "crate::serialize_sorted")]
+    pub custom: HashMap<u32, PropertyValue>,
+    #[serde(
+        serialize_with = "crate::serialize_sorted",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub raw_properties: HashMap<u32, PropertyValue>,
+    pub entry_id: Option<EntryId>,
+    pub store_entry_id: Option<EntryId>,
+    pub parent_entry_id: Option<EntryId>,
+    pub search_key: Option<String>,
+    pub change_key: Option<String>,
+    pub original: Option<OriginalMessage>,
+    pub last_action: Option<LastAction>,
+    pub last_action_time: Option<String>,
+    pub conversation_topic: Option<String>,
+    pub conversation: Option<ConversationIndex>,
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub reply_requested: bool,
+    pub response_requested: bool,
+    pub read_receipt_requested: bool,
+    pub disposition_notification_to: Vec<Address>,
+    pub return_receipt_to: Vec<Address>,
+    pub headers: Vec<(String, String)>,
+    pub security: Option<SecurityInfo>,
+    pub smime: Option<SmimeInfo>,
+    pub received_chain: Vec<ReceivedHop>,
+    pub sender_timezone: Option<SenderTimezone>,
+    pub locale: Locale,
+    pub status: MessageStatus,
+    pub text_direction: Option<TextDirection>,
+    pub remote_resources: Vec<RemoteResource>,
+}
+
+/// 条目类型及其特有内容
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum MessageKind {
+    /// 普通邮件 (IPM.Note)
+    Email,
+    /// 约会、会议请求及响应
+    Appointment(Box<Appointment>),
+    Contact(Box<Contact>),
+    Task(Task),
+    /// 任务分配消息
+    TaskRequest(TaskRequest),
+    /// 日记条目
+    Journal(JournalEntry),
+    /// RSS 文章
+    Rss(RssItem),
+    /// 公共文件夹帖子
+    Post(Post),
+    /// S/MIME 签名回执
+    SmimeReceipt(SmimeReceipt),
+    /// 送达/已读/未送达报告 (REPORT.*)
+    Report(Box<Report>),
+    /// 其他无法识别的消息类型，附带原始消息类型
+    Other(String),
+}
+
+/// 按条目类型区分的解析结果
+#[derive(Debug, Serialize)]
+pub struct MsgItem {
+    pub common: Envelope,
+    pub kind: MessageKind,
+}
+
+impl From<MsgEmail> for MsgItem {
+    fn from(email: MsgEmail) -> Self {
+        let class =
+            ItemKind::from_message_class(email.message_class.as_deref().unwrap_or_default());
+        let kind = if let Some(request) = email.task_request {
+            MessageKind::TaskRequest(request)
+        } else if let Some(task) = email.task {
+            MessageKind::Task(task)
+        } else if let Some(appointment) = email.appointment {
+            MessageKind::Appointment(Box::new(appointment))
+        } else if let Some(contact) = email.contact {
+            MessageKind::Contact(Box::new(contact))
+        } else if let Some(journal) = email.journal {
+            MessageKind::Journal(journal)
+        } else if let Some(rss) = email.rss {
+            MessageKind::Rss(rss)
+        } else if let Some(post) = email.post {
+            MessageKind::Post(post)
+        } else if let Some(receipt) = email.smime_receipt {
+            MessageKind::SmimeReceipt(receipt)
+        } else if class == ItemKind::Report {
+            MessageKind::Report(Box::new(email.report.unwrap_or_default()))
+        } else if class == ItemKind::Note {
+            MessageKind::Email
+        } else {
+            MessageKind::Other(email.message_class.clone().unwrap_or_default())
+        };
+
+        MsgItem {
+            common: Envelope {
+                message_class: email.message_class,
+                kind: email.kind,
+                subject: email.subject,
+                sender_name: email.sender_name,
+                sender_email: email.sender_email,
+                sender_email_source: email.sender_email_source,
+                from: email.from,
+                sender: email.sender,
+                on_behalf_of: email.on_behalf_of,
+                recipients: email.recipients,
+                cc_recipients: email.cc_recipients,
+                bcc_recipients: email.bcc_recipients,
+                reply_to: email.reply_to,
+                recipients_flat: email.recipients_flat,
+                cc_recipients_flat: email.cc_recipients_flat,
+                recipient_table: email.recipient_table,
+                sent_time: email.sent_time,
+                sent_time_unix: email.sent_time_unix,
+                client_submit_time: email.client_submit_time,
+                message_delivery_time: email.message_delivery_time,
+                creation_time: email.creation_time,
+                last_modification_time: email.last_modification_time,
+                body_text: email.body_text,
+                body_html: email.body_html,
+                body_rtf: email.body_rtf,
+                attachments: email.attachments,
+                is_template: email.is_template,
+                truncated: email.truncated,
+                warnings: email.warnings,
+                named_properties: email.named_properties,
+                categories: email.categories,
+                flag_request: email.flag_request,
+                flag: email.flag,
+                voting: email.voting,
+                custom: email.custom,
+                raw_properties: email.raw_properties,
+                entry_id: email.entry_id,
+                store_entry_id: email.store_entry_id,
+                parent_entry_id: email.parent_entry_id,
+                search_key: email.search_key,
+                change_key: email.change_key,
+                original: email.original,
+                last_action: email.last_action,
+                last_action_time: email.last_action_time,
+                conversation_topic: email.conversation_topic,
+                conversation: email.conversation,
+                message_id: email.message_id,
+                in_reply_to: email.in_reply_to,
+                references: email.references,
+                reply_requested: email.reply_requested,
+                response_requested: email.response_requested,
+                read_receipt_requested: email.read_receipt_requested,
+                disposition_notification_to: email.disposition_notification_to,
+                return_receipt_to: email.return_receipt_to,
+                headers: email.headers,
+                security: email.security,
+                smime: email.smime,
+                received_chain: email.received_chain,
+                sender_timezone: email.sender_timezone,
+                locale: email.locale,
+                status: email.status,
+                text_direction: email.text_direction,
+                remote_resources: email.remote_resources,
+            },
+            kind,
+        }
+    }
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件并按条目类型返回 `{ common, kind: { type, value } }`
+#[wasm_bindgen]
+pub fn parse_msg_item(file_data: &[u8]) -> Result<JsValue, JsValue> {
+    let item = parse_msg_to_item(file_data).map_err(|e| e.to_js())?;
+
+    serde_wasm_bindgen::to_value(&item)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 解析 MSG 文件并按条目类型返回，见 [`parse_msg_item`]
+pub fn parse_msg_to_item(file_data: &[u8]) -> Result<MsgItem, MsgParseError> {
+    parse_msg_to_struct(file_data).map(MsgItem::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_kind_from_email() {
+        let item = MsgItem::from(MsgEmail {
+            message_class: Some("IPM.Note.SMIME".to_string()),
+            subject: Some("Hi".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(item.kind, MessageKind::Email));
+        assert_eq!(item.common.subject.as_deref(), Some("Hi"));
+
+        let item = MsgItem::from(MsgEmail {
+            message_class: Some("IPM.Appointment".to_string()),
+            appointment: Some(Appointment::default()),
+            ..Default::default()
+        });
+        assert!(matches!(item.kind, MessageKind::Appointment(_)));
+
+        let item = MsgItem::from(MsgEmail {
+            message_class: Some("REPORT.IPM.Note.NDR".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(item.kind, MessageKind::Report(_)));
+
+        let item = MsgItem::from(MsgEmail {
+            message_class: Some("IPM.StickyNote".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(item.kind, MessageKind::Other(class) if class == "IPM.StickyNote"));
+    }
+}
@@ -0,0 +1,69 @@
+//! 日记条目（IPM.Activity）
+
+use crate::named::{NameIdMap, PSETID_COMMON, PSETID_LOG};
+use crate::props::{PropertyStore, PropertyValue};
+use serde::Serialize;
+
+// PSETID_Log 下的 LID
+const LID_LOG_TYPE: u32 = 0x8700;
+const LID_LOG_START: u32 = 0x8706;
+const LID_LOG_DURATION: u32 = 0x8707;
+const LID_LOG_END: u32 = 0x8708;
+const LID_LOG_TYPE_DESC: u32 = 0x8712;
+
+// PSETID_Common 下的 LID
+const LID_COMPANIES: u32 = 0x8539;
+
+/// 日记条目
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JournalEntry {
+    /// 条目类型，如 `Phone call`、`E-mail Message`
+    pub entry_type: Option<String>,
+    /// 条目类型的本地化描述，如 `电话`
+    pub entry_type_description: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    /// 持续时间（分钟）
+    pub duration_minutes: Option<i32>,
+    /// 关联的公司
+    pub companies: Vec<String>,
+}
+
+/// 是否为日记条目
+pub(crate) fn is_journal_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class == "IPM.ACTIVITY" || class.starts_with("IPM.ACTIVITY.")
+}
+
+/// 从顶层属性中读取日记条目
+pub(crate) fn read_journal(store: &PropertyStore, names: &NameIdMap) -> JournalEntry {
+    let log = |lid| names.get_lid(store, PSETID_LOG, lid);
+    let text = |value: Option<PropertyValue>| {
+        value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+
+    JournalEntry {
+        entry_type: text(log(LID_LOG_TYPE)),
+        entry_type_description: text(log(LID_LOG_TYPE_DESC)),
+        start_time: text(log(LID_LOG_START)),
+        end_time: text(log(LID_LOG_END)),
+        duration_minutes: log(LID_LOG_DURATION).and_then(|v| v.as_i32()),
+        companies: match names.get_lid(store, PSETID_COMMON, LID_COMPANIES) {
+            Some(PropertyValue::MultiString(companies)) => companies,
+            _ => Vec::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_journal_class() {
+        assert!(is_journal_class("IPM.Activity"));
+        assert!(!is_journal_class("IPM.Appointment"));
+    }
+}
@@ -0,0 +1,80 @@
+//! 按消息类型粗分条目
+//!
+//! .msg 不一定是邮件，约会、联系人、任务、便笺等 Outlook 条目也以同样的格式导出。
+//! PR_MESSAGE_CLASS 的前缀决定了条目类型（MS-OXCMSG §2.2.1.3），下游据此判断能否按邮件渲染。
+
+use serde::Serialize;
+
+/// 条目类型，由 PR_MESSAGE_CLASS 推断
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    /// 普通邮件 (IPM.Note 及其子类，如 `IPM.Note.SMIME`)；没有消息类型时也按邮件处理
+    #[default]
+    Note,
+    /// 约会 (IPM.Appointment)
+    Appointment,
+    /// 会议请求、响应及取消 (IPM.Schedule.Meeting.*)
+    MeetingRequest,
+    /// 联系人及通讯组列表 (IPM.Contact、IPM.DistList)
+    Contact,
+    /// 任务及任务分配消息 (IPM.Task、IPM.TaskRequest.*)
+    Task,
+    /// 便笺 (IPM.StickyNote)
+    StickyNote,
+    /// 送达/已读/未送达报告 (REPORT.*)
+    Report,
+    /// 其他消息类型，如日记、帖子
+    Other,
+}
+
+impl ItemKind {
+    /// 按消息类型推断条目类型，不区分大小写
+    pub fn from_message_class(message_class: &str) -> ItemKind {
+        let class = message_class.to_ascii_uppercase();
+        let is = |prefix: &str| class == prefix || class.starts_with(&format!("{}.", prefix));
+        if class.is_empty() || class == "IPM" || is("IPM.NOTE") {
+            ItemKind::Note
+        } else if is("REPORT") {
+            ItemKind::Report
+        } else if is("IPM.APPOINTMENT") {
+            ItemKind::Appointment
+        } else if class.starts_with("IPM.SCHEDULE.MEETING.") {
+            ItemKind::MeetingRequest
+        } else if is("IPM.CONTACT") || is("IPM.DISTLIST") {
+            ItemKind::Contact
+        } else if is("IPM.TASK") || is("IPM.TASKREQUEST") {
+            ItemKind::Task
+        } else if is("IPM.STICKYNOTE") {
+            ItemKind::StickyNote
+        } else {
+            ItemKind::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_message_class() {
+        let cases = [
+            ("", ItemKind::Note),
+            ("IPM.Note", ItemKind::Note),
+            ("ipm.note.smime.multipartsigned", ItemKind::Note),
+            ("IPM.Notebook", ItemKind::Other),
+            ("IPM.Appointment", ItemKind::Appointment),
+            ("IPM.Schedule.Meeting.Request", ItemKind::MeetingRequest),
+            ("IPM.Schedule.Meeting.Canceled", ItemKind::MeetingRequest),
+            ("IPM.DistList", ItemKind::Contact),
+            ("IPM.TaskRequest.Accept", ItemKind::Task),
+            ("IPM.StickyNote", ItemKind::StickyNote),
+            ("REPORT.IPM.Note.NDR", ItemKind::Report),
+            ("IPM.Activity", ItemKind::Other),
+        ];
+        for (class, kind) in cases {
+            assert_eq!(ItemKind::from_message_class(class), kind, "{}", class);
+        }
+    }
+}
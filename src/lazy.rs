@@ -0,0 +1,133 @@
+//! 按需读取附件
+//!
+//! 浏览器中往往只需要其中一个附件，完整解析会把全部附件数据复制过 WASM 边界。
+//! 这里分两步：先用 [`list_attachments`] 列出元数据，再用 [`get_attachment_data`]
+//! 按序号只读取需要的那一个。序号与 `parse_msg_file` 结果中 `attachments` 的顺序一致。
+
+use crate::{detect, quirks, read_attachment_entry, AttachmentEntry, MsgParseError};
+use cfb::CompoundFile;
+use serde::Serialize;
+use std::io::{Read, Seek};
+use wasm_bindgen::prelude::*;
+
+/// 附件元数据，不含数据
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentInfo {
+    /// 附件序号，传给 [`get_attachment_data`]
+    pub index: usize,
+    pub filename: String,
+    pub content_type: Option<String>,
+    pub content_id: Option<String>,
+    /// 数据大小（字节）
+    pub size: u64,
+    pub attach_num: Option<u32>,
+    pub storage_index: Option<u32>,
+    /// 是否为嵌入消息（作为附件转发的邮件），此时没有附件数据
+    pub is_embedded_message: bool,
+}
+
+/// WASM 导出接口
+/// 列出全部附件的元数据：`{ index, filename, content_type, content_id, size, ... }[]`
+#[wasm_bindgen]
+pub fn list_attachments(file_data: &[u8]) -> Result<JsValue, JsValue> {
+    let attachments = list_msg_attachments(file_data).map_err(|e| e.to_js())?;
+
+    serde_wasm_bindgen::to_value(&attachments)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// WASM 导出接口
+/// 只读取第 `index` 个附件的数据（`Uint8Array`）
+#[wasm_bindgen]
+pub fn get_attachment_data(file_data: &[u8], index: usize) -> Result<Vec<u8>, JsValue> {
+    read_attachment_data(file_data, index).map_err(|e| e.to_js())
+}
+
+/// 列出全部附件的元数据，见 [`list_attachments`]
+pub fn list_msg_attachments(file_data: &[u8]) -> Result<Vec<AttachmentInfo>, MsgParseError> {
+    let mut comp = detect::open_msg(file_data)?;
+    Ok(attachment_entries(&mut comp)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (entry, is_embedded_message))| AttachmentInfo {
+            index,
+            filename: entry.attachment.filename,
+            content_type: entry.attachment.content_type,
+            content_id: entry.attachment.content_id,
+            size: entry.data_len,
+            attach_num: entry.attachment.attach_num,
+            storage_index: entry.attachment.storage_index,
+            is_embedded_message,
+        })
+        .collect())
+}
+
+/// 读取第 `index` 个附件的数据，见 [`get_attachment_data`]；嵌入消息返回空数据
+pub fn read_attachment_data(file_data: &[u8], index: usize) -> Result<Vec<u8>, MsgParseError> {
+    let mut comp = detect::open_msg(file_data)?;
+    let (entry, _) = attachment_entries(&mut comp)
+        .into_iter()
+        .nth(index)
+        .ok_or(MsgParseError::AttachmentNotFound { index })?;
+    entry.read_data(&mut comp).map_err(|e| {
+        let path = match &entry.ole_object {
+            Some((storage, _)) => storage.clone(),
+            None => entry.data_path.clone().unwrap_or_default(),
+        };
+        MsgParseError::corrupt_stream(path, e)
+    })
+}
+
+/// 按 `parse_msg_file` 的顺序和规则列出根消息的附件，不读取附件数据
+fn attachment_entries<R: Read + Seek>(comp: &mut CompoundFile<R>) -> Vec<(AttachmentEntry, bool)> {
+    let mut quirks = quirks::Quirks::new(true);
+    let paths: Vec<_> = comp
+        .read_root_storage()
+        .filter(|entry| {
+            entry.is_storage()
+                && quirks
+                    .canonical_name(entry.name())
+                    .starts_with("__attach_version1.0_")
+        })
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let entry = read_attachment_entry(comp, &path, &mut quirks).ok()?;
+            let is_embedded = entry.embedded_storage.is_some();
+            // 与完整解析一致：没有名称也没有数据的附件存储被忽略
+            if entry.data_len == 0 && entry.attachment.filename == "未命名附件" && !is_embedded
+            {
+                return None;
+            }
+            Some((entry, is_embedded))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_attachments() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let email = crate::parse_msg_to_struct(data).unwrap();
+
+        let list = list_msg_attachments(data).unwrap();
+        assert_eq!(list.len(), email.attachments.len());
+        for (info, attachment) in list.iter().zip(&email.attachments) {
+            assert_eq!(info.filename, attachment.filename);
+            assert_eq!(info.size, attachment.data.len() as u64);
+            assert_eq!(
+                read_attachment_data(data, info.index).unwrap(),
+                attachment.data
+            );
+        }
+
+        let err = read_attachment_data(data, list.len()).unwrap_err();
+        assert_eq!(err.code(), "attachment_not_found");
+    }
+}
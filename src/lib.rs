@@ -1,10 +1,159 @@
 use cfb::CompoundFile;
-use encoding_rs;
-use serde::Serialize;
-use std::io::{Cursor, Read};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
 use wasm_bindgen::prelude::*;
 
+mod action;
+#[cfg(feature = "archive")]
+mod archive;
+mod auth;
+#[cfg(feature = "eml-zip")]
+mod batch;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+mod binary;
+mod calendar;
+mod capabilities;
+mod contact;
+mod conversation;
+mod csp;
+mod datetime;
+mod der;
+mod detect;
+mod direction;
+mod eml;
+mod entryid;
+mod events;
+mod filter;
+mod flag;
+mod headers;
+mod html;
+mod ical;
+mod importance;
+#[cfg(any(feature = "mail-parser", feature = "lettre"))]
+mod interop;
+mod item;
+mod journal;
+mod kind;
+mod lazy;
+mod locale;
+mod memory;
+mod mime;
+mod named;
+#[cfg(any(feature = "tokio", feature = "mmap"))]
+mod native;
+#[cfg(feature = "ndjson")]
+mod ndjson;
+mod ole;
+mod original;
+mod output;
+mod perf;
+mod post;
+mod props;
+#[cfg(feature = "protobuf")]
+mod proto;
+mod quirks;
+mod receipt;
+mod received;
+mod recipient;
+mod recurrence;
+mod remote;
+mod reply_to;
+mod report;
+mod rss;
+mod rtf;
+mod sender;
+mod sink;
+mod smime;
+#[cfg(feature = "smime-verify")]
+mod smime_verify;
+mod task;
+mod timezone;
+mod tnef;
+mod transform;
+mod vcard;
+mod visitor;
+mod voting;
+#[cfg(feature = "wmf-preview")]
+mod wmf;
+#[cfg(feature = "zip-input")]
+mod zip_input;
+
+pub use action::LastAction;
+#[cfg(feature = "archive")]
+pub use archive::ArchiveEntry;
+pub use auth::{AuthResult, DkimResult, ReceivedSpf, SecurityInfo};
+#[cfg(feature = "eml-zip")]
+pub use batch::{convert_msgs_to_eml_zip, msgs_to_eml_zip};
+#[cfg(feature = "cbor")]
+pub use binary::{parse_msg_to_cbor, to_cbor};
+#[cfg(feature = "msgpack")]
+pub use binary::{parse_msg_to_msgpack, to_msgpack};
+pub use calendar::{Appointment, BusyStatus};
+pub use capabilities::{enabled_features, supported_features, version};
+pub use contact::{Contact, PostalAddress};
+pub use conversation::{ConversationIndex, ConversationReply};
+pub use direction::TextDirection;
+pub use eml::{convert_msg_to_eml, msg_to_eml};
+pub use entryid::EntryId;
+pub use events::{MsgEvent, MsgEventReader};
+pub use flag::{FlagColor, FlagStatus, FollowUpFlag};
+pub use headers::{parse_msg_headers, parse_msg_headers_to_struct, MsgHeaders};
+pub use ical::{convert_msg_to_ical, msg_to_ical};
+pub use importance::{Importance, MessageFlags, MessageStatus, Priority, Sensitivity};
+#[cfg(feature = "lettre")]
+pub use interop::to_lettre_message;
+#[cfg(feature = "mail-parser")]
+pub use interop::to_mail_parser_message;
+pub use item::{parse_msg_item, parse_msg_to_item, Envelope, MessageKind, MsgItem};
+pub use journal::JournalEntry;
+pub use kind::ItemKind;
+pub use lazy::{
+    get_attachment_data, list_attachments, list_msg_attachments, read_attachment_data,
+    AttachmentInfo,
+};
+pub use locale::Locale;
+pub use memory::approximate_memory_usage;
+pub use named::{NamedProperty, NamedPropertyRequest};
+#[cfg(feature = "mmap")]
+pub use native::{parse_msg_mmap, parse_msg_mmap_with_options};
+#[cfg(feature = "tokio")]
+pub use native::{parse_msg_path, parse_msg_path_with_options};
+#[cfg(feature = "ndjson")]
+pub use ndjson::{convert_msgs_to_ndjson, write_ndjson};
+pub use original::OriginalMessage;
+pub use output::{parse_msg_file_with_output_options, OutputOptions};
+pub use post::Post;
+pub use props::PropertyValue;
+#[cfg(feature = "protobuf")]
+pub use proto::{parse_msg_to_protobuf, to_protobuf, ProtoAttachment, ProtoMsgEmail};
+pub use receipt::SmimeReceipt;
+pub use received::ReceivedHop;
+pub use recipient::{Address, Recipient, RecipientType};
+pub use recurrence::{
+    expand_msg_occurrences, Occurrence, RecurrenceEnd, RecurrenceFrequency, RecurrencePattern,
+    RecurrencePatternType, Weekday,
+};
+pub use remote::{RemoteKind, RemoteResource};
+pub use report::{Report, ReportRecipient, ReportType};
+pub use rss::RssItem;
+pub use sender::{SenderSource, DEFAULT_SENDER_RESOLUTION};
+pub use sink::{parse_msg_with_sink, AttachmentSink};
+pub use smime::{SmimeCertificate, SmimeInfo, SmimeKind};
+#[cfg(feature = "smime-verify")]
+pub use smime_verify::{
+    verify_msg_smime_signature, verify_smime_signature, ChainCertificate, SignatureVerification,
+};
+pub use task::{Task, TaskAssignmentState, TaskRequest, TaskStatus};
+pub use timezone::{SenderTimezone, TimezoneSource};
+pub use transform::{parse_msg_with_pipeline, BodyPipeline, BodyStep, BodyTransform};
+pub use vcard::{convert_msg_to_vcard, msg_to_vcard};
+pub use visitor::{walk_properties, PropertyLocation, PropertyVisitor};
+pub use voting::{Voting, VotingOption};
+#[cfg(feature = "zip-input")]
+pub use zip_input::{parse_msg_zip, parse_msgs_in_zip, ZipMsgEntry};
+
 // MAPI Property Tags (first 4 characters of the stream name after __substg1.0_)
 const TAG_SUBJECT: &str = "0037";
 const TAG_SENDER_NAME: &str = "0C1A";
@@ -12,9 +161,9 @@ const TAG_SENDER_EMAIL_1: &str = "0C1F";
 const TAG_SENDER_EMAIL_2: &str = "5D01";
 const TAG_SENDER_EMAIL_3: &str = "0065";
 const TAG_DISPLAY_TO: &str = "0E04";
-const TAG_RECIPIENT_EMAIL_1: &str = "0E03";
-const TAG_RECIPIENT_EMAIL_2: &str = "0076";
+const TAG_RECIPIENT_EMAIL: &str = "0076";
 const TAG_DISPLAY_CC: &str = "0E02";
+const TAG_DISPLAY_BCC: &str = "0E03";
 const TAG_TRANSPORT_HEADERS: &str = "007D";
 const TAG_CLIENT_SUBMIT_TIME: &str = "0039";
 const TAG_MESSAGE_DELIVERY_TIME: &str = "0E06";
@@ -22,6 +171,45 @@ const TAG_BODY: &str = "1000";
 const TAG_BODY_RTF: &str = "1009";
 const TAG_BODY_HTML: &str = "1013";
 
+/// PT_BINARY 属性类型
+const PT_BINARY: u16 = 0x0102;
+
+// PR_MESSAGE_FLAGS 及其标志位
+const PROP_MESSAGE_FLAGS: u16 = 0x0E07;
+const PROP_ATTACH_NUM: u16 = 0x0E21;
+const PROP_ATTACH_METHOD: u16 = 0x3705;
+/// PR_ATTACH_METHOD 取值：附件数据是 OLE 对象
+const ATTACH_OLE: u32 = 6;
+const MSGFLAG_UNSENT: u32 = 0x0008;
+
+// PR_MESSAGE_CLASS
+const PROP_MESSAGE_CLASS: u16 = 0x001A;
+
+// 条目标识
+const PROP_ENTRYID: u16 = 0x0FFF;
+const PROP_STORE_ENTRYID: u16 = 0x0FFB;
+const PROP_PARENT_ENTRYID: u16 = 0x0E09;
+const PROP_SEARCH_KEY: u16 = 0x300B;
+const PROP_CHANGE_KEY: u16 = 0x65E2;
+
+// 回复/回执请求
+const PROP_READ_RECEIPT_REQUESTED: u16 = 0x0029;
+const PROP_RESPONSE_REQUESTED: u16 = 0x0063;
+const PROP_REPLY_REQUESTED: u16 = 0x0C17;
+
+// 时间属性
+pub(crate) const PROP_CLIENT_SUBMIT_TIME: u16 = 0x0039;
+const PROP_MESSAGE_DELIVERY_TIME: u16 = 0x0E06;
+const PROP_CREATION_TIME: u16 = 0x3007;
+const PROP_LAST_MODIFICATION_TIME: u16 = 0x3008;
+
+// PR_TRANSPORT_MESSAGE_HEADERS
+const PROP_TRANSPORT_MESSAGE_HEADERS: u16 = 0x007D;
+
+// 常用命名属性：类别 (PS_PUBLIC_STRINGS "Keywords")、后续标记文本 (PidLidFlagRequest)
+const NAME_KEYWORDS: &str = "Keywords";
+const LID_FLAG_REQUEST: u32 = 0x8530;
+
 // Attachment Tags
 const TAG_ATTACH_FILENAME_LONG: &str = "3707";
 const TAG_ATTACH_FILENAME_SHORT: &str = "3704";
@@ -30,20 +218,165 @@ const TAG_ATTACH_EXTENSION: &str = "3703";
 const TAG_ATTACH_MIME_TAG: &str = "370E";
 const TAG_ATTACH_CONTENT_ID: &str = "3712";
 const TAG_ATTACH_DATA_BIN: &str = "3701";
+#[cfg(feature = "wmf-preview")]
+const TAG_ATTACH_RENDERING: &str = "3709";
 
 /// 邮件结构体
 #[derive(Debug, Default, Serialize)]
 pub struct MsgEmail {
+    /// 消息类型 (PR_MESSAGE_CLASS)，如 `IPM.Note`、`IPM.Appointment`
+    pub message_class: Option<String>,
+    /// 由消息类型推断的条目类型，渲染前据此判断是否为邮件
+    pub kind: ItemKind,
     pub subject: Option<String>,
     pub sender_name: Option<String>,
     pub sender_email: Option<String>,
-    pub recipients: Vec<String>,
-    pub cc_recipients: Vec<String>,
+    /// `sender_email` 取自哪个属性，见 [`ParseOptions::sender_resolution`]
+    pub sender_email_source: Option<SenderSource>,
+    /// RFC 5322 形式的发件人，如 `Alice <alice@example.com>`；显示名含特殊字符时加引号，
+    /// 含非 ASCII 字符时按 RFC 2047 编码
+    pub from: Option<String>,
+    /// 实际发送邮件的人 (PR_SENDER_*)；代理发送时为代理人
+    pub sender: Option<Address>,
+    /// 代理发送时的被代表者 (PR_SENT_REPRESENTING_*)，与 `sender` 为同一人时为空
+    pub on_behalf_of: Option<Address>,
+    /// 收件人，取自收件人表；没有收件人表时由 PR_DISPLAY_TO 等显示字符串拆分得到
+    pub recipients: Vec<Address>,
+    /// 抄送人，来源同 `recipients`
+    pub cc_recipients: Vec<Address>,
+    /// 密送人，来源同 `recipients`；只有草稿和已发送邮件中才有
+    pub bcc_recipients: Vec<Address>,
+    /// 回复地址 (PR_REPLY_RECIPIENT_ENTRIES)，能解出 SMTP 地址时为地址，否则为显示名；
+    /// 属性缺失时取传输头 Reply-To
+    pub reply_to: Vec<String>,
+    /// 旧版本的纯字符串收件人（显示字符串按分号拆分），仅在 `ParseOptions::flat_recipients` 时填充
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipients_flat: Vec<String>,
+    /// 旧版本的纯字符串抄送人，同 `recipients_flat`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cc_recipients_flat: Vec<String>,
+    /// 收件人表中的每一行，包含收件人类型及 PR_RECIPIENT_FLAGS 等标志
+    pub recipient_table: Vec<Recipient>,
+    /// 发送时间，RFC 3339 格式的 UTC 时间，如 `2023-10-27T08:44:20Z`；
+    /// Date 头无法识别时为原文
     pub sent_time: Option<String>,
+    /// 发送时间的 Unix 时间戳（秒）
+    pub sent_time_unix: Option<i64>,
+    /// 以下时间各自取自对应属性，格式同 `sent_time`，便于取证时相互比对。
+    /// 客户端提交时间 (PR_CLIENT_SUBMIT_TIME)
+    pub client_submit_time: Option<String>,
+    /// 投递时间 (PR_MESSAGE_DELIVERY_TIME)；未发送的消息中为保存时间
+    pub message_delivery_time: Option<String>,
+    /// 创建时间 (PR_CREATION_TIME)
+    pub creation_time: Option<String>,
+    /// 最后修改时间 (PR_LAST_MODIFICATION_TIME)
+    pub last_modification_time: Option<String>,
     pub body_text: Option<String>,
     pub body_html: Option<String>,
     pub body_rtf: Option<String>,
     pub attachments: Vec<Attachment>,
+    /// 是否为 Outlook 模板 (.oft)
+    pub is_template: bool,
+    /// 解析过程中遇到的非致命问题
+    pub warnings: Vec<String>,
+    /// 按 `ParseOptions::named_properties` 请求提取到的命名属性；
+    /// 设置 `ParseOptions::all_named_properties` 时为全部命名属性
+    pub named_properties: Vec<NamedProperty>,
+    /// 类别（"Keywords" 命名属性）
+    pub categories: Vec<String>,
+    /// 后续标记文本 (PidLidFlagRequest)，如 "Follow up"
+    pub flag_request: Option<String>,
+    /// 后续标记的状态、颜色、截止日期和提醒，未标记时为 `None`
+    pub flag: Option<FollowUpFlag>,
+    /// 投票按钮及答复中的投票结果
+    pub voting: Option<Voting>,
+    /// 按 `ParseOptions::custom_properties` 额外提取的属性，键为请求时给出的属性标签；
+    /// 序列化时按键排序
+    #[serde(serialize_with = "serialize_sorted")]
+    pub custom: HashMap<u32, PropertyValue>,
+    /// 设置 `ParseOptions::include_raw_properties` 时为顶层消息的全部属性，键为完整属性标签；
+    /// 序列化时按键排序
+    #[serde(
+        serialize_with = "serialize_sorted",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub raw_properties: HashMap<u32, PropertyValue>,
+    /// 消息的 PR_ENTRYID
+    pub entry_id: Option<EntryId>,
+    /// 所在存储（邮箱）的 PR_STORE_ENTRYID
+    pub store_entry_id: Option<EntryId>,
+    /// 所在文件夹的 PR_PARENT_ENTRYID
+    pub parent_entry_id: Option<EntryId>,
+    /// PR_SEARCH_KEY（十六进制），同一邮件多次导出时保持不变，适合用来去重
+    pub search_key: Option<String>,
+    /// PR_CHANGE_KEY（十六进制），标识邮件的某个版本
+    pub change_key: Option<String>,
+    /// 转发或重发的消息中原始作者、主题和时间 (PR_ORIGINAL_*)
+    pub original: Option<OriginalMessage>,
+    /// 最后执行的操作（回复、全部回复、转发）
+    pub last_action: Option<LastAction>,
+    /// 最后操作的时间
+    pub last_action_time: Option<String>,
+    /// 会话主题 (PR_CONVERSATION_TOPIC)，即去掉回复/转发前缀的原始主题
+    pub conversation_topic: Option<String>,
+    /// 解码后的 PR_CONVERSATION_INDEX：会话 GUID 及按时间差重建的回复时间线
+    pub conversation: Option<ConversationIndex>,
+    /// Message-ID (PR_INTERNET_MESSAGE_ID)，缺失时取传输头
+    pub message_id: Option<String>,
+    /// 所回复邮件的 Message-ID (PR_IN_REPLY_TO_ID)，缺失时取传输头
+    pub in_reply_to: Option<String>,
+    /// References 中的各个 Message-ID，由远及近排列 (PR_INTERNET_REFERENCES)
+    pub references: Vec<String>,
+    /// 发件人要求答复 (PR_REPLY_REQUESTED)
+    pub reply_requested: bool,
+    /// 发件人要求响应，如会议请求的接受/拒绝 (PR_RESPONSE_REQUESTED)
+    pub response_requested: bool,
+    /// 发件人要求已读回执 (PR_READ_RECEIPT_REQUESTED)
+    pub read_receipt_requested: bool,
+    /// 传输头 Disposition-Notification-To 中的已读回执接收地址 (RFC 8098)
+    pub disposition_notification_to: Vec<Address>,
+    /// 传输头 Return-Receipt-To 中的投递回执接收地址（非标准，部分旧客户端使用）
+    pub return_receipt_to: Vec<Address>,
+    /// 传输头 (PR_TRANSPORT_MESSAGE_HEADERS) 中的全部头，按出现顺序保留原始头名；
+    /// 折行已展开，RFC 2047 编码字已解码
+    pub headers: Vec<(String, String)>,
+    /// 传输头中的 SPF、DKIM、DMARC 验证结果 (Authentication-Results / Received-SPF)
+    pub security: Option<SecurityInfo>,
+    /// 逐跳解析的 Received 头，最新的一跳在前
+    pub received_chain: Vec<ReceivedHop>,
+    /// 根据 Date 头和最早一跳 Received 头推断的发件人 UTC 偏移
+    pub sender_timezone: Option<SenderTimezone>,
+    /// 区域和代码页原始值
+    pub locale: Locale,
+    /// 重要性、敏感度、已读等状态（属性流中的定长属性）
+    pub status: MessageStatus,
+    /// 正文的主要书写方向（希伯来文、阿拉伯文为 `rtl`），没有正文时为 `None`
+    pub text_direction: Option<TextDirection>,
+    /// HTML 正文引用的远程资源（图片、跟踪像素、样式表、链接等），供加载前提示用户
+    pub remote_resources: Vec<RemoteResource>,
+    /// 正文是否按 `ParseOptions::max_body_chars` 被截断
+    pub truncated: bool,
+    /// 日历项和会议请求的约会信息
+    pub appointment: Option<Appointment>,
+    /// 任务 (IPM.Task) 的任务信息
+    pub task: Option<Task>,
+    /// 任务分配消息 (IPM.TaskRequest.*) 的分配状态及嵌入的任务
+    pub task_request: Option<TaskRequest>,
+    /// 联系人 (IPM.Contact) 信息
+    pub contact: Option<Contact>,
+    /// 日记条目 (IPM.Activity) 信息
+    pub journal: Option<JournalEntry>,
+    /// RSS 文章 (IPM.Post.Rss) 信息
+    pub rss: Option<RssItem>,
+    /// 公共文件夹帖子 (IPM.Post) 的发布者和会话信息
+    pub post: Option<Post>,
+    /// S/MIME 签名回执 (IPM.Note.Receipt.SMIME) 及其指向的原始签名邮件
+    pub smime_receipt: Option<SmimeReceipt>,
+    /// S/MIME 签名或加密邮件 (IPM.Note.SMIME*) 的封装方式和签名证书。签名邮件的内容
+    /// 已解包到正文和附件字段
+    pub smime: Option<SmimeInfo>,
+    /// 送达/已读/未送达报告 (REPORT.*)
+    pub report: Option<Report>,
 }
 
 /// 附件结构体
@@ -55,342 +388,1546 @@ pub struct Attachment {
     pub content_id: Option<String>,
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
+    /// 附件数据的原始大小（字节）。超出 `ParseOptions::max_attachment_size` 时 `data` 为空
+    pub size: u64,
+    /// PR_ATTACH_NUM，附件在所属邮件中的编号
+    pub attach_num: Option<u32>,
+    /// 附件存储编号，即 `__attach_version1.0_#0000000A` 中的十六进制数。文件名重复时
+    /// 可用它唯一指代附件
+    pub storage_index: Option<u32>,
+    /// 嵌入消息 (PR_ATTACH_METHOD = ATTACH_EMBEDDED_MSG) 的解析结果，此时 `data` 为空
+    pub embedded_message: Option<Box<MsgEmail>>,
+    /// ZIP 附件中的条目列表（`archive` feature），不是 ZIP 时为 `None`
+    #[cfg(feature = "archive")]
+    pub archive_entries: Option<Vec<ArchiveEntry>>,
+    /// OLE 附件的 WMF 预览图转成的 PNG（`wmf-preview` feature）
+    #[cfg(feature = "wmf-preview")]
+    #[serde(with = "serde_bytes")]
+    pub preview_png: Option<Vec<u8>>,
+}
+
+/// 解析选项
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ParseOptions {
+    /// 输出体积预算（字节）。解析前根据目录项大小估算结果体积，超出预算时直接返回
+    /// `MsgParseError::OutputTooLarge`，避免恶意构造的文件撑爆浏览器标签页内存。
+    /// `None` 表示不限制
+    pub max_output_size: Option<u64>,
+    /// 严格模式：关闭针对第三方生成器（Aspose、Independentsoft 等）的兼容处理
+    pub strict: bool,
+    /// 额外提取的命名属性（属性集 GUID + 名称或 LID），结果放在 `MsgEmail::named_properties`
+    pub named_properties: Vec<NamedPropertyRequest>,
+    /// 额外提取的属性标签，如 `0x0E080003`（PR_MESSAGE_SIZE）；也可只给出 16 位属性 ID
+    pub custom_properties: Vec<u32>,
+    /// 文本和 HTML 正文的最大字符数，超出部分被截断并设置 `MsgEmail::truncated`。
+    /// 适合只需要摘要的列表视图；`None` 表示不截断
+    pub max_body_chars: Option<usize>,
+    /// 把 `body_html` 中的字符集声明改写为 UTF-8（没有时注入 `<meta charset="utf-8">`），
+    /// 避免放进 iframe 后被浏览器按原字符集再解码一次
+    pub rewrite_html_charset: bool,
+    /// 附件白名单，写法同 HTML `accept` 属性：`.pdf` 按扩展名、`image/*` 按 MIME 类型匹配。
+    /// 为空表示不限制；未通过筛选的附件不会读取数据
+    pub attachment_allow: Vec<String>,
+    /// 附件黑名单，写法同 `attachment_allow`，例如用 `image/*` 跳过签名图片
+    pub attachment_deny: Vec<String>,
+    /// 把 `body_html` 改写为可在严格 CSP 沙箱 iframe 中渲染的形式：去掉脚本和内联事件，
+    /// 样式限定在 `.msg-body` 容器内，`cid:` 图片转为 data URI，远程资源移除
+    pub csp_safe_html: bool,
+    /// 附件和收件人按存储编号排序，属性按名称顺序读取，不依赖复合文档的目录遍历顺序；
+    /// 用于快照测试或对结果做内容哈希
+    pub deterministic_order: bool,
+    /// 不读取也不解压 RTF 正文，`body_rtf` 始终为 `None`
+    pub skip_rtf: bool,
+    /// 不读取 HTML 正文，`body_html` 始终为 `None`
+    pub skip_html: bool,
+    /// 不读取传输头（只在缺少提交时间时用来取 Date 行）
+    pub skip_header_parsing: bool,
+    /// 发件人地址的解析顺序，取第一个可用的来源；为空时使用 [`DEFAULT_SENDER_RESOLUTION`]
+    /// （SMTP 地址属性 → ENTRYID → Exchange DN）。未列出的来源不会使用
+    pub sender_resolution: Vec<SenderSource>,
+    /// 兼容旧版本：额外输出按分号拆分的纯字符串收件人 `recipients_flat` / `cc_recipients_flat`
+    pub flat_recipients: bool,
+    /// 顶层消息 8 位字符串属性（PT_STRING8）的字符集标签，如 `gbk`、`windows-1252`、`shift_jis`。
+    /// 指定后不再使用消息声明的代码页 (PR_MESSAGE_CODEPAGE) 或自动探测；用于代码页信息缺失或有误的旧文件。
+    /// `None` 表示按声明的代码页解码，没有声明时自动探测
+    pub encoding: Option<String>,
+    /// 解析完成后依次执行的内置正文处理步骤，见 [`BodyStep`]。
+    /// 同时设置 `csp_safe_html`、`max_body_chars` 时，这两项先于流水线执行
+    pub body_pipeline: Vec<BodyStep>,
+    /// 嵌入消息（作为附件转发的邮件）的最大递归解析层数，`Some(0)` 表示不解析嵌入消息；
+    /// `None` 时为 [`DEFAULT_MAX_EMBEDDED_DEPTH`]
+    pub max_embedded_depth: Option<usize>,
+    /// 返回全部能映射回名称的命名属性（0x8000 以上），放在 `MsgEmail::named_properties`，
+    /// 此时 `named_properties` 请求被忽略
+    pub all_named_properties: bool,
+    /// 不读取附件，`attachments` 始终为空
+    pub skip_attachments: bool,
+    /// 只解析信封（主题、发件人、收件人、时间、传输头及各类标志），不读取正文和附件，
+    /// 适合只需要显示主题行的列表视图
+    pub headers_only: bool,
+    /// 附件数据的最大字节数，超出的附件只保留元数据（`data` 为空，`size` 为原始大小）
+    /// 并记录一条警告；`None` 表示不限制
+    pub max_attachment_size: Option<u64>,
+    /// 正文流的最大字节数：文本和 HTML 正文只读取开头这么多字节并设置 `MsgEmail::truncated`，
+    /// 超出的 RTF 正文不读取。与 `max_body_chars` 不同，超出部分不会读入内存；`None` 表示不限制
+    pub max_body_size: Option<u64>,
+    /// 在 `MsgEmail::raw_properties` 中返回顶层消息的全部属性
+    pub include_raw_properties: bool,
+}
+
+/// 嵌入消息默认的最大递归解析层数
+pub const DEFAULT_MAX_EMBEDDED_DEPTH: usize = 8;
+
+/// 解析错误
+#[derive(Debug)]
+pub enum MsgParseError {
+    /// 估算的输出体积超出调用方设置的预算
+    OutputTooLarge { estimated: u64, limit: u64 },
+    /// 文件是合法的复合文档，但不是 Outlook 邮件（例如 .doc/.xls/.vsd）
+    NotMsgFile { format: &'static str },
+    /// `ParseOptions::encoding` 不是可识别的字符集标签
+    UnknownEncoding { label: String },
+    /// 文件头不是 CFB 复合文档签名，例如 .eml、PDF 或空文件
+    NotCfbFile,
+    /// 文件头签名正确，但复合文档结构损坏，通常是文件被截断
+    CorruptFile { reason: String },
+    /// 读取存储或流失败
+    CorruptStream { path: String, reason: String },
+    /// 附件数据写入 `AttachmentSink` 失败
+    AttachmentError { index: usize, reason: String },
+    /// 按序号读取附件时序号超出范围
+    AttachmentNotFound { index: usize },
+    /// 校验签名时邮件不是 S/MIME 签名邮件，或签名结构无法解析
+    SmimeError { reason: String },
+    /// 条目类型不支持所请求的转换，例如把普通邮件转为 .ics
+    WrongItemKind { expected: &'static str },
+    /// 传给 WASM 接口的参数或选项格式不对
+    InvalidArgument { reason: String },
+    /// 输入不是可读取的 ZIP 压缩包
+    NotZipFile { reason: String },
+    /// 写出结果（ZIP、NDJSON 行）失败
+    WriteError { reason: String },
+    /// 批量处理时第 `index` 个文件（从 0 开始）出错，错误码沿用 `error` 的错误码
+    InFile {
+        index: usize,
+        error: Box<MsgParseError>,
+    },
+}
+
+impl MsgParseError {
+    /// 稳定的错误码，供调用方按类型区分错误
+    pub fn code(&self) -> &'static str {
+        match self {
+            MsgParseError::OutputTooLarge { .. } => "output_too_large",
+            MsgParseError::NotMsgFile { .. } => "not_msg_file",
+            MsgParseError::UnknownEncoding { .. } => "unknown_encoding",
+            MsgParseError::NotCfbFile => "not_cfb_file",
+            MsgParseError::CorruptFile { .. } => "corrupt_file",
+            MsgParseError::CorruptStream { .. } => "corrupt_stream",
+            MsgParseError::AttachmentError { .. } => "attachment_error",
+            MsgParseError::AttachmentNotFound { .. } => "attachment_not_found",
+            MsgParseError::SmimeError { .. } => "smime_error",
+            MsgParseError::WrongItemKind { .. } => "wrong_item_kind",
+            MsgParseError::InvalidArgument { .. } => "invalid_argument",
+            MsgParseError::NotZipFile { .. } => "not_zip_file",
+            MsgParseError::WriteError { .. } => "write_error",
+            MsgParseError::InFile { error, .. } => error.code(),
+        }
+    }
+
+    /// 读取存储或流失败
+    pub(crate) fn corrupt_stream(path: impl AsRef<Path>, reason: impl std::fmt::Display) -> Self {
+        MsgParseError::CorruptStream {
+            path: path.as_ref().display().to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid_argument(reason: impl std::fmt::Display) -> Self {
+        MsgParseError::InvalidArgument {
+            reason: reason.to_string(),
+        }
+    }
+
+    /// 转为 JS 对象 `{ code, message }`
+    pub(crate) fn to_js(&self) -> JsValue {
+        #[derive(Serialize)]
+        struct JsError<'a> {
+            code: &'a str,
+            message: String,
+        }
+        serde_wasm_bindgen::to_value(&JsError {
+            code: self.code(),
+            message: self.to_string(),
+        })
+        .unwrap_or_else(|_| JsValue::from_str(&self.to_string()))
+    }
+}
+
+impl std::fmt::Display for MsgParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MsgParseError::OutputTooLarge { estimated, limit } => {
+                write!(f, "预计输出大小 {} 字节超出限制 {} 字节", estimated, limit)
+            }
+            MsgParseError::NotMsgFile { format } => {
+                write!(f, "该文件是{}，不是 Outlook 邮件 (.msg)", format)
+            }
+            MsgParseError::UnknownEncoding { label } => {
+                write!(f, "无法识别的字符集: {}", label)
+            }
+            MsgParseError::NotCfbFile => write!(f, "不是 CFB 复合文档，无法作为 .msg 文件解析"),
+            MsgParseError::CorruptFile { reason } => {
+                write!(f, "复合文档已损坏或被截断: {}", reason)
+            }
+            MsgParseError::CorruptStream { path, reason } => {
+                write!(f, "读取 {} 失败: {}", path, reason)
+            }
+            MsgParseError::AttachmentError { index, reason } => {
+                write!(f, "写出第 {} 个附件失败: {}", index + 1, reason)
+            }
+            MsgParseError::AttachmentNotFound { index } => {
+                write!(f, "附件序号 {} 超出范围", index)
+            }
+            MsgParseError::SmimeError { reason } => write!(f, "无法校验 S/MIME 签名: {}", reason),
+            MsgParseError::WrongItemKind { expected } => write!(f, "不是{}，无法转换", expected),
+            MsgParseError::InvalidArgument { reason } => write!(f, "参数格式错误: {}", reason),
+            MsgParseError::NotZipFile { reason } => write!(f, "无法读取 ZIP 压缩包: {}", reason),
+            MsgParseError::WriteError { reason } => write!(f, "写出结果失败: {}", reason),
+            MsgParseError::InFile { index, error } => {
+                write!(f, "第 {} 个文件转换失败: {}", index + 1, error)
+            }
+        }
+    }
 }
 
+impl std::error::Error for MsgParseError {}
+
 /// WASM 导出接口
 /// 解析 MSG 文件并返回邮件结构体
 #[wasm_bindgen]
 pub fn parse_msg_file(file_data: &[u8]) -> Result<JsValue, JsValue> {
-    let email = parse_msg_to_struct(file_data).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+
+    to_js_value(&email)
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件，若预计输出超过 `max_output_size` 字节则直接报错而不分配结果
+#[wasm_bindgen]
+pub fn parse_msg_file_with_budget(
+    file_data: &[u8],
+    max_output_size: f64,
+) -> Result<JsValue, JsValue> {
+    // NaN 和负数转换为 u64 时会变成 0，当作参数错误
+    if max_output_size.is_nan() || max_output_size < 0.0 {
+        return Err(MsgParseError::invalid_argument(format!(
+            "max_output_size 必须是非负数: {}",
+            max_output_size
+        ))
+        .to_js());
+    }
+    let options = ParseOptions {
+        max_output_size: Some(max_output_size as u64),
+        ..Default::default()
+    };
+    let email = parse_msg_with_options(file_data, &options).map_err(|e| e.to_js())?;
+
+    to_js_value(&email)
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件，`body_html` 改写为可在严格 CSP 沙箱 iframe 中渲染的形式，
+/// 见 [`ParseOptions::csp_safe_html`]
+#[wasm_bindgen]
+pub fn parse_msg_file_csp_safe(file_data: &[u8]) -> Result<JsValue, JsValue> {
+    let options = ParseOptions {
+        csp_safe_html: true,
+        ..Default::default()
+    };
+    let email = parse_msg_with_options(file_data, &options).map_err(|e| e.to_js())?;
+
+    to_js_value(&email)
+}
+
+/// `parse_msg_file_with_options` 接收的 JS 选项对象：[`ParseOptions`] 的各字段加上
+/// 输出方式 `output`（见 [`OutputOptions`]），字段均可省略
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsParseOptions {
+    #[serde(flatten)]
+    parse: ParseOptions,
+    output: OutputOptions,
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const PARSE_OPTIONS_TS: &str = r#"
+export interface NamedPropertyRequest {
+    guid: string;
+    name?: string;
+    lid?: number;
+}
+
+export type BodyStep = "sanitize" | "inline_cid_images" | "linkify" | { truncate: number };
 
-    serde_wasm_bindgen::to_value(&email)
+export type SenderSource = "smtp_address" | "email_address" | "entry_id" | "recipient_table" | "exchange_dn";
+
+export interface ParseOptions {
+    max_output_size?: number;
+    strict?: boolean;
+    named_properties?: NamedPropertyRequest[];
+    custom_properties?: number[];
+    max_body_chars?: number;
+    rewrite_html_charset?: boolean;
+    attachment_allow?: string[];
+    attachment_deny?: string[];
+    csp_safe_html?: boolean;
+    deterministic_order?: boolean;
+    skip_rtf?: boolean;
+    skip_html?: boolean;
+    skip_header_parsing?: boolean;
+    sender_resolution?: SenderSource[];
+    flat_recipients?: boolean;
+    encoding?: string;
+    body_pipeline?: BodyStep[];
+    max_embedded_depth?: number;
+    all_named_properties?: boolean;
+    skip_attachments?: boolean;
+    headers_only?: boolean;
+    max_attachment_size?: number;
+    max_body_size?: number;
+    include_raw_properties?: boolean;
+    output?: OutputOptions;
+}
+"#;
+
+/// WASM 导出接口
+/// 按 `options`（见 `ParseOptions`，可省略）解析 MSG 文件：体积和正文长度限制、跳过部分内容、
+/// 指定字符集、结果在 JS 侧的表示方式等。不带选项时与 `parse_msg_file` 相同
+#[wasm_bindgen]
+pub fn parse_msg_file_with_options(file_data: &[u8], options: JsValue) -> Result<JsValue, JsValue> {
+    let options: JsParseOptions = if options.is_undefined() || options.is_null() {
+        JsParseOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|e| MsgParseError::invalid_argument(e).to_js())?
+    };
+    let email = parse_msg_with_options(file_data, &options.parse).map_err(|e| e.to_js())?;
+
+    let _phase = perf::phase("serialize");
+    email
+        .serialize(&options.output.serializer())
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// WASM 导出接口
+/// 不做完整解析，直接返回顶层消息某个属性未经解码的原始字节，找不到时返回 `undefined`
+///
+/// `tag` 为完整属性标签（如 `0x0FFF0102`）或 16 位属性 ID（如 `0x300B`）。
+/// 定长属性返回属性流中的 8 字节值区域。
+#[wasm_bindgen]
+pub fn get_raw_property(file_data: &[u8], tag: u32) -> Result<Option<Vec<u8>>, JsValue> {
+    get_raw_property_bytes(file_data, tag).map_err(|e| e.to_js())
+}
+
+/// 读取顶层消息某个属性的原始字节，见 [`get_raw_property`]
+pub fn get_raw_property_bytes(
+    file_data: &[u8],
+    tag: u32,
+) -> Result<Option<Vec<u8>>, MsgParseError> {
+    let mut comp = detect::open_msg(file_data)?;
+
+    let (id, prop_type) = if tag > 0xFFFF {
+        ((tag >> 16) as u16, Some((tag & 0xFFFF) as u16))
+    } else {
+        (tag as u16, None)
+    };
+
+    // 先找变长属性流
+    let mut quirks = quirks::Quirks::new(true);
+    let stream_path = comp.read_root_storage().find_map(|entry| {
+        let name = quirks.canonical_name(entry.name());
+        let stream_tag = name.strip_prefix("__substg1.0_")?;
+        let matches = match prop_type {
+            Some(t) => stream_tag == format!("{:04X}{:04X}", id, t),
+            None => stream_tag.len() == 8 && stream_tag.starts_with(&format!("{:04X}", id)),
+        };
+        (matches && entry.is_stream()).then(|| entry.path().to_path_buf())
+    });
+    if let Some(path) = stream_path {
+        return props::read_stream(&mut comp, &path)
+            .map(Some)
+            .map_err(|e| MsgParseError::corrupt_stream(&path, e));
+    }
+
+    // 再找属性流中的定长属性
+    let data =
+        props::read_stream(&mut comp, format!("/{}", props::PROPERTIES_STREAM)).unwrap_or_default();
+    let header_len = quirks.property_header_len(data.len(), props::TOP_LEVEL_HEADER_LEN);
+    Ok(props::parse_property_stream(&data, header_len)
+        .into_iter()
+        .find(|prop| prop.id() == id && prop_type.is_none_or(|t| t == prop.prop_type()))
+        .map(|prop| prop.value.to_vec()))
+}
+
+fn to_js_value(email: &MsgEmail) -> Result<JsValue, JsValue> {
+    let _phase = perf::phase("serialize");
+    serde_wasm_bindgen::to_value(email)
         .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
 }
 
 /// 内部解析函数，方便在 Rust 单元测试中调用
-pub fn parse_msg_to_struct(file_data: &[u8]) -> Result<MsgEmail, Box<dyn std::error::Error>> {
-    let cursor = Cursor::new(file_data);
+pub fn parse_msg_to_struct(file_data: &[u8]) -> Result<MsgEmail, MsgParseError> {
+    parse_msg_with_options(file_data, &ParseOptions::default())
+}
+
+/// 按选项解析 MSG 文件
+pub fn parse_msg_with_options(
+    file_data: &[u8],
+    options: &ParseOptions,
+) -> Result<MsgEmail, MsgParseError> {
+    parse_msg_internal(file_data, options, None)
+}
+
+/// 解析主流程；提供 `sink` 时附件数据写入 sink，结果中的附件只保留元数据
+pub(crate) fn parse_msg_internal(
+    file_data: &[u8],
+    options: &ParseOptions,
+    sink: Option<&mut dyn AttachmentSink>,
+) -> Result<MsgEmail, MsgParseError> {
+    let encoding = match &options.encoding {
+        Some(label) => Some(
+            encoding_rs::Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| {
+                MsgParseError::UnknownEncoding {
+                    label: label.clone(),
+                }
+            })?,
+        ),
+        None => None,
+    };
+    let mut comp = {
+        let _phase = perf::phase("open-cfb");
+        detect::open_msg(file_data)?
+    };
 
-    let mut comp = CompoundFile::open(cursor)?;
+    let mut email = parse_message(&mut comp, Path::new("/"), options, encoding, sink, 0)?;
+    email.is_template = detect::is_template(&comp);
+    Ok(email)
+}
 
+/// 解析 `root` 存储中的一封消息：根存储，或附件中的嵌入消息 (`__substg1.0_3701000D`)。
+/// `depth` 为嵌套层数，根消息为 0
+fn parse_message<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    root: &Path,
+    options: &ParseOptions,
+    encoding: Option<&'static encoding_rs::Encoding>,
+    mut sink: Option<&mut dyn AttachmentSink>,
+    depth: usize,
+) -> Result<MsgEmail, MsgParseError> {
     let mut email = MsgEmail::default();
 
+    let mut quirks = quirks::Quirks::new(!options.strict);
     let mut streams: Vec<(String, PathBuf)> = Vec::new();
     let mut attachment_dirs: Vec<(String, PathBuf)> = Vec::new();
+    let mut recipient_dirs: Vec<PathBuf> = Vec::new();
+    let mut estimated_size: u64 = 0;
 
-    comp.walk().for_each(|entry| {
-        let name = entry.name().to_string();
+    let walk_phase = perf::phase("walk");
+    let walk = comp
+        .walk_storage(root)
+        .map_err(|e| MsgParseError::CorruptStream {
+            path: root.display().to_string(),
+            reason: e.to_string(),
+        })?;
+    let skip_attachments = options.skip_attachments || options.headers_only;
+    walk.for_each(|entry| {
+        let name = quirks.canonical_name(entry.name());
         let path = entry.path().to_path_buf();
 
-        if name.starts_with("__substg1.0_") {
+        let in_attachment = path
+            .strip_prefix(root)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .is_some_and(|first| {
+                first
+                    .as_os_str()
+                    .to_string_lossy()
+                    .starts_with("__attach_version1.0_")
+            });
+        if entry.is_stream() && !(skip_attachments && in_attachment) {
+            estimated_size =
+                estimated_size.saturating_add(estimate_stream_output(&name, entry.len()));
+        }
+
+        // 只收集本消息存储下的属性流、收件人和附件，其中的属性由各自的解析函数处理；
+        // 嵌入消息的内容在解析对应附件时递归处理
+        let is_top_level = path.parent() == Some(root);
+        if name.starts_with("__substg1.0_") && is_top_level {
             streams.push((name, path));
-        } else if name.starts_with("__attach_version1.0_") {
+        } else if name.starts_with("__recip_version1.0_") && is_top_level {
+            recipient_dirs.push(path);
+        } else if name.starts_with("__attach_version1.0_") && is_top_level && !skip_attachments {
             attachment_dirs.push((name, path));
         }
     });
 
-    // 解析顶级属性
-    for (name, path) in &streams {
-        if let Ok(mut stream) = comp.open_stream(path) {
-            let mut data = Vec::new();
-            if stream.read_to_end(&mut data).is_ok() && !data.is_empty() {
-                parse_property(&mut email, name, &data);
-            }
-        }
-    }
+    drop(walk_phase);
 
-    // 解析附件
-    for (att_dir, _) in &attachment_dirs {
-        if let Ok(attachment) = parse_attachment_internal(&mut comp, att_dir) {
-            email.attachments.push(attachment);
-        }
+    if options.deterministic_order {
+        // 存储编号是定长的十六进制，按大写路径排序即按编号排序
+        streams.sort_by_cached_key(|(name, _)| name.to_ascii_uppercase());
+        recipient_dirs.sort_by_cached_key(|path| path.to_string_lossy().to_ascii_uppercase());
+        attachment_dirs.sort_by_cached_key(|(_, path)| path.to_string_lossy().to_ascii_uppercase());
     }
 
-    Ok(email)
-}
-
-fn parse_property(email: &mut MsgEmail, prop_name: &str, data: &[u8]) {
-    let tag = if prop_name.len() >= 20 {
-        &prop_name[12..16]
-    } else {
-        return;
-    };
-
-    match tag {
-        TAG_SUBJECT => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                email.subject = Some(text);
-            }
-        }
-        TAG_SENDER_NAME => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                email.sender_name = Some(text);
-            }
-        }
-        TAG_SENDER_EMAIL_1 | TAG_SENDER_EMAIL_2 | TAG_SENDER_EMAIL_3 => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                email.sender_email = Some(text);
-            }
-        }
-        TAG_DISPLAY_TO => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                for recipient in text.split(';') {
-                    let r = recipient.trim().to_string();
-                    if !r.is_empty() {
-                        email.recipients.push(r);
-                    }
-                }
-            }
-        }
-        TAG_RECIPIENT_EMAIL_1 | TAG_RECIPIENT_EMAIL_2 => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                for recipient in text.split(';') {
-                    let r = recipient.trim().to_string();
-                    if !r.is_empty() && r.contains('@') {
-                        email.recipients.push(r);
+    if let Some(limit) = options.max_output_size.filter(|_| depth == 0) {
+        // 压缩 RTF 解压后体积以其头部声明的原始大小为准
+        for (name, path) in &streams {
+            if name.get(12..16) == Some(TAG_BODY_RTF) && !options.skip_rtf {
+                if let Ok(mut stream) = comp.open_stream(path) {
+                    let mut header = [0u8; 8];
+                    if stream.read_exact(&mut header).is_ok() {
+                        let raw_size =
+                            u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+                        estimated_size = estimated_size.saturating_add(raw_size as u64);
                     }
                 }
             }
         }
-        TAG_DISPLAY_CC => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                for cc in text.split(';') {
-                    let c = cc.trim().to_string();
-                    if !c.is_empty() {
-                        email.cc_recipients.push(c);
-                    }
-                }
-            }
+
+        if estimated_size > limit {
+            return Err(MsgParseError::OutputTooLarge {
+                estimated: estimated_size,
+                limit,
+            });
         }
-        TAG_TRANSPORT_HEADERS => {
-            if email.sent_time.is_none() {
-                if let Some((text, _)) = decode_with_encoding(data) {
-                    for line in text.lines() {
-                        if line.to_lowercase().starts_with("date:") {
-                            email.sent_time = Some(line[5..].trim().to_string());
-                            break;
-                        }
-                    }
-                }
-            }
+    }
+
+    // 解析顶级属性
+    let properties_phase = perf::phase("properties");
+
+    // 定长属性（时间、标志位等）只存放在属性流中
+    let mut fixed_properties = Vec::new();
+    if let Ok(data) = props::read_stream(comp, root.join(props::PROPERTIES_STREAM)) {
+        let default_header_len = if depth == 0 {
+            props::TOP_LEVEL_HEADER_LEN
+        } else {
+            props::EMBEDDED_HEADER_LEN
+        };
+        let header_len = quirks.property_header_len(data.len(), default_header_len);
+        fixed_properties = props::parse_property_stream(&data, header_len);
+    } else {
+        quirks.note_missing_property_stream();
+    }
+    // 未发送的消息（草稿、模板）的投递时间只是保存时间，不能当作发送时间
+    let unsent = fixed_properties
+        .iter()
+        .find(|prop| prop.id() == PROP_MESSAGE_FLAGS && !prop.is_error())
+        .is_some_and(|prop| prop.as_u32() & MSGFLAG_UNSENT != 0);
+    for prop in fixed_properties.iter().filter(|prop| prop.is_fixed_width()) {
+        if prop.is_error() {
+            let code = prop.as_u32();
+            email.warnings.push(format!(
+                "属性 0x{:04X} 取值失败: {} (0x{:08X})",
+                prop.id(),
+                props::mapi_error_name(code).unwrap_or("未知错误"),
+                code
+            ));
+            continue;
         }
-        TAG_CLIENT_SUBMIT_TIME | TAG_MESSAGE_DELIVERY_TIME => {
-            if data.len() >= 8 {
-                let filetime = u64::from_le_bytes([
-                    data[0], data[1], data[2], data[3], data[4], data[5], data[6], data[7],
-                ]);
-                if let Some(datetime) = filetime_to_string(filetime) {
-                    if email.sent_time.is_none() || tag == TAG_CLIENT_SUBMIT_TIME {
-                        email.sent_time = Some(datetime);
-                    }
-                }
-            }
+        let tag = format!("{:04X}", prop.id());
+        if unsent && tag == TAG_MESSAGE_DELIVERY_TIME {
+            continue;
         }
-        TAG_BODY => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                if !text.trim().is_empty() {
-                    email.body_text = Some(text);
-                }
-            }
+        if let Some(value) = props::decode_fixed(prop) {
+            apply_property(&mut email, &tag, &value);
         }
-        TAG_BODY_HTML => {
-            if let Some((text, _)) = decode_with_encoding(data) {
-                if !text.trim().is_empty() {
-                    email.body_html = Some(text);
-                }
-            }
+    }
+
+    let mut store = props::PropertyStore::default();
+    store.set_fixed(fixed_properties);
+    // 按消息声明的代码页解码 8 位字符串和 HTML 正文，没有声明时才按内容探测
+    let declared = locale::Charsets::declared(&store);
+    let charsets = locale::Charsets {
+        string8: encoding.or(declared.string8),
+        ..declared
+    };
+    store.set_string8_encoding(charsets.string8);
+    for (name, path) in &streams {
+        if is_skipped_stream(name, options) {
+            continue;
         }
-        TAG_BODY_RTF => {
-            if data.len() >= 16 {
-                if let Ok(decompressed) = compressed_rtf::decompress_rtf(data) {
-                    if !decompressed.trim().is_empty() {
-                        email.body_rtf = Some(decompressed);
-                    }
+        let data = match options.max_body_size.filter(|_| is_body_stream(name)) {
+            Some(max_size) => props::read_stream_prefix(comp, path, max_size).map(|(data, cut)| {
+                email.truncated |= cut;
+                // 截断的压缩 RTF 无法解压，直接丢弃
+                if cut && name.get(12..16) == Some(TAG_BODY_RTF) {
+                    Vec::new()
+                } else {
+                    data
                 }
+            }),
+            None => props::read_stream(comp, path),
+        };
+        if let Ok(data) = data {
+            if !data.is_empty() {
+                parse_property(&mut email, name, &data, charsets);
+                store.insert_stream(name, data);
             }
         }
-        _ => {}
     }
-}
 
-fn parse_attachment_internal<R: Read + std::io::Seek>(
-    comp: &mut CompoundFile<R>,
-    attach_dir: &str,
-) -> Result<Attachment, Box<dyn std::error::Error>> {
-    let mut attachment = Attachment {
-        filename: "未命名附件".to_string(),
-        ..Default::default()
+    fill_bodies_from_rtf(&mut email, options);
+
+    email.sent_time_unix = email.sent_time.as_deref().and_then(datetime::parse_rfc3339);
+
+    let entry_id = |id| match store.get(id) {
+        Some(PropertyValue::Binary(data)) => entryid::parse_entry_id(&data),
+        _ => None,
     };
+    email.entry_id = entry_id(PROP_ENTRYID);
+    email.store_entry_id = entry_id(PROP_STORE_ENTRYID);
+    email.parent_entry_id = entry_id(PROP_PARENT_ENTRYID);
 
-    let mut attachment_streams: Vec<(String, PathBuf)> = Vec::new();
+    let hex_key = |id| match store.get(id) {
+        Some(PropertyValue::Binary(data)) if !data.is_empty() => Some(props::to_hex(&data)),
+        _ => None,
+    };
+    email.search_key = hex_key(PROP_SEARCH_KEY);
+    email.change_key = hex_key(PROP_CHANGE_KEY);
+    email.original = original::read_original(&store);
+    (email.last_action, email.last_action_time) = action::read_last_action(&store);
+    email.conversation_topic = conversation::read_conversation_topic(&store);
+    email.conversation = conversation::read_conversation_index(&store);
+    conversation::read_message_ids(&mut email, &store);
+    email.reply_to = reply_to::read_reply_to(&store);
+    email.locale = locale::read_locale(&store);
+    email.status = importance::read_status(&store);
 
-    comp.walk().for_each(|entry| {
-        let full_path = entry.path();
-        let path_str = full_path.to_string_lossy();
+    let time = |id| match store.get(id) {
+        Some(PropertyValue::Time(time)) => Some(time),
+        _ => None,
+    };
+    email.client_submit_time = time(PROP_CLIENT_SUBMIT_TIME);
+    email.message_delivery_time = time(PROP_MESSAGE_DELIVERY_TIME);
+    email.creation_time = time(PROP_CREATION_TIME);
+    email.last_modification_time = time(PROP_LAST_MODIFICATION_TIME);
 
-        if path_str.contains(attach_dir) && entry.is_stream() {
-            let name = entry.name().to_string();
-            attachment_streams.push((name, full_path.to_path_buf()));
+    let flag = |id| store.get(id).and_then(|v| v.as_bool()).unwrap_or(false);
+    email.reply_requested = flag(PROP_REPLY_REQUESTED);
+    email.response_requested = flag(PROP_RESPONSE_REQUESTED);
+    email.read_receipt_requested = flag(PROP_READ_RECEIPT_REQUESTED);
+    if let Some(PropertyValue::String(text)) = store.get(PROP_TRANSPORT_MESSAGE_HEADERS) {
+        let headers = headers::parse_header_block(&text);
+        headers::read_receipt_headers(&mut email, &headers);
+        conversation::read_message_id_headers(&mut email, &headers);
+        if email.reply_to.is_empty() {
+            email.reply_to = reply_to::read_reply_to_header(&headers);
         }
-    });
+        email.sender_timezone = timezone::infer_sender_timezone(&headers);
+        email.security = auth::read_security(&headers);
+        email.received_chain = received::read_received_chain(&headers);
+        email.headers = headers::header_fields(&text)
+            .into_iter()
+            .map(|(name, value)| (name, mime::decode_encoded_words(&value)))
+            .collect();
+    }
 
-    for (name, path) in attachment_streams {
-        if let Ok(mut stream) = comp.open_stream(&path) {
-            let mut stream_data = Vec::new();
-            if stream.read_to_end(&mut stream_data).is_ok() {
-                let tag = if name.len() >= 8 {
-                    &name[name.len() - 8..name.len() - 4]
-                } else {
-                    continue;
-                };
+    if options.include_raw_properties {
+        email.raw_properties = store.iter().collect();
+    }
 
-                match tag {
-                    TAG_ATTACH_FILENAME_LONG => {
-                        if let Some((text, _)) = decode_with_encoding(&stream_data) {
-                            attachment.filename = text;
-                        }
-                    }
-                    TAG_ATTACH_FILENAME_SHORT | TAG_ATTACH_DISPLAY_NAME
-                        if attachment.filename == "未命名附件" =>
-                    {
-                        if let Some((text, _)) = decode_with_encoding(&stream_data) {
-                            attachment.filename = text;
-                        }
-                    }
-                    TAG_ATTACH_EXTENSION if attachment.filename == "未命名附件" => {
-                        if let Some((ext, _)) = decode_with_encoding(&stream_data) {
-                            if !ext.is_empty() {
-                                attachment.filename = format!("attachment{}", ext);
-                            }
-                        }
-                    }
-                    TAG_ATTACH_MIME_TAG => {
-                        if let Some((text, _)) = decode_with_encoding(&stream_data) {
-                            attachment.content_type = Some(text);
-                        }
-                    }
-                    TAG_ATTACH_CONTENT_ID => {
-                        if let Some((text, _)) = decode_with_encoding(&stream_data) {
-                            let cid = text
-                                .trim()
-                                .trim_matches(|c| c == '<' || c == '>')
-                                .to_string();
-                            if !cid.is_empty() {
-                                attachment.content_id = Some(cid);
-                            }
-                        }
-                    }
-                    TAG_ATTACH_DATA_BIN => {
-                        attachment.data = stream_data;
-                    }
-                    _ => {}
-                }
-            }
+    for &tag in &options.custom_properties {
+        let id = if tag > 0xFFFF {
+            (tag >> 16) as u16
+        } else {
+            tag as u16
+        };
+        if let Some(value) = store.get(id) {
+            email.custom.insert(tag, value);
         }
     }
 
-    if attachment.data.is_empty() && attachment.filename == "未命名附件" {
-        return Err("附件数据为空".into());
+    for path in &recipient_dirs {
+        if let Ok(recipient) = recipient::read_recipient(comp, path, &mut quirks) {
+            email.recipient_table.push(recipient);
+        }
+    }
+    if email.recipient_table.is_empty() {
+        email.recipients = email
+            .recipients_flat
+            .iter()
+            .filter_map(|s| Address::parse(s))
+            .collect();
+        email.cc_recipients = email
+            .cc_recipients_flat
+            .iter()
+            .filter_map(|s| Address::parse(s))
+            .collect();
+    } else {
+        let of_type = |kind| {
+            email
+                .recipient_table
+                .iter()
+                .filter(|r| r.recipient_type == Some(kind))
+                .map(Address::from)
+                .collect()
+        };
+        email.recipients = of_type(RecipientType::To);
+        email.cc_recipients = of_type(RecipientType::Cc);
+        email.bcc_recipients = of_type(RecipientType::Bcc);
+    }
+    if !options.flat_recipients {
+        email.recipients_flat.clear();
+        email.cc_recipients_flat.clear();
     }
 
-    Ok(attachment)
-}
+    // Exchange 内部邮件的发件人地址可能只有 DN，需借助收件人表中的 SMTP 地址
+    let sender = sender::resolve_sender(&store, &email.recipient_table, &options.sender_resolution);
+    email.sender_email_source = sender.as_ref().map(|(_, source)| *source);
+    email.sender_email = sender.map(|(address, _)| address);
+    (email.sender, email.on_behalf_of) =
+        sender::resolve_identities(&store, &email.recipient_table, &options.sender_resolution);
+    email.from = eml::format_sender(&email);
 
-fn filetime_to_string(filetime: u64) -> Option<String> {
-    if filetime == 0 {
-        return None;
+    let name_map = named::NameIdMap::read(comp);
+    let message_class = match store.get(PROP_MESSAGE_CLASS) {
+        Some(PropertyValue::String(class)) => class,
+        _ => String::new(),
+    };
+    email.message_class = Some(message_class.clone()).filter(|class| !class.is_empty());
+    email.kind = ItemKind::from_message_class(&message_class);
+    if calendar::is_appointment_class(&message_class) {
+        email.appointment = Some(calendar::read_appointment(&store, &name_map));
     }
-    const FILETIME_TO_UNIX_EPOCH: u64 = 116444736000000000;
-    if filetime < FILETIME_TO_UNIX_EPOCH {
-        return None;
+    if task::is_task_class(&message_class) {
+        email.task = Some(task::read_task(&store, &name_map));
+    }
+    let attachment_paths: Vec<_> = attachment_dirs.iter().map(|(_, p)| p.as_path()).collect();
+    if let Some(state) = task::assignment_state(&message_class) {
+        email.task_request = Some(TaskRequest {
+            state,
+            task: task::read_embedded_task(comp, &attachment_paths, &name_map, &mut quirks),
+        });
     }
 
-    let unix_time = (filetime - FILETIME_TO_UNIX_EPOCH) / 10000000;
+    if let Some(PropertyValue::MultiString(categories)) =
+        name_map.get_name(&store, named::PS_PUBLIC_STRINGS, NAME_KEYWORDS)
+    {
+        email.categories = categories;
+    }
+    email.flag_request = name_map
+        .get_lid(&store, named::PSETID_COMMON, LID_FLAG_REQUEST)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|text| !text.is_empty());
+    email.flag = flag::read_follow_up_flag(&store, &name_map, email.flag_request.clone());
+    email.voting = voting::read_voting(&store, &name_map);
 
-    // Improved time calculation
-    let total_days = unix_time / 86400;
-    let remaining_seconds = unix_time % 86400;
-    let hours = remaining_seconds / 3600;
-    let minutes = (remaining_seconds % 3600) / 60;
-    let seconds = remaining_seconds % 60;
+    if options.all_named_properties {
+        email.named_properties = named::resolve_all(&name_map, &store);
+    } else if !options.named_properties.is_empty() {
+        for request in &options.named_properties {
+            let kind = match (&request.name, request.lid) {
+                (Some(name), _) => named::NameKind::Name(name.clone()),
+                (None, Some(lid)) => named::NameKind::Lid(lid),
+                (None, None) => continue,
+            };
+            let value = name_map
+                .find(&request.guid, &kind)
+                .and_then(|id| store.get(id));
+            if let Some(value) = value {
+                email.named_properties.push(NamedProperty {
+                    guid: named::normalize_guid(&request.guid),
+                    name: request.name.clone(),
+                    lid: request.lid,
+                    value,
+                });
+            }
+        }
+    }
 
-    // Simplistic year/month calculation (good enough for basic display)
-    let year = 1970 + total_days / 365;
-    let day_of_year = total_days % 365;
-    let month = (day_of_year / 30) + 1;
-    let day = (day_of_year % 30) + 1;
+    finish_bodies(&mut email, options);
 
-    Some(format!(
-        "{}-{:02}-{:02} {:02}:{:02}:{:02} (UTC)",
-        year, month, day, hours, minutes, seconds
-    ))
-}
+    if journal::is_journal_class(&message_class) {
+        email.journal = Some(journal::read_journal(&store, &name_map));
+    }
 
-fn decode_with_encoding(data: &[u8]) -> Option<(String, String)> {
-    if data.is_empty() {
-        return None;
+    if rss::is_rss_class(&message_class) {
+        email.rss = Some(rss::read_rss_item(&store, &name_map));
     }
 
-    // 1. Try UTF-16 LE (most common for modern MSG)
-    if data.len() >= 2 && data.len() % 2 == 0 {
-        let u16_vec: Vec<u16> = data
-            .chunks_exact(2)
-            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
-            .take_while(|&val| val != 0) // Stop at null terminator
-            .collect();
+    if post::is_post_class(&message_class) {
+        email.post = Some(post::read_post(&store));
+    }
 
-        if !u16_vec.is_empty() {
-            let text = String::from_utf16_lossy(&u16_vec);
-            let trimmed = text.trim();
-            // Heuristic: check if it looks like reasonable text
-            if !trimmed.is_empty()
-                && trimmed
-                    .chars()
-                    .any(|c| c.is_alphanumeric() || c.is_whitespace())
+    let mut photo_path = None;
+    if contact::is_contact_class(&message_class) {
+        email.contact = Some(contact::read_contact(&store, &name_map));
+        // 跳过附件时同样不读取联系人照片
+        if !skip_attachments {
+            photo_path = contact::find_photo_attachment(
+                comp,
+                &store,
+                &name_map,
+                &attachment_paths,
+                &mut quirks,
+            );
+        }
+    }
+
+    drop(properties_phase);
+
+    // 解析附件
+    let _attachments_phase = perf::phase("attachments");
+    for (_, att_path) in &attachment_dirs {
+        if photo_path.as_ref() == Some(att_path) {
+            // 联系人照片与普通附件一样受筛选规则和大小限制约束
+            let photo = parse_attachment_internal(
+                comp,
+                att_path,
+                &mut quirks,
+                &options.attachment_allow,
+                &options.attachment_deny,
+                options.max_attachment_size,
+            );
+            if let (Some(contact), Ok(photo)) = (email.contact.as_mut(), photo) {
+                if let Some(max_size) = options
+                    .max_attachment_size
+                    .filter(|&max_size| photo.size > max_size)
+                {
+                    email.warnings.push(format!(
+                        "联系人照片大小 {} 字节超出限制 {} 字节，未读取数据",
+                        photo.size, max_size
+                    ));
+                }
+                contact.photo = Some(photo);
+            }
+            continue;
+        }
+        let attachment = if let Some(sink) = sink.as_deref_mut() {
+            let index = email.attachments.len();
+            sink::write_attachment(comp, att_path, &mut quirks, options, index, sink).map_err(
+                |e| MsgParseError::AttachmentError {
+                    index,
+                    reason: e.to_string(),
+                },
+            )?
+        } else {
+            parse_attachment_internal(
+                comp,
+                att_path,
+                &mut quirks,
+                &options.attachment_allow,
+                &options.attachment_deny,
+                options.max_attachment_size,
+            )
+            .ok()
+        };
+        if let Some(mut attachment) = attachment {
+            if let Some(max_size) = options
+                .max_attachment_size
+                .filter(|&max_size| attachment.size > max_size)
             {
-                return Some((trimmed.to_string(), "UTF-16 LE".to_string()));
+                email.warnings.push(format!(
+                    "附件 {} 大小 {} 字节超出限制 {} 字节，未读取数据",
+                    attachment.filename, attachment.size, max_size
+                ));
+            }
+            // 嵌入消息的附件数据不经过 sink，直接放在嵌套的解析结果中
+            let max_depth = options
+                .max_embedded_depth
+                .unwrap_or(DEFAULT_MAX_EMBEDDED_DEPTH);
+            if let Some(embedded) =
+                embedded_message_storage(comp, att_path, &mut quirks).filter(|_| depth < max_depth)
+            {
+                attachment.embedded_message =
+                    parse_message(comp, &embedded, options, encoding, None, depth + 1)
+                        .ok()
+                        .map(Box::new);
             }
+            email.attachments.push(attachment);
         }
     }
 
-    // 2. Try UTF-8
-    if let Ok(text) = String::from_utf8(data.to_vec()) {
-        let text = text.trim_end_matches('\0').trim();
-        if !text.is_empty() {
-            return Some((text.to_string(), "UTF-8".to_string()));
-        }
+    if receipt::is_receipt_class(&message_class) {
+        email.smime_receipt = receipt::read_receipt(&email.attachments, &store);
+    }
+    if smime::is_smime_class(&message_class) {
+        email.smime = smime::unwrap_smime(&mut email, options);
+        // 正文来自解包出的内容，需要重新处理
+        finish_bodies(&mut email, options);
+    }
+    if tnef::unwrap_tnef(&mut email, options, depth) {
+        fill_bodies_from_rtf(&mut email, options);
+        finish_bodies(&mut email, options);
+    }
+    if report::is_report_class(&message_class) {
+        let report = report::read_report(
+            comp,
+            &store,
+            &message_class,
+            &recipient_dirs,
+            &email.headers,
+            email.body_text.as_deref(),
+            &mut quirks,
+        );
+        email.report = Some(report);
     }
 
-    // 3. Try GBK (common in Chinese environments)
-    let (decoded, _, had_errors) = encoding_rs::GBK.decode(data);
-    if !had_errors {
-        let text = decoded.trim_end_matches('\0').trim();
-        if !text.is_empty() {
-            return Some((text.to_string(), "GBK".to_string()));
+    if options.csp_safe_html {
+        if let Some(body) = &email.body_html {
+            email.body_html = Some(csp::make_csp_safe(body, &email.attachments));
         }
     }
 
-    // 4. Fallback to Lossy UTF-8
-    let text = String::from_utf8_lossy(data).to_string();
-    let text = text.trim_end_matches('\0').trim();
-    if !text.is_empty() {
-        return Some((text.to_string(), "UTF-8 (lossy)".to_string()));
-    }
+    transform::BodyPipeline::from_steps(&options.body_pipeline).apply(&mut email);
 
-    None
+    email.warnings.extend(quirks.warnings());
+
+    Ok(email)
 }
 
-#[cfg(test)]
-mod tests {
+/// 是否按 `ParseOptions` 的 skip 选项跳过该属性流（不读取数据）
+fn is_skipped_stream(name: &str, options: &ParseOptions) -> bool {
+    match name.get(12..16) {
+        Some(TAG_BODY) => options.headers_only,
+        Some(TAG_BODY_RTF) => options.skip_rtf || options.headers_only,
+        Some(TAG_BODY_HTML) => options.skip_html || options.headers_only,
+        Some(TAG_TRANSPORT_HEADERS) => options.skip_header_parsing,
+        _ => false,
+    }
+}
+
+/// 文本、HTML 或 RTF 正文流
+fn is_body_stream(name: &str) -> bool {
+    matches!(
+        name.get(12..16),
+        Some(TAG_BODY | TAG_BODY_RTF | TAG_BODY_HTML)
+    )
+}
+
+/// 按键排序后序列化 `HashMap`，保证输出稳定
+pub(crate) fn serialize_sorted<S: serde::Serializer, K: Serialize + Ord, V: Serialize>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
+}
+
+/// 估算单个流在结果中占用的字节数
+///
+/// 附件二进制按原样计入；文本属性可能由单字节编码转为 UTF-8（中文 GBK → UTF-8 约 1.5 倍），
+/// 因此按 2 倍保守估计；其余内部流（属性流、命名属性映射等）不进入结果，不计入。
+fn estimate_stream_output(name: &str, len: u64) -> u64 {
+    if !name.starts_with("__substg1.0_") {
+        return 0;
+    }
+    match name.get(16..20) {
+        Some("001E" | "001F") => len.saturating_mul(2),
+        Some(_) => len,
+        None => 0,
+    }
+}
+
+/// 只有压缩 RTF 正文时，从中还原 HTML 或文本正文 (MS-OXRTFEX)
+fn fill_bodies_from_rtf(email: &mut MsgEmail, options: &ParseOptions) {
+    if let Some(body_rtf) = &email.body_rtf {
+        match rtf::de_encapsulate(body_rtf) {
+            Some(rtf::Encapsulated::Html(html))
+                if email.body_html.is_none() && !options.skip_html && !html.trim().is_empty() =>
+            {
+                email.body_html = Some(html);
+            }
+            Some(rtf::Encapsulated::Text(text))
+                if email.body_text.is_none() && !text.trim().is_empty() =>
+            {
+                email.body_text = Some(text);
+            }
+            _ => {}
+        }
+        if email.body_text.is_none() {
+            let text = rtf::rtf_to_text(body_rtf);
+            if !text.is_empty() {
+                email.body_text = Some(text);
+            }
+        }
+    }
+}
+
+/// 按选项改写 HTML 字符集声明，根据最终的正文计算远程资源和书写方向，并按
+/// `max_body_chars` 截断。正文被 S/MIME、TNEF 解包替换后需要再次调用
+fn finish_bodies(email: &mut MsgEmail, options: &ParseOptions) {
+    if options.rewrite_html_charset {
+        if let Some(body) = &mut email.body_html {
+            *body = html::force_utf8_charset(body);
+        }
+    }
+
+    if let Some(body) = &email.body_html {
+        email.remote_resources = remote::list_remote_resources(body);
+    }
+
+    email.text_direction = email
+        .body_text
+        .as_deref()
+        .or(email.body_html.as_deref())
+        .and_then(direction::detect_direction);
+
+    if let Some(max_chars) = options.max_body_chars {
+        for body in [&mut email.body_text, &mut email.body_html]
+            .into_iter()
+            .flatten()
+        {
+            email.truncated |= truncate_chars(body, max_chars);
+        }
+    }
+}
+
+/// 把字符串截断到至多 `max_chars` 个字符，返回是否发生了截断
+pub(crate) fn truncate_chars(text: &mut String, max_chars: usize) -> bool {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            text.truncate(end);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 按流名中的属性 ID 和类型后缀（001F 为 UTF-16 LE，001E 为 8 位字符串，0102 为二进制）
+/// 解码 `__substg1.0_` 流，并写入邮件结构体
+fn parse_property(email: &mut MsgEmail, prop_name: &str, data: &[u8], charsets: locale::Charsets) {
+    let (Some(tag), Some(prop_type)) = (prop_name.get(12..16), stream_type(prop_name)) else {
+        return;
+    };
+    let value = if tag == TAG_BODY_HTML && prop_type == PT_BINARY {
+        // HTML 正文通常以二进制存储，字符集由 PR_INTERNET_CPID 声明
+        PropertyValue::String(decode_string8(data, charsets.html))
+    } else {
+        match props::decode_stream(prop_type, data, charsets.string8) {
+            Some(value) => value,
+            None => return,
+        }
+    };
+
+    apply_property(email, tag, &value);
+}
+
+/// `__substg1.0_XXXXTTTT` 流名中的属性类型 `TTTT`
+pub(crate) fn stream_type(name: &str) -> Option<u16> {
+    u16::from_str_radix(name.get(16..20)?, 16).ok()
+}
+
+/// 按属性 ID（4 位十六进制）把属性值写入邮件结构体
+fn apply_property(email: &mut MsgEmail, tag: &str, value: &PropertyValue) {
+    let text = value
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty());
+    match (tag, text) {
+        (TAG_SUBJECT, Some(text)) => email.subject = Some(text.to_string()),
+        (TAG_SENDER_NAME, Some(text)) => email.sender_name = Some(text.to_string()),
+        (TAG_SENDER_EMAIL_1 | TAG_SENDER_EMAIL_2 | TAG_SENDER_EMAIL_3, Some(text)) => {
+            email.sender_email = Some(text.to_string());
+        }
+        (TAG_DISPLAY_TO, Some(text)) => {
+            for recipient in text.split(';') {
+                let r = recipient.trim().to_string();
+                if !r.is_empty() {
+                    email.recipients_flat.push(r);
+                }
+            }
+        }
+        (TAG_RECIPIENT_EMAIL, Some(text)) => {
+            for recipient in text.split(';') {
+                let r = recipient.trim().to_string();
+                if !r.is_empty() && r.contains('@') {
+                    email.recipients_flat.push(r);
+                }
+            }
+        }
+        (TAG_DISPLAY_CC, Some(text)) => {
+            for cc in text.split(';') {
+                let c = cc.trim().to_string();
+                if !c.is_empty() {
+                    email.cc_recipients_flat.push(c);
+                }
+            }
+        }
+        // 有收件人表时会被收件人表中的密送行覆盖
+        (TAG_DISPLAY_BCC, Some(text)) => {
+            email.bcc_recipients = text.split(';').filter_map(Address::parse).collect();
+        }
+        (TAG_TRANSPORT_HEADERS, Some(text)) if email.sent_time.is_none() => {
+            for line in text.lines() {
+                if line.to_lowercase().starts_with("date:") {
+                    let date = line[5..].trim();
+                    email.sent_time = Some(
+                        datetime::parse_rfc2822(date)
+                            .map_or_else(|| date.to_string(), datetime::format_rfc3339),
+                    );
+                    break;
+                }
+            }
+        }
+        (TAG_CLIENT_SUBMIT_TIME | TAG_MESSAGE_DELIVERY_TIME, Some(datetime))
+            if matches!(value, PropertyValue::Time(_))
+                && (email.sent_time.is_none() || tag == TAG_CLIENT_SUBMIT_TIME) =>
+        {
+            email.sent_time = Some(datetime.to_string());
+        }
+        (TAG_BODY, Some(text)) => email.body_text = Some(text.to_string()),
+        (TAG_BODY_HTML, Some(text)) => email.body_html = Some(text.to_string()),
+        (TAG_BODY_RTF, _) => {
+            if let PropertyValue::Binary(data) = value {
+                if data.len() >= 16 {
+                    if let Ok(decompressed) = compressed_rtf::decompress_rtf(data) {
+                        if !decompressed.trim().is_empty() {
+                            email.body_rtf = Some(decompressed);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_attachment_internal<R: Read + std::io::Seek>(
+    comp: &mut CompoundFile<R>,
+    attach_path: &std::path::Path,
+    quirks: &mut quirks::Quirks,
+    allow: &[String],
+    deny: &[String],
+    max_size: Option<u64>,
+) -> Result<Attachment, Box<dyn std::error::Error>> {
+    let mut entry = read_attachment_entry(comp, attach_path, quirks)?;
+    let mut attachment = std::mem::take(&mut entry.attachment);
+
+    if !filter::accepts(&attachment, allow, deny) {
+        return Err("附件已被筛选排除".into());
+    }
+
+    let too_large = max_size.is_some_and(|max_size| entry.data_len > max_size);
+    if !too_large {
+        if let Ok(data) = entry.read_data(comp) {
+            attachment.data = data;
+        }
+    }
+
+    let is_embedded = entry.embedded_storage.is_some();
+    if entry.data_len == 0 && attachment.filename == "未命名附件" && !is_embedded {
+        return Err("附件数据为空".into());
+    }
+
+    #[cfg(feature = "archive")]
+    {
+        attachment.archive_entries = archive::list_zip(&attachment.data);
+    }
+
+    Ok(attachment)
+}
+
+/// 把从 S/MIME、TNEF 等封装中解出的附件加入邮件，与存储中的附件一样经过
+/// `attachment_allow` / `attachment_deny` 筛选，超出 `max_attachment_size` 时丢弃数据
+pub(crate) fn push_unwrapped_attachment(
+    email: &mut MsgEmail,
+    mut attachment: Attachment,
+    options: &ParseOptions,
+) {
+    if !filter::accepts(
+        &attachment,
+        &options.attachment_allow,
+        &options.attachment_deny,
+    ) {
+        return;
+    }
+    if let Some(max_size) = options
+        .max_attachment_size
+        .filter(|&max_size| attachment.size > max_size)
+    {
+        email.warnings.push(format!(
+            "附件 {} 大小 {} 字节超出限制 {} 字节，未读取数据",
+            attachment.filename, attachment.size, max_size
+        ));
+        attachment.data = Vec::new();
+    }
+    #[cfg(feature = "archive")]
+    if attachment.archive_entries.is_none() {
+        attachment.archive_entries = archive::list_zip(&attachment.data);
+    }
+    email.attachments.push(attachment);
+}
+
+/// 附件元数据，以及尚未读取的二进制数据流的位置
+pub(crate) struct AttachmentEntry {
+    /// `data` 为空的附件结构体
+    pub attachment: Attachment,
+    /// PR_ATTACH_DATA_BIN 流的路径
+    pub data_path: Option<PathBuf>,
+    /// 二进制数据长度（字节）
+    pub data_len: u64,
+    /// OLE 对象附件的对象存储和识别结果，此时 `data_path` 为空，数据在读取时才转换
+    pub ole_object: Option<(PathBuf, ole::OleObject)>,
+    /// 嵌入消息的存储
+    pub embedded_storage: Option<PathBuf>,
+}
+
+impl AttachmentEntry {
+    /// 读取附件数据，OLE 对象在此时转换为文件；没有数据时返回空
+    pub(crate) fn read_data<R: Read + std::io::Seek>(
+        &self,
+        comp: &mut CompoundFile<R>,
+    ) -> std::io::Result<Vec<u8>> {
+        match (&self.ole_object, &self.data_path) {
+            (Some((storage, object)), _) => object.read(comp, storage),
+            (None, Some(path)) => props::read_stream(comp, path),
+            (None, None) => Ok(Vec::new()),
+        }
+    }
+}
+
+/// 附件属性流中的定长属性，返回 (PR_ATTACH_NUM, PR_ATTACH_METHOD)
+fn read_attachment_numbers<R: Read + std::io::Seek>(
+    comp: &mut CompoundFile<R>,
+    attach_path: &std::path::Path,
+    quirks: &mut quirks::Quirks,
+    buffer: &mut Vec<u8>,
+) -> (Option<u32>, Option<u32>) {
+    if props::read_stream_into(comp, attach_path.join(props::PROPERTIES_STREAM), buffer).is_err() {
+        return (None, None);
+    }
+    let header_len = quirks.property_header_len(buffer.len(), props::CHILD_HEADER_LEN);
+    let props = props::parse_property_stream(buffer, header_len);
+    let find = |id| {
+        props
+            .iter()
+            .find(|prop| prop.id() == id && !prop.is_error())
+            .map(|prop| prop.as_u32())
+    };
+    (find(PROP_ATTACH_NUM), find(PROP_ATTACH_METHOD))
+}
+
+/// 附件的嵌入消息存储。PR_ATTACH_METHOD 为 ATTACH_OLE 时同名存储中是 OLE 对象，
+/// 不算嵌入消息；没有附件方式属性时按嵌入消息处理
+fn embedded_storage_for<R: Read + std::io::Seek>(
+    comp: &CompoundFile<R>,
+    attach_path: &std::path::Path,
+    method: Option<u32>,
+) -> Option<PathBuf> {
+    let storage = attach_path.join(task::EMBEDDED_MESSAGE_STORAGE);
+    (method != Some(ATTACH_OLE) && comp.is_storage(&storage)).then_some(storage)
+}
+
+/// 附件的嵌入消息存储，见 [`embedded_storage_for`]
+pub(crate) fn embedded_message_storage<R: Read + std::io::Seek>(
+    comp: &mut CompoundFile<R>,
+    attach_path: &std::path::Path,
+    quirks: &mut quirks::Quirks,
+) -> Option<PathBuf> {
+    let (_, method) = read_attachment_numbers(comp, attach_path, quirks, &mut Vec::new());
+    embedded_storage_for(comp, attach_path, method)
+}
+
+/// 读取附件存储中除二进制数据以外的全部属性
+pub(crate) fn read_attachment_entry<R: Read + std::io::Seek>(
+    comp: &mut CompoundFile<R>,
+    attach_path: &std::path::Path,
+    quirks: &mut quirks::Quirks,
+) -> Result<AttachmentEntry, Box<dyn std::error::Error>> {
+    let mut entry = AttachmentEntry {
+        attachment: Attachment {
+            filename: "未命名附件".to_string(),
+            ..Default::default()
+        },
+        data_path: None,
+        data_len: 0,
+        ole_object: None,
+        embedded_storage: None,
+    };
+
+    let mut attachment_streams: Vec<(String, PathBuf, u64)> = Vec::new();
+
+    // 只读取附件存储的直接子项，嵌入消息的子存储由专门的逻辑处理
+    comp.read_storage(attach_path)?.for_each(|child| {
+        if child.is_stream() {
+            let name = quirks.canonical_name(child.name());
+            attachment_streams.push((name, child.path().to_path_buf(), child.len()));
+        }
+    });
+
+    // 属性值解码后即丢弃，所有流复用同一个缓冲区
+    let mut buffer = Vec::new();
+    for (name, path, len) in attachment_streams {
+        let Some(tag) = name
+            .len()
+            .checked_sub(8)
+            .and_then(|start| name.get(start..start + 4))
+        else {
+            continue;
+        };
+
+        if tag == TAG_ATTACH_DATA_BIN {
+            entry.data_path = Some(path);
+            entry.data_len = len;
+            entry.attachment.size = len;
+            continue;
+        }
+
+        if props::read_stream_into(comp, &path, &mut buffer).is_ok() {
+            let prop_type = name
+                .get(name.len() - 4..)
+                .and_then(|prop_type| u16::from_str_radix(prop_type, 16).ok());
+            apply_attachment_property(&mut entry.attachment, tag, prop_type, &buffer);
+        }
+    }
+
+    // 附件编号和附件方式是定长属性，只存放在附件的属性流中
+    let (attach_num, method) = read_attachment_numbers(comp, attach_path, quirks, &mut buffer);
+    entry.attachment.attach_num = attach_num;
+    entry.attachment.storage_index = attach_path
+        .file_name()
+        .and_then(|name| name.to_str()?.rsplit_once('#'))
+        .and_then(|(_, index)| u32::from_str_radix(index, 16).ok());
+
+    // OLE 对象附件 (ATTACH_OLE) 没有二进制数据流，这里只识别对象类型，数据在读取时转换
+    entry.embedded_storage = embedded_storage_for(comp, attach_path, method);
+    let storage = attach_path.join(task::EMBEDDED_MESSAGE_STORAGE);
+    if method == Some(ATTACH_OLE) && entry.data_path.is_none() && comp.is_storage(&storage) {
+        if let Some(object) = ole::read_ole_object(comp, &storage) {
+            apply_ole_object(&mut entry, storage, object);
+        }
+    }
+
+    Ok(entry)
+}
+
+/// 用识别出的 OLE 对象补全附件：没有文件名或扩展名时按对象类型命名
+fn apply_ole_object(entry: &mut AttachmentEntry, storage: PathBuf, object: ole::OleObject) {
+    let attachment = &mut entry.attachment;
+    let unnamed = attachment.filename == "未命名附件";
+    if unnamed || ole::extension(&attachment.filename).is_none() {
+        attachment.filename = match object.filename.clone() {
+            Some(filename) => filename,
+            None if unnamed => format!("attachment.{}", object.extension),
+            None => format!("{}.{}", attachment.filename, object.extension),
+        };
+    }
+    if attachment.content_type.is_none() {
+        attachment.content_type = Some(object.content_type.to_string());
+    }
+    entry.data_len = object.size;
+    attachment.size = object.size;
+    entry.ole_object = Some((storage, object));
+}
+
+fn apply_attachment_property(
+    attachment: &mut Attachment,
+    tag: &str,
+    prop_type: Option<u16>,
+    stream_data: &[u8],
+) {
+    let text = || {
+        let text = match prop_type {
+            Some(prop_type @ (0x001E | 0x001F)) => {
+                match props::decode_stream(prop_type, stream_data, None)? {
+                    PropertyValue::String(text) => text,
+                    _ => return None,
+                }
+            }
+            // 类型后缀异常的流名按内容探测
+            _ => decode_with_encoding(stream_data)?.0,
+        };
+        Some(text.trim().to_string()).filter(|text| !text.is_empty())
+    };
+    match tag {
+        TAG_ATTACH_FILENAME_LONG => {
+            if let Some(text) = text() {
+                attachment.filename = mime::decode_filename(&text);
+            }
+        }
+        TAG_ATTACH_FILENAME_SHORT | TAG_ATTACH_DISPLAY_NAME
+            if attachment.filename == "未命名附件" =>
+        {
+            if let Some(text) = text() {
+                attachment.filename = mime::decode_filename(&text);
+            }
+        }
+        TAG_ATTACH_EXTENSION if attachment.filename == "未命名附件" => {
+            if let Some(ext) = text() {
+                attachment.filename = format!("attachment{}", ext);
+            }
+        }
+        TAG_ATTACH_MIME_TAG => {
+            if let Some(text) = text() {
+                // 其他邮件系统转换来的文件可能只在 MIME 类型的 name 参数中保留文件名
+                if attachment.filename == "未命名附件" {
+                    if let Some(name) = mime::name_parameter(&text) {
+                        attachment.filename = name;
+                    }
+                }
+                attachment.content_type = Some(text);
+            }
+        }
+        #[cfg(feature = "wmf-preview")]
+        TAG_ATTACH_RENDERING => {
+            attachment.preview_png = wmf::wmf_to_png(stream_data);
+        }
+        TAG_ATTACH_CONTENT_ID => {
+            if let Some(text) = text() {
+                let cid = text.trim_matches(|c| c == '<' || c == '>').to_string();
+                if !cid.is_empty() {
+                    attachment.content_id = Some(cid);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn decode_with_encoding(data: &[u8]) -> Option<(String, String)> {
+    if data.is_empty() {
+        return None;
+    }
+
+    // 1. Try UTF-16 LE (most common for modern MSG)
+    if data.len() >= 2 && data.len().is_multiple_of(2) {
+        let u16_vec: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+            .take_while(|&val| val != 0) // Stop at null terminator
+            .collect();
+
+        if !u16_vec.is_empty() {
+            let text = String::from_utf16_lossy(&u16_vec);
+            let trimmed = text.trim();
+            // Heuristic: check if it looks like reasonable text
+            if !trimmed.is_empty()
+                && trimmed
+                    .chars()
+                    .any(|c| c.is_alphanumeric() || c.is_whitespace())
+            {
+                return Some((trimmed.to_string(), "UTF-16 LE".to_string()));
+            }
+        }
+    }
+
+    detect_8bit(data)
+}
+
+/// 解码 8 位字符串属性 (PT_STRING8)：已知字符集时按其解码，否则依次探测 UTF-8、GBK
+pub(crate) fn decode_string8(
+    data: &[u8],
+    encoding: Option<&'static encoding_rs::Encoding>,
+) -> String {
+    let data = data.split(|&b| b == 0).next().unwrap_or_default();
+    match encoding {
+        Some(encoding) => encoding.decode_without_bom_handling(data).0.into_owned(),
+        None => detect_8bit(data).map(|(text, _)| text).unwrap_or_default(),
+    }
+}
+
+fn detect_8bit(data: &[u8]) -> Option<(String, String)> {
+    // 2. Try UTF-8
+    if let Ok(text) = String::from_utf8(data.to_vec()) {
+        let text = text.trim_end_matches('\0').trim();
+        if !text.is_empty() {
+            return Some((text.to_string(), "UTF-8".to_string()));
+        }
+    }
+
+    // 3. Try GBK (common in Chinese environments)
+    let (decoded, _, had_errors) = encoding_rs::GBK.decode(data);
+    if !had_errors {
+        let text = decoded.trim_end_matches('\0').trim();
+        if !text.is_empty() {
+            return Some((text.to_string(), "GBK".to_string()));
+        }
+    }
+
+    // 4. Fallback to Lossy UTF-8
+    let text = String::from_utf8_lossy(data).to_string();
+    let text = text.trim_end_matches('\0').trim();
+    if !text.is_empty() {
+        return Some((text.to_string(), "UTF-8 (lossy)".to_string()));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
+    use std::io::{Cursor, Write};
+
+    /// 在内存中构造一个只包含给定流的 MSG 文件
+    pub(crate) fn build_msg(streams: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut comp = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        for (path, data) in streams {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                if !comp.exists(parent) {
+                    comp.create_storage_all(parent).unwrap();
+                }
+            }
+            comp.create_stream(path).unwrap().write_all(data).unwrap();
+        }
+        comp.into_inner().into_inner()
+    }
+
+    /// 构造命名属性映射的三个流，第 i 个 (GUID, LID) 分配到属性 ID 0x8000 + i
+    fn nameid_streams(props: &[(&str, u32)]) -> Vec<(&'static str, Vec<u8>)> {
+        let mut guids: Vec<&str> = Vec::new();
+        let mut entries = Vec::new();
+        for (i, (guid, lid)) in props.iter().enumerate() {
+            let index = match guids.iter().position(|g| g == guid) {
+                Some(index) => index,
+                None => {
+                    guids.push(guid);
+                    guids.len() - 1
+                }
+            };
+            entries.extend_from_slice(&lid.to_le_bytes());
+            entries.extend_from_slice(&(((index as u16) + 3) << 1).to_le_bytes());
+            entries.extend_from_slice(&(i as u16).to_le_bytes());
+        }
+        let guid_bytes = guids
+            .iter()
+            .flat_map(|guid| {
+                let hex: String = guid.chars().filter(|c| *c != '-').collect();
+                let b: Vec<u8> = (0..16)
+                    .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap())
+                    .collect();
+                [
+                    b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6], b[8], b[9], b[10], b[11],
+                    b[12], b[13], b[14], b[15],
+                ]
+            })
+            .collect();
+        vec![
+            ("/__nameid_version1.0/__substg1.0_00020102", guid_bytes),
+            ("/__nameid_version1.0/__substg1.0_00030102", entries),
+            ("/__nameid_version1.0/__substg1.0_00040102", Vec::new()),
+        ]
+    }
+
+    /// 构造属性流，`header_len` 为头部长度
+    fn property_stream(header_len: usize, props: &[(u32, u64)]) -> Vec<u8> {
+        let mut data = vec![0u8; header_len];
+        for (tag, value) in props {
+            data.extend_from_slice(&tag.to_le_bytes());
+            data.extend_from_slice(&6u32.to_le_bytes());
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
 
     #[test]
     fn test_filetime_to_string() {
-        // 2023-10-27 08:44:20 (UTC) approx
         let ft: u64 = 133428698600000000;
-        let s = filetime_to_string(ft).unwrap();
-        assert!(s.contains("2023"));
-        assert!(s.contains("UTC"));
+        let s = datetime::filetime_to_string(ft).unwrap();
+        assert_eq!(s, "2023-10-27T08:44:20Z");
     }
 
     #[test]
@@ -412,10 +1949,51 @@ mod tests {
     fn test_parse_property_subject() {
         let mut email = MsgEmail::default();
         let data = vec![0x54, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74, 0x00]; // "Test" in UTF-16 LE
-        parse_property(&mut email, "__substg1.0_0037001F", &data);
+        parse_property(
+            &mut email,
+            "__substg1.0_0037001F",
+            &data,
+            Default::default(),
+        );
         assert_eq!(email.subject, Some("Test".to_string()));
     }
 
+    #[test]
+    fn test_parse_property_type_suffix() {
+        let mut email = MsgEmail::default();
+        // 偶数长度的 8 位字符串不能当作 UTF-16 解码
+        parse_property(
+            &mut email,
+            "__substg1.0_0037001E",
+            b"Hi",
+            Default::default(),
+        );
+        assert_eq!(email.subject.as_deref(), Some("Hi"));
+        // 没有字母数字的 UTF-16 字符串不能当作 8 位字符串解码
+        parse_property(
+            &mut email,
+            "__substg1.0_0C1A001F",
+            b"-\0-\0",
+            Default::default(),
+        );
+        assert_eq!(email.sender_name.as_deref(), Some("--"));
+        parse_property(
+            &mut email,
+            "__substg1.0_10130102",
+            b"<p>",
+            Default::default(),
+        );
+        assert_eq!(email.body_html.as_deref(), Some("<p>"));
+        // 正文不是字符串类型时忽略
+        parse_property(
+            &mut email,
+            "__substg1.0_10000003",
+            b"abcd",
+            Default::default(),
+        );
+        assert_eq!(email.body_text, None);
+    }
+
     #[test]
 
     fn test_parse_property_time() {
@@ -425,11 +2003,1340 @@ mod tests {
 
         let data = 133428698600000000u64.to_le_bytes().to_vec();
 
-        parse_property(&mut email, "__substg1.0_00390040", &data);
+        parse_property(
+            &mut email,
+            "__substg1.0_00390040",
+            &data,
+            Default::default(),
+        );
 
         assert!(email.sent_time.is_some());
     }
 
+    #[test]
+    fn test_estimate_stream_output() {
+        assert_eq!(estimate_stream_output("__substg1.0_37010102", 100), 100);
+        assert_eq!(estimate_stream_output("__substg1.0_0037001E", 100), 200);
+        assert_eq!(estimate_stream_output("__properties_version1.0", 100), 0);
+        assert_eq!(estimate_stream_output("__substg1.0_00370ééé", 100), 0);
+    }
+
+    #[test]
+    fn test_non_ascii_stream_name() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            ("/__substg1.0_0037001F", utf16("Hello")),
+            ("/__substg1.0_00370ééé", b"x".to_vec()),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3707ééé",
+                b"x".to_vec(),
+            ),
+        ]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_output_budget_exceeded() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let options = ParseOptions {
+            max_output_size: Some(1),
+            ..Default::default()
+        };
+
+        let err = parse_msg_with_options(file_data, &options).unwrap_err();
+
+        assert!(matches!(
+            err,
+            MsgParseError::OutputTooLarge { limit: 1, .. }
+        ));
+        assert_eq!(err.code(), "output_too_large");
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+
+        let err = parse_msg_to_struct(b"From: alice@example.com\r\n").unwrap_err();
+        assert!(matches!(err, MsgParseError::NotCfbFile));
+        let err = parse_msg_to_struct(&file_data[..600]).unwrap_err();
+        assert_eq!(err.code(), "corrupt_file");
+
+        let mut doc = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        doc.create_stream("/WordDocument").unwrap();
+        let doc = doc.into_inner().into_inner();
+        let err = parse_msg_to_struct(&doc).unwrap_err();
+        assert_eq!(err.code(), "not_msg_file");
+    }
+
+    #[test]
+    fn test_unsent_message_has_no_sent_time() {
+        let file_data = build_msg(&[
+            (
+                "/__substg1.0_0037001F",
+                "Draft".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            ),
+            (
+                "/__properties_version1.0",
+                property_stream(32, &[(0x0E07_0003, 0x8), (0x0E06_0040, 133428698600000000)]),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        assert_eq!(email.subject.as_deref(), Some("Draft"));
+        assert!(email.sent_time.is_none());
+        assert!(!email.is_template);
+    }
+
+    #[test]
+    fn test_transport_headers() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[(
+            "/__substg1.0_007D001F",
+            utf16(concat!(
+                "Received: from a\r\n\tby b\r\n",
+                "Message-ID: <1@example.com>\r\n",
+                "Subject: =?UTF-8?B?5L2g5aW9?=\r\n",
+                "X-Custom: one\r\n two\r\n",
+                "Date: Fri, 27 Oct 2023 16:44:20 +0800\r\n\r\n",
+            )),
+        )]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let pairs: Vec<_> = email
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("Received", "from a by b"),
+                ("Message-ID", "<1@example.com>"),
+                ("Subject", "你好"),
+                ("X-Custom", "one two"),
+                ("Date", "Fri, 27 Oct 2023 16:44:20 +0800"),
+            ]
+        );
+        assert_eq!(email.sent_time.as_deref(), Some("2023-10-27T08:44:20Z"));
+    }
+
+    #[test]
+    fn test_date_properties() {
+        let file_data = build_msg(&[(
+            "/__properties_version1.0",
+            property_stream(
+                32,
+                &[
+                    (0x0039_0040, 133428698600000000),
+                    (0x0E06_0040, 133428698610000000),
+                    (0x3007_0040, 133428698620000000),
+                    (0x3008_0040, 133428698630000000),
+                ],
+            ),
+        )]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.sent_time.as_deref(), Some("2023-10-27T08:44:20Z"));
+        assert_eq!(email.sent_time_unix, Some(1698396260));
+        assert_eq!(email.client_submit_time, email.sent_time);
+        assert_eq!(
+            email.message_delivery_time.as_deref(),
+            Some("2023-10-27T08:44:21Z")
+        );
+        assert_eq!(email.creation_time.as_deref(), Some("2023-10-27T08:44:22Z"));
+        assert_eq!(
+            email.last_modification_time.as_deref(),
+            Some("2023-10-27T08:44:23Z")
+        );
+    }
+
+    #[test]
+    fn test_quirks_lowercase_tags() {
+        let file_data = build_msg(&[(
+            "/__substg1.0_0c1a001f",
+            "Aspose".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        )]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.sender_name.as_deref(), Some("Aspose"));
+        assert!(!email.warnings.is_empty());
+
+        let strict = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &strict).unwrap();
+        assert!(email.sender_name.is_none());
+    }
+
+    #[test]
+    fn test_named_property_extraction() {
+        // 条目 0：PS_PUBLIC_STRINGS 下名为 "x-crm-id" 的字符串属性 → 0x8000
+        let name: Vec<u8> = "x-crm-id"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let mut strings = (name.len() as u32).to_le_bytes().to_vec();
+        strings.extend_from_slice(&name);
+        let mut entries = 0u32.to_le_bytes().to_vec();
+        entries.extend_from_slice(&((2u16 << 1) | 1).to_le_bytes());
+        entries.extend_from_slice(&0u16.to_le_bytes());
+
+        let file_data = build_msg(&[
+            (
+                "/__substg1.0_0037001F",
+                "Hi".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            ),
+            ("/__nameid_version1.0/__substg1.0_00020102", Vec::new()),
+            ("/__nameid_version1.0/__substg1.0_00030102", entries),
+            ("/__nameid_version1.0/__substg1.0_00040102", strings),
+            (
+                "/__substg1.0_8000001F",
+                "A-42".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            ),
+        ]);
+        let options = ParseOptions {
+            named_properties: vec![NamedPropertyRequest {
+                guid: "{00020329-0000-0000-C000-000000000046}".to_string(),
+                name: Some("x-crm-id".to_string()),
+                lid: None,
+            }],
+            ..Default::default()
+        };
+
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+
+        assert_eq!(email.named_properties.len(), 1);
+        assert_eq!(
+            email.named_properties[0].value,
+            PropertyValue::String("A-42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_categories_and_flag_request() {
+        let utf16 = |s: &str| {
+            s.encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<u8>>()
+        };
+        // 条目 0：PSETID_Common 的 PidLidFlagRequest → 0x8000
+        // 条目 1：PS_PUBLIC_STRINGS 下的 "Keywords" → 0x8001
+        let mut streams = nameid_streams(&[(named::PSETID_COMMON, LID_FLAG_REQUEST)]);
+        let name = utf16(NAME_KEYWORDS);
+        streams[1].1.extend_from_slice(&0u32.to_le_bytes());
+        streams[1]
+            .1
+            .extend_from_slice(&((2u16 << 1) | 1).to_le_bytes());
+        streams[1].1.extend_from_slice(&1u16.to_le_bytes());
+        streams[2].1 = [&(name.len() as u32).to_le_bytes()[..], &name].concat();
+        streams.extend([
+            ("/__substg1.0_8000001F", utf16("Follow up")),
+            ("/__substg1.0_8001101F", vec![0u8; 8]),
+            ("/__substg1.0_8001101F-00000000", utf16("Red")),
+            ("/__substg1.0_8001101F-00000001", utf16("Project X")),
+        ]);
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.categories, ["Red", "Project X"]);
+        assert_eq!(email.flag_request.as_deref(), Some("Follow up"));
+        let flag = email.flag.unwrap();
+        assert_eq!(flag.request.as_deref(), Some("Follow up"));
+        assert_eq!(flag.status, None);
+        assert!(email.named_properties.is_empty());
+
+        let options = ParseOptions {
+            all_named_properties: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        let names: Vec<_> = email
+            .named_properties
+            .iter()
+            .map(|p| (p.guid.as_str(), p.name.as_deref(), p.lid))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (named::PSETID_COMMON, None, Some(LID_FLAG_REQUEST)),
+                (named::PS_PUBLIC_STRINGS, Some(NAME_KEYWORDS), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_property_projection() {
+        let file_data = build_msg(&[
+            (
+                "/__substg1.0_0037001F",
+                "Hi".encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            ),
+            (
+                "/__properties_version1.0",
+                property_stream(32, &[(0x0E08_0003, 2048)]),
+            ),
+        ]);
+        let options = ParseOptions {
+            custom_properties: vec![0x0E08_0003, 0x0037, 0x1234_001F],
+            ..Default::default()
+        };
+
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+
+        assert_eq!(
+            email.custom.get(&0x0E08_0003),
+            Some(&PropertyValue::Int32(2048))
+        );
+        assert_eq!(
+            email.custom.get(&0x0037),
+            Some(&PropertyValue::String("Hi".to_string()))
+        );
+        assert!(!email.custom.contains_key(&0x1234_001F));
+    }
+
+    #[test]
+    fn test_get_raw_property_bytes() {
+        let search_key = vec![0xAB; 16];
+        let file_data = build_msg(&[
+            ("/__substg1.0_300B0102", search_key.clone()),
+            (
+                "/__properties_version1.0",
+                property_stream(32, &[(0x0E07_0003, 0x11)]),
+            ),
+        ]);
+
+        assert_eq!(
+            get_raw_property_bytes(&file_data, 0x300B_0102).unwrap(),
+            Some(search_key.clone())
+        );
+        assert_eq!(
+            get_raw_property_bytes(&file_data, 0x300B).unwrap(),
+            Some(search_key)
+        );
+        assert_eq!(
+            get_raw_property_bytes(&file_data, 0x0E07).unwrap(),
+            Some(0x11u64.to_le_bytes().to_vec())
+        );
+        assert_eq!(get_raw_property_bytes(&file_data, 0x0037).unwrap(), None);
+    }
+
+    #[test]
+    fn test_search_and_change_keys() {
+        let file_data = build_msg(&[
+            ("/__substg1.0_300B0102", vec![0x01, 0xAB, 0xFF]),
+            ("/__substg1.0_65E20102", vec![0x10, 0x20]),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        assert_eq!(email.search_key.as_deref(), Some("01ABFF"));
+        assert_eq!(email.change_key.as_deref(), Some("1020"));
+    }
+
+    #[test]
+    fn test_conversation_topic_and_index() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut index = vec![0x01, 0xD2, 0x00, 0x00, 0x00, 0x00];
+        index.extend_from_slice(&[0x11; 16]);
+        index.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0x00]);
+        let file_data = build_msg(&[
+            ("/__substg1.0_0037001F", utf16("RE: Budget")),
+            ("/__substg1.0_0070001F", utf16("Budget")),
+            ("/__substg1.0_00710102", index),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        assert_eq!(email.conversation_topic.as_deref(), Some("Budget"));
+        let conversation = email.conversation.unwrap();
+        assert_eq!(conversation.guid, "11111111-1111-1111-1111-111111111111");
+        assert_eq!(conversation.replies.len(), 1);
+    }
+
+    #[test]
+    fn test_message_ids() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let headers = "Message-ID: <header@example.com>\r\nIn-Reply-To: <parent@example.com>\r\nReferences: <root@example.com>\r\n <parent@example.com>\r\n\r\n";
+        let file_data = build_msg(&[
+            ("/__substg1.0_1035001F", utf16("<prop@example.com>")),
+            ("/__substg1.0_007D001F", utf16(headers)),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        // 属性优先，缺失的字段取传输头
+        assert_eq!(email.message_id.as_deref(), Some("<prop@example.com>"));
+        assert_eq!(email.in_reply_to.as_deref(), Some("<parent@example.com>"));
+        assert_eq!(
+            email.references,
+            ["<root@example.com>", "<parent@example.com>"]
+        );
+    }
+
+    #[test]
+    fn test_appointment_fields() {
+        let mut streams = nameid_streams(&[
+            (named::PSETID_APPOINTMENT, 0x8205),
+            (named::PSETID_APPOINTMENT, 0x8215),
+            (named::PSETID_COMMON, 0x8501),
+            (named::PSETID_COMMON, 0x8503),
+            (named::PSETID_COMMON, 0x8506),
+            (named::PSETID_APPOINTMENT, 0x823B),
+        ]);
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        streams.extend([
+            ("/__substg1.0_8005001F", utf16("Alice; Bob ;")),
+            ("/__substg1.0_0C1A001F", utf16("Carol")),
+        ]);
+        streams.push((
+            "/__substg1.0_001A001F",
+            "IPM.Appointment"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        ));
+        streams.push((
+            "/__properties_version1.0",
+            property_stream(
+                32,
+                &[
+                    (0x8000_0003, 3),
+                    (0x8001_000B, 1),
+                    (0x8002_0003, 15),
+                    (0x8003_000B, 1),
+                    (0x8004_000B, 1),
+                ],
+            ),
+        ));
+        let file_data = build_msg(&streams);
+
+        let appointment = parse_msg_to_struct(&file_data)
+            .unwrap()
+            .appointment
+            .unwrap();
+
+        assert_eq!(appointment.busy_status, Some(BusyStatus::OutOfOffice));
+        assert!(appointment.all_day);
+        assert_eq!(appointment.reminder_minutes, Some(15));
+        assert!(appointment.is_private);
+        assert_eq!(appointment.organizer.as_deref(), Some("Carol"));
+        assert_eq!(appointment.required_attendees, ["Alice", "Bob"]);
+        assert!(appointment.optional_attendees.is_empty());
+    }
+
+    #[test]
+    fn test_task_request_embedded_task() {
+        let embedded = "/__attach_version1.0_#00000000/__substg1.0_3701000D";
+        let mut streams =
+            nameid_streams(&[(named::PSETID_TASK, 0x8101), (named::PSETID_TASK, 0x811C)]);
+        streams.push((
+            "/__substg1.0_001A001F",
+            "IPM.TaskRequest.Accept"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        ));
+        let embedded_props = format!("{}/__properties_version1.0", embedded);
+        streams.push((
+            &embedded_props,
+            property_stream(24, &[(0x8000_0003, 2), (0x8001_000B, 1)]),
+        ));
+        let file_data = build_msg(&streams);
+
+        let request = parse_msg_to_struct(&file_data)
+            .unwrap()
+            .task_request
+            .unwrap();
+
+        assert_eq!(request.state, TaskAssignmentState::Accepted);
+        let task = request.task.unwrap();
+        assert_eq!(task.status, Some(TaskStatus::Complete));
+        assert!(task.complete);
+    }
+
+    #[test]
+    fn test_task_fields() {
+        let mut streams = nameid_streams(&[
+            (named::PSETID_TASK, 0x8101),
+            (named::PSETID_TASK, 0x8102),
+            (named::PSETID_TASK, 0x8105),
+            (named::PSETID_COMMON, 0x8503),
+            (named::PSETID_COMMON, 0x8502),
+        ]);
+        streams.push((
+            "/__substg1.0_001A001F",
+            "IPM.Task"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        ));
+        streams.push((
+            "/__properties_version1.0",
+            property_stream(
+                32,
+                &[
+                    (0x8000_0003, 1),
+                    (0x8001_0005, 0.5f64.to_bits()),
+                    (0x8002_0040, 133_428_698_600_000_000),
+                    (0x8003_000B, 1),
+                    (0x8004_0040, 133_428_662_600_000_000),
+                ],
+            ),
+        ));
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.kind, ItemKind::Task);
+        let task = email.task.unwrap();
+        assert_eq!(task.status, Some(TaskStatus::InProgress));
+        assert_eq!(task.percent_complete, Some(0.5));
+        assert_eq!(task.due_date.as_deref(), Some("2023-10-27T08:44:20Z"));
+        assert_eq!(task.reminder_time.as_deref(), Some("2023-10-27T07:44:20Z"));
+        assert!(!task.complete);
+    }
+
+    #[test]
+    fn test_follow_up_flag() {
+        let mut streams = nameid_streams(&[
+            (named::PSETID_TASK, 0x8105),
+            (named::PSETID_COMMON, 0x8503),
+            (named::PSETID_COMMON, 0x8502),
+        ]);
+        streams.push((
+            "/__properties_version1.0",
+            property_stream(
+                32,
+                &[
+                    (0x1090_0003, 2),
+                    (0x1095_0003, 6),
+                    (0x8000_0040, 133_428_698_600_000_000),
+                    (0x8001_000B, 1),
+                    (0x8002_0040, 133_428_662_600_000_000),
+                ],
+            ),
+        ));
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let flag = email.flag.unwrap();
+        assert_eq!(flag.status, Some(FlagStatus::Flagged));
+        assert_eq!(flag.color, Some(FlagColor::Red));
+        assert_eq!(flag.request, None);
+        assert_eq!(flag.due_date.as_deref(), Some("2023-10-27T08:44:20Z"));
+        assert_eq!(flag.reminder_time.as_deref(), Some("2023-10-27T07:44:20Z"));
+        assert!(email.task.is_none());
+    }
+
+    #[test]
+    fn test_voting_response() {
+        let mut streams = nameid_streams(&[(named::PSETID_COMMON, 0x8524)]);
+        streams.push((
+            "/__substg1.0_8000001F",
+            "Approve"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        ));
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let voting = email.voting.unwrap();
+        assert!(voting.options.is_empty());
+        assert_eq!(voting.response.as_deref(), Some("Approve"));
+    }
+
+    #[test]
+    fn test_smime_clear_signed() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let signed = b"Content-Type: multipart/signed; protocol=\"application/pkcs7-signature\"; boundary=\"s\"\r\n\r\n--s\r\nContent-Type: multipart/alternative; boundary=\"a\"\r\n\r\n--a\r\nContent-Type: text/plain\r\n\r\nSigned text\r\n--a\r\nContent-Type: text/html\r\n\r\n<p><img src=\"http://example.com/x.png\"></p>\r\n--a--\r\n--s\r\nContent-Type: application/pkcs7-signature; name=\"smime.p7s\"\r\nContent-Transfer-Encoding: base64\r\n\r\nMAA=\r\n--s--\r\n";
+        let file_data = build_msg(&[
+            (
+                "/__substg1.0_001A001F",
+                utf16("IPM.Note.SMIME.MultipartSigned"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3707001F",
+                utf16("smime.p7m"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_370E001F",
+                utf16("multipart/signed"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_37010102",
+                signed.to_vec(),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        let smime = email.smime.unwrap();
+        assert_eq!(smime.kind, SmimeKind::ClearSigned);
+        assert!(smime.signer.is_none());
+        assert_eq!(email.body_text.as_deref(), Some("Signed text"));
+        assert!(email.body_html.unwrap().contains("<img"));
+        assert_eq!(email.remote_resources.len(), 1);
+        assert!(email.attachments.is_empty());
+
+        let options = ParseOptions {
+            rewrite_html_charset: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert!(email
+            .body_html
+            .unwrap()
+            .contains("<meta charset=\"utf-8\">"));
+    }
+
+    #[test]
+    fn test_contact_photo_attachment() {
+        let photo = "/__attach_version1.0_#00000001";
+        let mut streams = nameid_streams(&[(named::PSETID_ADDRESS, 0x8015)]);
+        streams.extend([
+            (
+                "/__substg1.0_001A001F",
+                "IPM.Contact"
+                    .encode_utf16()
+                    .flat_map(u16::to_le_bytes)
+                    .collect(),
+            ),
+            (
+                "/__properties_version1.0",
+                property_stream(32, &[(0x8000_000B, 1)]),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_37010102",
+                b"resume".to_vec(),
+            ),
+        ]);
+        let photo_props = format!("{}/__properties_version1.0", photo);
+        let photo_data = format!("{}/__substg1.0_37010102", photo);
+        streams.push((&photo_props, property_stream(8, &[(0x7FFF_000B, 1)])));
+        streams.push((&photo_data, b"\x89PNG".to_vec()));
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].data, b"resume");
+        let photo = email.contact.unwrap().photo.unwrap();
+        assert_eq!(photo.data, b"\x89PNG");
+
+        let options = ParseOptions {
+            max_attachment_size: Some(2),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        let photo = email.contact.unwrap().photo.unwrap();
+        assert!(photo.data.is_empty());
+        assert_eq!(photo.size, 4);
+        assert!(email.warnings.iter().any(|w| w.contains("联系人照片")));
+
+        let options = ParseOptions {
+            skip_attachments: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert!(email.attachments.is_empty());
+        assert!(email.contact.unwrap().photo.is_none());
+    }
+
+    #[test]
+    fn test_contact_fields() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut streams = nameid_streams(&[
+            (named::PSETID_ADDRESS, 0x8083),
+            (named::PSETID_ADDRESS, 0x80A3),
+            (named::PSETID_ADDRESS, 0x8046),
+        ]);
+        streams.extend([
+            ("/__substg1.0_001A001F", utf16("IPM.Contact")),
+            ("/__substg1.0_8000001F", utf16("bob@work.example")),
+            ("/__substg1.0_8001001F", utf16("bob@home.example")),
+            ("/__substg1.0_8002001F", utf16("Berlin")),
+            ("/__substg1.0_3A1C001F", utf16("+49 170 000000")),
+            ("/__substg1.0_3A16001F", utf16("Example GmbH")),
+            ("/__substg1.0_3A59001F", utf16("Potsdam")),
+        ]);
+        let file_data = build_msg(&streams);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.kind, ItemKind::Contact);
+        let contact = email.contact.unwrap();
+
+        assert_eq!(contact.emails, ["bob@work.example", "bob@home.example"]);
+        assert_eq!(contact.mobile_phone.as_deref(), Some("+49 170 000000"));
+        assert_eq!(contact.company.as_deref(), Some("Example GmbH"));
+        assert_eq!(
+            contact.work_address.unwrap().city.as_deref(),
+            Some("Berlin")
+        );
+        assert_eq!(
+            contact.home_address.unwrap().city.as_deref(),
+            Some("Potsdam")
+        );
+        assert!(contact.other_address.is_none());
+    }
+
+    #[test]
+    fn test_journal_entry() {
+        let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        let mut streams = nameid_streams(&[
+            (named::PSETID_LOG, 0x8700),
+            (named::PSETID_LOG, 0x8707),
+            (named::PSETID_COMMON, 0x8539),
+        ]);
+        streams.extend([
+            ("/__substg1.0_001A001F", utf16("IPM.Activity")),
+            ("/__substg1.0_8000001F", utf16("Phone call")),
+            (
+                "/__properties_version1.0",
+                property_stream(32, &[(0x8001_0003, 25)]),
+            ),
+            ("/__substg1.0_8002101F", vec![0; 8]),
+            ("/__substg1.0_8002101F-00000000", utf16("Contoso")),
+            ("/__substg1.0_8002101F-00000001", utf16("Fabrikam")),
+        ]);
+        let file_data = build_msg(&streams);
+
+        let journal = parse_msg_to_struct(&file_data).unwrap().journal.unwrap();
+
+        assert_eq!(journal.entry_type.as_deref(), Some("Phone call"));
+        assert_eq!(journal.duration_minutes, Some(25));
+        assert_eq!(journal.companies, ["Contoso", "Fabrikam"]);
+    }
+
+    #[test]
+    fn test_rss_item() {
+        let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        let mut streams = nameid_streams(&[
+            (named::PSETID_POST_RSS, 0x8900),
+            (named::PSETID_POST_RSS, 0x8901),
+        ]);
+        streams.extend([
+            ("/__substg1.0_001A001F", utf16("IPM.Post.Rss")),
+            ("/__substg1.0_8000001F", utf16("https://blog.example/feed")),
+            ("/__substg1.0_8001001F", utf16("https://blog.example/p/1")),
+        ]);
+        let file_data = build_msg(&streams);
+
+        let rss = parse_msg_to_struct(&file_data).unwrap().rss.unwrap();
+
+        assert_eq!(rss.feed_url.as_deref(), Some("https://blog.example/feed"));
+        assert_eq!(rss.item_link.as_deref(), Some("https://blog.example/p/1"));
+    }
+
+    #[test]
+    fn test_recipient_table() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+
+        let email = parse_msg_to_struct(file_data).unwrap();
+
+        assert_eq!(email.recipient_table.len(), 1);
+        let recipient = &email.recipient_table[0];
+        assert_eq!(recipient.display_name.as_deref(), Some("Bob"));
+        assert_eq!(recipient.email.as_deref(), Some("bob@example.com"));
+        assert_eq!(recipient.recipient_type, Some(RecipientType::To));
+    }
+
+    #[test]
+    fn test_locale_values() {
+        let file_data = build_msg(&[(
+            "/__properties_version1.0",
+            property_stream(32, &[(0x3FF1_0003, 2052), (0x3FFD_0003, 936)]),
+        )]);
+
+        let locale = parse_msg_to_struct(&file_data).unwrap().locale;
+
+        assert_eq!(locale.message_locale_id, Some(2052));
+        assert_eq!(locale.message_codepage, Some(936));
+        assert_eq!(locale.internet_cpid, None);
+    }
+
+    #[test]
+    fn test_rewrite_html_charset() {
+        let html: Vec<u8> = "<html><head><meta charset=gb2312></head><body>hi</body></html>"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let file_data = build_msg(&[("/__substg1.0_1013001F", html)]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert!(email.body_html.unwrap().contains("charset=gb2312"));
+
+        let options = ParseOptions {
+            rewrite_html_charset: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(
+            email.body_html.as_deref(),
+            Some("<html><head><meta charset=utf-8></head><body>hi</body></html>")
+        );
+    }
+
+    #[test]
+    fn test_request_flags() {
+        let file_data = build_msg(&[(
+            "/__properties_version1.0",
+            property_stream(32, &[(0x0029_000B, 1), (0x0C17_000B, 0)]),
+        )]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert!(email.read_receipt_requested);
+        assert!(!email.reply_requested);
+        assert!(!email.response_requested);
+    }
+
+    #[test]
+    fn test_from_field() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            ("/__substg1.0_0C1A001F", utf16("Smith, Alice")),
+            ("/__substg1.0_5D01001F", utf16("alice@example.com")),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(
+            email.from.as_deref(),
+            Some("\"Smith, Alice\" <alice@example.com>")
+        );
+        assert_eq!(email.sender_email_source, Some(SenderSource::SmtpAddress));
+    }
+
+    #[test]
+    fn test_sent_on_behalf_of() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            ("/__substg1.0_0C1A001F", utf16("Assistant")),
+            ("/__substg1.0_5D01001F", utf16("assistant@example.com")),
+            ("/__substg1.0_0042001F", utf16("Boss")),
+            ("/__substg1.0_5D02001F", utf16("boss@example.com")),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let sender = email.sender.unwrap();
+        assert_eq!(sender.email.as_deref(), Some("assistant@example.com"));
+        let principal = email.on_behalf_of.unwrap();
+        assert_eq!(principal.name.as_deref(), Some("Boss"));
+        assert_eq!(principal.email.as_deref(), Some("boss@example.com"));
+    }
+
+    #[test]
+    fn test_structured_recipients() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let recip = "/__recip_version1.0_#00000000";
+        let file_data = build_msg(&[
+            (
+                "/__substg1.0_0E04001F",
+                utf16("Bob Smith; carol@example.com"),
+            ),
+            (
+                &format!("{}/__properties_version1.0", recip),
+                property_stream(8, &[(0x0C15_0003, 1)]),
+            ),
+            (
+                &format!("{}/__substg1.0_3001001F", recip),
+                utf16("Bob Smith"),
+            ),
+            (
+                &format!("{}/__substg1.0_3003001F", recip),
+                utf16("bob@example.com"),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(
+            email.recipients,
+            vec![Address {
+                name: Some("Bob Smith".to_string()),
+                email: Some("bob@example.com".to_string()),
+            }]
+        );
+        assert!(email.recipients_flat.is_empty());
+
+        let options = ParseOptions {
+            flat_recipients: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(
+            email.recipients_flat,
+            vec!["Bob Smith", "carol@example.com"]
+        );
+
+        // 没有收件人表时由显示字符串拆分
+        let file_data = build_msg(&[(
+            "/__substg1.0_0E04001F",
+            utf16("Bob Smith; carol@example.com"),
+        )]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.recipients[0].name.as_deref(), Some("Bob Smith"));
+        assert_eq!(
+            email.recipients[1].email.as_deref(),
+            Some("carol@example.com")
+        );
+    }
+
+    #[test]
+    fn test_reply_to() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        // 一次性 ENTRYID（ANSI 字符串）
+        let mut one_off = vec![0u8; 4];
+        one_off.extend_from_slice(&[
+            0x81, 0x2B, 0x1F, 0xA4, 0xBE, 0xA3, 0x10, 0x19, 0x9D, 0x6E, 0x00, 0xDD, 0x01, 0x0F,
+            0x54, 0x02,
+        ]);
+        one_off.extend_from_slice(&[0, 0, 0, 0]);
+        one_off.extend_from_slice(b"Support\0SMTP\0support@example.com\0");
+        let mut entries = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        entries.extend_from_slice(&(one_off.len() as u32).to_le_bytes());
+        entries.extend_from_slice(&one_off);
+        entries.resize(entries.len().next_multiple_of(4), 0);
+        // 无法解码的通讯簿条目，退回显示名
+        entries.extend_from_slice(&[4, 0, 0, 0, 1, 2, 3, 4]);
+        let file_data = build_msg(&[
+            ("/__substg1.0_004F0102", entries),
+            ("/__substg1.0_0050001F", utf16("Support; Team Lead")),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.reply_to, ["support@example.com", "Team Lead"]);
+
+        let file_data = build_msg(&[(
+            "/__substg1.0_007D001F",
+            utf16("Reply-To: \"Help Desk\" <help@example.com>\r\n\r\n"),
+        )]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.reply_to, ["help@example.com"]);
+    }
+
+    #[test]
+    fn test_bcc_recipients() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let recip = "/__recip_version1.0_#00000000";
+
+        // 没有收件人表时由 PR_DISPLAY_BCC 拆分，且不混入收件人
+        let file_data = build_msg(&[
+            ("/__substg1.0_0E04001F", utf16("Bob Smith")),
+            ("/__substg1.0_0E03001F", utf16("Dave; erin@example.com")),
+        ]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.recipients.len(), 1);
+        assert_eq!(email.bcc_recipients[0].name.as_deref(), Some("Dave"));
+        assert_eq!(
+            email.bcc_recipients[1].email.as_deref(),
+            Some("erin@example.com")
+        );
+
+        // 有收件人表时取类型为 MAPI_BCC 的行
+        let file_data = build_msg(&[
+            ("/__substg1.0_0E03001F", utf16("Dave Jones")),
+            (
+                &format!("{}/__properties_version1.0", recip),
+                property_stream(8, &[(0x0C15_0003, 3)]),
+            ),
+            (
+                &format!("{}/__substg1.0_3001001F", recip),
+                utf16("Dave Jones"),
+            ),
+            (
+                &format!("{}/__substg1.0_3003001F", recip),
+                utf16("dave@example.com"),
+            ),
+        ]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert!(email.recipients.is_empty());
+        assert_eq!(
+            email.bcc_recipients,
+            vec![Address {
+                name: Some("Dave Jones".to_string()),
+                email: Some("dave@example.com".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_non_delivery_report() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let recip = "/__recip_version1.0_#00000000";
+        let file_data = build_msg(&[
+            ("/__substg1.0_001A001F", utf16("REPORT.IPM.Note.NDR")),
+            ("/__substg1.0_1042001F", utf16("<abc@example.com>")),
+            (
+                &format!("{}/__properties_version1.0", recip),
+                property_stream(8, &[(0x0C15_0003, 1), (0x0C05_0003, 0), (0x0C20_0003, 550)]),
+            ),
+            (
+                &format!("{}/__substg1.0_39FE001F", recip),
+                utf16("nobody@example.com"),
+            ),
+            (
+                &format!("{}/__substg1.0_0C1B001F", recip),
+                utf16("#550 5.1.1 RESOLVER.ADR.RecipNotFound; not found ##"),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let report = email.report.unwrap();
+        assert_eq!(report.report_type, ReportType::NonDelivery);
+        assert_eq!(report.original_message_class.as_deref(), Some("IPM.Note"));
+        assert_eq!(
+            report.original_message_id.as_deref(),
+            Some("<abc@example.com>")
+        );
+        assert_eq!(report.recipients.len(), 1);
+        let failed = &report.recipients[0];
+        assert_eq!(failed.email.as_deref(), Some("nobody@example.com"));
+        assert_eq!(failed.diagnostic_code, Some(0));
+        assert_eq!(failed.status_code, Some(550));
+        assert_eq!(failed.dsn_status.as_deref(), Some("5.1.1"));
+    }
+
+    #[test]
+    fn test_error_properties() {
+        let file_data = build_msg(&[(
+            "/__properties_version1.0",
+            property_stream(
+                32,
+                &[(0x0039_000A, 0x8004_010F), (0x0E07_000A, 0x8007_000E)],
+            ),
+        )]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.sent_time, None);
+        assert!(!email.is_template);
+        assert!(email
+            .warnings
+            .contains(&"属性 0x0039 取值失败: MAPI_E_NOT_FOUND (0x8004010F)".to_string()));
+        assert_eq!(email.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_skip_flags() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            ("/__substg1.0_1013001F", utf16("<p>hi</p>")),
+            (
+                "/__substg1.0_007D001F",
+                utf16("Date: Mon, 1 Jan 2024 00:00:00 +0000\r\n"),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert!(email.body_html.is_some());
+        assert!(email.sent_time.is_some());
+
+        let options = ParseOptions {
+            skip_html: true,
+            skip_header_parsing: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.body_html, None);
+        assert_eq!(email.sent_time, None);
+    }
+
+    #[test]
+    fn test_headers_only_and_size_limits() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            ("/__substg1.0_0037001F", utf16("Report")),
+            ("/__substg1.0_1000001F", utf16("0123456789")),
+            ("/__substg1.0_1013001F", utf16("<p>hi</p>")),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3707001F",
+                utf16("a.bin"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_37010102",
+                vec![7; 64],
+            ),
+        ]);
+
+        let options = ParseOptions {
+            headers_only: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("Report"));
+        assert_eq!((email.body_text, email.body_html), (None, None));
+        assert!(email.attachments.is_empty());
+
+        let options = ParseOptions {
+            max_body_size: Some(8),
+            max_attachment_size: Some(16),
+            include_raw_properties: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.body_text.as_deref(), Some("0123"));
+        assert!(email.truncated);
+        assert_eq!(email.attachments[0].filename, "a.bin");
+        assert_eq!(email.attachments[0].size, 64);
+        assert!(email.attachments[0].data.is_empty());
+        assert!(email.warnings.iter().any(|w| w.contains("a.bin")));
+        assert_eq!(
+            email.raw_properties.get(&0x0037_001F),
+            Some(&PropertyValue::String("Report".to_string()))
+        );
+
+        let options = ParseOptions {
+            skip_attachments: true,
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert!(email.attachments.is_empty());
+        assert!(email.raw_properties.is_empty());
+    }
+
+    #[test]
+    fn test_encoding_override() {
+        let file_data = build_msg(&[("/__substg1.0_0037001E", b"caf\xE9\0".to_vec())]);
+        let options = ParseOptions {
+            encoding: Some("windows-1252".to_string()),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("café"));
+
+        let options = ParseOptions {
+            encoding: Some("no-such-charset".to_string()),
+            ..Default::default()
+        };
+        let err = parse_msg_with_options(&file_data, &options).unwrap_err();
+        assert_eq!(err.to_string(), "无法识别的字符集: no-such-charset");
+    }
+
+    #[test]
+    fn test_declared_codepage() {
+        let utf16 = |s: &str| {
+            s.encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>()
+        };
+        // "日本" 的 Shift-JIS 编码长度为偶数，按 UTF-16 探测会得到乱码
+        let file_data = build_msg(&[
+            (
+                "/__properties_version1.0",
+                property_stream(
+                    props::TOP_LEVEL_HEADER_LEN,
+                    &[(0x3FFD_0003, 932), (0x3FDE_0003, 1252)],
+                ),
+            ),
+            ("/__substg1.0_0037001E", b"\x93\xfa\x96\x7b".to_vec()),
+            ("/__substg1.0_10130102", b"<p>caf\xE9</p>".to_vec()),
+            ("/__substg1.0_0C1A001F", utf16("Alice")),
+        ]);
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.subject.as_deref(), Some("日本"));
+        assert_eq!(email.body_html.as_deref(), Some("<p>café</p>"));
+        assert_eq!(email.sender_name.as_deref(), Some("Alice"));
+    }
+
+    #[test]
+    fn test_js_parse_options() {
+        use serde::de::{value::Error, IntoDeserializer};
+
+        let fields = std::collections::BTreeMap::from([("skip_rtf", true), ("strict", true)]);
+        let options =
+            JsParseOptions::deserialize(IntoDeserializer::<Error>::into_deserializer(fields))
+                .unwrap();
+        assert!(options.parse.skip_rtf && options.parse.strict);
+        assert!(!options.parse.skip_html);
+        assert!(!options.output.maps_as_objects);
+    }
+
+    #[test]
+    fn test_embedded_message() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let inner = "/__attach_version1.0_#00000000/__substg1.0_3701000D";
+        let file_data = build_msg(&[
+            ("/__substg1.0_0037001F", utf16("Fwd: plan")),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3001001F",
+                utf16("plan"),
+            ),
+            (&format!("{}/__substg1.0_0037001F", inner), utf16("Plan")),
+            (
+                &format!(
+                    "{}/__attach_version1.0_#00000000/__substg1.0_3707001F",
+                    inner
+                ),
+                utf16("plan.txt"),
+            ),
+            (
+                &format!(
+                    "{}/__attach_version1.0_#00000000/__substg1.0_37010102",
+                    inner
+                ),
+                b"v1".to_vec(),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.attachments.len(), 1);
+        let embedded = email.attachments[0].embedded_message.as_ref().unwrap();
+        assert_eq!(embedded.subject.as_deref(), Some("Plan"));
+        assert_eq!(embedded.attachments[0].filename, "plan.txt");
+        assert_eq!(embedded.attachments[0].data, b"v1");
+
+        let options = ParseOptions {
+            max_embedded_depth: Some(0),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert!(email.attachments[0].embedded_message.is_none());
+    }
+
+    #[test]
+    fn test_ole_attachment() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let object = "/__attach_version1.0_#00000000/__substg1.0_3701000D";
+        let mut native = 2u16.to_le_bytes().to_vec();
+        native.extend(b"notes.txt\0C:\\notes.txt\0");
+        native.extend(0x0003_0000u32.to_le_bytes());
+        native.extend(0u32.to_le_bytes());
+        native.extend(3u32.to_le_bytes());
+        native.extend(b"abc");
+        let mut stream = (native.len() as u32).to_le_bytes().to_vec();
+        stream.extend(native);
+        let file_data = build_msg(&[
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3001001F",
+                utf16("Package"),
+            ),
+            (&format!("{}/\u{1}Ole10Native", object), stream),
+            (
+                "/__attach_version1.0_#00000000/__properties_version1.0",
+                property_stream(8, &[(0x3705_0003, ATTACH_OLE as u64)]),
+            ),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        let attachment = &email.attachments[0];
+        assert!(attachment.embedded_message.is_none());
+        assert_eq!(attachment.filename, "notes.txt");
+        assert_eq!(attachment.data, b"abc");
+        assert_eq!(attachment.size, 3);
+
+        let list = lazy::list_msg_attachments(&file_data).unwrap();
+        assert!(!list[0].is_embedded_message);
+        assert_eq!(list[0].filename, "notes.txt");
+        assert_eq!(list[0].size, 3);
+        assert_eq!(lazy::read_attachment_data(&file_data, 0).unwrap(), b"abc");
+
+        // 超出大小限制的对象不转换
+        let options = ParseOptions {
+            max_attachment_size: Some(2),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert!(email.attachments[0].data.is_empty());
+        assert_eq!(email.attachments[0].size, 3);
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let file_data = build_msg(&[
+            (
+                "/__attach_version1.0_#00000001/__substg1.0_3707001F",
+                utf16("b.txt"),
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_3707001F",
+                utf16("a.txt"),
+            ),
+            (
+                "/__attach_version1.0_#00000001/__substg1.0_37010102",
+                vec![2],
+            ),
+            (
+                "/__attach_version1.0_#00000000/__substg1.0_37010102",
+                vec![1],
+            ),
+        ]);
+        let options = ParseOptions {
+            deterministic_order: true,
+            ..Default::default()
+        };
+
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        let names: Vec<_> = email
+            .attachments
+            .iter()
+            .map(|a| a.filename.as_str())
+            .collect();
+        assert_eq!(names, ["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_attachment_ids() {
+        let utf16 = |s: &str| s.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let attach = "/__attach_version1.0_#0000000A";
+        let file_data = build_msg(&[
+            (
+                &format!("{}/__properties_version1.0", attach),
+                property_stream(8, &[(0x0E21_0003, 3)]),
+            ),
+            (&format!("{}/__substg1.0_3707001F", attach), utf16("a.txt")),
+            (&format!("{}/__substg1.0_37010102", attach), vec![1]),
+        ]);
+
+        let email = parse_msg_to_struct(&file_data).unwrap();
+        assert_eq!(email.attachments[0].attach_num, Some(3));
+        assert_eq!(email.attachments[0].storage_index, Some(10));
+    }
+
+    #[test]
+    fn test_attachment_filter() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+
+        let options = ParseOptions {
+            attachment_allow: vec![".pdf".to_string()],
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(file_data, &options).unwrap();
+        assert!(email.attachments.is_empty());
+
+        let options = ParseOptions {
+            attachment_allow: vec![".TXT".to_string()],
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(file_data, &options).unwrap();
+        assert_eq!(email.attachments[0].filename, "notes.txt");
+    }
+
+    #[test]
+    fn test_max_body_chars() {
+        let body: Vec<u8> = "日志行日志行"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let file_data = build_msg(&[("/__substg1.0_1000001F", body)]);
+
+        let options = ParseOptions {
+            max_body_chars: Some(4),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.body_text.as_deref(), Some("日志行日"));
+        assert!(email.truncated);
+
+        let options = ParseOptions {
+            max_body_chars: Some(6),
+            ..Default::default()
+        };
+        let email = parse_msg_with_options(&file_data, &options).unwrap();
+        assert_eq!(email.body_text.as_deref(), Some("日志行日志行"));
+        assert!(!email.truncated);
+    }
+
     #[test]
 
     fn test_parse_real_msg_file() {
@@ -0,0 +1,84 @@
+//! 区域和代码页信息，用于排查正文解码问题
+
+use crate::props::PropertyStore;
+use encoding_rs::Encoding;
+use serde::Serialize;
+
+const PROP_INTERNET_CPID: u16 = 0x3FDE;
+const PROP_MESSAGE_LOCALE_ID: u16 = 0x3FF1;
+const PROP_MESSAGE_CODEPAGE: u16 = 0x3FFD;
+
+/// 消息中记录的区域和代码页原始值
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Locale {
+    /// PR_MESSAGE_LOCALE_ID，Windows LCID，如 2052 (zh-CN)
+    pub message_locale_id: Option<u32>,
+    /// PR_MESSAGE_CODEPAGE，8 位字符串属性使用的代码页，如 936 (GBK)
+    pub message_codepage: Option<u32>,
+    /// PR_INTERNET_CPID，HTML/RTF 正文的代码页，如 65001 (UTF-8)
+    pub internet_cpid: Option<u32>,
+}
+
+/// Windows 代码页对应的字符集；UTF-16 (1200/1201) 等非 8 位代码页返回 `None`
+pub(crate) fn encoding_for_codepage(codepage: u32) -> Option<&'static Encoding> {
+    let label = match codepage {
+        65001 => "utf-8",
+        874 | 1250..=1258 => {
+            return Encoding::for_label(format!("windows-{}", codepage).as_bytes())
+        }
+        // US-ASCII 和 ISO-8859-1 按 WHATWG 惯例用 windows-1252 解码
+        20127 | 28591 => "windows-1252",
+        28592..=28606 => {
+            return Encoding::for_label(format!("iso-8859-{}", codepage - 28590).as_bytes())
+        }
+        932 => "shift_jis",
+        936 => "gbk",
+        54936 => "gb18030",
+        949 | 51949 => "euc-kr",
+        950 => "big5",
+        51932 | 20932 => "euc-jp",
+        50220..=50222 => "iso-2022-jp",
+        866 => "ibm866",
+        20866 => "koi8-r",
+        21866 => "koi8-u",
+        10000 => "macintosh",
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+/// 解码文本属性时使用的字符集，`None` 表示未声明，由内容探测
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Charsets {
+    /// 8 位字符串属性 (PT_STRING8)
+    pub string8: Option<&'static Encoding>,
+    /// 以二进制存储的 HTML 正文
+    pub html: Option<&'static Encoding>,
+}
+
+impl Charsets {
+    /// 消息声明的字符集：8 位字符串优先取 PR_MESSAGE_CODEPAGE，HTML 正文优先取 PR_INTERNET_CPID，
+    /// 缺失时互为后备
+    pub fn declared(store: &PropertyStore) -> Self {
+        Charsets {
+            string8: declared_encoding(store, &[PROP_MESSAGE_CODEPAGE, PROP_INTERNET_CPID]),
+            html: declared_encoding(store, &[PROP_INTERNET_CPID, PROP_MESSAGE_CODEPAGE]),
+        }
+    }
+}
+
+fn declared_encoding(store: &PropertyStore, ids: &[u16]) -> Option<&'static Encoding> {
+    ids.iter()
+        .filter_map(|&id| store.get(id)?.as_i32())
+        .find_map(|codepage| encoding_for_codepage(codepage as u32))
+}
+
+pub(crate) fn read_locale(store: &PropertyStore) -> Locale {
+    let value = |id| store.get(id).and_then(|v| v.as_i32()).map(|v| v as u32);
+
+    Locale {
+        message_locale_id: value(PROP_MESSAGE_LOCALE_ID),
+        message_codepage: value(PROP_MESSAGE_CODEPAGE),
+        internet_cpid: value(PROP_INTERNET_CPID),
+    }
+}
@@ -0,0 +1,225 @@
+//! 解析结果的内存占用估算
+//!
+//! 宿主缓存大量解析结果时，可以按占用大小而不是条数淘汰。估算只统计正文、附件和字符串等
+//! 堆上数据，再加上结构体自身大小，不追求精确到分配器开销。
+
+use crate::named::NamedProperty;
+use crate::props::PropertyValue;
+use crate::recipient::{Address, Recipient};
+use crate::{Attachment, MsgEmail, MsgParseError};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::mem::size_of;
+use wasm_bindgen::prelude::*;
+
+/// WASM 导出接口
+/// 估算一个解析结果（`parse_msg_file` 等函数的返回值）占用的内存字节数
+///
+/// 按字符串长度、`Uint8Array` 长度和每个数字 8 字节累加，JS 引擎中的实际占用会略高。
+#[wasm_bindgen]
+pub fn approximate_memory_usage(result: JsValue) -> Result<f64, JsValue> {
+    let Footprint(size) = serde_wasm_bindgen::from_value(result)
+        .map_err(|e| MsgParseError::invalid_argument(e).to_js())?;
+    Ok(size as f64)
+}
+
+impl MsgEmail {
+    /// 估算解析结果占用的内存字节数，见 [`approximate_memory_usage`]
+    pub fn approximate_memory_usage(&self) -> usize {
+        let strings = [
+            &self.message_class,
+            &self.subject,
+            &self.sender_name,
+            &self.sender_email,
+            &self.sent_time,
+            &self.client_submit_time,
+            &self.message_delivery_time,
+            &self.creation_time,
+            &self.last_modification_time,
+            &self.body_text,
+            &self.body_html,
+            &self.body_rtf,
+            &self.search_key,
+            &self.change_key,
+            &self.last_action_time,
+            &self.flag_request,
+        ];
+
+        size_of::<Self>()
+            + strings.into_iter().map(option_str_len).sum::<usize>()
+            + vec_usage(&self.recipients, address_usage)
+            + vec_usage(&self.cc_recipients, address_usage)
+            + vec_usage(&self.bcc_recipients, address_usage)
+            + vec_usage(&self.reply_to, |s| s.len())
+            + vec_usage(&self.disposition_notification_to, address_usage)
+            + vec_usage(&self.return_receipt_to, address_usage)
+            + vec_usage(&self.headers, |(name, value)| name.len() + value.len())
+            + vec_usage(&self.received_chain, |hop| hop.raw.len() * 2)
+            + vec_usage(&self.recipients_flat, |s| s.len())
+            + vec_usage(&self.cc_recipients_flat, |s| s.len())
+            + vec_usage(&self.recipient_table, recipient_usage)
+            + vec_usage(&self.attachments, Attachment::approximate_memory_usage)
+            + vec_usage(&self.warnings, |s| s.len())
+            + vec_usage(&self.remote_resources, |r| {
+                r.url.len() + option_str_len(&r.host)
+            })
+            + vec_usage(&self.named_properties, named_usage)
+            + vec_usage(&self.categories, |s| s.len())
+            + [&self.custom, &self.raw_properties]
+                .into_iter()
+                .flat_map(|map| map.values())
+                .map(|value| size_of::<(u32, PropertyValue)>() + value_usage(value))
+                .sum::<usize>()
+    }
+}
+
+impl Attachment {
+    /// 估算附件占用的内存字节数，不含 `Attachment` 结构体自身
+    pub fn approximate_memory_usage(&self) -> usize {
+        #[allow(unused_mut)]
+        let mut size = self.filename.len()
+            + option_str_len(&self.content_type)
+            + option_str_len(&self.content_id)
+            + self.data.len()
+            + self
+                .embedded_message
+                .as_ref()
+                .map_or(0, |email| email.approximate_memory_usage());
+        #[cfg(feature = "archive")]
+        if let Some(entries) = &self.archive_entries {
+            size += vec_usage(entries, |entry| entry.name.len());
+        }
+        #[cfg(feature = "wmf-preview")]
+        if let Some(png) = &self.preview_png {
+            size += png.len();
+        }
+        size
+    }
+}
+
+fn option_str_len(value: &Option<String>) -> usize {
+    value.as_ref().map_or(0, String::len)
+}
+
+/// Vec 中元素自身大小加上各元素的堆上数据
+fn vec_usage<T>(items: &[T], heap: impl Fn(&T) -> usize) -> usize {
+    items.iter().map(|item| size_of::<T>() + heap(item)).sum()
+}
+
+fn address_usage(address: &Address) -> usize {
+    option_str_len(&address.name) + option_str_len(&address.email)
+}
+
+fn recipient_usage(recipient: &Recipient) -> usize {
+    option_str_len(&recipient.display_name)
+        + option_str_len(&recipient.email)
+        + option_str_len(&recipient.address_type)
+        + option_str_len(&recipient.exchange_dn)
+}
+
+fn named_usage(property: &NamedProperty) -> usize {
+    property.guid.len() + option_str_len(&property.name) + value_usage(&property.value)
+}
+
+fn value_usage(value: &PropertyValue) -> usize {
+    match value {
+        PropertyValue::Time(s) | PropertyValue::String(s) | PropertyValue::Guid(s) => s.len(),
+        PropertyValue::Binary(data) => data.len(),
+        PropertyValue::MultiInt32(values) => values.len() * size_of::<i32>(),
+        PropertyValue::MultiString(values) => vec_usage(values, |s| s.len()),
+        _ => 0,
+    }
+}
+
+/// 反序列化任意 JS 值时累加其大小
+struct Footprint(usize);
+
+impl<'de> Deserialize<'de> for Footprint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FootprintVisitor)
+    }
+}
+
+struct FootprintVisitor;
+
+/// 数字和布尔值按 8 字节计
+const SCALAR_SIZE: usize = 8;
+
+impl<'de> Visitor<'de> for FootprintVisitor {
+    type Value = Footprint;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("解析结果")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Footprint, E> {
+        Ok(Footprint(SCALAR_SIZE))
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<Footprint, E> {
+        Ok(Footprint(SCALAR_SIZE))
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<Footprint, E> {
+        Ok(Footprint(SCALAR_SIZE))
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<Footprint, E> {
+        Ok(Footprint(SCALAR_SIZE))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Footprint, E> {
+        Ok(Footprint(v.len()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Footprint, E> {
+        Ok(Footprint(v.len()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Footprint, E> {
+        Ok(Footprint(0))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Footprint, E> {
+        Ok(Footprint(0))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Footprint, D::Error> {
+        Footprint::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Footprint, A::Error> {
+        let mut size = 0;
+        while let Some(Footprint(item)) = seq.next_element()? {
+            size += item;
+        }
+        Ok(Footprint(size))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Footprint, A::Error> {
+        let mut size = 0;
+        while let Some((Footprint(key), Footprint(value))) = map.next_entry()? {
+            size += key + value;
+        }
+        Ok(Footprint(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approximate_memory_usage() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let email = crate::parse_msg_to_struct(data).unwrap();
+        let usage = email.approximate_memory_usage();
+
+        let attachments: usize = email.attachments.iter().map(|a| a.data.len()).sum();
+        assert!(usage > size_of::<MsgEmail>() + attachments);
+
+        let html_len = option_str_len(&email.body_html);
+        let mut email = email;
+        email.body_html = Some("x".repeat(1000));
+        assert_eq!(email.approximate_memory_usage(), usage - html_len + 1000);
+    }
+}
@@ -0,0 +1,425 @@
+//! MIME 编码的附件文件名解码及 MIME 实体解析
+//!
+//! 其他邮件系统转换来的 MSG 文件中，附件名有时原样保留了 MIME 头中的编码形式：
+//! RFC 2047 编码字（`=?utf-8?B?5pyI5oqlLnBkZg==?=`）或 RFC 2231 扩展参数
+//! （`utf-8''%E6%9C%88%E6%8A%A5.pdf`）。这里把它们还原为普通文本，无法识别时原样返回。
+//!
+//! S/MIME 邮件的真正内容是附件中的一个完整 MIME 实体，[`parse_entity`] 把它拆成树形的
+//! 各个部分，并解开 base64 / quoted-printable 传输编码。
+
+use crate::eml;
+use crate::headers;
+use encoding_rs::{Encoding, UTF_8};
+
+/// multipart 的最大嵌套层数，更深的部分不再拆分，整体作为一个不透明的部分。
+/// 内容来自不可信的附件，需要防止递归耗尽栈
+pub(crate) const MAX_DEPTH: usize = 16;
+
+/// 解析后的 MIME 实体
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MimePart {
+    /// 小写的媒体类型，如 `text/plain`；缺少 Content-Type 时为 `text/plain`
+    pub mime_type: String,
+    /// 原始 Content-Type 头，含参数
+    pub content_type: String,
+    /// 原始 Content-Disposition 头
+    pub disposition: Option<String>,
+    /// 去掉尖括号的 Content-ID
+    pub content_id: Option<String>,
+    /// 解开传输编码后的内容，multipart 时为空
+    pub body: Vec<u8>,
+    /// multipart 的各个子部分
+    pub parts: Vec<MimePart>,
+}
+
+impl MimePart {
+    /// 按 charset 参数解码文本内容，未声明或无法识别时按 UTF-8
+    pub(crate) fn text(&self) -> String {
+        let encoding = parameter(&self.content_type, "charset")
+            .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+            .unwrap_or(UTF_8);
+        encoding
+            .decode_without_bom_handling(&self.body)
+            .0
+            .into_owned()
+    }
+
+    /// Content-Disposition 的 filename 参数，其次 Content-Type 的 name 参数
+    pub(crate) fn filename(&self) -> Option<String> {
+        self.disposition
+            .as_deref()
+            .and_then(|disposition| parameter(disposition, "filename"))
+            .map(|name| decode_filename(&name))
+            .filter(|name| !name.is_empty())
+            .or_else(|| name_parameter(&self.content_type))
+    }
+
+    /// 是否声明为附件 (`Content-Disposition: attachment`)
+    pub(crate) fn is_attachment(&self) -> bool {
+        self.disposition.as_deref().is_some_and(|disposition| {
+            disposition
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("attachment")
+        })
+    }
+}
+
+/// 解析 MIME 实体，multipart 按 boundary 递归拆分，最多 [`MAX_DEPTH`] 层
+pub(crate) fn parse_entity(data: &[u8]) -> MimePart {
+    parse_entity_nested(data, 0)
+}
+
+fn parse_entity_nested(data: &[u8], depth: usize) -> MimePart {
+    let (head, body) = split_head(data);
+    let fields = headers::header_fields(&String::from_utf8_lossy(head));
+    let header = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+    };
+
+    let content_type = header("Content-Type").unwrap_or_else(|| "text/plain".to_string());
+    let mime_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let mut part = MimePart {
+        disposition: header("Content-Disposition"),
+        content_id: header("Content-ID")
+            .map(|id| id.trim_matches(['<', '>']).to_string())
+            .filter(|id| !id.is_empty()),
+        ..Default::default()
+    };
+
+    let boundary = parameter(&content_type, "boundary")
+        .filter(|_| mime_type.starts_with("multipart/") && depth < MAX_DEPTH);
+    match boundary {
+        Some(boundary) => {
+            part.parts = split_multipart(body, &boundary)
+                .into_iter()
+                .map(|part| parse_entity_nested(part, depth + 1))
+                .collect();
+        }
+        None => {
+            let encoding = header("Content-Transfer-Encoding")
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            part.body = match encoding.as_str() {
+                "base64" => eml::decode_base64(&String::from_utf8_lossy(body))
+                    .unwrap_or_else(|| body.to_vec()),
+                "quoted-printable" => decode_quoted_printable(body),
+                _ => body.to_vec(),
+            };
+        }
+    }
+    part.mime_type = mime_type;
+    part.content_type = content_type;
+    part
+}
+
+/// multipart 实体各部分的原始字节（含各部分的头部），不是 multipart 时为空。
+/// 校验明文签名时需要被签名部分的原样字节
+pub(crate) fn multipart_parts(data: &[u8]) -> Vec<&[u8]> {
+    let (head, body) = split_head(data);
+    let fields = headers::header_fields(&String::from_utf8_lossy(head));
+    fields
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("Content-Type"))
+        .filter(|(_, value)| {
+            value
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("multipart/")
+        })
+        .and_then(|(_, value)| parameter(value, "boundary"))
+        .map(|boundary| split_multipart(body, &boundary))
+        .unwrap_or_default()
+}
+
+/// 按第一个空行分开头部和正文；没有头部时整个作为正文
+fn split_head(data: &[u8]) -> (&[u8], &[u8]) {
+    let mut offset = 0;
+    while offset < data.len() {
+        let end = data[offset..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |i| offset + i + 1);
+        let line = &data[offset..end];
+        if line == b"\r\n" || line == b"\n" {
+            return (&data[..offset], &data[end..]);
+        }
+        // 第一行就不像头部（如直接以分隔行开始）
+        if offset == 0 && !line.contains(&b':') {
+            return (&[], data);
+        }
+        offset = end;
+    }
+    (data, &[])
+}
+
+/// 拆分 multipart 正文，分隔行前的换行属于分隔行，不计入各部分
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    while offset < body.len() {
+        let end = body[offset..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| offset + i + 1);
+        let line = &body[offset..end];
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if let Some(start) = start {
+                parts.push(strip_line_break(&body[start..offset]));
+            }
+            if rest.starts_with(b"--") {
+                return parts;
+            }
+            start = Some(end);
+        }
+        offset = end;
+    }
+    // 缺少结束分隔行
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn strip_line_break(data: &[u8]) -> &[u8] {
+    data.strip_suffix(b"\r\n")
+        .or_else(|| data.strip_suffix(b"\n"))
+        .unwrap_or(data)
+}
+
+/// 头部参数的值，如 `multipart/signed; boundary="abc"` 中的 `abc`；
+/// 同时识别 RFC 2231 的 `参数名*=字符集'语言'值`
+pub(crate) fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        let key = key.trim();
+        let value = value.trim().trim_matches('"');
+        if key.eq_ignore_ascii_case(name) {
+            Some(value.to_string())
+        } else if key.strip_suffix('*')?.eq_ignore_ascii_case(name) {
+            decode_rfc2231(value)
+        } else {
+            None
+        }
+    })
+}
+
+/// quoted-printable (RFC 2045 §6.7)：`=XX` 为一个字节，行尾的 `=` 为软换行
+fn decode_quoted_printable(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        if data[i] != b'=' {
+            out.push(data[i]);
+            i += 1;
+            continue;
+        }
+        let rest = &data[i + 1..];
+        if rest.starts_with(b"\r\n") {
+            i += 3;
+        } else if rest.starts_with(b"\n") {
+            i += 2;
+        } else if let Some(byte) = rest
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(b'=');
+            i += 1;
+        }
+    }
+    out
+}
+
+/// 解码附件文件名，不含编码时原样返回
+pub(crate) fn decode_filename(name: &str) -> String {
+    if let Some(decoded) = decode_rfc2231(name) {
+        return decoded;
+    }
+    if name.contains("=?") {
+        return decode_encoded_words(name);
+    }
+    name.to_string()
+}
+
+/// 从 MIME 类型（如 `application/pdf; name="a.pdf"`）的参数中取出文件名
+pub(crate) fn name_parameter(content_type: &str) -> Option<String> {
+    parameter(content_type, "name")
+        .map(|name| decode_filename(&name))
+        .filter(|name| !name.is_empty())
+}
+
+/// RFC 2231：`字符集'语言'百分号编码`，格式不符时返回 `None`
+fn decode_rfc2231(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let charset = parts.next()?;
+    let _language = parts.next()?;
+    let encoded = parts.next()?;
+    let encoding = Encoding::for_label(charset.as_bytes())?;
+
+    let bytes = percent_decode(encoded)?;
+    Some(encoding.decode_without_bom_handling(&bytes).0.into_owned())
+}
+
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            out.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            out.push(b);
+        }
+    }
+    Some(out)
+}
+
+/// Q 编码：`_` 表示空格，`=XX` 表示一个字节
+fn decode_q(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'_' => out.push(b' '),
+            b'=' => {
+                let hex = [bytes.next()?, bytes.next()?];
+                out.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b => out.push(b),
+        }
+    }
+    Some(out)
+}
+
+/// RFC 2047：逐个解码 `=?字符集?B|Q?文本?=`，相邻编码字之间的空白按规范忽略
+pub(crate) fn decode_encoded_words(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        let Some((decoded, len)) = decode_word(word) else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+            continue;
+        };
+        if !(after_word && before.trim().is_empty()) {
+            out.push_str(before);
+        }
+        out.push_str(&decoded);
+        rest = &word[len..];
+        after_word = true;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 解码以 `=?` 开头的一个编码字，返回文本和编码字长度
+fn decode_word(word: &str) -> Option<(String, usize)> {
+    let body = word.strip_prefix("=?")?;
+    let (charset, body) = body.split_once('?')?;
+    let (kind, body) = body.split_once('?')?;
+    let end = body.find("?=")?;
+    let encoded = &body[..end];
+    // RFC 2231 允许在字符集后附加 *语言
+    let charset = charset.split('*').next()?;
+    let encoding = Encoding::for_label(charset.as_bytes())?;
+
+    let bytes = match kind {
+        "B" | "b" => eml::decode_base64(encoded)?,
+        "Q" | "q" => decode_q(encoded)?,
+        _ => return None,
+    };
+    let len = word.len() - body.len() + end + 2;
+    Some((
+        encoding.decode_without_bom_handling(&bytes).0.into_owned(),
+        len,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_filename() {
+        assert_eq!(decode_filename("=?utf-8?B?5pyI5oqlLnBkZg==?="), "月报.pdf");
+        assert_eq!(
+            decode_filename("=?ISO-8859-1?Q?caf=E9?= =?ISO-8859-1?Q?_menu.txt?="),
+            "café menu.txt"
+        );
+        assert_eq!(decode_filename("utf-8''%E6%9C%88%E6%8A%A5.pdf"), "月报.pdf");
+        assert_eq!(decode_filename("report =?x?"), "report =?x?");
+        assert_eq!(decode_filename("it's Bob's.txt"), "it's Bob's.txt");
+    }
+
+    #[test]
+    fn test_name_parameter() {
+        assert_eq!(
+            name_parameter("application/pdf; name*=utf-8''%E6%9C%88%E6%8A%A5.pdf").as_deref(),
+            Some("月报.pdf")
+        );
+        assert_eq!(
+            name_parameter("text/plain; charset=utf-8; name=\"a.txt\"").as_deref(),
+            Some("a.txt")
+        );
+        assert_eq!(name_parameter("text/plain"), None);
+    }
+
+    #[test]
+    fn test_parse_entity() {
+        let data = b"Content-Type: multipart/mixed; boundary=\"b1\"\r\n\r\n\
+preamble\r\n\
+--b1\r\n\
+Content-Type: text/plain; charset=iso-8859-1\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\r\n\
+caf=E9 au =\r\nlait\r\n\
+--b1\r\n\
+Content-Type: application/octet-stream\r\n\
+Content-Disposition: attachment; filename*=utf-8''%E6%9C%88.bin\r\n\
+Content-Transfer-Encoding: base64\r\n\r\n\
+AQID\r\n\
+--b1--\r\n";
+
+        let entity = parse_entity(data);
+        assert_eq!(entity.mime_type, "multipart/mixed");
+        assert_eq!(entity.parts.len(), 2);
+        assert_eq!(entity.parts[0].text(), "café au lait");
+        let attachment = &entity.parts[1];
+        assert!(attachment.is_attachment());
+        assert_eq!(attachment.filename().as_deref(), Some("月.bin"));
+        assert_eq!(attachment.body, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_entity_depth_limit() {
+        let mut data = Vec::new();
+        for i in 0..10_000 {
+            let header =
+                format!("Content-Type: multipart/mixed; boundary=\"b{i}\"\r\n\r\n--b{i}\r\n");
+            data.extend_from_slice(header.as_bytes());
+        }
+        let mut entity = parse_entity(&data);
+        let mut depth = 0;
+        while let Some(child) = entity.parts.pop() {
+            entity = child;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH);
+    }
+}
@@ -0,0 +1,250 @@
+//! 命名属性 (`__nameid_version1.0`) 映射
+//!
+//! 0x8000 以上的属性 ID 是按文件分配的，需要通过命名属性映射查回它们的属性集 GUID
+//! 和名称（字符串名或数字 LID）。映射存储包含三个流：GUID 流（每项 16 字节）、
+//! 条目流（每项 8 字节）和字符串流（4 字节长度 + UTF-16 LE 名称，按 4 字节对齐）。
+
+use crate::props::{self, decode_utf16, format_guid, PropertyStore, PropertyValue};
+use cfb::CompoundFile;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek};
+
+const NAMEID_STORAGE: &str = "/__nameid_version1.0";
+const GUID_STREAM: &str = "__substg1.0_00020102";
+const ENTRY_STREAM: &str = "__substg1.0_00030102";
+const STRING_STREAM: &str = "__substg1.0_00040102";
+
+/// PS_MAPI，GUID 索引 1
+pub(crate) const PS_MAPI: &str = "00020328-0000-0000-C000-000000000046";
+/// PS_PUBLIC_STRINGS，GUID 索引 2
+pub(crate) const PS_PUBLIC_STRINGS: &str = "00020329-0000-0000-C000-000000000046";
+
+/// PSETID_Appointment，日历项属性
+pub(crate) const PSETID_APPOINTMENT: &str = "00062002-0000-0000-C000-000000000046";
+/// PSETID_Address，联系人属性
+pub(crate) const PSETID_ADDRESS: &str = "00062004-0000-0000-C000-000000000046";
+/// PSETID_Task，任务属性
+pub(crate) const PSETID_TASK: &str = "00062003-0000-0000-C000-000000000046";
+/// PSETID_Log，日记条目属性
+pub(crate) const PSETID_LOG: &str = "0006200A-0000-0000-C000-000000000046";
+/// PSETID_PostRss，RSS 文章属性
+pub(crate) const PSETID_POST_RSS: &str = "00062041-0000-0000-C000-000000000046";
+/// PSETID_Common，各类条目共用的属性（提醒、私有标记等）
+pub(crate) const PSETID_COMMON: &str = "00062008-0000-0000-C000-000000000046";
+/// PSETID_Meeting，会议请求属性
+pub(crate) const PSETID_MEETING: &str = "6ED8DA90-450B-101B-98DA-00AA003F1305";
+
+/// 调用方请求提取的命名属性，`name` 与 `lid` 二选一
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NamedPropertyRequest {
+    /// 属性集 GUID，大小写和花括号均可
+    pub guid: String,
+    /// 字符串名称
+    pub name: Option<String>,
+    /// 数字 LID
+    pub lid: Option<u32>,
+}
+
+/// 提取到的命名属性
+#[derive(Debug, Clone, Serialize)]
+pub struct NamedProperty {
+    pub guid: String,
+    pub name: Option<String>,
+    pub lid: Option<u32>,
+    pub value: PropertyValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum NameKind {
+    Lid(u32),
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct NamedEntry {
+    /// 分配到的属性 ID（0x8000 + 条目索引）
+    pub prop_id: u16,
+    pub guid: String,
+    pub kind: NameKind,
+}
+
+/// 文件内的命名属性映射
+#[derive(Debug, Default)]
+pub(crate) struct NameIdMap {
+    entries: Vec<NamedEntry>,
+}
+
+impl NameIdMap {
+    /// 从复合文件中读取映射，缺少映射存储时返回空映射
+    pub fn read<R: Read + Seek>(comp: &mut CompoundFile<R>) -> Self {
+        let mut read = |name: &str| {
+            props::read_stream(comp, format!("{}/{}", NAMEID_STORAGE, name)).unwrap_or_default()
+        };
+        let guids = read(GUID_STREAM);
+        let entries = read(ENTRY_STREAM);
+        let strings = read(STRING_STREAM);
+        Self::parse(&guids, &entries, &strings)
+    }
+
+    pub fn parse(guids: &[u8], entries: &[u8], strings: &[u8]) -> Self {
+        let entries = entries
+            .chunks_exact(8)
+            .filter_map(|entry| {
+                let id_or_offset = u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]);
+                let index_and_kind = u16::from_le_bytes([entry[4], entry[5]]);
+                let prop_index = u16::from_le_bytes([entry[6], entry[7]]);
+
+                let guid = match index_and_kind >> 1 {
+                    1 => PS_MAPI.to_string(),
+                    2 => PS_PUBLIC_STRINGS.to_string(),
+                    n if n >= 3 => {
+                        let start = (n as usize - 3) * 16;
+                        format_guid(guids.get(start..start + 16)?)
+                    }
+                    _ => return None,
+                };
+
+                let kind = if index_and_kind & 1 == 0 {
+                    NameKind::Lid(id_or_offset)
+                } else {
+                    // 偏移和长度来自文件，wasm32 上相加可能溢出
+                    let offset = id_or_offset as usize;
+                    let start = offset.checked_add(4)?;
+                    let len_bytes = strings.get(offset..start)?;
+                    let len = u32::from_le_bytes([
+                        len_bytes[0],
+                        len_bytes[1],
+                        len_bytes[2],
+                        len_bytes[3],
+                    ]) as usize;
+                    NameKind::Name(decode_utf16(strings.get(start..start.checked_add(len)?)?))
+                };
+
+                Some(NamedEntry {
+                    prop_id: 0x8000u16.checked_add(prop_index)?,
+                    guid,
+                    kind,
+                })
+            })
+            .collect();
+
+        NameIdMap { entries }
+    }
+
+    /// 按属性集 GUID 和名称/LID 查找分配到的属性 ID
+    pub fn find(&self, guid: &str, kind: &NameKind) -> Option<u16> {
+        let guid = normalize_guid(guid);
+        self.entries
+            .iter()
+            .find(|e| e.guid == guid && &e.kind == kind)
+            .map(|e| e.prop_id)
+    }
+
+    /// 查找已分配属性 ID 对应的属性集 GUID 和名称
+    pub fn lookup(&self, prop_id: u16) -> Option<&NamedEntry> {
+        self.entries.iter().find(|e| e.prop_id == prop_id)
+    }
+
+    /// 按属性集 GUID 和字符串名称从属性集合中读取命名属性的值
+    pub fn get_name(&self, store: &PropertyStore, guid: &str, name: &str) -> Option<PropertyValue> {
+        store.get(self.find(guid, &NameKind::Name(name.to_string()))?)
+    }
+
+    /// 按属性集 GUID 和 LID 从属性集合中读取命名属性的值
+    pub fn get_lid(&self, store: &PropertyStore, guid: &str, lid: u32) -> Option<PropertyValue> {
+        store.get(self.find(guid, &NameKind::Lid(lid))?)
+    }
+}
+
+/// 列出属性集合中全部能映射回名称的命名属性，按属性 ID 排序
+pub(crate) fn resolve_all(map: &NameIdMap, store: &PropertyStore) -> Vec<NamedProperty> {
+    let mut props: Vec<_> = store
+        .iter()
+        .filter_map(|(tag, value)| {
+            let id = (tag >> 16) as u16;
+            let entry = map.lookup(id)?;
+            Some((id, entry, value))
+        })
+        .collect();
+    props.sort_by_key(|(id, _, _)| *id);
+    props.dedup_by_key(|(id, _, _)| *id);
+    props
+        .into_iter()
+        .map(|(_, entry, value)| {
+            let (name, lid) = match &entry.kind {
+                NameKind::Lid(lid) => (None, Some(*lid)),
+                NameKind::Name(name) => (Some(name.clone()), None),
+            };
+            NamedProperty {
+                guid: entry.guid.clone(),
+                name,
+                lid,
+                value,
+            }
+        })
+        .collect()
+}
+
+/// 统一 GUID 书写形式：去掉花括号并转为大写
+pub(crate) fn normalize_guid(guid: &str) -> String {
+    guid.trim()
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nameid_map() {
+        // 条目 0：PS_PUBLIC_STRINGS 下的字符串名 "Keywords"
+        // 条目 1：自定义 GUID 下的 LID 0x8503
+        let guids: Vec<u8> = (0u8..16).collect();
+        let mut strings = Vec::new();
+        let name: Vec<u8> = "Keywords"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        strings.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        strings.extend_from_slice(&name);
+
+        let mut entries = Vec::new();
+        entries.extend_from_slice(&0u32.to_le_bytes());
+        entries.extend_from_slice(&((2u16 << 1) | 1).to_le_bytes());
+        entries.extend_from_slice(&0u16.to_le_bytes());
+        entries.extend_from_slice(&0x8503u32.to_le_bytes());
+        entries.extend_from_slice(&(3u16 << 1).to_le_bytes());
+        entries.extend_from_slice(&1u16.to_le_bytes());
+
+        let map = NameIdMap::parse(&guids, &entries, &strings);
+
+        assert_eq!(
+            map.find(
+                "{00020329-0000-0000-c000-000000000046}",
+                &NameKind::Name("Keywords".into())
+            ),
+            Some(0x8000)
+        );
+        assert_eq!(
+            map.find(&format_guid(&guids), &NameKind::Lid(0x8503)),
+            Some(0x8001)
+        );
+        assert_eq!(map.find(PS_MAPI, &NameKind::Lid(0x8503)), None);
+
+        // 越界或接近 usize 上限的字符串偏移、长度只丢弃该条目
+        let mut entries = Vec::new();
+        entries.extend_from_slice(&u32::MAX.to_le_bytes());
+        entries.extend_from_slice(&((2u16 << 1) | 1).to_le_bytes());
+        entries.extend_from_slice(&0u16.to_le_bytes());
+        let map = NameIdMap::parse(&guids, &entries, &u32::MAX.to_le_bytes());
+        assert!(map.entries.is_empty());
+        let mut entries = Vec::new();
+        entries.extend_from_slice(&0u32.to_le_bytes());
+        entries.extend_from_slice(&((2u16 << 1) | 1).to_le_bytes());
+        entries.extend_from_slice(&0u16.to_le_bytes());
+        let map = NameIdMap::parse(&guids, &entries, &u32::MAX.to_le_bytes());
+        assert!(map.entries.is_empty());
+    }
+}
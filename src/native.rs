@@ -0,0 +1,90 @@
+//! 原生（非 WASM）环境下的文件解析接口
+
+use crate::{parse_msg_with_options, MsgEmail, ParseOptions};
+use std::io;
+use std::path::Path;
+
+/// 异步读取并解析磁盘上的 .msg 文件（`tokio` feature）
+///
+/// 读取使用 `tokio::fs`，CPU 密集的解析放到 `spawn_blocking` 中执行，
+/// 批量导入成千上万封邮件时不会阻塞执行器。解析错误以 `InvalidData` 返回。
+#[cfg(feature = "tokio")]
+pub async fn parse_msg_path(path: impl AsRef<Path>) -> io::Result<MsgEmail> {
+    parse_msg_path_with_options(path, ParseOptions::default()).await
+}
+
+/// 按选项异步解析磁盘上的 .msg 文件，见 [`parse_msg_path`]
+#[cfg(feature = "tokio")]
+pub async fn parse_msg_path_with_options(
+    path: impl AsRef<Path>,
+    options: ParseOptions,
+) -> io::Result<MsgEmail> {
+    let data = tokio::fs::read(path).await?;
+    tokio::task::spawn_blocking(move || {
+        parse_msg_with_options(&data, &options)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// 以内存映射方式解析磁盘上的 .msg 文件（`mmap` feature）
+///
+/// 文件内容由操作系统按需换页，不会整份复制到堆上，适合服务端批量处理体积很大的邮件。
+/// 解析错误以 `InvalidData` 返回。
+#[cfg(feature = "mmap")]
+pub fn parse_msg_mmap(path: impl AsRef<Path>) -> io::Result<MsgEmail> {
+    parse_msg_mmap_with_options(path, &ParseOptions::default())
+}
+
+/// 按选项以内存映射方式解析磁盘上的 .msg 文件，见 [`parse_msg_mmap`]
+#[cfg(feature = "mmap")]
+pub fn parse_msg_mmap_with_options(
+    path: impl AsRef<Path>,
+    options: &ParseOptions,
+) -> io::Result<MsgEmail> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: 映射只读，解析期间调用方不应截断或改写该文件
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    parse_msg_with_options(&map, options)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_parse_msg_path() {
+        let path = std::env::temp_dir().join("msg-parser-wasm-native-test.msg");
+        std::fs::write(
+            &path,
+            include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg"),
+        )
+        .unwrap();
+
+        let email = super::parse_msg_path(&path).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(email.subject.is_some());
+
+        let err = super::parse_msg_path("/nonexistent.msg").await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_parse_msg_mmap() {
+        let path = std::env::temp_dir().join("msg-parser-wasm-mmap-test.msg");
+        std::fs::write(
+            &path,
+            include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg"),
+        )
+        .unwrap();
+
+        let email = super::parse_msg_mmap(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(email.subject.as_deref(), Some("Quarterly report"));
+
+        let err = super::parse_msg_mmap("/nonexistent.msg").unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+}
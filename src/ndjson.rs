@@ -0,0 +1,123 @@
+//! 批量解析并输出 NDJSON（`ndjson` feature）
+//!
+//! 每解析完一封邮件就输出一行 JSON，下游可以边接收边处理，整个文件夹的结果不必同时驻留内存。
+//! 单个文件解析失败只输出一行错误，不影响后续文件。
+
+use crate::{parse_msg_to_struct, MsgEmail, MsgParseError};
+use serde::Serialize;
+use serde_bytes::ByteBuf;
+use std::io::{self, Write};
+use wasm_bindgen::prelude::*;
+
+/// NDJSON 中的一行
+#[derive(Serialize)]
+struct Line<'a> {
+    /// 文件在输入中的序号，从 0 开始
+    index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<&'a MsgEmail>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// 依次解析 `files`，每封邮件写出一行 JSON 并立即 flush
+///
+/// 成功的行形如 `{"index":0,"email":{...}}`，失败的行形如 `{"index":1,"error":"..."}`。
+/// 只有写入 `writer` 失败时才返回错误。
+pub fn write_ndjson<'a, W: Write>(
+    files: impl IntoIterator<Item = &'a [u8]>,
+    mut writer: W,
+) -> io::Result<()> {
+    for (index, data) in files.into_iter().enumerate() {
+        let (email, error) = match parse_msg_to_struct(data) {
+            Ok(email) => (Some(email), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let line = Line {
+            index,
+            email: email.as_ref(),
+            error,
+        };
+        serde_json::to_writer(&mut writer, &line)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// 接收每一行 NDJSON 的回调
+    #[wasm_bindgen(typescript_type = "(line: string) => void")]
+    pub type NdjsonCallback;
+
+    #[wasm_bindgen(method, catch, js_name = call)]
+    fn call(this: &NdjsonCallback, context: &JsValue, line: &str) -> Result<JsValue, JsValue>;
+}
+
+/// WASM 导出接口
+/// 依次解析多个 MSG 文件（`Uint8Array[]`），每解析完一个就以一行 JSON（不含换行符）调用 `on_line`
+#[wasm_bindgen]
+pub fn convert_msgs_to_ndjson(files: JsValue, on_line: &NdjsonCallback) -> Result<(), JsValue> {
+    let files: Vec<ByteBuf> = serde_wasm_bindgen::from_value(files)
+        .map_err(|e| MsgParseError::invalid_argument(e).to_js())?;
+
+    let writer = CallbackWriter {
+        callback: on_line,
+        line: Vec::new(),
+    };
+    write_ndjson(files.iter().map(|f| f.as_slice()), writer).map_err(|e| {
+        MsgParseError::WriteError {
+            reason: e.to_string(),
+        }
+        .to_js()
+    })
+}
+
+/// 把每次 flush 前写入的内容作为一行交给 JS 回调
+struct CallbackWriter<'a> {
+    callback: &'a NdjsonCallback,
+    line: Vec<u8>,
+}
+
+impl Write for CallbackWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.line.is_empty() {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&self.line);
+        self.callback
+            .call(&JsValue::NULL, line.trim_end_matches('\n'))
+            .map_err(|e| io::Error::other(e.as_string().unwrap_or_else(|| format!("{:?}", e))))?;
+        self.line.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_ndjson() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let mut out = Vec::new();
+        write_ndjson([&data[..], b"not a msg"], &mut out).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["index"], 0);
+        assert_eq!(first["email"]["subject"], "Quarterly report");
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["index"], 1);
+        assert!(second["error"].is_string());
+    }
+}
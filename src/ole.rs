@@ -0,0 +1,667 @@
+//! OLE 对象附件 (PR_ATTACH_METHOD = ATTACH_OLE) 的提取 (MS-OLEDS)
+//!
+//! 在 RTF 正文中插入的对象（Word/Excel 文档、Package 包装的任意文件、静态图片等）以附件
+//! 形式存放，数据不在 PR_ATTACH_DATA_BIN 流中，而是 `__substg1.0_3701000D` 下的一个 OLE
+//! 存储，与嵌入消息共用同一个存储名，由 PR_ATTACH_METHOD 区分。这里按对象类型把它转换为
+//! 可直接保存的文件：
+//!
+//! - `\x01Ole10Native`：OLE1 对象，Package 中带有原始文件名和文件内容
+//! - `Package` 流：Office 2007 以后的 OOXML 对象，内容就是 .docx/.xlsx/.pptx
+//! - 静态图片（以及只能取到预览的对象）：`CONTENTS` 或 `\x02OlePres000` 中的
+//!   DIB/WMF/EMF，补上文件头成为 .bmp/.wmf/.emf
+//! - 其余对象把整个存储复制为一个独立的复合文档，如 Word 97-2003 对象即 .doc
+//!
+//! 识别对象只读取各流的头部，得到文件名、类型和大小；数据在真正读取附件时才转换，
+//! 列出附件或被筛选排除的对象不会复制数据。
+
+use crate::props;
+use cfb::CompoundFile;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const COMP_OBJ_STREAM: &str = "\u{1}CompObj";
+const OLE10_NATIVE_STREAM: &str = "\u{1}Ole10Native";
+const PACKAGE_STREAM: &str = "Package";
+const CONTENTS_STREAM: &str = "CONTENTS";
+const PRESENTATION_STREAM: &str = "\u{2}OlePres000";
+
+/// CompObj 流头部：Reserved1(4) + Version(4) + Reserved2(20)
+const COMP_OBJ_HEADER_LEN: usize = 28;
+
+// 静态对象的 CLSID：Static Metafile、Static DIB、Static Enhanced Metafile
+const STATIC_CLSIDS: [&str; 3] = [
+    "00000315-0000-0000-c000-000000000046",
+    "00000316-0000-0000-c000-000000000046",
+    "00000319-0000-0000-c000-000000000046",
+];
+
+// 剪贴板格式
+const CF_METAFILEPICT: u32 = 3;
+const CF_DIB: u32 = 8;
+const CF_ENHMETAFILE: u32 = 14;
+
+const BITMAPFILEHEADER_LEN: u32 = 14;
+const BI_BITFIELDS: u32 = 3;
+const PLACEABLE_KEY: u32 = 0x9AC6_CDD7;
+/// 可放置 WMF 头部的长度
+const PLACEABLE_HEADER_LEN: u64 = 22;
+/// HIMETRIC 单位（0.01 毫米）每英寸的数量
+const HIMETRIC_PER_INCH: u16 = 2540;
+
+/// 识别对象时每个流最多读取的字节数，足以容纳各类对象的头部
+const HEADER_PREFIX_LEN: u64 = 8192;
+
+/// 识别出的 OLE 对象：转换后的文件类型和数据所在位置，数据由 [`OleObject::read`] 读取
+#[derive(Debug)]
+pub(crate) struct OleObject {
+    /// 对象中记录的原始文件名（Package 对象）
+    pub filename: Option<String>,
+    /// 不带点的扩展名
+    pub extension: &'static str,
+    pub content_type: &'static str,
+    /// 转换后的数据大小（字节）。复制为复合文档的对象取各流长度之和，只是估计值
+    pub size: u64,
+    source: Source,
+}
+
+/// 转换后的文件数据从哪里来
+#[derive(Debug)]
+enum Source {
+    /// 流中 `offset` 起的 `len` 个字节，原样作为文件
+    Range {
+        stream: &'static str,
+        offset: u64,
+        len: u64,
+    },
+    /// 演示流中的图片数据，补上文件头
+    Picture {
+        stream: &'static str,
+        format: u32,
+        offset: u64,
+        len: u64,
+        width: u32,
+        height: u32,
+    },
+    /// 把整个存储复制为一个独立的复合文档
+    Storage,
+}
+
+impl OleObject {
+    /// 读取对象数据并转换为文件
+    pub(crate) fn read<R: Read + Seek>(
+        &self,
+        comp: &mut CompoundFile<R>,
+        storage: &Path,
+    ) -> std::io::Result<Vec<u8>> {
+        match self.source {
+            Source::Range {
+                stream,
+                offset,
+                len,
+            } => read_range(comp, &storage.join(stream), offset, len),
+            Source::Picture {
+                stream,
+                format,
+                offset,
+                len,
+                width,
+                height,
+            } => {
+                let payload = read_range(comp, &storage.join(stream), offset, len)?;
+                match format {
+                    CF_DIB => {
+                        let header = bitmap_file_header(&payload, len).ok_or_else(|| {
+                            std::io::Error::new(std::io::ErrorKind::InvalidData, "位图信息头无效")
+                        })?;
+                        Ok([&header[..], &payload].concat())
+                    }
+                    CF_METAFILEPICT => Ok(placeable_wmf(&payload, width, height)),
+                    _ => Ok(payload),
+                }
+            }
+            Source::Storage => copy_storage(comp, storage),
+        }
+    }
+}
+
+/// 识别 OLE 对象存储，只读取各流的头部；无法识别时返回 `None`
+pub(crate) fn read_ole_object<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    storage: &Path,
+) -> Option<OleObject> {
+    let prog_id = read_prefix(comp, &storage.join(COMP_OBJ_STREAM))
+        .and_then(|(data, _)| read_prog_id(&data))
+        .unwrap_or_default();
+
+    if let Some((prefix, len)) = read_prefix(comp, &storage.join(OLE10_NATIVE_STREAM)) {
+        if let Some(object) = read_ole10_native(&prefix, len, &prog_id) {
+            return Some(object);
+        }
+    }
+    if let Some(len) = stream_len(comp, &storage.join(PACKAGE_STREAM)) {
+        let (extension, content_type) = ooxml_type(&prog_id);
+        return Some(OleObject {
+            filename: None,
+            extension,
+            content_type,
+            size: len,
+            source: Source::Range {
+                stream: PACKAGE_STREAM,
+                offset: 0,
+                len,
+            },
+        });
+    }
+    let contents = read_prefix(comp, &storage.join(CONTENTS_STREAM));
+    if let Some((_, len)) = contents
+        .as_ref()
+        .filter(|(data, _)| data.starts_with(b"%PDF"))
+    {
+        return Some(OleObject {
+            filename: None,
+            extension: "pdf",
+            content_type: "application/pdf",
+            size: *len,
+            source: Source::Range {
+                stream: CONTENTS_STREAM,
+                offset: 0,
+                len: *len,
+            },
+        });
+    }
+
+    let is_static = comp
+        .entry(storage)
+        .is_ok_and(|entry| STATIC_CLSIDS.contains(&entry.clsid().to_string().as_str()));
+    if is_static {
+        let presentation = read_prefix(comp, &storage.join(PRESENTATION_STREAM));
+        return [
+            (CONTENTS_STREAM, contents),
+            (PRESENTATION_STREAM, presentation),
+        ]
+        .into_iter()
+        .find_map(|(stream, prefix)| {
+            let (data, len) = prefix?;
+            read_presentation(stream, &data, len)
+        });
+    }
+
+    let (extension, content_type) = compound_type(&prog_id);
+    let size = comp
+        .walk_storage(storage)
+        .ok()?
+        .filter(|entry| entry.is_stream())
+        .map(|entry| entry.len())
+        .sum();
+    Some(OleObject {
+        filename: None,
+        extension,
+        content_type,
+        size,
+        source: Source::Storage,
+    })
+}
+
+/// 流的长度，流不存在时返回 `None`
+fn stream_len<R: Read + Seek>(comp: &CompoundFile<R>, path: &Path) -> Option<u64> {
+    comp.entry(path)
+        .ok()
+        .filter(|entry| entry.is_stream())
+        .map(|entry| entry.len())
+}
+
+/// 流开头最多 [`HEADER_PREFIX_LEN`] 字节，以及流的总长度
+fn read_prefix<R: Read + Seek>(comp: &mut CompoundFile<R>, path: &Path) -> Option<(Vec<u8>, u64)> {
+    let len = stream_len(comp, path)?;
+    let (data, _) = props::read_stream_prefix(comp, path, HEADER_PREFIX_LEN).ok()?;
+    Some((data, len))
+}
+
+/// 流中 `offset` 起的 `len` 个字节
+fn read_range<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    path: &Path,
+    offset: u64,
+    len: u64,
+) -> std::io::Result<Vec<u8>> {
+    let mut stream = comp.open_stream(path)?;
+    stream.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    stream.take(len).read_to_end(&mut data)?;
+    if (data.len() as u64) < len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "OLE 对象数据不完整",
+        ));
+    }
+    Ok(data)
+}
+
+/// 从 `offset` 开始的 `len` 个字节，越界或偏移溢出时返回 `None`
+fn bytes_at(data: &[u8], offset: usize, len: usize) -> Option<&[u8]> {
+    data.get(offset..offset.checked_add(len)?)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes_at(data, offset, 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes_at(data, offset, 4)?.try_into().ok()?,
+    ))
+}
+
+/// 跳过长度前缀(4) + 内容，返回之后的偏移
+fn skip_length_prefixed(data: &[u8], offset: usize) -> Option<usize> {
+    let len = u32_at(data, offset)? as usize;
+    offset.checked_add(4)?.checked_add(len)
+}
+
+/// 以 0 结尾的 ANSI 字符串，返回字符串和之后的偏移
+fn z_string(data: &[u8], offset: usize) -> Option<(&[u8], usize)> {
+    let len = data.get(offset..)?.iter().position(|&b| b == 0)?;
+    Some((&data[offset..offset + len], offset + len + 1))
+}
+
+/// CompObj 流中 AnsiUserType、AnsiClipboardFormat 之后的 ProgID，如 `Word.Document.8`
+fn read_prog_id(data: &[u8]) -> Option<String> {
+    // AnsiUserType：长度（含结尾的 0）+ 字符串
+    let offset = skip_length_prefixed(data, COMP_OBJ_HEADER_LEN)?;
+    // AnsiClipboardFormat：0 表示没有，0xFFFFFFFF/0xFFFFFFFE 后跟 4 字节格式号，否则为长度
+    let offset = match u32_at(data, offset)? {
+        0 => offset.checked_add(4)?,
+        0xFFFF_FFFE | 0xFFFF_FFFF => offset.checked_add(8)?,
+        _ => skip_length_prefixed(data, offset)?,
+    };
+    let len = u32_at(data, offset)? as usize;
+    let prog_id = bytes_at(data, offset.checked_add(4)?, len)?;
+    let prog_id = String::from_utf8_lossy(prog_id.split(|&b| b == 0).next()?);
+    Some(prog_id.trim().to_string()).filter(|prog_id| !prog_id.is_empty())
+}
+
+/// `\x01Ole10Native`：长度(4) + OLE1 原生数据。`prefix` 为流的开头，`stream_len` 为流的总长度
+fn read_ole10_native(prefix: &[u8], stream_len: u64, prog_id: &str) -> Option<OleObject> {
+    // 声明的长度超出流时取到流末尾
+    let native_len = u64::from(u32_at(prefix, 0)?).min(stream_len.checked_sub(4)?);
+    let native = &prefix[4..];
+    let native = native.get(..native_len as usize).unwrap_or(native);
+
+    if prog_id.eq_ignore_ascii_case("Package") || prog_id.is_empty() {
+        if let Some((filename, offset, len)) = read_package(native).filter(|&(_, offset, len)| {
+            offset.checked_add(len).is_some_and(|end| end <= native_len)
+        }) {
+            return Some(OleObject {
+                filename,
+                extension: "bin",
+                content_type: "application/octet-stream",
+                size: len,
+                source: Source::Range {
+                    stream: OLE10_NATIVE_STREAM,
+                    offset: 4 + offset,
+                    len,
+                },
+            });
+        }
+    }
+    // 画图 (PBrush) 对象的原生数据就是一个 BMP 文件
+    if native.starts_with(b"BM") {
+        return Some(OleObject {
+            filename: None,
+            extension: "bmp",
+            content_type: "image/bmp",
+            size: native_len,
+            source: Source::Range {
+                stream: OLE10_NATIVE_STREAM,
+                offset: 4,
+                len: native_len,
+            },
+        });
+    }
+    None
+}
+
+/// Package 对象的原生数据：类型(2) + 标签 + 源路径 + 保留(4) + 临时路径长度(4) + 临时路径 +
+/// 数据长度(4) + 数据。只解析头部，返回文件名以及数据在原生数据中的偏移和长度
+fn read_package(native: &[u8]) -> Option<(Option<String>, u64, u64)> {
+    let (label, offset) = z_string(native, 2)?;
+    let (path, offset) = z_string(native, offset)?;
+    let offset = skip_length_prefixed(native, offset.checked_add(4)?)?;
+    let data_len = u32_at(native, offset)?;
+
+    let path = crate::decode_string8(path, None);
+    let basename = path.rsplit(['\\', '/']).next().unwrap_or_default();
+    let label = crate::decode_string8(label, None);
+    let filename = [label.as_str(), basename]
+        .into_iter()
+        .find(|name| extension(name).is_some())
+        .or(Some(basename).filter(|name| !name.is_empty()))
+        .map(str::to_string);
+    Some((filename, offset as u64 + 4, u64::from(data_len)))
+}
+
+/// 文件名的扩展名（不含点）
+pub(crate) fn extension(filename: &str) -> Option<&str> {
+    filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext)
+        .filter(|ext| !ext.is_empty() && ext.len() <= 5 && !ext.contains(' '))
+}
+
+/// OLEPresentationStream：剪贴板格式、目标设备，之后是 Aspect、Lindex、Advf、
+/// Reserved1、Width、Height（HIMETRIC）各 4 字节，再是数据长度和数据。
+/// `prefix` 为流的开头，`stream_len` 为流的总长度
+fn read_presentation(stream: &'static str, prefix: &[u8], stream_len: u64) -> Option<OleObject> {
+    let format = match u32_at(prefix, 0)? {
+        0xFFFF_FFFE | 0xFFFF_FFFF => u32_at(prefix, 4)?,
+        _ => return None,
+    };
+    // TargetDeviceSize 包含自身的 4 字节
+    let offset = 8usize.checked_add((u32_at(prefix, 8)? as usize).max(4))?;
+    let width = u32_at(prefix, offset.checked_add(16)?)?;
+    let height = u32_at(prefix, offset.checked_add(20)?)?;
+    let len = u64::from(u32_at(prefix, offset.checked_add(24)?)?);
+    let offset = offset.checked_add(28)?;
+    if (offset as u64).checked_add(len)? > stream_len {
+        return None;
+    }
+    let payload = prefix.get(offset..).unwrap_or_default();
+
+    let (size, extension, content_type) = match format {
+        CF_DIB => {
+            bitmap_file_header(payload, len)?;
+            (len + u64::from(BITMAPFILEHEADER_LEN), "bmp", "image/bmp")
+        }
+        CF_METAFILEPICT => {
+            let header_len = match u32_at(payload, 0) {
+                Some(PLACEABLE_KEY) => 0,
+                _ => PLACEABLE_HEADER_LEN,
+            };
+            (len + header_len, "wmf", "image/wmf")
+        }
+        CF_ENHMETAFILE => (len, "emf", "image/emf"),
+        _ => return None,
+    };
+    Some(OleObject {
+        filename: None,
+        extension,
+        content_type,
+        size,
+        source: Source::Picture {
+            stream,
+            format,
+            offset: offset as u64,
+            len,
+            width,
+            height,
+        },
+    })
+}
+
+/// DIB 前要补上的 BITMAPFILEHEADER，像素数据偏移 = 文件头 + 信息头 + 调色板。
+/// `dib` 至少包含信息头，`dib_len` 为 DIB 的总长度
+fn bitmap_file_header(dib: &[u8], dib_len: u64) -> Option<[u8; 14]> {
+    let header_len = u32_at(dib, 0)?;
+    let bit_count = u16_at(dib, 14)?;
+    let compression = u32_at(dib, 16)?;
+    let colors_used = u32_at(dib, 32).unwrap_or(0);
+    let colors = match colors_used {
+        0 if bit_count <= 8 => 1 << bit_count,
+        n => n,
+    };
+    let masks = if header_len == 40 && compression == BI_BITFIELDS {
+        12
+    } else {
+        0
+    };
+    let pixel_offset = BITMAPFILEHEADER_LEN
+        .checked_add(header_len)?
+        .checked_add(masks)?
+        .checked_add(colors.checked_mul(4)?)?;
+    let file_len = u32::try_from(dib_len)
+        .ok()?
+        .checked_add(BITMAPFILEHEADER_LEN)?;
+
+    let mut header = [0u8; 14];
+    header[..2].copy_from_slice(b"BM");
+    header[2..6].copy_from_slice(&file_len.to_le_bytes());
+    header[10..].copy_from_slice(&pixel_offset.to_le_bytes());
+    Some(header)
+}
+
+/// 图元文件前补上可放置 WMF 头部，边界框取对象尺寸（HIMETRIC）
+fn placeable_wmf(metafile: &[u8], width: u32, height: u32) -> Vec<u8> {
+    if u32_at(metafile, 0) == Some(PLACEABLE_KEY) {
+        return metafile.to_vec();
+    }
+    let mut header = Vec::with_capacity(PLACEABLE_HEADER_LEN as usize + metafile.len());
+    header.extend_from_slice(&PLACEABLE_KEY.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    for value in [
+        0,
+        0,
+        width.min(i16::MAX as u32),
+        height.min(i16::MAX as u32),
+    ] {
+        header.extend_from_slice(&(value as u16).to_le_bytes());
+    }
+    header.extend_from_slice(&HIMETRIC_PER_INCH.to_le_bytes());
+    header.extend_from_slice(&0u32.to_le_bytes());
+    // 校验和为前 10 个字的异或
+    let checksum = header.chunks_exact(2).fold(0u16, |sum, word| {
+        sum ^ u16::from_le_bytes([word[0], word[1]])
+    });
+    header.extend_from_slice(&checksum.to_le_bytes());
+    header.extend_from_slice(metafile);
+    header
+}
+
+/// Package 流中 OOXML 对象的扩展名和 MIME 类型
+fn ooxml_type(prog_id: &str) -> (&'static str, &'static str) {
+    let app = prog_id.split('.').next().unwrap_or_default();
+    match app {
+        "Word" => (
+            "docx",
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        ),
+        "Excel" => (
+            "xlsx",
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        "PowerPoint" => (
+            "pptx",
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        ),
+        _ => ("zip", "application/zip"),
+    }
+}
+
+/// 复制为复合文档的对象的扩展名和 MIME 类型
+fn compound_type(prog_id: &str) -> (&'static str, &'static str) {
+    let app = prog_id.split('.').next().unwrap_or_default();
+    match app {
+        "Word" => ("doc", "application/msword"),
+        "Excel" => ("xls", "application/vnd.ms-excel"),
+        "PowerPoint" => ("ppt", "application/vnd.ms-powerpoint"),
+        "Visio" => ("vsd", "application/vnd.visio"),
+        _ => ("bin", "application/octet-stream"),
+    }
+}
+
+/// 把存储及其全部子项复制为一个独立的复合文档，保留各存储的 CLSID
+fn copy_storage<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    storage: &Path,
+) -> std::io::Result<Vec<u8>> {
+    let entries: Vec<_> = comp
+        .walk_storage(storage)?
+        .map(|entry| {
+            let path = entry.path().strip_prefix(storage).map(Path::to_path_buf);
+            (
+                entry.path().to_path_buf(),
+                path,
+                entry.is_stream(),
+                *entry.clsid(),
+            )
+        })
+        .collect();
+
+    let mut out = CompoundFile::create(Cursor::new(Vec::new()))?;
+    for (source, path, is_stream, clsid) in entries {
+        let Ok(path) = path else { continue };
+        let target = Path::new("/").join(path);
+        if is_stream {
+            let data = props::read_stream(comp, &source)?;
+            out.create_stream(&target)?.write_all(&data)?;
+        } else {
+            if target != Path::new("/") {
+                out.create_storage(&target)?;
+            }
+            out.set_storage_clsid(&target, clsid)?;
+        }
+    }
+    out.flush()?;
+    Ok(out.into_inner().into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn length_prefixed(text: &str) -> Vec<u8> {
+        let mut out = (text.len() as u32 + 1).to_le_bytes().to_vec();
+        out.extend_from_slice(text.as_bytes());
+        out.push(0);
+        out
+    }
+
+    fn comp_obj(prog_id: &str) -> Vec<u8> {
+        let mut data = vec![0u8; COMP_OBJ_HEADER_LEN];
+        data.extend(length_prefixed("OLE Package"));
+        data.extend(0u32.to_le_bytes());
+        data.extend(length_prefixed(prog_id));
+        data
+    }
+
+    fn build(streams: &[(&str, Vec<u8>)]) -> CompoundFile<Cursor<Vec<u8>>> {
+        let mut comp = CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        comp.create_storage("/obj").unwrap();
+        for (name, data) in streams {
+            comp.create_stream(Path::new("/obj").join(name))
+                .unwrap()
+                .write_all(data)
+                .unwrap();
+        }
+        comp
+    }
+
+    #[test]
+    fn test_package() {
+        let mut native = 2u16.to_le_bytes().to_vec();
+        native.extend(b"report.txt\0C:\\Users\\a\\report.txt\0");
+        native.extend(0x0003_0000u32.to_le_bytes());
+        native.extend(length_prefixed("C:\\Temp\\report.txt"));
+        native.extend(5u32.to_le_bytes());
+        native.extend(b"hello");
+        let mut stream = (native.len() as u32).to_le_bytes().to_vec();
+        stream.extend(native);
+
+        let mut comp = build(&[
+            (COMP_OBJ_STREAM, comp_obj("Package")),
+            (OLE10_NATIVE_STREAM, stream),
+        ]);
+        let storage = Path::new("/obj");
+        let object = read_ole_object(&mut comp, storage).unwrap();
+        assert_eq!(object.filename.as_deref(), Some("report.txt"));
+        assert_eq!(object.size, 5);
+        assert_eq!(object.read(&mut comp, storage).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_static_dib() {
+        // 1x1 24 位位图
+        let mut dib = 40u32.to_le_bytes().to_vec();
+        dib.extend(1i32.to_le_bytes());
+        dib.extend(1i32.to_le_bytes());
+        dib.extend(1u16.to_le_bytes());
+        dib.extend(24u16.to_le_bytes());
+        dib.extend([0u8; 24]);
+        dib.extend([0, 0, 255, 0]);
+
+        let mut presentation = 0xFFFF_FFFFu32.to_le_bytes().to_vec();
+        presentation.extend(CF_DIB.to_le_bytes());
+        presentation.extend(4u32.to_le_bytes());
+        presentation.extend([0u8; 16]);
+        presentation.extend(26u32.to_le_bytes());
+        presentation.extend(26u32.to_le_bytes());
+        presentation.extend((dib.len() as u32).to_le_bytes());
+        presentation.extend(&dib);
+
+        let mut comp = build(&[(CONTENTS_STREAM, presentation)]);
+        let clsid = "00000316-0000-0000-c000-000000000046".parse().unwrap();
+        comp.set_storage_clsid("/obj", clsid).unwrap();
+
+        let storage = Path::new("/obj");
+        let object = read_ole_object(&mut comp, storage).unwrap();
+        assert_eq!(object.extension, "bmp");
+        assert_eq!(object.size, dib.len() as u64 + 14);
+        let data = object.read(&mut comp, storage).unwrap();
+        assert_eq!(&data[..2], b"BM");
+        assert_eq!(u32_at(&data, 2), Some(dib.len() as u32 + 14));
+        assert_eq!(u32_at(&data, 10), Some(54));
+        assert_eq!(data.len() as u64, object.size);
+    }
+
+    #[test]
+    fn test_malformed_lengths() {
+        // 长度字段接近 u32::MAX 时不溢出，按无法识别处理
+        let mut dib = 40u32.to_le_bytes().to_vec();
+        dib.extend([0u8; 10]);
+        dib.extend(8u16.to_le_bytes());
+        dib.extend([0u8; 16]);
+        dib.extend(u32::MAX.to_le_bytes());
+        assert!(bitmap_file_header(&dib, dib.len() as u64).is_none());
+
+        let mut presentation = 0xFFFF_FFFFu32.to_le_bytes().to_vec();
+        presentation.extend(CF_DIB.to_le_bytes());
+        presentation.extend(u32::MAX.to_le_bytes());
+        let len = presentation.len() as u64;
+        assert!(read_presentation(CONTENTS_STREAM, &presentation, len).is_none());
+
+        let mut native = 2u16.to_le_bytes().to_vec();
+        native.extend(b"a.txt\0a.txt\0");
+        native.extend(0u32.to_le_bytes());
+        native.extend(u32::MAX.to_le_bytes());
+        assert!(read_package(&native).is_none());
+
+        let mut stream = u32::MAX.to_le_bytes().to_vec();
+        stream.extend(b"BM");
+        let object = read_ole10_native(&stream, stream.len() as u64, "PBrush").unwrap();
+        assert_eq!(object.size, 2);
+
+        let mut comp_obj = vec![0u8; COMP_OBJ_HEADER_LEN];
+        comp_obj.extend(u32::MAX.to_le_bytes());
+        assert!(read_prog_id(&comp_obj).is_none());
+    }
+
+    #[test]
+    fn test_compound_copy() {
+        let mut comp = build(&[
+            (COMP_OBJ_STREAM, comp_obj("Word.Document.8")),
+            ("WordDocument", b"doc".to_vec()),
+        ]);
+        let storage = Path::new("/obj");
+        let object = read_ole_object(&mut comp, storage).unwrap();
+        assert_eq!(object.extension, "doc");
+
+        let data = object.read(&mut comp, storage).unwrap();
+        let mut copy = CompoundFile::open(Cursor::new(data)).unwrap();
+        assert_eq!(
+            props::read_stream(&mut copy, "/WordDocument").unwrap(),
+            b"doc"
+        );
+        assert!(copy.is_stream("/\u{1}CompObj"));
+    }
+}
@@ -0,0 +1,65 @@
+//! 转发/重发消息中原始作者的信息 (PR_ORIGINAL_*)
+
+use crate::props::PropertyStore;
+use serde::Serialize;
+
+const PROP_ORIGINAL_SUBJECT: u16 = 0x0049;
+const PROP_ORIGINAL_SUBMIT_TIME: u16 = 0x004E;
+const PROP_ORIGINAL_DELIVERY_TIME: u16 = 0x0055;
+const PROP_ORIGINAL_SENDER_NAME: u16 = 0x005A;
+const PROP_ORIGINAL_SENT_REPRESENTING_NAME: u16 = 0x005D;
+const PROP_ORIGINAL_SENDER_EMAIL_ADDRESS: u16 = 0x0067;
+const PROP_ORIGINAL_SENT_REPRESENTING_EMAIL_ADDRESS: u16 = 0x0069;
+
+/// 原始消息的作者、主题和时间
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OriginalMessage {
+    pub sender_name: Option<String>,
+    pub sender_email: Option<String>,
+    /// 原始消息“代表谁发送”的姓名
+    pub sent_representing_name: Option<String>,
+    pub sent_representing_email: Option<String>,
+    pub subject: Option<String>,
+    pub submit_time: Option<String>,
+    pub delivery_time: Option<String>,
+}
+
+/// 读取原始消息信息，一个相关属性都没有时返回 `None`
+pub(crate) fn read_original(store: &PropertyStore) -> Option<OriginalMessage> {
+    let text = |id| {
+        store
+            .get(id)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+
+    let original = OriginalMessage {
+        sender_name: text(PROP_ORIGINAL_SENDER_NAME),
+        sender_email: text(PROP_ORIGINAL_SENDER_EMAIL_ADDRESS),
+        sent_representing_name: text(PROP_ORIGINAL_SENT_REPRESENTING_NAME),
+        sent_representing_email: text(PROP_ORIGINAL_SENT_REPRESENTING_EMAIL_ADDRESS),
+        subject: text(PROP_ORIGINAL_SUBJECT),
+        submit_time: text(PROP_ORIGINAL_SUBMIT_TIME),
+        delivery_time: text(PROP_ORIGINAL_DELIVERY_TIME),
+    };
+    (original != OriginalMessage::default()).then_some(original)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_original() {
+        let mut store = PropertyStore::default();
+        assert_eq!(read_original(&store), None);
+
+        let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        store.insert_stream("__substg1.0_005A001F", utf16("Carol"));
+        store.insert_stream("__substg1.0_0049001F", utf16("Budget"));
+
+        let original = read_original(&store).unwrap();
+        assert_eq!(original.sender_name.as_deref(), Some("Carol"));
+        assert_eq!(original.subject.as_deref(), Some("Budget"));
+    }
+}
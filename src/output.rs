@@ -0,0 +1,60 @@
+//! 结果在 JS 侧的表示方式
+//!
+//! 默认沿用 serde_wasm_bindgen 的行为：映射输出为 `Map`，64 位整数输出为 number，
+//! `None` 输出为 `undefined`。调用方可以按需改成普通对象、BigInt 或 `null`。
+
+use crate::{parse_msg_to_struct, perf, MsgParseError};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// 序列化选项，对应 serde_wasm_bindgen 的 `Serializer` 配置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OutputOptions {
+    /// 映射（如 `custom`）输出为普通对象而不是 `Map`
+    pub maps_as_objects: bool,
+    /// 64 位整数（附件大小、`custom` 中的 PT_I8 等）输出为 BigInt，
+    /// 超过 2^53 也不丢精度；默认输出为 number
+    pub large_numbers_as_bigints: bool,
+    /// 缺失的字段输出为 `null` 而不是 `undefined`
+    pub missing_as_null: bool,
+}
+
+impl OutputOptions {
+    pub(crate) fn serializer(&self) -> serde_wasm_bindgen::Serializer {
+        serde_wasm_bindgen::Serializer::new()
+            .serialize_maps_as_objects(self.maps_as_objects)
+            .serialize_large_number_types_as_bigints(self.large_numbers_as_bigints)
+            .serialize_missing_as_null(self.missing_as_null)
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const OUTPUT_OPTIONS_TS: &str = r#"
+export interface OutputOptions {
+    maps_as_objects?: boolean;
+    large_numbers_as_bigints?: boolean;
+    missing_as_null?: boolean;
+}
+"#;
+
+/// WASM 导出接口
+/// 解析 MSG 文件，按 `options`（见 `OutputOptions`，可省略）指定的方式把结果转换为 JS 值
+#[wasm_bindgen]
+pub fn parse_msg_file_with_output_options(
+    file_data: &[u8],
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options: OutputOptions = if options.is_undefined() || options.is_null() {
+        OutputOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|e| MsgParseError::invalid_argument(e).to_js())?
+    };
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+
+    let _phase = perf::phase("serialize");
+    email
+        .serialize(&options.serializer())
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
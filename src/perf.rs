@@ -0,0 +1,60 @@
+//! 浏览器 User Timing 埋点
+//!
+//! 开启 `perf-marks` feature 并编译到 wasm32 时，每个解析阶段会调用
+//! `performance.mark` / `performance.measure`，开发者可以直接在 DevTools 的
+//! Performance 面板里看到各阶段耗时。其他情况下 [`Phase`] 是零开销的空实现。
+
+#[cfg(all(feature = "perf-marks", target_arch = "wasm32"))]
+mod imp {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        // 使用 catch：宿主环境没有 performance 对象时静默忽略而不是抛异常
+        #[wasm_bindgen(catch, js_namespace = performance, js_name = mark)]
+        fn performance_mark(name: &str) -> Result<JsValue, JsValue>;
+
+        #[wasm_bindgen(catch, js_namespace = performance, js_name = measure)]
+        fn performance_measure(name: &str, start: &str, end: &str) -> Result<JsValue, JsValue>;
+    }
+
+    pub(crate) fn mark(name: &str) {
+        let _ = performance_mark(name);
+    }
+
+    pub(crate) fn measure(name: &str, start: &str, end: &str) {
+        let _ = performance_measure(name, start, end);
+    }
+}
+
+/// 一个解析阶段的计时守卫，创建时打开始标记，析构时打结束标记并生成 measure
+pub(crate) struct Phase {
+    #[cfg(all(feature = "perf-marks", target_arch = "wasm32"))]
+    name: &'static str,
+}
+
+/// 开始一个名为 `name` 的阶段，在返回值被 drop 时结束
+pub(crate) fn phase(name: &'static str) -> Phase {
+    #[cfg(all(feature = "perf-marks", target_arch = "wasm32"))]
+    {
+        imp::mark(&format!("msg-parser:{}:start", name));
+        Phase { name }
+    }
+    #[cfg(not(all(feature = "perf-marks", target_arch = "wasm32")))]
+    {
+        let _ = name;
+        Phase {}
+    }
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        #[cfg(all(feature = "perf-marks", target_arch = "wasm32"))]
+        {
+            let start = format!("msg-parser:{}:start", self.name);
+            let end = format!("msg-parser:{}:end", self.name);
+            imp::mark(&end);
+            imp::measure(&format!("msg-parser:{}", self.name), &start, &end);
+        }
+    }
+}
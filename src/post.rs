@@ -0,0 +1,83 @@
+//! 公共文件夹帖子（IPM.Post）
+//!
+//! 帖子没有传输收件人，发布者和会话信息比收件人列表更有意义。
+
+use crate::props::{self, PropertyStore, PropertyValue};
+use serde::Serialize;
+
+const PROP_SENT_REPRESENTING_NAME: u16 = 0x0042;
+const PROP_SENT_REPRESENTING_EMAIL_ADDRESS: u16 = 0x0065;
+const PROP_SENDER_NAME: u16 = 0x0C1A;
+const PROP_SENDER_EMAIL_ADDRESS: u16 = 0x0C1F;
+const PROP_CONVERSATION_TOPIC: u16 = 0x0070;
+const PROP_CONVERSATION_INDEX: u16 = 0x0071;
+const PROP_INTERNET_MESSAGE_ID: u16 = 0x1035;
+const PROP_IN_REPLY_TO_ID: u16 = 0x1042;
+
+/// 帖子信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Post {
+    /// 发布者姓名
+    pub poster_name: Option<String>,
+    /// 发布者地址
+    pub poster_email: Option<String>,
+    /// 会话主题（不含 RE: 等前缀）
+    pub conversation_topic: Option<String>,
+    /// PR_CONVERSATION_INDEX（十六进制），前 22 字节相同的帖子属于同一会话
+    pub conversation_index: Option<String>,
+    pub message_id: Option<String>,
+    /// 所回复帖子的 Message-ID
+    pub in_reply_to: Option<String>,
+}
+
+/// 是否为帖子
+pub(crate) fn is_post_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class == "IPM.POST" || class.starts_with("IPM.POST.")
+}
+
+/// 从顶层属性中读取帖子信息，发布者优先取“代表发送”的身份
+pub(crate) fn read_post(store: &PropertyStore) -> Post {
+    let text = |id| {
+        store
+            .get(id)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+
+    Post {
+        poster_name: text(PROP_SENT_REPRESENTING_NAME).or_else(|| text(PROP_SENDER_NAME)),
+        poster_email: text(PROP_SENT_REPRESENTING_EMAIL_ADDRESS)
+            .or_else(|| text(PROP_SENDER_EMAIL_ADDRESS)),
+        conversation_topic: text(PROP_CONVERSATION_TOPIC),
+        conversation_index: match store.get(PROP_CONVERSATION_INDEX) {
+            Some(PropertyValue::Binary(data)) if !data.is_empty() => Some(props::to_hex(&data)),
+            _ => None,
+        },
+        message_id: text(PROP_INTERNET_MESSAGE_ID),
+        in_reply_to: text(PROP_IN_REPLY_TO_ID),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_post() {
+        let utf16 = |s: &str| -> Vec<u8> { s.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        let mut store = PropertyStore::default();
+        store.insert_stream("__substg1.0_0C1A001F", utf16("Alice"));
+        store.insert_stream("__substg1.0_0042001F", utf16("Team Lead"));
+        store.insert_stream("__substg1.0_0070001F", utf16("Release plan"));
+        store.insert_stream("__substg1.0_00710102", vec![0x01, 0xD2]);
+
+        let post = read_post(&store);
+
+        assert_eq!(post.poster_name.as_deref(), Some("Team Lead"));
+        assert_eq!(post.conversation_topic.as_deref(), Some("Release plan"));
+        assert_eq!(post.conversation_index.as_deref(), Some("01D2"));
+        assert!(is_post_class("IPM.Post"));
+        assert!(!is_post_class("IPM.PostX"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Protobuf 编码（`protobuf` feature）
+//!
+//! 结构体按 `proto/msg_email.proto` 手写，避免引入 protoc 构建依赖。其他语言的服务
+//! 可以直接用该 .proto 生成代码来读取结果，而不必依赖 JS 对象的形状。
+
+use crate::{parse_msg_to_struct, Address, MsgEmail};
+use prost::Message;
+use wasm_bindgen::prelude::*;
+
+/// 对应 `msg_parser.MsgEmail`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoMsgEmail {
+    #[prost(string, optional, tag = "1")]
+    pub subject: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub sender_name: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub sender_email: Option<String>,
+    #[prost(string, repeated, tag = "4")]
+    pub recipients: Vec<String>,
+    #[prost(string, repeated, tag = "5")]
+    pub cc_recipients: Vec<String>,
+    #[prost(string, optional, tag = "6")]
+    pub sent_time: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    pub body_text: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub body_html: Option<String>,
+    #[prost(string, optional, tag = "9")]
+    pub body_rtf: Option<String>,
+    #[prost(message, repeated, tag = "10")]
+    pub attachments: Vec<ProtoAttachment>,
+    #[prost(bool, tag = "11")]
+    pub is_template: bool,
+    #[prost(string, repeated, tag = "12")]
+    pub warnings: Vec<String>,
+    #[prost(string, optional, tag = "13")]
+    pub search_key: Option<String>,
+    #[prost(string, optional, tag = "14")]
+    pub change_key: Option<String>,
+    #[prost(int64, optional, tag = "15")]
+    pub sent_time_unix: Option<i64>,
+    #[prost(string, repeated, tag = "16")]
+    pub bcc_recipients: Vec<String>,
+}
+
+/// 对应 `msg_parser.Attachment`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtoAttachment {
+    #[prost(string, tag = "1")]
+    pub filename: String,
+    #[prost(string, optional, tag = "2")]
+    pub content_type: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub content_id: Option<String>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub data: Vec<u8>,
+}
+
+impl From<MsgEmail> for ProtoMsgEmail {
+    fn from(email: MsgEmail) -> Self {
+        ProtoMsgEmail {
+            subject: email.subject,
+            sender_name: email.sender_name,
+            sender_email: email.sender_email,
+            recipients: email.recipients.iter().map(Address::to_string).collect(),
+            cc_recipients: email.cc_recipients.iter().map(Address::to_string).collect(),
+            bcc_recipients: email
+                .bcc_recipients
+                .iter()
+                .map(Address::to_string)
+                .collect(),
+            sent_time: email.sent_time,
+            sent_time_unix: email.sent_time_unix,
+            body_text: email.body_text,
+            body_html: email.body_html,
+            body_rtf: email.body_rtf,
+            attachments: email
+                .attachments
+                .into_iter()
+                .map(|a| ProtoAttachment {
+                    filename: a.filename,
+                    content_type: a.content_type,
+                    content_id: a.content_id,
+                    data: a.data,
+                })
+                .collect(),
+            is_template: email.is_template,
+            warnings: email.warnings,
+            search_key: email.search_key,
+            change_key: email.change_key,
+        }
+    }
+}
+
+/// 将解析结果编码为 Protobuf 字节
+pub fn to_protobuf(email: MsgEmail) -> Vec<u8> {
+    ProtoMsgEmail::from(email).encode_to_vec()
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件并以 Protobuf (`msg_parser.MsgEmail`) 字节返回
+#[wasm_bindgen]
+pub fn parse_msg_to_protobuf(file_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+    Ok(to_protobuf(email))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attachment;
+
+    #[test]
+    fn test_protobuf_roundtrip() {
+        let email = MsgEmail {
+            subject: Some("Hello".to_string()),
+            recipients: vec![Address::parse("bob@example.com").unwrap()],
+            attachments: vec![Attachment {
+                filename: "a.bin".to_string(),
+                data: vec![1, 2, 3],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let bytes = to_protobuf(email);
+        let decoded = ProtoMsgEmail::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded.subject.as_deref(), Some("Hello"));
+        assert_eq!(decoded.recipients, vec!["bob@example.com"]);
+        assert_eq!(decoded.attachments[0].data, vec![1, 2, 3]);
+    }
+}
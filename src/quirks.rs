@@ -0,0 +1,139 @@
+//! 第三方 MSG 生成器兼容处理
+//!
+//! Outlook 之外的库（Aspose.Email、Independentsoft MSG.NET、部分 Java/Python 导出工具）
+//! 生成的文件常常与 [MS-OXMSG] 有细微出入。默认开启兼容处理，`ParseOptions::strict`
+//! 可关闭；每种生效的兼容处理都会记录到 `MsgEmail::warnings` 中，方便排查来源。
+
+use crate::props::EMBEDDED_HEADER_LEN;
+use std::collections::BTreeSet;
+
+const SUBSTG_PREFIX: &str = "__substg1.0_";
+const ATTACH_PREFIX: &str = "__attach_version1.0_";
+const RECIP_PREFIX: &str = "__recip_version1.0_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Quirk {
+    /// 流名中的属性标签使用小写十六进制（常见于部分 Java/Python 导出工具）
+    LowercaseTags,
+    /// 存储名前缀大小写不规范，如 `__Attach_Version1.0_#0`（Independentsoft 旧版本）
+    NonstandardStorageNames,
+    /// 顶层属性流使用了 24 字节头部（Aspose 旧版本按嵌入消息格式写出）
+    ShortPropertyHeader,
+    /// 缺少 `__properties_version1.0`，定长属性只能从 `__substg1.0_` 流中读取
+    MissingPropertyStream,
+}
+
+impl Quirk {
+    fn description(self) -> &'static str {
+        match self {
+            Quirk::LowercaseTags => "兼容处理：属性流名使用小写十六进制标签",
+            Quirk::NonstandardStorageNames => "兼容处理：存储名大小写不规范",
+            Quirk::ShortPropertyHeader => "兼容处理：顶层属性流使用 24 字节头部",
+            Quirk::MissingPropertyStream => "兼容处理：缺少属性流，定长属性可能不完整",
+        }
+    }
+}
+
+/// 兼容处理层，记录本次解析实际生效的处理
+#[derive(Debug)]
+pub(crate) struct Quirks {
+    enabled: bool,
+    applied: BTreeSet<Quirk>,
+}
+
+impl Quirks {
+    pub fn new(enabled: bool) -> Self {
+        Quirks {
+            enabled,
+            applied: BTreeSet::new(),
+        }
+    }
+
+    /// 返回规范化后的目录项名称，未开启兼容处理时原样返回
+    pub fn canonical_name(&mut self, name: &str) -> String {
+        if !self.enabled {
+            return name.to_string();
+        }
+
+        for prefix in [SUBSTG_PREFIX, ATTACH_PREFIX, RECIP_PREFIX] {
+            if name.len() < prefix.len() || !name.is_char_boundary(prefix.len()) {
+                continue;
+            }
+            let (head, rest) = name.split_at(prefix.len());
+            if !head.eq_ignore_ascii_case(prefix) {
+                continue;
+            }
+            if head != prefix {
+                self.applied.insert(Quirk::NonstandardStorageNames);
+            }
+            if prefix == SUBSTG_PREFIX && rest.bytes().any(|b| b.is_ascii_lowercase()) {
+                self.applied.insert(Quirk::LowercaseTags);
+                return format!("{}{}", prefix, rest.to_ascii_uppercase());
+            }
+            return format!("{}{}", prefix, rest);
+        }
+
+        name.to_string()
+    }
+
+    /// 根据属性流长度确定头部长度
+    ///
+    /// 记录区总是 16 字节对齐，若按标准头部长度无法对齐而按 24 字节可以对齐，则按 24 字节解析。
+    pub fn property_header_len(&mut self, stream_len: usize, expected: usize) -> usize {
+        if self.enabled
+            && expected != EMBEDDED_HEADER_LEN
+            && stream_len >= EMBEDDED_HEADER_LEN
+            && !stream_len.saturating_sub(expected).is_multiple_of(16)
+            && (stream_len - EMBEDDED_HEADER_LEN).is_multiple_of(16)
+        {
+            self.applied.insert(Quirk::ShortPropertyHeader);
+            return EMBEDDED_HEADER_LEN;
+        }
+        expected
+    }
+
+    pub fn note_missing_property_stream(&mut self) {
+        if self.enabled {
+            self.applied.insert(Quirk::MissingPropertyStream);
+        }
+    }
+
+    /// 生效的兼容处理说明，按固定顺序输出
+    pub fn warnings(&self) -> impl Iterator<Item = String> + '_ {
+        self.applied.iter().map(|q| q.description().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_name() {
+        let mut quirks = Quirks::new(true);
+        assert_eq!(
+            quirks.canonical_name("__substg1.0_0037001f"),
+            "__substg1.0_0037001F"
+        );
+        assert_eq!(
+            quirks.canonical_name("__Attach_Version1.0_#0"),
+            "__attach_version1.0_#0"
+        );
+        assert_eq!(quirks.warnings().count(), 2);
+
+        let mut strict = Quirks::new(false);
+        assert_eq!(
+            strict.canonical_name("__substg1.0_0037001f"),
+            "__substg1.0_0037001f"
+        );
+        assert_eq!(strict.warnings().count(), 0);
+    }
+
+    #[test]
+    fn test_property_header_len() {
+        let mut quirks = Quirks::new(true);
+        assert_eq!(quirks.property_header_len(32 + 48, 32), 32);
+        assert_eq!(quirks.property_header_len(24 + 48, 32), 24);
+        assert_eq!(quirks.property_header_len(8 + 48, 8), 8);
+    }
+}
@@ -0,0 +1,164 @@
+//! S/MIME 签名回执（IPM.Note.Receipt.SMIME）
+//!
+//! 回执本身是一个 CMS SignedData，封装内容类型为 id-ct-receipt (RFC 2634)，
+//! 其中的 signedContentIdentifier 和 originatorSignatureValue 唯一指向被回执的原始签名邮件。
+//! 回执放在附件（通常是 smime.p7m）中，可能是 DER 原文，也可能是带 MIME 头的 base64 文本。
+
+use crate::der::{
+    decode_mime_base64, format_oid, Tlv, TAG_CONTEXT_0, TAG_INTEGER, TAG_OID, TAG_SEQUENCE, TAG_SET,
+};
+use crate::props::{self, PropertyStore};
+use crate::Attachment;
+use serde::Serialize;
+
+const PROP_IN_REPLY_TO_ID: u16 = 0x1042;
+
+/// id-ct-receipt
+const OID_CT_RECEIPT: &str = "1.2.840.113549.1.9.16.1.1";
+
+/// 签名回执
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SmimeReceipt {
+    /// 原始邮件的内容类型 OID（通常为 id-data 1.2.840.113549.1.7.1）
+    pub content_type: String,
+    /// 原始邮件 ReceiptRequest 中的 signedContentIdentifier（十六进制）
+    pub signed_content_identifier: String,
+    /// 原始邮件的签名值（十六进制），可与原始邮件 SignerInfo 中的签名比对
+    pub originator_signature_value: String,
+    /// 原始邮件的 Message-ID (PR_IN_REPLY_TO_ID)
+    pub in_reply_to: Option<String>,
+}
+
+/// 是否为 S/MIME 签名回执
+pub(crate) fn is_receipt_class(message_class: &str) -> bool {
+    message_class.eq_ignore_ascii_case("IPM.Note.Receipt.SMIME")
+}
+
+/// 在附件中找到回执并解析
+pub(crate) fn read_receipt(
+    attachments: &[Attachment],
+    store: &PropertyStore,
+) -> Option<SmimeReceipt> {
+    let mut receipt = attachments
+        .iter()
+        .find_map(|attachment| parse_receipt(&attachment.data))?;
+    receipt.in_reply_to = store
+        .get(PROP_IN_REPLY_TO_ID)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|s| !s.is_empty());
+    Some(receipt)
+}
+
+/// 从 DER/BER 编码或 base64 MIME 实体中解析回执
+fn parse_receipt(data: &[u8]) -> Option<SmimeReceipt> {
+    let decoded;
+    let der = if data.first() == Some(&TAG_SEQUENCE) {
+        data
+    } else {
+        decoded = decode_mime_base64(data)?;
+        &decoded
+    };
+
+    // ContentInfo ::= SEQUENCE { contentType, [0] EXPLICIT SignedData }
+    let content_info = Tlv::read(der)?.expect(TAG_SEQUENCE)?;
+    let mut items = content_info.children();
+    items.next()?.expect(TAG_OID)?;
+    let signed_data = items.next()?.expect(TAG_CONTEXT_0)?.children().next()?;
+
+    // SignedData ::= SEQUENCE { version, digestAlgorithms, encapContentInfo, ... }
+    let mut items = signed_data.expect(TAG_SEQUENCE)?.children();
+    items.next()?.expect(TAG_INTEGER)?;
+    items.next()?.expect(TAG_SET)?;
+    let mut encap = items.next()?.expect(TAG_SEQUENCE)?.children();
+    if format_oid(encap.next()?.expect(TAG_OID)?.body) != OID_CT_RECEIPT {
+        return None;
+    }
+    let econtent = encap.next()?.expect(TAG_CONTEXT_0)?.children().next()?;
+    let econtent = econtent.octets()?;
+
+    // Receipt ::= SEQUENCE { version, contentType, signedContentIdentifier, originatorSignatureValue }
+    let receipt = Tlv::read(&econtent)?.expect(TAG_SEQUENCE)?;
+    let mut items = receipt.children();
+    items.next()?.expect(TAG_INTEGER)?;
+    let content_type = format_oid(items.next()?.expect(TAG_OID)?.body);
+    let identifier = items.next()?.octets()?;
+    let signature = items.next()?.octets()?;
+
+    Some(SmimeReceipt {
+        content_type,
+        signed_content_identifier: props::to_hex(&identifier),
+        originator_signature_value: props::to_hex(&signature),
+        in_reply_to: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::der::{TAG_OCTET_STRING, TAG_OCTET_STRING_CONSTRUCTED};
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag, content.len() as u8];
+        out.extend_from_slice(content);
+        out
+    }
+
+    /// 构造一个封装了回执的 SignedData，eContent 使用不定长分段编码
+    fn sample_receipt() -> Vec<u8> {
+        // id-data 1.2.840.113549.1.7.1
+        let id_data = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01];
+        // id-ct-receipt 1.2.840.113549.1.9.16.1.1
+        let id_receipt = [
+            0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x10, 0x01, 0x01,
+        ];
+        // id-signedData 1.2.840.113549.1.7.2
+        let id_signed = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+
+        let receipt = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[1]),
+                tlv(TAG_OID, &id_data),
+                tlv(TAG_OCTET_STRING, &[0xAA, 0xBB]),
+                tlv(TAG_OCTET_STRING, &[0x01, 0x02, 0x03]),
+            ]
+            .concat(),
+        );
+        let (head, tail) = receipt.split_at(4);
+        let mut econtent = vec![TAG_OCTET_STRING_CONSTRUCTED, 0x80];
+        econtent.extend(tlv(TAG_OCTET_STRING, head));
+        econtent.extend(tlv(TAG_OCTET_STRING, tail));
+        econtent.extend([0, 0]);
+
+        let encap = tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_OID, &id_receipt), tlv(TAG_CONTEXT_0, &econtent)].concat(),
+        );
+        let signed_data = tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_INTEGER, &[3]), tlv(TAG_SET, &[]), encap].concat(),
+        );
+        tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_OID, &id_signed), tlv(TAG_CONTEXT_0, &signed_data)].concat(),
+        )
+    }
+
+    #[test]
+    fn test_parse_receipt() {
+        let expected = SmimeReceipt {
+            content_type: "1.2.840.113549.1.7.1".to_string(),
+            signed_content_identifier: "AABB".to_string(),
+            originator_signature_value: "010203".to_string(),
+            in_reply_to: None,
+        };
+        let der = sample_receipt();
+        assert_eq!(parse_receipt(&der), Some(expected.clone()));
+
+        let mime = format!(
+            "Content-Type: application/pkcs7-mime; smime-type=signed-receipt\r\n\r\n{}\r\n",
+            crate::eml::base64(&der)
+        );
+        assert_eq!(parse_receipt(mime.as_bytes()), Some(expected));
+    }
+}
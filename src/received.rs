@@ -0,0 +1,195 @@
+//! Received 头逐跳解析
+//!
+//! 每经过一台邮件服务器就会在传输头顶部加一条 Received 头（RFC 5321 §4.4），
+//! 形如 `from 发送方 (注释) by 接收方 with 协议 id 队列号 for <收件人>; 时间`。
+//! 解析成逐跳的结构后，JS 端可以直接追溯钓鱼邮件的来源和各跳延迟。
+
+use crate::datetime;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 一跳投递记录
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReceivedHop {
+    /// `from` 子句中发送方自称的主机名
+    pub from_host: Option<String>,
+    /// 接收方记录的发送方 IP，取自 `from` 子句注释中的 `[地址]`，没有时取发送方自报的地址字面量
+    pub from_ip: Option<String>,
+    /// 接收方 (`by`) 主机名
+    pub by_host: Option<String>,
+    /// 传输协议 (`with`)，如 `ESMTPS`、`SMTP`、`HTTP`
+    pub protocol: Option<String>,
+    /// 接收方的队列号 (`id`)
+    pub id: Option<String>,
+    /// 收件人 (`for`)
+    pub for_address: Option<String>,
+    /// 接收时间，RFC 3339 格式的 UTC 时间
+    pub timestamp: Option<String>,
+    /// 接收时间的 Unix 时间戳（秒）
+    pub timestamp_unix: Option<i64>,
+    /// 与上一跳（更早的一跳）相隔的秒数
+    pub delay_seconds: Option<i64>,
+    /// 原始头内容
+    pub raw: String,
+}
+
+/// 按传输头中的顺序解析全部 Received 头：最新的一跳（最终收件服务器）在前，
+/// 最后一项离发件人最近。`headers` 为 [`parse_header_block`] 的结果
+///
+/// [`parse_header_block`]: crate::headers::parse_header_block
+pub(crate) fn read_received_chain(headers: &BTreeMap<String, Vec<String>>) -> Vec<ReceivedHop> {
+    let mut hops: Vec<ReceivedHop> = headers
+        .get("received")
+        .into_iter()
+        .flatten()
+        .map(|value| parse_received(value))
+        .collect();
+    for i in 0..hops.len().saturating_sub(1) {
+        if let (Some(time), Some(previous)) = (hops[i].timestamp_unix, hops[i + 1].timestamp_unix) {
+            hops[i].delay_seconds = Some(time - previous);
+        }
+    }
+    hops
+}
+
+/// 解析一条 Received 头
+fn parse_received(value: &str) -> ReceivedHop {
+    let mut hop = ReceivedHop {
+        raw: value.to_string(),
+        ..Default::default()
+    };
+    let (clauses, date) = match value.rsplit_once(';') {
+        Some((clauses, date)) => (clauses, Some(date.trim())),
+        None => (value, None),
+    };
+    hop.timestamp_unix = date.and_then(datetime::parse_rfc2822);
+    hop.timestamp = hop.timestamp_unix.map(datetime::format_rfc3339);
+
+    let mut keyword: Option<String> = None;
+    for token in tokens(clauses) {
+        if let Some(comment) = token.strip_prefix('(') {
+            // 注释跟在所属子句的值之后，`from` 子句的注释里是接收方实际看到的地址，
+            // 优先于发送方自报的地址字面量
+            if keyword.as_deref() == Some("from") {
+                if let Some(ip) = bracketed_ip(comment) {
+                    hop.from_ip = Some(ip);
+                }
+            }
+            continue;
+        }
+        let lower = token.to_ascii_lowercase();
+        if matches!(
+            lower.as_str(),
+            "from" | "by" | "via" | "with" | "id" | "for"
+        ) {
+            keyword = Some(lower);
+            continue;
+        }
+        let value = Some(token.to_string());
+        match keyword.as_deref() {
+            Some("from") if hop.from_host.is_none() => {
+                // 没有主机名时发送方直接写地址字面量，如 `from [192.0.2.1]`
+                if token.starts_with('[') {
+                    hop.from_ip = bracketed_ip(token);
+                }
+                hop.from_host = value;
+            }
+            Some("by") if hop.by_host.is_none() => hop.by_host = value,
+            Some("with") if hop.protocol.is_none() => hop.protocol = value,
+            Some("id") if hop.id.is_none() => hop.id = value,
+            Some("for") if hop.for_address.is_none() => {
+                hop.for_address = Some(token.trim_matches(|c| c == '<' || c == '>').to_string());
+            }
+            _ => {}
+        }
+    }
+    hop
+}
+
+/// 取 `[192.0.2.1]`、`[IPv6:2001:db8::1]` 中的地址
+fn bracketed_ip(text: &str) -> Option<String> {
+    let start = text.find('[')? + 1;
+    let end = start + text[start..].find(']')?;
+    let address = &text[start..end];
+    let address = address
+        .strip_prefix("IPv6:")
+        .or_else(|| address.strip_prefix("ipv6:"))
+        .unwrap_or(address);
+    Some(address.to_string()).filter(|a| !a.is_empty())
+}
+
+/// 按空白拆分，括号注释（可嵌套）整体作为一个以 `(` 开头的词
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => {
+                if depth == 0 {
+                    if let Some(s) = start.take() {
+                        tokens.push(&text[s..i]);
+                    }
+                    start = Some(i);
+                }
+                depth += 1;
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    tokens.extend(start.take().map(|s| &text[s..i]));
+                }
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    tokens.push(&text[s..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    tokens.extend(start.map(|s| &text[s..]));
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::parse_header_block;
+
+    #[test]
+    fn test_read_received_chain() {
+        let headers = parse_header_block(concat!(
+            "Received: from mail.example.com (mail.example.com [192.0.2.1])\r\n",
+            "\tby mx.example.org (Postfix) with ESMTPS id 4Abc12\r\n",
+            "\tfor <bob@example.org>; Mon, 1 Jan 2024 02:00:05 +0000\r\n",
+            "Received: from [10.0.0.5] (unknown [IPv6:2001:db8::1]) by smtp.example.com\r\n",
+            " with ESMTPSA; Mon, 1 Jan 2024 07:30:01 +0530 (IST)\r\n",
+        ));
+
+        let chain = read_received_chain(&headers);
+        assert_eq!(chain.len(), 2);
+        assert_eq!(
+            chain[0],
+            ReceivedHop {
+                from_host: Some("mail.example.com".to_string()),
+                from_ip: Some("192.0.2.1".to_string()),
+                by_host: Some("mx.example.org".to_string()),
+                protocol: Some("ESMTPS".to_string()),
+                id: Some("4Abc12".to_string()),
+                for_address: Some("bob@example.org".to_string()),
+                timestamp: Some("2024-01-01T02:00:05Z".to_string()),
+                timestamp_unix: Some(1704074405),
+                delay_seconds: Some(4),
+                raw: headers["received"][0].clone(),
+            }
+        );
+        assert_eq!(chain[1].from_host.as_deref(), Some("[10.0.0.5]"));
+        assert_eq!(chain[1].from_ip.as_deref(), Some("2001:db8::1"));
+        assert_eq!(chain[1].by_host.as_deref(), Some("smtp.example.com"));
+        assert_eq!(chain[1].timestamp.as_deref(), Some("2024-01-01T02:00:01Z"));
+        assert_eq!(chain[1].delay_seconds, None);
+    }
+}
@@ -0,0 +1,261 @@
+//! 收件人表（`__recip_version1.0_#xxxxxxxx` 存储）
+
+use crate::props::{self, PropertyStore, PropertyValue};
+use crate::{quirks, sender};
+use cfb::CompoundFile;
+use serde::Serialize;
+use std::fmt;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+const PROP_RECIPIENT_TYPE: u16 = 0x0C15;
+const PROP_RESPONSIBILITY: u16 = 0x0E0F;
+const PROP_ENTRYID: u16 = 0x0FFF;
+const PROP_DISPLAY_NAME: u16 = 0x3001;
+const PROP_ADDRTYPE: u16 = 0x3002;
+const PROP_EMAIL_ADDRESS: u16 = 0x3003;
+const PROP_SMTP_ADDRESS: u16 = 0x39FE;
+const PROP_RECIPIENT_FLAGS: u16 = 0x5FFD;
+
+// PR_RECIPIENT_FLAGS 标志位
+const RECIP_SENDABLE: u32 = 0x0001;
+const RECIP_ORGANIZER: u32 = 0x0002;
+
+/// 收件人类型 (PR_RECIPIENT_TYPE)
+///
+/// 会议请求中 To 表示必选参与者，Cc 表示可选参与者，Bcc 表示会议资源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipientType {
+    To,
+    Cc,
+    Bcc,
+}
+
+/// 收件人表中的一行
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Recipient {
+    pub display_name: Option<String>,
+    /// 可直接用于发送的地址：Exchange 收件人优先取 SMTP 地址，取不到时才是 Exchange DN
+    pub email: Option<String>,
+    /// 地址类型 (PR_ADDRTYPE)，如 `SMTP`、`EX`
+    pub address_type: Option<String>,
+    /// Exchange 收件人的 legacyExchangeDN（`/O=.../CN=...`），用于与发件人 DN 比对
+    pub exchange_dn: Option<String>,
+    pub recipient_type: Option<RecipientType>,
+    /// 原始 PR_RECIPIENT_FLAGS
+    pub flags: u32,
+    /// 可发送：重新发送时应包含该收件人
+    pub sendable: bool,
+    /// 会议组织者
+    pub organizer: bool,
+    /// PR_RESPONSIBILITY：是否已有传输程序对该收件人负责（已投递）
+    pub responsibility: bool,
+}
+
+/// 邮件地址，显示名和地址至少有一个
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Address {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl Address {
+    /// 解析 `名称 <地址>`、纯地址或纯显示名，空字符串返回 `None`
+    pub(crate) fn parse(text: &str) -> Option<Address> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        if let Some((name, rest)) = text.split_once('<') {
+            if let Some(email) = rest.strip_suffix('>') {
+                let name = name.trim().trim_matches('"').trim();
+                return Some(Address {
+                    name: (!name.is_empty()).then(|| name.to_string()),
+                    email: Some(email.trim().to_string()),
+                });
+            }
+        }
+        let is_email = text.contains('@') && !text.contains(char::is_whitespace);
+        Some(Address {
+            name: (!is_email).then(|| text.to_string()),
+            email: is_email.then(|| text.to_string()),
+        })
+    }
+}
+
+impl From<&Recipient> for Address {
+    fn from(recipient: &Recipient) -> Self {
+        Address {
+            name: recipient.display_name.clone(),
+            email: recipient.email.clone(),
+        }
+    }
+}
+
+/// `名称 <地址>` 形式，显示名含特殊字符时加引号
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.name.as_deref().map(|name| {
+            if name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c)) {
+                format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                name.to_string()
+            }
+        });
+        match (name, &self.email) {
+            (Some(name), Some(email)) => write!(f, "{} <{}>", name, email),
+            (Some(name), None) => f.write_str(&name),
+            (None, Some(email)) => f.write_str(email),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// 读取一个收件人存储
+pub(crate) fn read_recipient<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    path: &Path,
+    quirks: &mut quirks::Quirks,
+) -> Result<Recipient, Box<dyn std::error::Error>> {
+    let store = props::read_store(comp, path, props::CHILD_HEADER_LEN, quirks)?;
+    Ok(recipient_from_store(&store))
+}
+
+fn recipient_from_store(store: &PropertyStore) -> Recipient {
+    let text = |id| {
+        store
+            .get(id)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+    let flags = store
+        .get(PROP_RECIPIENT_FLAGS)
+        .and_then(|v| v.as_i32())
+        .unwrap_or(0) as u32;
+
+    let address_type = text(PROP_ADDRTYPE);
+    let address = text(PROP_EMAIL_ADDRESS);
+    let is_smtp = match &address_type {
+        Some(addr_type) => addr_type.eq_ignore_ascii_case("SMTP"),
+        None => address
+            .as_deref()
+            .is_some_and(|a| a.contains('@') && !a.starts_with('/')),
+    };
+    let entry_id = match store.get(PROP_ENTRYID) {
+        Some(PropertyValue::Binary(data)) => Some(data),
+        _ => None,
+    };
+    let entry_id_address = || entry_id.as_deref().and_then(sender::one_off_smtp_address);
+    let exchange_dn = address
+        .clone()
+        .filter(|a| sender::is_exchange_dn(a))
+        .or_else(|| entry_id.as_deref().and_then(sender::address_book_dn));
+    let email = if is_smtp {
+        address.or_else(|| text(PROP_SMTP_ADDRESS))
+    } else {
+        // Exchange DN 等地址无法直接发送，优先取 SMTP 地址
+        text(PROP_SMTP_ADDRESS)
+            .or_else(entry_id_address)
+            .or(address)
+    };
+
+    Recipient {
+        display_name: text(PROP_DISPLAY_NAME),
+        email,
+        address_type,
+        exchange_dn,
+        recipient_type: match store.get(PROP_RECIPIENT_TYPE).and_then(|v| v.as_i32()) {
+            // 高位可能带有 MAPI_P1 等标志，只看低 4 位
+            Some(t) => match t & 0x0F {
+                1 => Some(RecipientType::To),
+                2 => Some(RecipientType::Cc),
+                3 => Some(RecipientType::Bcc),
+                _ => None,
+            },
+            None => None,
+        },
+        flags,
+        sendable: flags & RECIP_SENDABLE != 0,
+        organizer: flags & RECIP_ORGANIZER != 0,
+        responsibility: store.get(PROP_RESPONSIBILITY) == Some(PropertyValue::Boolean(true)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_flags() {
+        let record = |tag: u32, value: u64| {
+            [
+                &tag.to_le_bytes()[..],
+                &6u32.to_le_bytes(),
+                &value.to_le_bytes(),
+            ]
+            .concat()
+        };
+        let data = [
+            vec![0u8; props::CHILD_HEADER_LEN],
+            record(0x0C15_0003, 2),
+            record(0x5FFD_0003, 0x3),
+            record(0x0E0F_000B, 1),
+        ]
+        .concat();
+        let mut store = PropertyStore::default();
+        store.set_fixed(props::parse_property_stream(&data, props::CHILD_HEADER_LEN));
+
+        let recipient = recipient_from_store(&store);
+
+        assert_eq!(recipient.recipient_type, Some(RecipientType::Cc));
+        assert!(recipient.sendable);
+        assert!(recipient.organizer);
+        assert!(recipient.responsibility);
+    }
+
+    #[test]
+    fn test_exchange_recipient_email() {
+        let utf16 = |s: &str| {
+            s.encode_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>()
+        };
+        let mut store = PropertyStore::default();
+        store.insert_stream("__substg1.0_3002001F", utf16("EX"));
+        store.insert_stream("__substg1.0_3003001F", utf16("/O=Contoso/CN=bob"));
+        assert_eq!(
+            recipient_from_store(&store).email.as_deref(),
+            Some("/O=Contoso/CN=bob")
+        );
+
+        store.insert_stream("__substg1.0_39FE001F", utf16("bob@contoso.com"));
+        let recipient = recipient_from_store(&store);
+        assert_eq!(recipient.email.as_deref(), Some("bob@contoso.com"));
+        assert_eq!(recipient.address_type.as_deref(), Some("EX"));
+        assert_eq!(recipient.exchange_dn.as_deref(), Some("/O=Contoso/CN=bob"));
+    }
+
+    #[test]
+    fn test_address() {
+        let parse = |s| Address::parse(s).unwrap();
+        assert_eq!(
+            parse("\"Smith, Bob\" <bob@example.com>"),
+            Address {
+                name: Some("Smith, Bob".to_string()),
+                email: Some("bob@example.com".to_string()),
+            }
+        );
+        assert_eq!(
+            parse(" bob@example.com ").email.as_deref(),
+            Some("bob@example.com")
+        );
+        assert_eq!(parse("Bob Smith").name.as_deref(), Some("Bob Smith"));
+        assert_eq!(Address::parse("  "), None);
+
+        assert_eq!(
+            parse("\"Smith, Bob\" <bob@example.com>").to_string(),
+            "\"Smith, Bob\" <bob@example.com>"
+        );
+    }
+}
@@ -0,0 +1,508 @@
+//! 周期约会的重复模式 (MS-OXOCAL §2.2.1.44)
+//!
+//! PidLidAppointmentRecur 是一段二进制结构：先是通用的 RecurrencePattern（频率、间隔、
+//! 结束方式、删除/修改过的实例日期），再是约会特有的开始/结束时间偏移。
+//! 日期和时间都是约会所在时区的本地时间，这里不做时区换算：展开出的实例以本地挂钟时间
+//! 表示，`*_unix` 字段是把该挂钟时间当作 UTC 得到的时间戳。
+
+use crate::datetime::{civil_from_days, days_from_civil, format_rfc3339};
+use crate::parse_msg_to_struct;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// 1601-01-01 到 1970-01-01 的天数
+const DAYS_1601_TO_1970: i64 = 134_774;
+
+/// 展开时最多向后推算的周期数（天、周或月），防止无结束日期的模式无限展开
+const MAX_PERIODS: i64 = 100_000;
+
+/// 重复频率 (RecurFrequency)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// 重复规则 (PatternType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrencePatternType {
+    /// 每隔若干天
+    Day,
+    /// 每隔若干周的指定星期
+    Week,
+    /// 每隔若干月的第几天
+    Month,
+    /// 每隔若干月的第几个星期几，第 5 个表示最后一个
+    MonthNth,
+    /// 每隔若干月的最后一天
+    MonthEnd,
+    /// 伊斯兰历的按月规则，不支持展开
+    HijriMonth,
+    HijriMonthNth,
+    HijriMonthEnd,
+}
+
+/// 结束方式 (EndType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceEnd {
+    /// 到结束日期为止
+    EndDate,
+    /// 重复指定次数
+    Count,
+    /// 无结束日期
+    Never,
+}
+
+/// 星期，取值与 JavaScript `Date.getDay()` 一致（0 为星期日）
+pub type Weekday = u8;
+
+/// 重复模式
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecurrencePattern {
+    pub frequency: RecurrenceFrequency,
+    pub pattern_type: RecurrencePatternType,
+    /// 间隔：按天重复时为天数，按周为周数，按月为月数（按年重复为 12 的倍数）
+    pub interval: u32,
+    /// 按周或“第几个星期几”重复时适用的星期
+    pub days_of_week: Vec<Weekday>,
+    /// 按月重复的日期 (1-31)
+    pub day_of_month: Option<u32>,
+    /// “第几个星期几”中的序号，1-4，5 表示最后一个
+    pub nth_week: Option<u32>,
+    pub end: RecurrenceEnd,
+    /// 按次数结束时的重复次数
+    pub occurrence_count: Option<u32>,
+    /// 一周从星期几开始
+    pub first_day_of_week: Weekday,
+    /// 第一个实例所在日期，`YYYY-MM-DD`
+    pub start_date: String,
+    /// 最后一个实例所在日期；无结束日期时为 `None`
+    pub end_date: Option<String>,
+    /// 每个实例的开始时间，午夜起的分钟数
+    pub start_minute: u32,
+    /// 每个实例的持续时间（分钟）
+    pub duration_minutes: u32,
+    /// 已删除的实例的原始日期
+    pub deleted_dates: Vec<String>,
+    /// 单独修改过的实例的原始日期
+    pub modified_dates: Vec<String>,
+    #[serde(skip)]
+    start_day: i64,
+    #[serde(skip)]
+    end_day: Option<i64>,
+    #[serde(skip)]
+    deleted_days: Vec<i64>,
+    #[serde(skip)]
+    modified_days: Vec<i64>,
+}
+
+/// 展开得到的一个实例，时间为约会时区的本地挂钟时间
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Occurrence {
+    /// 开始时间，`YYYY-MM-DDTHH:MM:SS`
+    pub start: String,
+    /// 结束时间，`YYYY-MM-DDTHH:MM:SS`
+    pub end: String,
+    /// 把开始时间当作 UTC 得到的时间戳（秒）
+    pub start_unix: i64,
+    pub end_unix: i64,
+    /// 该实例被单独修改过（时间、地点等以例外信息为准，这里仍给出原始时间）
+    pub modified: bool,
+}
+
+/// WASM 导出接口
+/// 展开周期约会中开始时间落在 `[range_start, range_end)` 内的实例，
+/// 参数为本地挂钟时间当作 UTC 的 Unix 时间戳（秒）；不是周期约会时返回空数组
+#[wasm_bindgen]
+pub fn expand_msg_occurrences(
+    file_data: &[u8],
+    range_start: f64,
+    range_end: f64,
+) -> Result<JsValue, JsValue> {
+    let email = parse_msg_to_struct(file_data).map_err(|e| e.to_js())?;
+    let occurrences = email
+        .appointment
+        .and_then(|appointment| appointment.recurrence)
+        .map(|pattern| pattern.expand_occurrences(range_start as i64, range_end as i64))
+        .unwrap_or_default();
+
+    serde_wasm_bindgen::to_value(&occurrences)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 解析 PidLidAppointmentRecur，数据不完整或无法识别时返回 `None`
+pub(crate) fn parse_recurrence(data: &[u8]) -> Option<RecurrencePattern> {
+    let mut reader = Reader { data, pos: 0 };
+    reader.skip(4)?; // ReaderVersion, WriterVersion
+    let frequency = match reader.u16()? {
+        0x200A => RecurrenceFrequency::Daily,
+        0x200B => RecurrenceFrequency::Weekly,
+        0x200C => RecurrenceFrequency::Monthly,
+        0x200D => RecurrenceFrequency::Yearly,
+        _ => return None,
+    };
+    let pattern_type = match reader.u16()? {
+        0x0000 => RecurrencePatternType::Day,
+        0x0001 => RecurrencePatternType::Week,
+        0x0002 => RecurrencePatternType::Month,
+        0x0003 => RecurrencePatternType::MonthNth,
+        0x0004 => RecurrencePatternType::MonthEnd,
+        0x000A => RecurrencePatternType::HijriMonth,
+        0x000B => RecurrencePatternType::HijriMonthNth,
+        0x000C => RecurrencePatternType::HijriMonthEnd,
+        _ => return None,
+    };
+    reader.skip(2)?; // CalendarType
+    reader.skip(4)?; // FirstDateTime
+    let period = reader.u32()?;
+    reader.skip(4)?; // SlidingFlag
+
+    let mut days_of_week = Vec::new();
+    let mut day_of_month = None;
+    let mut nth_week = None;
+    match pattern_type {
+        RecurrencePatternType::Day => {}
+        RecurrencePatternType::Week => days_of_week = weekdays(reader.u32()?),
+        RecurrencePatternType::MonthNth | RecurrencePatternType::HijriMonthNth => {
+            days_of_week = weekdays(reader.u32()?);
+            nth_week = Some(reader.u32()?);
+        }
+        _ => day_of_month = Some(reader.u32()?),
+    }
+
+    let end = match reader.u32()? {
+        0x2021 => RecurrenceEnd::EndDate,
+        0x2022 => RecurrenceEnd::Count,
+        _ => RecurrenceEnd::Never,
+    };
+    let occurrence_count = reader.u32()?;
+    let first_day_of_week = reader.u32()? as Weekday;
+    let deleted_count = reader.u32()? as usize;
+    let deleted_days = reader.days(deleted_count)?;
+    let modified_count = reader.u32()? as usize;
+    let modified_days = reader.days(modified_count)?;
+    let start_day = reader.day()?;
+    let end_day = reader.day()?;
+    reader.skip(8)?; // ReaderVersion2, WriterVersion2
+    let start_minute = reader.u32()?;
+    let end_minute = reader.u32()?;
+
+    // 按天重复时 Period 以分钟计
+    let interval = match pattern_type {
+        RecurrencePatternType::Day => period / 1440,
+        _ => period,
+    }
+    .max(1);
+    // 无结束日期时 EndDate 为 4500 年前后的占位值
+    let end_day = (end != RecurrenceEnd::Never).then_some(end_day);
+
+    Some(RecurrencePattern {
+        frequency,
+        pattern_type,
+        interval,
+        days_of_week,
+        day_of_month,
+        nth_week,
+        end,
+        occurrence_count: (end == RecurrenceEnd::Count).then_some(occurrence_count),
+        first_day_of_week: first_day_of_week % 7,
+        start_date: format_date(start_day),
+        end_date: end_day.map(format_date),
+        start_minute,
+        duration_minutes: end_minute.saturating_sub(start_minute),
+        deleted_dates: deleted_days.iter().copied().map(format_date).collect(),
+        modified_dates: modified_days.iter().copied().map(format_date).collect(),
+        start_day,
+        end_day,
+        deleted_days,
+        modified_days,
+    })
+}
+
+impl RecurrencePattern {
+    /// 列出开始时间落在 `[range_start, range_end)` 内的实例，已删除的实例不包含在内
+    ///
+    /// 参数与返回的 `*_unix` 一样，是把约会时区的本地挂钟时间当作 UTC 得到的时间戳（秒）。
+    pub fn expand_occurrences(&self, range_start: i64, range_end: i64) -> Vec<Occurrence> {
+        let mut occurrences = Vec::new();
+        for (generated, day) in self.days().enumerate() {
+            if self.end_day.is_some_and(|end| day > end)
+                || self
+                    .occurrence_count
+                    .is_some_and(|count| generated >= count as usize)
+            {
+                break;
+            }
+            let start = day * 86400 + self.start_minute as i64 * 60;
+            if start >= range_end {
+                break;
+            }
+            if start < range_start || self.deleted_days.contains(&day) {
+                continue;
+            }
+            let end = start + self.duration_minutes as i64 * 60;
+            occurrences.push(Occurrence {
+                start: local_time(start),
+                end: local_time(end),
+                start_unix: start,
+                end_unix: end,
+                modified: self.modified_days.contains(&day),
+            });
+        }
+        occurrences
+    }
+
+    /// 按规则依次生成实例日期（1970-01-01 起的天数），按时间先后排列
+    fn days(&self) -> Box<dyn Iterator<Item = i64> + '_> {
+        let start = self.start_day;
+        let interval = self.interval as i64;
+        match self.pattern_type {
+            RecurrencePatternType::Day => {
+                Box::new((0..MAX_PERIODS).map(move |n| start + n * interval))
+            }
+            RecurrencePatternType::Week => {
+                // 从包含开始日期的那一周的第一天起，每隔 interval 周取指定的星期
+                let week_start =
+                    start - (weekday(start) as i64 - self.first_day_of_week as i64).rem_euclid(7);
+                Box::new(
+                    (0..MAX_PERIODS)
+                        .flat_map(move |n| {
+                            let week = week_start + n * 7 * interval;
+                            (0..7).map(move |d| week + d)
+                        })
+                        .filter(move |&day| {
+                            day >= start && self.days_of_week.contains(&weekday(day))
+                        }),
+                )
+            }
+            RecurrencePatternType::Month
+            | RecurrencePatternType::MonthEnd
+            | RecurrencePatternType::MonthNth => {
+                let (year, month, _) = civil_from_days(start);
+                Box::new(
+                    (0..MAX_PERIODS)
+                        .filter_map(move |n| {
+                            let months = year * 12 + month - 1 + n * interval;
+                            self.day_in_month(months / 12, months % 12 + 1)
+                        })
+                        .filter(move |&day| day >= start),
+                )
+            }
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    /// 某月中符合规则的那一天
+    fn day_in_month(&self, year: i64, month: i64) -> Option<i64> {
+        let first = days_from_civil(year, month, 1);
+        let next = if month == 12 {
+            days_from_civil(year + 1, 1, 1)
+        } else {
+            days_from_civil(year, month + 1, 1)
+        };
+        let length = next - first;
+        match self.pattern_type {
+            // 当月没有这一天时（如 31 日）取当月最后一天
+            RecurrencePatternType::Month => {
+                Some(first + (self.day_of_month? as i64).clamp(1, length) - 1)
+            }
+            RecurrencePatternType::MonthEnd => Some(next - 1),
+            RecurrencePatternType::MonthNth => {
+                let matching: Vec<i64> = (first..next)
+                    .filter(|&day| self.days_of_week.contains(&weekday(day)))
+                    .collect();
+                match self.nth_week? {
+                    5 => matching.last().copied(),
+                    n => matching.get(n.checked_sub(1)? as usize).copied(),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 星期位掩码（bit 0 为星期日）转为星期列表
+fn weekdays(mask: u32) -> Vec<Weekday> {
+    (0..7).filter(|d| mask & (1 << d) != 0).collect()
+}
+
+/// 1970-01-01 起的天数对应的星期，0 为星期日
+fn weekday(day: i64) -> Weekday {
+    // 1970-01-01 是星期四
+    (day + 4).rem_euclid(7) as Weekday
+}
+
+fn format_date(day: i64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn local_time(seconds: i64) -> String {
+    format_rfc3339(seconds).trim_end_matches('Z').to_string()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn skip(&mut self, len: usize) -> Option<()> {
+        (self.pos + len <= self.data.len()).then(|| self.pos += len)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.data.get(self.pos..self.pos + 2)?;
+        self.pos += 2;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.data.get(self.pos..self.pos + 4)?;
+        self.pos += 4;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// 1601-01-01 起的分钟数转为 1970-01-01 起的天数
+    fn day(&mut self) -> Option<i64> {
+        Some(self.u32()? as i64 / 1440 - DAYS_1601_TO_1970)
+    }
+
+    fn days(&mut self, count: usize) -> Option<Vec<i64>> {
+        (0..count).map(|_| self.day()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 MS-OXOCAL 布局拼出 AppointmentRecurrencePattern
+    fn recur_blob(
+        (frequency, pattern_type, period): (u16, u16, u32),
+        specific: &[u32],
+        end: (u32, u32),
+        deleted: &[i64],
+        dates: (i64, i64),
+        minutes: (u32, u32),
+    ) -> Vec<u8> {
+        let minutes_since_1601 = |day: i64| ((day + DAYS_1601_TO_1970) * 1440) as u32;
+        let mut data = Vec::new();
+        data.extend(0x3004u16.to_le_bytes());
+        data.extend(0x3004u16.to_le_bytes());
+        data.extend(frequency.to_le_bytes());
+        data.extend(pattern_type.to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        let mut push = |value: u32| data.extend(value.to_le_bytes());
+        push(0);
+        push(period);
+        push(0);
+        specific.iter().for_each(|&v| push(v));
+        push(end.0);
+        push(end.1);
+        push(0);
+        push(deleted.len() as u32);
+        deleted.iter().for_each(|&d| push(minutes_since_1601(d)));
+        push(0);
+        push(minutes_since_1601(dates.0));
+        push(minutes_since_1601(dates.1));
+        push(0x3006);
+        push(0x3008);
+        push(minutes.0);
+        push(minutes.1);
+        data
+    }
+
+    #[test]
+    fn test_weekly_pattern() {
+        // 2024-01-01（星期一）起，每两周的星期一和星期三 9:00-9:30，共 5 次，删除 01-03 那次
+        let start = days_from_civil(2024, 1, 1);
+        let blob = recur_blob(
+            (0x200B, 0x0001, 2),
+            &[0b0000_1010],
+            (0x2022, 5),
+            &[start + 2],
+            (start, start + 28),
+            (540, 570),
+        );
+        let pattern = parse_recurrence(&blob).unwrap();
+        assert_eq!(pattern.frequency, RecurrenceFrequency::Weekly);
+        assert_eq!(pattern.days_of_week, [1, 3]);
+        assert_eq!(pattern.start_date, "2024-01-01");
+        assert_eq!(pattern.deleted_dates, ["2024-01-03"]);
+        assert_eq!(pattern.duration_minutes, 30);
+
+        let occurrences = pattern.expand_occurrences(i64::MIN, i64::MAX);
+        let starts: Vec<&str> = occurrences.iter().map(|o| o.start.as_str()).collect();
+        assert_eq!(
+            starts,
+            [
+                "2024-01-01T09:00:00",
+                "2024-01-15T09:00:00",
+                "2024-01-17T09:00:00",
+                "2024-01-29T09:00:00",
+            ]
+        );
+        assert_eq!(occurrences[0].end, "2024-01-01T09:30:00");
+
+        let from = occurrences[1].start_unix;
+        assert_eq!(pattern.expand_occurrences(from, from + 1).len(), 1);
+    }
+
+    #[test]
+    fn test_monthly_patterns() {
+        let start = days_from_civil(2024, 1, 31);
+        // 每月 31 日，没有 31 日的月份取最后一天
+        let blob = recur_blob(
+            (0x200C, 0x0002, 1),
+            &[31],
+            (0x2023, 0),
+            &[],
+            (start, start),
+            (0, 60),
+        );
+        let pattern = parse_recurrence(&blob).unwrap();
+        assert_eq!(pattern.end, RecurrenceEnd::Never);
+        assert_eq!(pattern.end_date, None);
+        let range_end = days_from_civil(2024, 4, 1) * 86400;
+        let starts: Vec<String> = pattern
+            .expand_occurrences(0, range_end)
+            .into_iter()
+            .map(|o| o.start)
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                "2024-01-31T00:00:00",
+                "2024-02-29T00:00:00",
+                "2024-03-31T00:00:00"
+            ]
+        );
+
+        // 每年 11 月的第四个星期四
+        let start = days_from_civil(2024, 11, 28);
+        let blob = recur_blob(
+            (0x200D, 0x0003, 12),
+            &[0b0001_0000, 4],
+            (0x2022, 2),
+            &[],
+            (start, days_from_civil(2025, 11, 27)),
+            (600, 660),
+        );
+        let pattern = parse_recurrence(&blob).unwrap();
+        assert_eq!(pattern.frequency, RecurrenceFrequency::Yearly);
+        let starts: Vec<String> = pattern
+            .expand_occurrences(i64::MIN, i64::MAX)
+            .into_iter()
+            .map(|o| o.start)
+            .collect();
+        assert_eq!(starts, ["2024-11-28T10:00:00", "2025-11-27T10:00:00"]);
+
+        assert_eq!(parse_recurrence(&blob[..20]), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! HTML 正文引用的远程资源清单
+//!
+//! 列出正文中所有指向外部服务器的地址（图片、跟踪像素、样式表、链接等）及其主机名，
+//! 注重隐私的查看器可以在加载任何内容之前提示用户。只统计 `http(s)://` 和 `//` 开头的地址，
+//! `cid:`、`data:`、`mailto:` 等不会产生网络请求的地址不计入。
+
+use crate::csp::{self, Tag};
+use serde::Serialize;
+
+/// 远程资源的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteKind {
+    /// 普通图片，包括 `background` 属性和样式中的 `url()`
+    Image,
+    /// 宽高不超过 1 像素或隐藏的图片，通常用于追踪邮件是否被打开
+    TrackingPixel,
+    /// 外部样式表（`<link rel="stylesheet">`、`@import`）
+    Stylesheet,
+    /// 超链接目标，点击时才会访问
+    Link,
+    /// 脚本、内嵌框架、音视频等其他会加载的资源
+    Other,
+}
+
+/// 一个远程资源引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemoteResource {
+    pub kind: RemoteKind,
+    pub url: String,
+    /// 小写主机名，不含端口和用户信息
+    pub host: Option<String>,
+}
+
+/// 会加载资源的属性
+const RESOURCE_ATTRS: &[&str] = &["src", "background", "poster", "lowsrc", "dynsrc", "data"];
+
+/// 按出现顺序列出 HTML 中的远程资源，相同类型和地址只记录一次
+pub(crate) fn list_remote_resources(html: &str) -> Vec<RemoteResource> {
+    let mut resources = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        if html[start..].starts_with("<!--") {
+            pos = html[start..]
+                .find("-->")
+                .map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        let Some((tag, end)) = csp::parse_tag(html, start) else {
+            pos = start + 1;
+            continue;
+        };
+        pos = end;
+        if tag.closing {
+            continue;
+        }
+        if tag.name == "style" {
+            let content_end = csp::find_close(html, pos, "style").unwrap_or(html.len());
+            scan_css(&html[pos..content_end], &mut resources);
+            pos = content_end;
+            continue;
+        }
+        scan_tag(&tag, &mut resources);
+    }
+
+    resources
+}
+
+fn scan_tag(tag: &Tag, resources: &mut Vec<RemoteResource>) {
+    for (name, value) in &tag.attrs {
+        let Some(value) = value.as_deref() else {
+            continue;
+        };
+        match name.as_str() {
+            "style" => scan_css(value, resources),
+            "href" => {
+                let kind = match tag.name.as_str() {
+                    "link" if is_stylesheet(tag) => RemoteKind::Stylesheet,
+                    "link" => RemoteKind::Other,
+                    _ => RemoteKind::Link,
+                };
+                push(resources, kind, value);
+            }
+            "srcset" => {
+                for candidate in value.split(',') {
+                    if let Some(url) = candidate.split_whitespace().next() {
+                        push(resources, image_kind(tag), url);
+                    }
+                }
+            }
+            name if RESOURCE_ATTRS.contains(&name) => {
+                let kind = match tag.name.as_str() {
+                    "img" | "input" | "image" => image_kind(tag),
+                    _ if name == "background" || name == "poster" => RemoteKind::Image,
+                    _ => RemoteKind::Other,
+                };
+                push(resources, kind, value);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_stylesheet(tag: &Tag) -> bool {
+    tag.attr("rel").is_some_and(|rel| {
+        rel.split_whitespace()
+            .any(|r| r.eq_ignore_ascii_case("stylesheet"))
+    })
+}
+
+/// 宽高属性都不超过 1，或样式中隐藏、宽高为 0/1 像素的图片视为跟踪像素
+fn image_kind(tag: &Tag) -> RemoteKind {
+    let tiny = |value: Option<&str>| {
+        value
+            .map(|v| v.trim().trim_end_matches("px"))
+            .and_then(|v| v.parse::<f32>().ok())
+            .is_some_and(|v| v <= 1.0)
+    };
+    let style: String = tag
+        .attr("style")
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .split_whitespace()
+        .collect();
+    let hidden = style.contains("display:none") || style.contains("visibility:hidden");
+    let tiny_style = ["width:0", "width:1px"].iter().any(|w| style.contains(w))
+        && ["height:0", "height:1px"].iter().any(|h| style.contains(h));
+
+    if (tiny(tag.attr("width")) && tiny(tag.attr("height"))) || hidden || tiny_style {
+        RemoteKind::TrackingPixel
+    } else {
+        RemoteKind::Image
+    }
+}
+
+/// 提取样式中的 `url()` 和 `@import`
+fn scan_css(css: &str, resources: &mut Vec<RemoteResource>) {
+    let lower = css.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find("url(") {
+        let start = pos + offset;
+        let Some(len) = css[start..].find(')') else {
+            break;
+        };
+        let url = css[start + 4..start + len].trim().trim_matches(['"', '\'']);
+        let is_import = lower[..start].trim_end().ends_with("@import");
+        let kind = if is_import {
+            RemoteKind::Stylesheet
+        } else {
+            RemoteKind::Image
+        };
+        push(resources, kind, url);
+        pos = start + len + 1;
+    }
+
+    // @import "https://..."; 不带 url() 的写法
+    let mut pos = 0;
+    while let Some(offset) = lower[pos..].find("@import") {
+        let start = pos + offset + "@import".len();
+        pos = start;
+        let rest = css[start..].trim_start();
+        if let Some(quote @ ('"' | '\'')) = rest.chars().next() {
+            if let Some(len) = rest[1..].find(quote) {
+                push(resources, RemoteKind::Stylesheet, &rest[1..len + 1]);
+            }
+        }
+    }
+}
+
+fn push(resources: &mut Vec<RemoteResource>, kind: RemoteKind, url: &str) {
+    let url = url.trim();
+    let Some(host) = remote_host(url) else {
+        return;
+    };
+    if resources.iter().any(|r| r.kind == kind && r.url == url) {
+        return;
+    }
+    resources.push(RemoteResource {
+        kind,
+        url: url.to_string(),
+        host,
+    });
+}
+
+/// 远程地址返回 `Some(主机名)`，本地或内嵌地址返回 `None`
+fn remote_host(url: &str) -> Option<Option<String>> {
+    let lower = url.to_ascii_lowercase();
+    let rest = ["http://", "https://", "//"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))?;
+    let authority = rest.split(['/', '?', '#', '\\']).next().unwrap_or_default();
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    let host = if let Some(ipv6) = host_port.strip_prefix('[') {
+        ipv6.split(']').next().unwrap_or_default()
+    } else {
+        host_port.split(':').next().unwrap_or_default()
+    };
+    Some(Some(host.to_string()).filter(|h| !h.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_remote_resources() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="https://cdn.example.com/a.css">
+            <style>@import "//fonts.example.net/f.css"; td { background: url('http://img.example.com/bg.png') }</style>
+            </head><body>
+            <!-- <img src="https://hidden.example.com/x.png"> -->
+            <img src="cid:logo@01D9" alt="logo">
+            <img src="https://track.example.org/o.gif?id=1" width="1" height="1">
+            <img src="HTTPS://User@Photos.Example.com:8443/p.jpg">
+            <a href="https://www.example.com/offer">offer</a>
+            <a href="mailto:bob@example.com">mail</a>
+            <a href="https://www.example.com/offer">again</a>
+            </body></html>"#;
+
+        let resources = list_remote_resources(html);
+        let summary: Vec<_> = resources
+            .iter()
+            .map(|r| (r.kind, r.host.as_deref().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (RemoteKind::Stylesheet, "cdn.example.com"),
+                (RemoteKind::Image, "img.example.com"),
+                (RemoteKind::Stylesheet, "fonts.example.net"),
+                (RemoteKind::TrackingPixel, "track.example.org"),
+                (RemoteKind::Image, "photos.example.com"),
+                (RemoteKind::Link, "www.example.com"),
+            ]
+        );
+        assert_eq!(resources[3].url, "https://track.example.org/o.gif?id=1");
+    }
+}
@@ -0,0 +1,99 @@
+//! 回复地址 (Reply-To)
+//!
+//! PR_REPLY_RECIPIENT_ENTRIES 是 FLATENTRYLIST：条目数(4) + 总长度(4) + 若干 FLATENTRY，
+//! 每个 FLATENTRY 为长度(4) + ENTRYID，并按 4 字节对齐。PR_REPLY_RECIPIENT_NAMES 是与之
+//! 一一对应、以分号分隔的显示名。一次性 ENTRYID 中能直接取出 SMTP 地址，通讯簿 ENTRYID
+//! 只有 Exchange DN，此时退回显示名。
+
+use crate::headers::parse_address_list;
+use crate::props::{PropertyStore, PropertyValue};
+use crate::sender::one_off_smtp_address;
+use std::collections::BTreeMap;
+
+const PROP_REPLY_RECIPIENT_ENTRIES: u16 = 0x004F;
+const PROP_REPLY_RECIPIENT_NAMES: u16 = 0x0050;
+
+/// 读取回复地址，能解出 SMTP 地址的条目取地址，否则取对应的显示名
+pub(crate) fn read_reply_to(store: &PropertyStore) -> Vec<String> {
+    let names: Vec<String> = match store.get(PROP_REPLY_RECIPIENT_NAMES) {
+        Some(PropertyValue::String(names)) => names
+            .split(';')
+            .map(|name| name.trim().to_string())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let addresses: Vec<Option<String>> = match store.get(PROP_REPLY_RECIPIENT_ENTRIES) {
+        Some(PropertyValue::Binary(data)) => flat_entry_list(&data)
+            .into_iter()
+            .map(one_off_smtp_address)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let count = addresses.len().max(names.len());
+    (0..count)
+        .filter_map(|i| {
+            addresses
+                .get(i)
+                .cloned()
+                .flatten()
+                .or_else(|| names.get(i).cloned())
+                .filter(|address| !address.is_empty())
+        })
+        .collect()
+}
+
+/// 属性缺失时取传输头中的 Reply-To 地址
+pub(crate) fn read_reply_to_header(headers: &BTreeMap<String, Vec<String>>) -> Vec<String> {
+    headers
+        .get("reply-to")
+        .into_iter()
+        .flatten()
+        .flat_map(|value| parse_address_list(value))
+        .filter_map(|address| address.email.or(address.name))
+        .collect()
+}
+
+/// 拆分 FLATENTRYLIST，长度越界的条目及其后的数据忽略
+fn flat_entry_list(data: &[u8]) -> Vec<&[u8]> {
+    let read_u32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+    let Some(count) = read_u32(0) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    let mut offset = 8;
+    for _ in 0..count {
+        let Some(len) = read_u32(offset) else {
+            break;
+        };
+        let Some(entry) = data.get(offset + 4..offset + 4 + len) else {
+            break;
+        };
+        entries.push(entry);
+        offset += 4 + len.next_multiple_of(4);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_entry_list() {
+        let mut data = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&[3, 0, 0, 0, 0xAA, 0xBB, 0xCC, 0]);
+        data.extend_from_slice(&[4, 0, 0, 0, 1, 2, 3, 4]);
+        assert_eq!(
+            flat_entry_list(&data),
+            [&[0xAA, 0xBB, 0xCC][..], &[1, 2, 3, 4][..]]
+        );
+
+        // 长度越界
+        data[16] = 9;
+        assert_eq!(flat_entry_list(&data).len(), 1);
+    }
+}
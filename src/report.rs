@@ -0,0 +1,199 @@
+//! 送达/已读/未送达报告（REPORT.*）
+//!
+//! 报告的消息类型为 `REPORT.<原始消息类型>.<报告类型>`，如 `REPORT.IPM.Note.NDR`。
+//! 未送达报告的收件人表列出的是投递失败的收件人，每一行带有 PR_NDR_* 诊断码和
+//! 服务器给出的补充说明（通常包含 `5.1.1` 这样的 DSN 状态码）。
+
+use crate::props::{self, PropertyStore};
+use crate::quirks;
+use cfb::CompoundFile;
+use serde::Serialize;
+use std::io::{Read, Seek};
+use std::path::PathBuf;
+
+const PROP_REPORT_TIME: u16 = 0x0032;
+const PROP_REPORT_TEXT: u16 = 0x1001;
+const PROP_IN_REPLY_TO_ID: u16 = 0x1042;
+
+// 收件人表中的属性
+const PROP_DISPLAY_NAME: u16 = 0x3001;
+const PROP_EMAIL_ADDRESS: u16 = 0x3003;
+const PROP_SMTP_ADDRESS: u16 = 0x39FE;
+const PROP_NDR_REASON_CODE: u16 = 0x0C04;
+const PROP_NDR_DIAG_CODE: u16 = 0x0C05;
+const PROP_SUPPLEMENTARY_INFO: u16 = 0x0C1B;
+const PROP_NDR_STATUS_CODE: u16 = 0x0C20;
+
+/// 报告类型，取自消息类型的最后一段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportType {
+    /// 未送达报告 (NDR)
+    NonDelivery,
+    /// 送达报告 (DR)
+    Delivery,
+    /// 已读回执 (IPNRN)
+    Read,
+    /// 未读即删除的通知 (IPNNRN)
+    NotRead,
+    /// 延迟投递通知 (Delayed)
+    Delayed,
+    /// 其他报告，如 Relayed、Expanded
+    #[default]
+    Other,
+}
+
+/// 报告中的一个收件人
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReportRecipient {
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    /// PR_NDR_REASON_CODE
+    pub reason_code: Option<i32>,
+    /// PR_NDR_DIAG_CODE
+    pub diagnostic_code: Option<i32>,
+    /// PR_NDR_STATUS_CODE，SMTP 状态码如 550
+    pub status_code: Option<i32>,
+    /// 从补充说明中取出的 DSN 状态码 (RFC 3463)，如 `5.1.1`
+    pub dsn_status: Option<String>,
+    /// 服务器给出的补充说明 (PR_SUPPLEMENTARY_INFO)
+    pub supplementary_info: Option<String>,
+    /// 该收件人的报告时间
+    pub report_time: Option<String>,
+}
+
+/// 送达/已读/未送达报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    pub report_type: ReportType,
+    /// 被报告的原始消息的类型，如 `IPM.Note`
+    pub original_message_class: Option<String>,
+    /// 原始消息的 Message-ID
+    pub original_message_id: Option<String>,
+    /// 报告说明 (PR_REPORT_TEXT)
+    pub report_text: Option<String>,
+    pub report_time: Option<String>,
+    /// 报告涉及的收件人；未送达报告中即投递失败的收件人
+    pub recipients: Vec<ReportRecipient>,
+}
+
+/// 是否为报告
+pub(crate) fn is_report_class(message_class: &str) -> bool {
+    message_class.to_ascii_uppercase().starts_with("REPORT.")
+}
+
+/// 读取报告信息，`headers` 和 `body_text` 用于在属性缺失时查找原始消息的 Message-ID
+pub(crate) fn read_report<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    store: &PropertyStore,
+    message_class: &str,
+    recipient_dirs: &[PathBuf],
+    headers: &[(String, String)],
+    body_text: Option<&str>,
+    quirks: &mut quirks::Quirks,
+) -> Report {
+    let text = |store: &PropertyStore, id| {
+        store
+            .get(id)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+    let (original_message_class, suffix) =
+        match message_class.get(7..).and_then(|c| c.rsplit_once('.')) {
+            Some((class, suffix)) => (Some(class.to_string()), suffix),
+            None => (None, ""),
+        };
+    let report_type = match suffix.to_ascii_uppercase().as_str() {
+        "NDR" => ReportType::NonDelivery,
+        "DR" => ReportType::Delivery,
+        "IPNRN" => ReportType::Read,
+        "IPNNRN" => ReportType::NotRead,
+        "DELAYED" => ReportType::Delayed,
+        _ => ReportType::Other,
+    };
+
+    let recipients = recipient_dirs
+        .iter()
+        .filter_map(|path| props::read_store(comp, path, props::CHILD_HEADER_LEN, quirks).ok())
+        .map(|recipient| {
+            let code = |id| recipient.get(id).and_then(|v| v.as_i32());
+            let supplementary_info = text(&recipient, PROP_SUPPLEMENTARY_INFO);
+            ReportRecipient {
+                display_name: text(&recipient, PROP_DISPLAY_NAME),
+                email: text(&recipient, PROP_SMTP_ADDRESS)
+                    .or_else(|| text(&recipient, PROP_EMAIL_ADDRESS)),
+                reason_code: code(PROP_NDR_REASON_CODE),
+                diagnostic_code: code(PROP_NDR_DIAG_CODE),
+                status_code: code(PROP_NDR_STATUS_CODE),
+                dsn_status: supplementary_info.as_deref().and_then(dsn_status),
+                supplementary_info,
+                report_time: text(&recipient, PROP_REPORT_TIME),
+            }
+        })
+        .collect();
+
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+    };
+    let original_message_id = text(store, PROP_IN_REPLY_TO_ID)
+        .or_else(|| header("In-Reply-To"))
+        .or_else(|| body_text.and_then(message_id_in_body));
+
+    Report {
+        report_type,
+        original_message_class,
+        original_message_id,
+        report_text: text(store, PROP_REPORT_TEXT),
+        report_time: text(store, PROP_REPORT_TIME),
+        recipients,
+    }
+}
+
+/// 取文本中第一个 `X.Y.Z` 形式的 DSN 状态码，类别只能是 2、4、5
+fn dsn_status(text: &str) -> Option<String> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .find(|token| {
+            let parts: Vec<&str> = token.split('.').collect();
+            parts.len() == 3
+                && matches!(parts[0], "2" | "4" | "5")
+                && parts[1..]
+                    .iter()
+                    .all(|p| (1..=3).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .map(str::to_string)
+}
+
+/// 报告正文中附带的原始消息头（MDN 的 Original-Message-ID 或 NDR 附带的 Message-ID）
+fn message_id_in_body(body: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        let name = name.trim();
+        (name.eq_ignore_ascii_case("Original-Message-ID")
+            || name.eq_ignore_ascii_case("Message-ID"))
+        .then(|| value.trim().to_string())
+        .filter(|id| id.starts_with('<'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dsn_status_and_message_id() {
+        assert_eq!(
+            dsn_status("#550 5.1.1 RESOLVER.ADR.RecipNotFound; not found ##").as_deref(),
+            Some("5.1.1")
+        );
+        assert_eq!(dsn_status("Remote server 10.0.0.1 said no"), None);
+
+        let body = "Your message did not reach some recipients.\r\n\r\nOriginal message headers:\r\n\r\nMessage-ID: <abc@example.com>\r\n";
+        assert_eq!(
+            message_id_in_body(body).as_deref(),
+            Some("<abc@example.com>")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! RSS 文章（IPM.Post.Rss）
+//!
+//! Outlook 订阅 RSS 后，每篇文章保存为一条 IPM.Post.Rss 消息，
+//! 订阅源和文章链接放在 PSETID_PostRss 下的命名属性中。
+
+use crate::named::{NameIdMap, PSETID_POST_RSS};
+use crate::props::PropertyStore;
+use serde::Serialize;
+
+// PSETID_PostRss 下的 LID
+const LID_CHANNEL_LINK: u32 = 0x8900;
+const LID_ITEM_LINK: u32 = 0x8901;
+const LID_ITEM_GUID: u32 = 0x8903;
+const LID_CHANNEL: u32 = 0x8904;
+const LID_ITEM_XML: u32 = 0x8905;
+const LID_SUBSCRIPTION: u32 = 0x8906;
+
+/// RSS 文章信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RssItem {
+    /// 订阅源名称
+    pub feed_name: Option<String>,
+    /// 订阅源网址
+    pub feed_url: Option<String>,
+    /// 文章链接
+    pub item_link: Option<String>,
+    pub item_guid: Option<String>,
+    /// 订阅名称（Outlook 中显示的文件夹名）
+    pub subscription: Option<String>,
+    /// 文章摘要，取自原始条目 XML 中的 `<description>`
+    pub synopsis: Option<String>,
+}
+
+/// 是否为 RSS 文章
+pub(crate) fn is_rss_class(message_class: &str) -> bool {
+    message_class
+        .to_ascii_uppercase()
+        .starts_with("IPM.POST.RSS")
+}
+
+/// 从顶层属性中读取 RSS 文章信息
+pub(crate) fn read_rss_item(store: &PropertyStore, names: &NameIdMap) -> RssItem {
+    let text = |lid| {
+        names
+            .get_lid(store, PSETID_POST_RSS, lid)
+            .and_then(|v| v.as_str().map(str::to_string))
+            .filter(|s| !s.is_empty())
+    };
+
+    RssItem {
+        feed_name: text(LID_CHANNEL),
+        feed_url: text(LID_CHANNEL_LINK),
+        item_link: text(LID_ITEM_LINK),
+        item_guid: text(LID_ITEM_GUID),
+        subscription: text(LID_SUBSCRIPTION),
+        synopsis: text(LID_ITEM_XML).and_then(|xml| xml_element_text(&xml, "description")),
+    }
+}
+
+/// 取出 XML 中第一个指定元素的文本，去掉 CDATA 包装
+fn xml_element_text(xml: &str, element: &str) -> Option<String> {
+    let open = format!("<{}", element);
+    let start = xml.find(&open)?;
+    let content_start = start + xml[start..].find('>')? + 1;
+    let content_end = content_start + xml[content_start..].find(&format!("</{}>", element))?;
+    let content = xml[content_start..content_end].trim();
+    let content = content
+        .strip_prefix("<![CDATA[")
+        .and_then(|c| c.strip_suffix("]]>"))
+        .unwrap_or(content)
+        .trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_element_text() {
+        let xml = "<item><title>T</title><description><![CDATA[Short <b>summary</b>]]></description></item>";
+        assert_eq!(
+            xml_element_text(xml, "description").as_deref(),
+            Some("Short <b>summary</b>")
+        );
+        assert_eq!(xml_element_text(xml, "link"), None);
+        assert!(is_rss_class("IPM.Post.Rss"));
+    }
+}
@@ -0,0 +1,382 @@
+//! RTF 正文解封装 (MS-OXRTFEX)
+//!
+//! 不少邮件只带压缩 RTF 正文 (PR_RTF_COMPRESSED)。Outlook 把 HTML 或纯文本正文转成 RTF 时，
+//! 会用 `\fromhtml1` / `\fromtext` 标记，原始 HTML 标签保存在 `{\*\htmltag ...}` 组中，
+//! 仅用于 RTF 显示的内容包在 `\htmlrtf ... \htmlrtf0` 之间。这里按规范还原原始正文；
+//! 对普通 RTF 则提取可见文本，作为纯文本正文的后备。
+
+use crate::locale::encoding_for_codepage;
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// 从 RTF 中还原出的原始正文
+#[derive(Debug, PartialEq)]
+pub(crate) enum Encapsulated {
+    Html(String),
+    Text(String),
+}
+
+/// 不输出内容的目标组（字体表、样式表、图片等）
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "object",
+    "header",
+    "footer",
+    "headerl",
+    "headerr",
+    "footerl",
+    "footerr",
+    "listtable",
+    "listoverridetable",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "latentstyles",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "filetbl",
+    "revtbl",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// 还原封装的 HTML 或文本：输出 htmltag 组和 `\htmlrtf` 之外的文本
+    Encapsulated,
+    /// 提取全部可见文本
+    Plain,
+}
+
+#[derive(Clone, Copy)]
+struct Group {
+    skip: bool,
+    htmltag: bool,
+    htmlrtf: bool,
+    /// `\ucN`：`\uN` 之后跟随的替代字符数
+    uc: usize,
+}
+
+/// 按 MS-OXRTFEX 还原封装在 RTF 中的 HTML 或纯文本，普通 RTF 返回 `None`
+pub(crate) fn de_encapsulate(rtf: &str) -> Option<Encapsulated> {
+    let from = encapsulation(rtf.as_bytes())?;
+    let body = walk(rtf.as_bytes(), Mode::Encapsulated);
+    Some(match from {
+        From::Html => Encapsulated::Html(body),
+        From::Text => Encapsulated::Text(body),
+    })
+}
+
+/// 提取 RTF 的可见文本，段落以 CRLF 分隔
+pub(crate) fn rtf_to_text(rtf: &str) -> String {
+    walk(rtf.as_bytes(), Mode::Plain).trim().to_string()
+}
+
+enum From {
+    Html,
+    Text,
+}
+
+/// 在文档头（第一个子组或正文文本之前）查找 `\fromhtml` / `\fromtext`
+fn encapsulation(rtf: &[u8]) -> Option<From> {
+    let mut pos = rtf.iter().position(|&b| b == b'{')? + 1;
+    while pos < rtf.len() {
+        match rtf[pos] {
+            b'\\' => {
+                let (word, _, next) = control_word(rtf, pos + 1)?;
+                match word {
+                    "fromhtml" => return Some(From::Html),
+                    "fromtext" => return Some(From::Text),
+                    _ => pos = next,
+                }
+            }
+            b'\r' | b'\n' | b' ' => pos += 1,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// 读取 `start` 处（反斜杠之后）的控制字及其数字参数，返回下一个位置
+fn control_word(rtf: &[u8], start: usize) -> Option<(&str, Option<i32>, usize)> {
+    let mut pos = start;
+    while pos < rtf.len() && rtf[pos].is_ascii_alphabetic() {
+        pos += 1;
+    }
+    if pos == start {
+        return None;
+    }
+    let word = std::str::from_utf8(&rtf[start..pos]).ok()?;
+
+    let param_start = pos;
+    if pos < rtf.len() && rtf[pos] == b'-' {
+        pos += 1;
+    }
+    while pos < rtf.len() && rtf[pos].is_ascii_digit() {
+        pos += 1;
+    }
+    let param = std::str::from_utf8(&rtf[param_start..pos])
+        .ok()
+        .and_then(|p| p.parse().ok());
+    // 控制字后的一个空格是分隔符，不属于正文
+    if pos < rtf.len() && rtf[pos] == b' ' {
+        pos += 1;
+    }
+    Some((word, param, pos))
+}
+
+/// 输出缓冲：`\'hh` 等 8 位字节按文档代码页解码，`\uN` 直接为 Unicode 字符
+struct Output {
+    text: String,
+    bytes: Vec<u8>,
+    encoding: &'static Encoding,
+    /// 等待与下一个 `\uN` 组成代理对的高代理项
+    high_surrogate: Option<u16>,
+}
+
+impl Output {
+    fn push_byte(&mut self, byte: u8) {
+        self.bytes.push(byte);
+    }
+
+    fn push_str(&mut self, text: &str) {
+        self.flush();
+        self.text.push_str(text);
+    }
+
+    fn push_char(&mut self, c: char) {
+        self.flush();
+        self.text.push(c);
+    }
+
+    /// `\uN` 给出的 UTF-16 码元，代理对由相邻的两个 `\uN` 组成，无法配对时输出 U+FFFD
+    fn push_utf16(&mut self, unit: u16) {
+        // 中间夹有其他字符时高代理项不能再配对
+        let high = if self.bytes.is_empty() {
+            self.high_surrogate.take()
+        } else {
+            None
+        };
+        self.flush();
+        if (0xD800..0xDC00).contains(&unit) {
+            if high.is_some() {
+                self.text.push(char::REPLACEMENT_CHARACTER);
+            }
+            self.high_surrogate = Some(unit);
+            return;
+        }
+        self.text.extend(
+            char::decode_utf16(high.into_iter().chain([unit]))
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+        );
+    }
+
+    fn flush(&mut self) {
+        if self.high_surrogate.take().is_some() {
+            self.text.push(char::REPLACEMENT_CHARACTER);
+        }
+        if !self.bytes.is_empty() {
+            let (decoded, _, _) = self.encoding.decode(&self.bytes);
+            self.text.push_str(&decoded);
+            self.bytes.clear();
+        }
+    }
+}
+
+fn walk(rtf: &[u8], mode: Mode) -> String {
+    let mut out = Output {
+        text: String::with_capacity(rtf.len() / 2),
+        bytes: Vec::new(),
+        encoding: WINDOWS_1252,
+        high_surrogate: None,
+    };
+    let mut stack: Vec<Group> = Vec::new();
+    let mut group = Group {
+        skip: false,
+        htmltag: false,
+        htmlrtf: false,
+        uc: 1,
+    };
+    // 下一个控制字是否以 `\*` 开头（可忽略的目标）
+    let mut starred = false;
+    // `\uN` 之后待跳过的替代字符数
+    let mut pending_skip = 0usize;
+
+    let visible = |group: &Group| match mode {
+        Mode::Encapsulated => !group.skip && (group.htmltag || !group.htmlrtf),
+        Mode::Plain => !group.skip,
+    };
+
+    let mut pos = 0;
+    while pos < rtf.len() {
+        let byte = rtf[pos];
+        match byte {
+            b'{' => {
+                stack.push(group);
+                group.htmltag = false;
+                starred = false;
+                pos += 1;
+            }
+            b'}' => {
+                if let Some(outer) = stack.pop() {
+                    // 组结束时恢复外层的状态，组内的 \htmlrtf、\uc 开关不影响外层
+                    group = outer;
+                }
+                pending_skip = 0;
+                pos += 1;
+            }
+            b'\\' => {
+                let Some(&next) = rtf.get(pos + 1) else {
+                    break;
+                };
+                match next {
+                    b'\'' => {
+                        let hex = rtf.get(pos + 2..pos + 4).and_then(|h| {
+                            u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok()
+                        });
+                        pos += 4;
+                        if pending_skip > 0 {
+                            pending_skip -= 1;
+                        } else if let (Some(b), true) = (hex, visible(&group)) {
+                            out.push_byte(b);
+                        }
+                    }
+                    b'*' => {
+                        starred = true;
+                        pos += 2;
+                    }
+                    b'{' | b'}' | b'\\' => {
+                        if pending_skip > 0 {
+                            pending_skip -= 1;
+                        } else if visible(&group) {
+                            out.push_byte(next);
+                        }
+                        pos += 2;
+                    }
+                    b'~' => {
+                        if visible(&group) {
+                            out.push_char('\u{a0}');
+                        }
+                        pos += 2;
+                    }
+                    b'_' => {
+                        if visible(&group) {
+                            out.push_char('-');
+                        }
+                        pos += 2;
+                    }
+                    b'\r' | b'\n' => {
+                        if visible(&group) {
+                            out.push_str("\r\n");
+                        }
+                        pos += 2;
+                    }
+                    c if c.is_ascii_alphabetic() => {
+                        let Some((word, param, end)) = control_word(rtf, pos + 1) else {
+                            break;
+                        };
+                        pos = end;
+                        let is_starred = std::mem::take(&mut starred);
+                        match word {
+                            "htmltag" if mode == Mode::Encapsulated && !group.skip => {
+                                group.htmltag = true;
+                            }
+                            "htmlrtf" => group.htmlrtf = param != Some(0),
+                            "ansicpg" => {
+                                if let Some(encoding) =
+                                    param.and_then(|cp| encoding_for_codepage(cp as u32))
+                                {
+                                    out.flush();
+                                    out.encoding = encoding;
+                                }
+                            }
+                            "uc" => group.uc = param.unwrap_or(1).max(0) as usize,
+                            "u" => {
+                                if visible(&group) {
+                                    // 参数为有符号 16 位数
+                                    out.push_utf16(param.unwrap_or(0) as i16 as u16);
+                                }
+                                pending_skip = group.uc;
+                            }
+                            _ if is_starred || SKIPPED_DESTINATIONS.contains(&word) => {
+                                group.skip = true;
+                            }
+                            _ if !visible(&group) => {}
+                            "par" | "line" => out.push_str("\r\n"),
+                            "tab" => out.push_char('\t'),
+                            "emdash" => out.push_char('\u{2014}'),
+                            "endash" => out.push_char('\u{2013}'),
+                            "bullet" => out.push_char('\u{2022}'),
+                            "lquote" => out.push_char('\u{2018}'),
+                            "rquote" => out.push_char('\u{2019}'),
+                            "ldblquote" => out.push_char('\u{201c}'),
+                            "rdblquote" => out.push_char('\u{201d}'),
+                            _ => {}
+                        }
+                    }
+                    _ => pos += 2,
+                }
+            }
+            b'\r' | b'\n' => pos += 1,
+            _ => {
+                if pending_skip > 0 {
+                    pending_skip -= 1;
+                } else if visible(&group) {
+                    out.push_byte(byte);
+                }
+                pos += 1;
+            }
+        }
+    }
+    out.flush();
+    out.text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_encapsulate_html() {
+        let rtf = concat!(
+            r"{\rtf1\ansi\ansicpg1252\fromhtml1 \fbidis \deff0{\fonttbl{\f0\fswiss Arial;}}",
+            r"{\*\htmltag19 <html>}{\*\htmltag34 <head>}",
+            r"{\*\htmltag64 <p>}\htmlrtf {\htmlrtf0 caf\'e9 \{x\} \u8364?\htmlrtf\par\htmlrtf0}",
+            r"\htmlrtf0 {\*\htmltag72 </p>}",
+            r"{\*\mhtmltag84 <img src=cid:x>}\htmlrtf fallback\htmlrtf0 }"
+        );
+        assert_eq!(
+            de_encapsulate(rtf),
+            Some(Encapsulated::Html(
+                "<html><head><p>café {x} €</p>".to_string()
+            ))
+        );
+        assert_eq!(rtf_to_text(rtf), "café {x} €\r\nfallback".to_string());
+    }
+
+    #[test]
+    fn test_de_encapsulate_text_and_plain() {
+        let rtf = r"{\rtf1\ansi\fromtext \deff0{\fonttbl{\f0 Courier;}}Hello\par world\tab!}";
+        assert_eq!(
+            de_encapsulate(rtf),
+            Some(Encapsulated::Text("Hello\r\nworld\t!".to_string()))
+        );
+
+        let rtf = r"{\rtf1\ansi\ansicpg936{\fonttbl{\f0 SimSun;}}\f0 \'c4\'e3\'ba\'c3\par}";
+        assert_eq!(de_encapsulate(rtf), None);
+        assert_eq!(rtf_to_text(rtf), "你好");
+    }
+
+    #[test]
+    fn test_surrogate_pairs() {
+        let rtf = r"{\rtf1\ansi\fromtext \u-10179?\u-8704?!\u-10179?x\u-8704?\u-10179?}";
+        assert_eq!(
+            de_encapsulate(rtf),
+            Some(Encapsulated::Text(
+                "\u{1F600}!\u{FFFD}x\u{FFFD}\u{FFFD}".to_string()
+            ))
+        );
+    }
+}
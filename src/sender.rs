@@ -0,0 +1,365 @@
+//! 发件人地址解析
+//!
+//! 同一封邮件往往同时带有多个发件人地址属性：SMTP 地址、按地址类型存放的地址（可能是
+//! Exchange DN）、发件人 ENTRYID 等。按 [`ParseOptions::sender_resolution`] 给出的顺序
+//! 逐个尝试，取第一个可用的地址，并记录其来源。
+//!
+//! 代理发送（代表他人发送）的邮件中，PR_SENDER_* 是实际发送的代理人，
+//! PR_SENT_REPRESENTING_* 是被代表者，即收件人看到的“代表 … 发送”中的 …。
+//!
+//! [`ParseOptions::sender_resolution`]: crate::ParseOptions::sender_resolution
+
+use crate::props::{PropertyStore, PropertyValue};
+use crate::recipient::{Address, Recipient};
+use serde::{Deserialize, Serialize};
+
+const PROP_SENT_REPRESENTING_ENTRYID: u16 = 0x0041;
+const PROP_SENT_REPRESENTING_NAME: u16 = 0x0042;
+const PROP_SENT_REPRESENTING_ADDRTYPE: u16 = 0x0064;
+const PROP_SENT_REPRESENTING_EMAIL_ADDRESS: u16 = 0x0065;
+const PROP_SENDER_ENTRYID: u16 = 0x0C19;
+const PROP_SENDER_NAME: u16 = 0x0C1A;
+const PROP_SENDER_ADDRTYPE: u16 = 0x0C1E;
+const PROP_SENDER_EMAIL_ADDRESS: u16 = 0x0C1F;
+const PROP_SENDER_SMTP_ADDRESS: u16 = 0x5D01;
+const PROP_SENT_REPRESENTING_SMTP_ADDRESS: u16 = 0x5D02;
+
+/// 一次性 ENTRYID (One-Off EntryID) 的提供程序 UID
+const MUID_ONE_OFF: [u8; 16] = [
+    0x81, 0x2B, 0x1F, 0xA4, 0xBE, 0xA3, 0x10, 0x19, 0x9D, 0x6E, 0x00, 0xDD, 0x01, 0x0F, 0x54, 0x02,
+];
+/// 通讯簿 ENTRYID 的提供程序 UID，其中存放 Exchange DN
+const MUID_ADDRESS_BOOK: [u8; 16] = [
+    0xDC, 0xA7, 0x40, 0xC8, 0xC0, 0x42, 0x10, 0x1A, 0xB4, 0xB9, 0x08, 0x00, 0x2B, 0x2F, 0xE1, 0x82,
+];
+/// 一次性 ENTRYID 中字符串为 UTF-16 的标志位
+const ONE_OFF_UNICODE: u16 = 0x8000;
+
+/// 描述同一身份的一组属性
+struct IdentityProps {
+    name: u16,
+    entry_id: u16,
+    addr_type: u16,
+    email_address: u16,
+    smtp_address: u16,
+}
+
+const SENDER: IdentityProps = IdentityProps {
+    name: PROP_SENDER_NAME,
+    entry_id: PROP_SENDER_ENTRYID,
+    addr_type: PROP_SENDER_ADDRTYPE,
+    email_address: PROP_SENDER_EMAIL_ADDRESS,
+    smtp_address: PROP_SENDER_SMTP_ADDRESS,
+};
+
+const SENT_REPRESENTING: IdentityProps = IdentityProps {
+    name: PROP_SENT_REPRESENTING_NAME,
+    entry_id: PROP_SENT_REPRESENTING_ENTRYID,
+    addr_type: PROP_SENT_REPRESENTING_ADDRTYPE,
+    email_address: PROP_SENT_REPRESENTING_EMAIL_ADDRESS,
+    smtp_address: PROP_SENT_REPRESENTING_SMTP_ADDRESS,
+};
+
+/// 发件人地址的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SenderSource {
+    /// PR_SENDER_SMTP_ADDRESS / PR_SENT_REPRESENTING_SMTP_ADDRESS
+    SmtpAddress,
+    /// 地址类型为 SMTP 的 PR_SENDER_EMAIL_ADDRESS / PR_SENT_REPRESENTING_EMAIL_ADDRESS
+    EmailAddress,
+    /// 从 PR_SENDER_ENTRYID / PR_SENT_REPRESENTING_ENTRYID 一次性 ENTRYID 中取出的 SMTP 地址
+    EntryId,
+    /// 按发件人的 Exchange DN 在收件人表中找到同一人，取其 SMTP 地址 (PR_SMTP_ADDRESS)；
+    /// 发给自己或抄送自己的内部邮件中常见
+    RecipientTable,
+    /// Exchange DN（`/O=.../CN=...`），来自地址类型为 EX 的地址属性或通讯簿 ENTRYID
+    ExchangeDn,
+}
+
+/// 默认解析顺序：先 SMTP 地址属性，再从 ENTRYID 和收件人表推导，最后才用 Exchange DN
+pub const DEFAULT_SENDER_RESOLUTION: [SenderSource; 5] = [
+    SenderSource::SmtpAddress,
+    SenderSource::EmailAddress,
+    SenderSource::EntryId,
+    SenderSource::RecipientTable,
+    SenderSource::ExchangeDn,
+];
+
+/// 按 `order` 解析发件人地址，`order` 为空时使用 [`DEFAULT_SENDER_RESOLUTION`]
+pub(crate) fn resolve_sender(
+    store: &PropertyStore,
+    recipients: &[Recipient],
+    order: &[SenderSource],
+) -> Option<(String, SenderSource)> {
+    resolve(store, recipients, order, &[&SENDER, &SENT_REPRESENTING])
+}
+
+/// 分别解析实际发件人 (PR_SENDER_*) 和被代表者 (PR_SENT_REPRESENTING_*)。
+/// 两者为同一人时只返回发件人；只有被代表者属性时把它当作发件人
+pub(crate) fn resolve_identities(
+    store: &PropertyStore,
+    recipients: &[Recipient],
+    order: &[SenderSource],
+) -> (Option<Address>, Option<Address>) {
+    let identity = |props: &IdentityProps| {
+        let address = Address {
+            name: text(store, props.name),
+            email: resolve(store, recipients, order, &[props]).map(|(address, _)| address),
+        };
+        (address.name.is_some() || address.email.is_some()).then_some(address)
+    };
+    match (identity(&SENDER), identity(&SENT_REPRESENTING)) {
+        (Some(sender), Some(principal)) if !same_identity(&sender, &principal) => {
+            (Some(sender), Some(principal))
+        }
+        (sender, principal) => (sender.or(principal), None),
+    }
+}
+
+/// 地址相同（不区分大小写）即为同一人；缺地址时比较显示名
+fn same_identity(a: &Address, b: &Address) -> bool {
+    match (&a.email, &b.email) {
+        (Some(x), Some(y)) if x.eq_ignore_ascii_case(y) => true,
+        _ => a.name.is_some() && a.name == b.name,
+    }
+}
+
+fn resolve(
+    store: &PropertyStore,
+    recipients: &[Recipient],
+    order: &[SenderSource],
+    identities: &[&IdentityProps],
+) -> Option<(String, SenderSource)> {
+    let order = if order.is_empty() {
+        &DEFAULT_SENDER_RESOLUTION[..]
+    } else {
+        order
+    };
+    order
+        .iter()
+        .find_map(|&source| Some((read_source(store, recipients, source, identities)?, source)))
+}
+
+fn text(store: &PropertyStore, id: u16) -> Option<String> {
+    store
+        .get(id)
+        .and_then(|v| v.as_str().map(|s| s.trim().to_string()))
+        .filter(|s| !s.is_empty())
+}
+
+/// 按 `identities` 的顺序依次尝试各身份的属性
+fn read_source(
+    store: &PropertyStore,
+    recipients: &[Recipient],
+    source: SenderSource,
+    identities: &[&IdentityProps],
+) -> Option<String> {
+    let text = |id| text(store, id);
+    let binary = |id| match store.get(id) {
+        Some(PropertyValue::Binary(data)) => Some(data),
+        _ => None,
+    };
+
+    match source {
+        SenderSource::SmtpAddress => identities.iter().find_map(|props| text(props.smtp_address)),
+        SenderSource::EmailAddress => identities.iter().find_map(|props| {
+            let address = text(props.email_address)?;
+            let is_smtp = match text(props.addr_type) {
+                Some(addr_type) => addr_type.eq_ignore_ascii_case("SMTP"),
+                None => address.contains('@') && !is_exchange_dn(&address),
+            };
+            is_smtp.then_some(address)
+        }),
+        SenderSource::EntryId => identities
+            .iter()
+            .find_map(|props| one_off_smtp_address(&binary(props.entry_id)?)),
+        SenderSource::RecipientTable => identities.iter().find_map(|props| {
+            let dn = exchange_dn(store, props)?;
+            recipients
+                .iter()
+                .filter(|r| {
+                    r.exchange_dn
+                        .as_deref()
+                        .is_some_and(|d| d.eq_ignore_ascii_case(&dn))
+                })
+                .find_map(|r| {
+                    r.email
+                        .clone()
+                        .filter(|e| e.contains('@') && !is_exchange_dn(e))
+                })
+        }),
+        SenderSource::ExchangeDn => identities
+            .iter()
+            .find_map(|props| {
+                let address = text(props.email_address)?;
+                let is_ex = match text(props.addr_type) {
+                    Some(addr_type) => addr_type.eq_ignore_ascii_case("EX"),
+                    None => is_exchange_dn(&address),
+                };
+                is_ex.then_some(address)
+            })
+            .or_else(|| {
+                identities
+                    .iter()
+                    .find_map(|props| address_book_dn(&binary(props.entry_id)?))
+            }),
+    }
+}
+
+/// 一个身份的 Exchange DN：地址类型为 EX 的地址，或通讯簿 ENTRYID 中的 DN
+fn exchange_dn(store: &PropertyStore, props: &IdentityProps) -> Option<String> {
+    read_source(store, &[], SenderSource::ExchangeDn, &[props])
+}
+
+pub(crate) fn is_exchange_dn(address: &str) -> bool {
+    address
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("/O="))
+}
+
+/// 一次性 ENTRYID：标志(4) + UID(16) + 版本(2) + 标志(2) + 显示名 + 地址类型 + 地址，
+/// 字符串以 NUL 结尾，按标志位为 UTF-16 或 ANSI。只返回地址类型为 SMTP 的地址
+pub(crate) fn one_off_smtp_address(data: &[u8]) -> Option<String> {
+    if data.get(4..20)? != MUID_ONE_OFF {
+        return None;
+    }
+    let flags = u16::from_le_bytes([*data.get(22)?, *data.get(23)?]);
+    let rest = &data[24..];
+
+    let strings: Vec<String> = if flags & ONE_OFF_UNICODE != 0 {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        units
+            .split(|&u| u == 0)
+            .take(3)
+            .map(String::from_utf16_lossy)
+            .collect()
+    } else {
+        rest.split(|&b| b == 0)
+            .take(3)
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .collect()
+    };
+
+    match strings.as_slice() {
+        [_, addr_type, address]
+            if addr_type.eq_ignore_ascii_case("SMTP") && !address.is_empty() =>
+        {
+            Some(address.clone())
+        }
+        _ => None,
+    }
+}
+
+/// 通讯簿 ENTRYID：标志(4) + UID(16) + 版本(4) + 类型(4) + 以 NUL 结尾的 X500 DN
+pub(crate) fn address_book_dn(data: &[u8]) -> Option<String> {
+    if data.get(4..20)? != MUID_ADDRESS_BOOK {
+        return None;
+    }
+    let dn = data.get(28..)?.split(|&b| b == 0).next()?;
+    (!dn.is_empty()).then(|| String::from_utf8_lossy(dn).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_off(address: &str) -> Vec<u8> {
+        let mut data = vec![0u8; 4];
+        data.extend_from_slice(&MUID_ONE_OFF);
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(&ONE_OFF_UNICODE.to_le_bytes());
+        for s in ["Alice", "SMTP", address] {
+            data.extend(s.encode_utf16().flat_map(u16::to_le_bytes));
+            data.extend_from_slice(&[0, 0]);
+        }
+        data
+    }
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_resolve_sender() {
+        let mut store = PropertyStore::default();
+        store.insert_stream("__substg1.0_0C1E001F", utf16("EX"));
+        store.insert_stream("__substg1.0_0C1F001F", utf16("/O=Contoso/CN=alice"));
+        store.insert_stream("__substg1.0_0C190102", one_off("alice@example.com"));
+
+        assert_eq!(
+            resolve_sender(&store, &[], &[]),
+            Some(("alice@example.com".to_string(), SenderSource::EntryId))
+        );
+        assert_eq!(
+            resolve_sender(&store, &[], &[SenderSource::ExchangeDn]),
+            Some(("/O=Contoso/CN=alice".to_string(), SenderSource::ExchangeDn))
+        );
+
+        store.insert_stream("__substg1.0_5D01001F", utf16("a.smith@example.com"));
+        assert_eq!(
+            resolve_sender(&store, &[], &[]),
+            Some(("a.smith@example.com".to_string(), SenderSource::SmtpAddress))
+        );
+        assert_eq!(
+            resolve_sender(&store, &[], &[SenderSource::EmailAddress]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_sender_from_recipient_table() {
+        let mut store = PropertyStore::default();
+        store.insert_stream("__substg1.0_0C1E001F", utf16("EX"));
+        store.insert_stream("__substg1.0_0C1F001F", utf16("/O=Contoso/CN=alice"));
+        let recipients = [Recipient {
+            email: Some("alice@contoso.com".to_string()),
+            exchange_dn: Some("/o=contoso/cn=ALICE".to_string()),
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            resolve_sender(&store, &recipients, &[]),
+            Some((
+                "alice@contoso.com".to_string(),
+                SenderSource::RecipientTable
+            ))
+        );
+        assert_eq!(
+            resolve_sender(&store, &[], &[]),
+            Some(("/O=Contoso/CN=alice".to_string(), SenderSource::ExchangeDn))
+        );
+    }
+
+    #[test]
+    fn test_resolve_identities() {
+        let mut store = PropertyStore::default();
+        store.insert_stream("__substg1.0_0042001F", utf16("Boss"));
+        store.insert_stream("__substg1.0_5D02001F", utf16("boss@example.com"));
+        assert_eq!(
+            resolve_identities(&store, &[], &[]),
+            (
+                Some(Address {
+                    name: Some("Boss".to_string()),
+                    email: Some("boss@example.com".to_string()),
+                }),
+                None
+            )
+        );
+
+        store.insert_stream("__substg1.0_0C1A001F", utf16("Assistant"));
+        store.insert_stream("__substg1.0_5D01001F", utf16("assistant@example.com"));
+        let (sender, on_behalf_of) = resolve_identities(&store, &[], &[]);
+        assert_eq!(sender.unwrap().name.as_deref(), Some("Assistant"));
+        assert_eq!(
+            on_behalf_of.unwrap().email.as_deref(),
+            Some("boss@example.com")
+        );
+
+        store.insert_stream("__substg1.0_5D02001F", utf16("ASSISTANT@example.com"));
+        assert_eq!(resolve_identities(&store, &[], &[]).1, None);
+    }
+}
@@ -0,0 +1,228 @@
+//! 附件写入端
+//!
+//! 浏览器归档工具一次可能导出几百个附件，把它们全部放进解析结果会让内存翻倍。
+//! 提供 [`AttachmentSink`] 后，附件数据按块直接写给调用方（例如 OPFS 文件），
+//! 结果中的 `Attachment::data` 保持为空。
+
+use crate::{
+    filter, parse_msg_internal, quirks, read_attachment_entry, Attachment, MsgEmail, MsgParseError,
+    ParseOptions,
+};
+use cfb::CompoundFile;
+use std::io::{Read, Seek};
+use std::path::Path;
+use wasm_bindgen::prelude::*;
+
+/// 每次写入的数据块大小
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 接收附件数据的写入端，对每个附件依次调用 `open`、若干次 `write`、`close`
+pub trait AttachmentSink {
+    /// 开始写入第 `index` 个附件，`attachment.data` 为空
+    fn open(
+        &mut self,
+        index: usize,
+        attachment: &Attachment,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// 写入一块附件数据
+    fn write(&mut self, index: usize, chunk: &[u8]) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// 当前附件写入完毕
+    fn close(&mut self, index: usize) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// 按选项解析 MSG 文件，附件数据写入 `sink` 而不放进结果
+///
+/// 写入端返回错误时立即中止解析，返回 [`MsgParseError::AttachmentError`]。
+pub fn parse_msg_with_sink(
+    file_data: &[u8],
+    options: &ParseOptions,
+    sink: &mut dyn AttachmentSink,
+) -> Result<MsgEmail, MsgParseError> {
+    parse_msg_internal(file_data, options, Some(sink))
+}
+
+/// 读取一个附件存储并把数据写入 `sink`，返回不含数据的附件元数据
+///
+/// 附件存储本身损坏或未通过筛选时返回 `Ok(None)` 跳过该附件，与普通解析保持一致。
+pub(crate) fn write_attachment<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    attach_path: &Path,
+    quirks: &mut quirks::Quirks,
+    options: &ParseOptions,
+    index: usize,
+    sink: &mut dyn AttachmentSink,
+) -> Result<Option<Attachment>, Box<dyn std::error::Error>> {
+    let Ok(entry) = read_attachment_entry(comp, attach_path, quirks) else {
+        return Ok(None);
+    };
+    if entry.data_len == 0 && entry.attachment.filename == "未命名附件" {
+        return Ok(None);
+    }
+    if !filter::accepts(
+        &entry.attachment,
+        &options.attachment_allow,
+        &options.attachment_deny,
+    ) {
+        return Ok(None);
+    }
+
+    if options
+        .max_attachment_size
+        .is_some_and(|max_size| entry.data_len > max_size)
+    {
+        return Ok(Some(entry.attachment));
+    }
+
+    sink.open(index, &entry.attachment)?;
+    if let Some((storage, object)) = &entry.ole_object {
+        sink.write(index, &object.read(comp, storage)?)?;
+    } else if let Some(path) = &entry.data_path {
+        let mut stream = comp.open_stream(path)?;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        loop {
+            let read = stream.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            sink.write(index, &buffer[..read])?;
+        }
+    }
+    sink.close(index)?;
+
+    Ok(Some(entry.attachment))
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// JS 侧的附件写入端：`open(index, meta)` 返回一个 writer
+    #[wasm_bindgen(typescript_type = "AttachmentSink")]
+    pub type JsAttachmentSink;
+
+    #[wasm_bindgen(method, catch)]
+    fn open(
+        this: &JsAttachmentSink,
+        index: u32,
+        meta: JsValue,
+    ) -> Result<JsAttachmentWriter, JsValue>;
+
+    /// 形如 OPFS `FileSystemSyncAccessHandle` 的同步 writer，需实现 `write(chunk)` 和 `close()`
+    #[wasm_bindgen(typescript_type = "AttachmentWriter")]
+    pub type JsAttachmentWriter;
+
+    #[wasm_bindgen(method, catch)]
+    fn write(this: &JsAttachmentWriter, chunk: &[u8]) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn close(this: &JsAttachmentWriter) -> Result<JsValue, JsValue>;
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const ATTACHMENT_SINK_TS: &str = r#"
+export interface AttachmentWriter {
+    write(chunk: Uint8Array): unknown;
+    close(): unknown;
+}
+
+export interface AttachmentSink {
+    open(index: number, meta: { filename: string; content_type?: string; content_id?: string; attach_num?: number; storage_index?: number }): AttachmentWriter;
+}
+"#;
+
+/// 把 JS 写入端适配为 [`AttachmentSink`]
+struct JsSink<'a> {
+    sink: &'a JsAttachmentSink,
+    writer: Option<JsAttachmentWriter>,
+}
+
+fn js_error(e: JsValue) -> Box<dyn std::error::Error> {
+    e.as_string().unwrap_or_else(|| format!("{:?}", e)).into()
+}
+
+impl AttachmentSink for JsSink<'_> {
+    fn open(
+        &mut self,
+        index: usize,
+        attachment: &Attachment,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let meta = serde_wasm_bindgen::to_value(attachment)?;
+        self.writer = Some(self.sink.open(index as u32, meta).map_err(js_error)?);
+        Ok(())
+    }
+
+    fn write(&mut self, _index: usize, chunk: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(writer) = &self.writer {
+            writer.write(chunk).map_err(js_error)?;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self, _index: usize) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(writer) = self.writer.take() {
+            writer.close().map_err(js_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// WASM 导出接口
+/// 解析 MSG 文件，附件数据通过 `sink.open(index, meta)` 返回的 writer 逐块写出，
+/// 返回结果中附件的 `data` 为空
+#[wasm_bindgen]
+pub fn parse_msg_file_with_attachment_sink(
+    file_data: &[u8],
+    sink: &JsAttachmentSink,
+) -> Result<JsValue, JsValue> {
+    let mut adapter = JsSink { sink, writer: None };
+    let email = parse_msg_with_sink(file_data, &ParseOptions::default(), &mut adapter)
+        .map_err(|e| e.to_js())?;
+
+    crate::to_js_value(&email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Collector {
+        opened: Vec<(usize, String)>,
+        data: Vec<u8>,
+        closed: usize,
+    }
+
+    impl AttachmentSink for Collector {
+        fn open(
+            &mut self,
+            index: usize,
+            attachment: &Attachment,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.opened.push((index, attachment.filename.clone()));
+            Ok(())
+        }
+
+        fn write(&mut self, _index: usize, chunk: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+            self.data.extend_from_slice(chunk);
+            Ok(())
+        }
+
+        fn close(&mut self, _index: usize) -> Result<(), Box<dyn std::error::Error>> {
+            self.closed += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_attachment_sink() {
+        let data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let mut sink = Collector::default();
+        let email = parse_msg_with_sink(data, &ParseOptions::default(), &mut sink).unwrap();
+
+        assert_eq!(sink.opened, vec![(0, "notes.txt".to_string())]);
+        assert_eq!(sink.data, b"attachment body");
+        assert_eq!(sink.closed, 1);
+        assert_eq!(email.attachments.len(), 1);
+        assert!(email.attachments[0].data.is_empty());
+    }
+}
@@ -0,0 +1,727 @@
+//! S/MIME 签名及加密邮件（IPM.Note.SMIME*）
+//!
+//! 这类邮件的正文和附件不在 MSG 的属性中，而是整体放在一个附件里：
+//! - 明文签名 (IPM.Note.SMIME.MultipartSigned)：附件是完整的 multipart/signed 实体，
+//!   第一部分为原始内容，第二部分为 application/pkcs7-signature 签名；
+//! - 不透明签名 (IPM.Note.SMIME)：附件 smime.p7m 是 CMS SignedData，原始内容封装在其中；
+//! - 加密 (IPM.Note.SMIME)：附件 smime.p7m 是 CMS EnvelopedData，没有私钥无法读取。
+//!
+//! 签名的邮件解包后把内容并回正文和附件字段，并列出签名中携带的证书。这里不校验签名。
+
+use crate::datetime;
+use crate::der::{
+    decode_mime_base64, format_oid, Tlv, TAG_CONTEXT_0, TAG_INTEGER, TAG_OID, TAG_SEQUENCE, TAG_SET,
+};
+use crate::mime::{self, MimePart};
+use crate::props;
+use crate::{push_unwrapped_attachment, Attachment, MsgEmail, ParseOptions};
+use serde::Serialize;
+
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const OID_ENVELOPED_DATA: &str = "1.2.840.113549.1.7.3";
+const OID_AUTH_ENVELOPED_DATA: &str = "1.2.840.113549.1.9.16.1.23";
+const OID_EMAIL_ADDRESS: &str = "1.2.840.113549.1.9.1";
+const OID_SUBJECT_KEY_IDENTIFIER: &str = "2.5.29.14";
+const OID_SUBJECT_ALT_NAME: &str = "2.5.29.17";
+
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_CONTEXT_1: u8 = 0xA1;
+const TAG_CONTEXT_3: u8 = 0xA3;
+/// SignerIdentifier 的 [0] subjectKeyIdentifier
+const TAG_IMPLICIT_0: u8 = 0x80;
+/// GeneralName 的 [1] rfc822Name
+const TAG_IMPLICIT_1: u8 = 0x81;
+
+/// S/MIME 封装方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmimeKind {
+    /// multipart/signed，内容以明文放在签名旁边
+    ClearSigned,
+    /// application/pkcs7-mime 的 SignedData，内容封装在签名中
+    OpaqueSigned,
+    /// application/pkcs7-mime 的 EnvelopedData，内容已加密
+    Encrypted,
+}
+
+/// 证书摘要信息，取自 X.509 证书，未校验证书链
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SmimeCertificate {
+    /// 主题 DN，RFC 4514 格式，如 `CN=Alice,O=Example`
+    pub subject: String,
+    /// 颁发者 DN
+    pub issuer: String,
+    /// 序列号（十六进制）
+    pub serial_number: String,
+    /// 有效期起止，RFC 3339 格式的 UTC 时间
+    pub not_before: Option<String>,
+    pub not_after: Option<String>,
+    /// 主题中的 emailAddress，其次主题备用名称中的第一个 rfc822Name
+    pub email: Option<String>,
+}
+
+/// S/MIME 邮件的封装方式和签名证书
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SmimeInfo {
+    pub kind: SmimeKind,
+    /// 签名者的证书，签名中未携带时为 `None`
+    pub signer: Option<SmimeCertificate>,
+    /// 签名中携带的全部证书，通常还包括中间 CA
+    pub certificates: Vec<SmimeCertificate>,
+}
+
+/// 是否为 S/MIME 签名或加密邮件（不含签名回执）
+pub(crate) fn is_smime_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class == "IPM.NOTE.SMIME" || class.starts_with("IPM.NOTE.SMIME.")
+}
+
+/// 找到 S/MIME 附件并解包：签名邮件的内容并入正文和附件，原 S/MIME 附件随之移除。
+/// 解出的附件同样经过 `options` 的筛选和大小限制；`skip_html` 时不填充 HTML 正文
+pub(crate) fn unwrap_smime(email: &mut MsgEmail, options: &ParseOptions) -> Option<SmimeInfo> {
+    let index = email.attachments.iter().position(|attachment| {
+        is_smime_attachment(&attachment.filename, attachment.content_type.as_deref())
+    })?;
+    let attachment = &email.attachments[index];
+    let payload = read_payload(&attachment.data, attachment.content_type.as_deref())?;
+
+    let (info, content) = match payload {
+        Payload::ClearSigned(entity) => {
+            let signed = mime::parse_entity(&entity);
+            let signed_data = signed
+                .parts
+                .get(1)
+                .and_then(|signature| parse_signed_data(&signature.body));
+            let info = SmimeInfo {
+                kind: SmimeKind::ClearSigned,
+                signer: signed_data.as_ref().and_then(|s| s.signer.clone()),
+                certificates: signed_data.map(|s| s.certificates).unwrap_or_default(),
+            };
+            (info, signed.parts.into_iter().next())
+        }
+        Payload::Cms(der) => match content_info(&der)?.0.as_str() {
+            OID_ENVELOPED_DATA | OID_AUTH_ENVELOPED_DATA => {
+                let info = SmimeInfo {
+                    kind: SmimeKind::Encrypted,
+                    signer: None,
+                    certificates: Vec::new(),
+                };
+                return Some(info);
+            }
+            OID_SIGNED_DATA => {
+                let signed_data = parse_signed_data(&der)?;
+                let content = signed_data.content.as_deref().map(mime::parse_entity);
+                let info = SmimeInfo {
+                    kind: SmimeKind::OpaqueSigned,
+                    signer: signed_data.signer,
+                    certificates: signed_data.certificates,
+                };
+                (info, content)
+            }
+            _ => return None,
+        },
+    };
+
+    if let Some(content) = content {
+        email.attachments.remove(index);
+        let mut bodies = Bodies::default();
+        let mut attachments = Vec::new();
+        collect_parts(content, &mut bodies, &mut attachments, 0);
+        for attachment in attachments {
+            push_unwrapped_attachment(email, attachment, options);
+        }
+        if email
+            .body_text
+            .as_deref()
+            .is_none_or(|b| b.trim().is_empty())
+        {
+            email.body_text = bodies.text.or(email.body_text.take());
+        }
+        if !options.skip_html
+            && email
+                .body_html
+                .as_deref()
+                .is_none_or(|b| b.trim().is_empty())
+        {
+            email.body_html = bodies.html.or(email.body_html.take());
+        }
+    }
+    Some(info)
+}
+
+/// 是否为承载 S/MIME 内容的附件
+pub(crate) fn is_smime_attachment(filename: &str, content_type: Option<&str>) -> bool {
+    let name = filename.to_ascii_lowercase();
+    let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+    name.ends_with(".p7m")
+        || name.ends_with(".p7s")
+        || content_type.starts_with("multipart/signed")
+        || content_type.contains("pkcs7-mime")
+}
+
+/// S/MIME 附件的内容
+pub(crate) enum Payload {
+    /// 完整的 multipart/signed 实体
+    ClearSigned(Vec<u8>),
+    /// DER/BER 编码的 CMS ContentInfo
+    Cms(Vec<u8>),
+}
+
+/// 取出 S/MIME 附件的内容。Outlook 有时只保存 multipart/signed 的正文，
+/// 实体头放在附件的 MIME 类型中；smime.p7m 可能是二进制，也可能是 base64
+pub(crate) fn read_payload(data: &[u8], content_type: Option<&str>) -> Option<Payload> {
+    if mime::parse_entity(data).mime_type == "multipart/signed" {
+        return Some(Payload::ClearSigned(data.to_vec()));
+    }
+    if let Some(content_type) = content_type.filter(|content_type| {
+        content_type
+            .to_ascii_lowercase()
+            .starts_with("multipart/signed")
+    }) {
+        let mut entity = format!("Content-Type: {}\r\n\r\n", content_type).into_bytes();
+        entity.extend_from_slice(data);
+        if mime::multipart_parts(&entity).len() >= 2 {
+            return Some(Payload::ClearSigned(entity));
+        }
+    }
+    decode_der(data).map(Payload::Cms)
+}
+
+/// 二进制 DER/BER 原样返回，否则按 base64 MIME 实体解码
+pub(crate) fn decode_der(data: &[u8]) -> Option<Vec<u8>> {
+    if data.first() == Some(&TAG_SEQUENCE) {
+        Some(data.to_vec())
+    } else {
+        decode_mime_base64(data)
+    }
+}
+
+#[derive(Default)]
+struct Bodies {
+    text: Option<String>,
+    html: Option<String>,
+}
+
+/// 遍历 MIME 树：第一个非附件的文本和 HTML 部分作为正文，其余作为附件
+fn collect_parts(
+    part: MimePart,
+    bodies: &mut Bodies,
+    attachments: &mut Vec<Attachment>,
+    depth: usize,
+) {
+    if part.mime_type.starts_with("multipart/") {
+        if depth >= mime::MAX_DEPTH {
+            return;
+        }
+        // 嵌套签名只取被签名的内容
+        let parts = if part.mime_type == "multipart/signed" {
+            part.parts.into_iter().take(1).collect()
+        } else {
+            part.parts
+        };
+        for child in parts {
+            collect_parts(child, bodies, attachments, depth + 1);
+        }
+        return;
+    }
+    if !part.is_attachment() {
+        let slot = match part.mime_type.as_str() {
+            "text/plain" => Some(&mut bodies.text),
+            "text/html" => Some(&mut bodies.html),
+            _ => None,
+        };
+        if let Some(slot) = slot.filter(|slot| slot.is_none()) {
+            *slot = Some(part.text());
+            return;
+        }
+    }
+
+    let filename = part.filename().unwrap_or_else(|| {
+        match part.mime_type.as_str() {
+            "message/rfc822" => "message.eml",
+            "text/plain" => "attachment.txt",
+            "text/html" => "attachment.html",
+            _ => "attachment",
+        }
+        .to_string()
+    });
+    attachments.push(Attachment {
+        filename,
+        content_type: Some(part.mime_type),
+        content_id: part.content_id,
+        size: part.body.len() as u64,
+        data: part.body,
+        ..Default::default()
+    });
+}
+
+/// 解析出的 SignedData
+struct SignedData {
+    certificates: Vec<SmimeCertificate>,
+    signer: Option<SmimeCertificate>,
+    /// 封装的内容，明文签名时为空
+    content: Option<Vec<u8>>,
+}
+
+/// ContentInfo ::= SEQUENCE { contentType, [0] EXPLICIT content }
+pub(crate) fn content_info(der: &[u8]) -> Option<(String, Tlv<'_>)> {
+    let mut items = Tlv::read(der)?.expect(TAG_SEQUENCE)?.children();
+    let content_type = format_oid(items.next()?.expect(TAG_OID)?.body);
+    let content = items.next()?.expect(TAG_CONTEXT_0)?.children().next()?;
+    Some((content_type, content))
+}
+
+/// 解析 DER/BER 编码的 SignedData，也接受 base64 MIME 实体
+fn parse_signed_data(data: &[u8]) -> Option<SignedData> {
+    let der = decode_der(data)?;
+    let parts = signed_data_parts(&der)?;
+    let certificates: Vec<ParsedCertificate> = parts
+        .certificates
+        .into_iter()
+        .filter_map(parse_certificate)
+        .collect();
+    let signer = parts
+        .signer_infos
+        .first()
+        .and_then(|signer_info| find_signer(&certificates, signer_info))
+        .map(|index| certificates[index].info.clone());
+
+    Some(SignedData {
+        certificates: certificates.into_iter().map(|cert| cert.info).collect(),
+        signer,
+        content: parts.content,
+    })
+}
+
+/// SignedData 中各部分的原始 TLV
+pub(crate) struct SignedDataParts<'a> {
+    pub certificates: Vec<Tlv<'a>>,
+    pub signer_infos: Vec<Tlv<'a>>,
+    /// 封装的内容，明文签名时为空
+    pub content: Option<Vec<u8>>,
+}
+
+/// 拆分 ContentInfo 中的 SignedData，内容类型不是 SignedData 时返回 `None`
+pub(crate) fn signed_data_parts(der: &[u8]) -> Option<SignedDataParts<'_>> {
+    let (content_type, signed_data) = content_info(der)?;
+    if content_type != OID_SIGNED_DATA {
+        return None;
+    }
+
+    // SignedData ::= SEQUENCE { version, digestAlgorithms, encapContentInfo,
+    //     [0] IMPLICIT certificates OPTIONAL, [1] IMPLICIT crls OPTIONAL, signerInfos }
+    let mut items = signed_data.expect(TAG_SEQUENCE)?.children();
+    items.next()?.expect(TAG_INTEGER)?;
+    items.next()?.expect(TAG_SET)?;
+    let mut encap = items.next()?.expect(TAG_SEQUENCE)?.children();
+    encap.next()?.expect(TAG_OID)?;
+    let content = encap
+        .next()
+        .and_then(|econtent| econtent.expect(TAG_CONTEXT_0))
+        .and_then(|econtent| econtent.children().next()?.octets());
+
+    let mut certificates = Vec::new();
+    let mut signer_infos = Vec::new();
+    for item in items {
+        match item.tag {
+            // 证书集合中还可能有属性证书等其他选项，只取 X.509 证书
+            TAG_CONTEXT_0 => {
+                certificates.extend(item.children().filter(|cert| cert.tag == TAG_SEQUENCE));
+            }
+            TAG_CONTEXT_1 => {}
+            TAG_SET => signer_infos.extend(item.children()),
+            _ => {}
+        }
+    }
+    Some(SignedDataParts {
+        certificates,
+        signer_infos,
+        content,
+    })
+}
+
+/// 按 SignerInfo 中的标识找到签名者证书，返回其序号
+pub(crate) fn find_signer(certificates: &[ParsedCertificate], signer_info: &Tlv) -> Option<usize> {
+    let id = signer_id(signer_info)?;
+    certificates.iter().position(|cert| match &id {
+        SignerId::IssuerSerial(issuer, serial) => {
+            &cert.issuer_der == issuer && &cert.serial == serial
+        }
+        SignerId::KeyId(key_id) => cert.key_id.as_ref() == Some(key_id),
+    })
+}
+
+/// SignerInfo 中标识签名者证书的方式
+enum SignerId {
+    /// 颁发者 DN（DER 内容）+ 序列号
+    IssuerSerial(Vec<u8>, Vec<u8>),
+    /// 主题密钥标识符
+    KeyId(Vec<u8>),
+}
+
+/// SignerInfo ::= SEQUENCE { version, sid, ... }
+fn signer_id(signer_info: &Tlv) -> Option<SignerId> {
+    let mut items = signer_info.expect(TAG_SEQUENCE)?.children();
+    items.next()?.expect(TAG_INTEGER)?;
+    let sid = items.next()?;
+    match sid.tag {
+        TAG_SEQUENCE => {
+            let mut parts = sid.children();
+            let issuer = parts.next()?.expect(TAG_SEQUENCE)?.body.to_vec();
+            let serial = parts.next()?.expect(TAG_INTEGER)?.body.to_vec();
+            Some(SignerId::IssuerSerial(issuer, serial))
+        }
+        TAG_IMPLICIT_0 => Some(SignerId::KeyId(sid.body.to_vec())),
+        _ => None,
+    }
+}
+
+/// 证书摘要及用于匹配签名者、颁发者的原始字段
+pub(crate) struct ParsedCertificate {
+    pub info: SmimeCertificate,
+    /// 颁发者、主题 DN 的 DER 内容
+    pub issuer_der: Vec<u8>,
+    #[cfg_attr(not(feature = "smime-verify"), allow(dead_code))]
+    pub subject_der: Vec<u8>,
+    serial: Vec<u8>,
+    key_id: Option<Vec<u8>>,
+}
+
+/// Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature }
+pub(crate) fn parse_certificate(cert: Tlv) -> Option<ParsedCertificate> {
+    let tbs = cert.children().next()?.expect(TAG_SEQUENCE)?;
+    let mut items = tbs.children().peekable();
+    // [0] EXPLICIT version，v1 证书省略
+    if items.peek()?.tag == TAG_CONTEXT_0 {
+        items.next();
+    }
+    let serial = items.next()?.expect(TAG_INTEGER)?;
+    items.next()?.expect(TAG_SEQUENCE)?;
+    let issuer = items.next()?.expect(TAG_SEQUENCE)?;
+    let mut validity = items.next()?.expect(TAG_SEQUENCE)?.children();
+    let not_before = validity.next().and_then(|t| parse_time(&t));
+    let not_after = validity.next().and_then(|t| parse_time(&t));
+    let subject = items.next()?.expect(TAG_SEQUENCE)?;
+
+    let mut key_id = None;
+    let mut alt_email = None;
+    if let Some(extensions) = items.find(|item| item.tag == TAG_CONTEXT_3) {
+        let extensions = extensions.children().next()?.children();
+        for extension in extensions {
+            let mut parts = extension.children();
+            let Some(oid) = parts.next().and_then(|oid| oid.expect(TAG_OID)) else {
+                continue;
+            };
+            // critical BOOLEAN 可省略，取最后一项 extnValue
+            let Some(value) = parts.last().and_then(|value| value.octets()) else {
+                continue;
+            };
+            match format_oid(oid.body).as_str() {
+                OID_SUBJECT_KEY_IDENTIFIER => {
+                    key_id = Tlv::read(&value).and_then(|v| v.octets());
+                }
+                OID_SUBJECT_ALT_NAME => {
+                    alt_email = Tlv::read(&value).and_then(|names| {
+                        names
+                            .children()
+                            .find(|name| name.tag == TAG_IMPLICIT_1)
+                            .map(|name| String::from_utf8_lossy(name.body).into_owned())
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let subject_attributes = name_attributes(&subject);
+    let email = subject_attributes
+        .iter()
+        .find(|(oid, _)| oid == OID_EMAIL_ADDRESS)
+        .map(|(_, value)| value.clone())
+        .or(alt_email);
+    Some(ParsedCertificate {
+        info: SmimeCertificate {
+            subject: format_name(&subject_attributes),
+            issuer: format_name(&name_attributes(&issuer)),
+            serial_number: props::to_hex(serial.body),
+            not_before,
+            not_after,
+            email,
+        },
+        issuer_der: issuer.body.to_vec(),
+        subject_der: subject.body.to_vec(),
+        serial: serial.body.to_vec(),
+        key_id,
+    })
+}
+
+/// Name ::= SEQUENCE OF SET OF SEQUENCE { type, value }，按编码顺序返回 (OID, 值)
+fn name_attributes(name: &Tlv) -> Vec<(String, String)> {
+    name.children()
+        .flat_map(|rdn| rdn.children().collect::<Vec<_>>())
+        .filter_map(|attribute| {
+            let mut parts = attribute.children();
+            let oid = format_oid(parts.next()?.expect(TAG_OID)?.body);
+            let value = parts.next()?;
+            Some((oid, directory_string(&value)))
+        })
+        .collect()
+}
+
+/// 各种 ASN.1 字符串类型的值
+fn directory_string(value: &Tlv) -> String {
+    match value.tag {
+        // BMPString
+        0x1E => {
+            let units: Vec<u16> = value
+                .body
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        // TeletexString，实际多为 Latin-1
+        0x14 => value.body.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(value.body).into_owned(),
+    }
+}
+
+/// RFC 4514：从最后一个 RDN 起逆序排列，值中的特殊字符加反斜杠
+fn format_name(attributes: &[(String, String)]) -> String {
+    attributes
+        .iter()
+        .rev()
+        .map(|(oid, value)| {
+            let key = match oid.as_str() {
+                "2.5.4.3" => "CN",
+                "2.5.4.6" => "C",
+                "2.5.4.7" => "L",
+                "2.5.4.8" => "ST",
+                "2.5.4.9" => "STREET",
+                "2.5.4.10" => "O",
+                "2.5.4.11" => "OU",
+                "0.9.2342.19200300.100.1.25" => "DC",
+                "0.9.2342.19200300.100.1.1" => "UID",
+                OID_EMAIL_ADDRESS => "emailAddress",
+                other => other,
+            };
+            let mut escaped = String::with_capacity(value.len());
+            for c in value.chars() {
+                if matches!(c, ',' | '+' | '"' | '\\' | '<' | '>' | ';' | '=') {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+            }
+            format!("{}={}", key, escaped)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// UTCTime (`YYMMDDHHMMSSZ`) 或 GeneralizedTime (`YYYYMMDDHHMMSSZ`)
+pub(crate) fn parse_time(time: &Tlv) -> Option<String> {
+    let text = std::str::from_utf8(time.body).ok()?.strip_suffix('Z')?;
+    let (year, rest) = match time.tag {
+        TAG_UTC_TIME => {
+            let year: i64 = text.get(..2)?.parse().ok()?;
+            // RFC 5280：50 及以上为 19xx
+            (
+                if year >= 50 { 1900 + year } else { 2000 + year },
+                &text[2..],
+            )
+        }
+        TAG_GENERALIZED_TIME => (text.get(..4)?.parse().ok()?, &text[4..]),
+        _ => return None,
+    };
+    let field = |i: usize| -> Option<i64> { rest.get(i..i + 2)?.parse().ok() };
+    let days = datetime::days_from_civil(year, field(0)?, field(2)?);
+    let unix = days * 86400 + field(4)? * 3600 + field(6)? * 60 + field(8).unwrap_or(0);
+    Some(datetime::format_rfc3339(unix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::der::TAG_OCTET_STRING;
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        match content.len() {
+            len if len < 0x80 => out.push(len as u8),
+            len if len < 0x100 => out.extend_from_slice(&[0x81, len as u8]),
+            len => out.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    fn name(common_name: &str, organization: &str) -> Vec<u8> {
+        let rdn = |oid: &[u8], value: &str| {
+            tlv(
+                TAG_SET,
+                &tlv(
+                    TAG_SEQUENCE,
+                    &[tlv(TAG_OID, oid), tlv(0x0C, value.as_bytes())].concat(),
+                ),
+            )
+        };
+        tlv(
+            TAG_SEQUENCE,
+            &[
+                rdn(&[0x55, 0x04, 0x0A], organization),
+                rdn(&[0x55, 0x04, 0x03], common_name),
+            ]
+            .concat(),
+        )
+    }
+
+    fn certificate(serial: u8, subject: &[u8], issuer: &[u8]) -> Vec<u8> {
+        let algorithm = tlv(TAG_SEQUENCE, &tlv(TAG_OID, &[0x2B, 0x65, 0x70]));
+        // subjectAltName: rfc822Name
+        let alt_name = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_OID, &[0x55, 0x1D, 0x11]),
+                tlv(
+                    TAG_OCTET_STRING,
+                    &tlv(TAG_SEQUENCE, &tlv(TAG_IMPLICIT_1, b"alice@example.com")),
+                ),
+            ]
+            .concat(),
+        );
+        let tbs = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_CONTEXT_0, &tlv(TAG_INTEGER, &[2])),
+                tlv(TAG_INTEGER, &[serial]),
+                algorithm.clone(),
+                issuer.to_vec(),
+                tlv(
+                    TAG_SEQUENCE,
+                    &[
+                        tlv(TAG_UTC_TIME, b"240101000000Z"),
+                        tlv(TAG_GENERALIZED_TIME, b"20261231235959Z"),
+                    ]
+                    .concat(),
+                ),
+                subject.to_vec(),
+                tlv(TAG_SEQUENCE, &[algorithm.clone(), tlv(0x03, &[0])].concat()),
+                tlv(TAG_CONTEXT_3, &tlv(TAG_SEQUENCE, &alt_name)),
+            ]
+            .concat(),
+        );
+        tlv(TAG_SEQUENCE, &[tbs, algorithm, tlv(0x03, &[0])].concat())
+    }
+
+    /// 两张证书的 SignedData，签名者为第二张
+    fn signed_data(content: Option<&[u8]>) -> Vec<u8> {
+        let ca = name("Example CA", "Example");
+        let alice = name("Alice, Example", "Example");
+        let id_data = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01];
+        let id_signed = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+
+        let mut encap = tlv(TAG_OID, &id_data);
+        if let Some(content) = content {
+            encap.extend(tlv(TAG_CONTEXT_0, &tlv(TAG_OCTET_STRING, content)));
+        }
+        let signer_info = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[1]),
+                tlv(TAG_SEQUENCE, &[ca.clone(), tlv(TAG_INTEGER, &[7])].concat()),
+            ]
+            .concat(),
+        );
+        let signed = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[1]),
+                tlv(TAG_SET, &[]),
+                tlv(TAG_SEQUENCE, &encap),
+                tlv(
+                    TAG_CONTEXT_0,
+                    &[certificate(1, &ca, &ca), certificate(7, &alice, &ca)].concat(),
+                ),
+                tlv(TAG_SET, &signer_info),
+            ]
+            .concat(),
+        );
+        tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_OID, &id_signed), tlv(TAG_CONTEXT_0, &signed)].concat(),
+        )
+    }
+
+    #[test]
+    fn test_parse_signed_data() {
+        let content = b"Content-Type: text/plain\r\n\r\nHello";
+        let parsed = parse_signed_data(&signed_data(Some(content))).unwrap();
+        assert_eq!(parsed.content.as_deref(), Some(&content[..]));
+        assert_eq!(parsed.certificates.len(), 2);
+
+        let signer = parsed.signer.unwrap();
+        assert_eq!(signer.subject, "CN=Alice\\, Example,O=Example");
+        assert_eq!(signer.issuer, "CN=Example CA,O=Example");
+        assert_eq!(signer.serial_number, "07");
+        assert_eq!(signer.not_before.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(signer.not_after.as_deref(), Some("2026-12-31T23:59:59Z"));
+        assert_eq!(signer.email.as_deref(), Some("alice@example.com"));
+
+        // base64 形式的签名部分
+        let encoded = format!(
+            "Content-Type: application/pkcs7-signature\r\n\r\n{}",
+            crate::eml::base64(&signed_data(None))
+        );
+        let parsed = parse_signed_data(encoded.as_bytes()).unwrap();
+        assert!(parsed.content.is_none());
+        assert_eq!(parsed.signer.unwrap().serial_number, "07");
+    }
+
+    #[test]
+    fn test_unwrap_opaque_signed() {
+        let content = b"Content-Type: multipart/mixed; boundary=\"b\"\r\n\r\n--b\r\nContent-Type: text/plain\r\n\r\nHello\r\n--b\r\nContent-Type: application/pdf\r\nContent-Disposition: attachment; filename=\"a.pdf\"\r\n\r\n%PDF\r\n--b--\r\n";
+        let smime_email = || MsgEmail {
+            attachments: vec![Attachment {
+                filename: "smime.p7m".to_string(),
+                content_type: Some("application/pkcs7-mime".to_string()),
+                data: signed_data(Some(content)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let options = ParseOptions {
+            attachment_deny: vec![".pdf".to_string()],
+            ..Default::default()
+        };
+        let mut denied = smime_email();
+        unwrap_smime(&mut denied, &options).unwrap();
+        assert!(denied.attachments.is_empty());
+
+        let options = ParseOptions {
+            max_attachment_size: Some(2),
+            ..Default::default()
+        };
+        let mut limited = smime_email();
+        unwrap_smime(&mut limited, &options).unwrap();
+        assert!(limited.attachments[0].data.is_empty());
+        assert_eq!(limited.attachments[0].size, 4);
+        assert_eq!(limited.warnings.len(), 1);
+
+        let mut email = smime_email();
+        let info = unwrap_smime(&mut email, &ParseOptions::default()).unwrap();
+        assert_eq!(info.kind, SmimeKind::OpaqueSigned);
+        assert_eq!(email.body_text.as_deref(), Some("Hello"));
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].filename, "a.pdf");
+        assert_eq!(email.attachments[0].data, b"%PDF");
+    }
+
+    #[test]
+    fn test_is_smime_class() {
+        assert!(is_smime_class("IPM.Note.SMIME"));
+        assert!(is_smime_class("IPM.Note.SMIME.MultipartSigned"));
+        assert!(!is_smime_class("IPM.Note.Receipt.SMIME"));
+        assert!(!is_smime_class("IPM.Note.SMIMEX"));
+    }
+}
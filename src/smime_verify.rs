@@ -0,0 +1,735 @@
+//! S/MIME 签名校验（`smime-verify` feature）
+//!
+//! 只校验第一个签名者：先比较签名属性中的 messageDigest 与内容的摘要，判断内容是否被改动；
+//! 再用签名者证书的公钥校验签名。没有签名属性时签名直接针对内容的摘要。
+//!
+//! 证书链只用签名中携带的证书逐级校验颁发者的签名，不查询吊销状态，也不判断根证书是否可信，
+//! 这取决于调用方自己的信任列表。
+
+use crate::der::{format_oid, Tlv, TAG_CONTEXT_0, TAG_INTEGER, TAG_OID, TAG_SEQUENCE, TAG_SET};
+use crate::smime::{self, ParsedCertificate, Payload, SmimeCertificate, SmimeKind};
+use crate::{list_msg_attachments, mime, read_attachment_data, MsgParseError};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use wasm_bindgen::prelude::*;
+
+const TAG_BIT_STRING: u8 = 0x03;
+
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const OID_SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const OID_P256: &str = "1.2.840.10045.3.1.7";
+const OID_P384: &str = "1.3.132.0.34";
+
+/// 签名校验结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignatureVerification {
+    pub kind: SmimeKind,
+    /// 摘要算法，如 `sha256`；不支持的算法给出 OID
+    pub digest_algorithm: String,
+    /// 内容的摘要与签名中记录的一致，即签名后内容未被改动
+    pub digest_valid: bool,
+    /// 签名能用签名者证书的公钥验证
+    pub signature_valid: bool,
+    /// 签名者声明的签名时间 (signingTime 属性)
+    pub signing_time: Option<String>,
+    /// 签名者的证书，签名中未携带时为 `None`
+    pub signer: Option<SmimeCertificate>,
+    /// 从签名者证书起逐级向上的证书链，到自签名证书或签名中缺少的颁发者为止
+    pub chain: Vec<ChainCertificate>,
+    /// 未能完成的校验步骤，如不支持的签名算法
+    pub warnings: Vec<String>,
+}
+
+/// 证书链中的一张证书
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainCertificate {
+    pub certificate: SmimeCertificate,
+    /// 证书上的签名能否用颁发者证书的公钥验证；签名中没有颁发者证书时为 `None`
+    pub signature_valid: Option<bool>,
+    /// 是否自签名，通常即根证书
+    pub self_signed: bool,
+}
+
+/// WASM 导出接口
+/// 校验 S/MIME 签名邮件的签名，返回摘要、签名是否有效以及签名者的证书链
+#[wasm_bindgen]
+pub fn verify_smime_signature(file_data: &[u8]) -> Result<JsValue, JsValue> {
+    let result = verify_msg_smime_signature(file_data).map_err(|e| e.to_js())?;
+
+    serde_wasm_bindgen::to_value(&result)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 校验 S/MIME 签名邮件的签名，见 [`verify_smime_signature`]
+///
+/// 邮件没有签名或已加密时返回 `MsgParseError::SmimeError`；签名无效不是错误，
+/// 通过结果中的 `digest_valid`、`signature_valid` 判断。
+pub fn verify_msg_smime_signature(
+    file_data: &[u8],
+) -> Result<SignatureVerification, MsgParseError> {
+    let attachment = list_msg_attachments(file_data)?
+        .into_iter()
+        .find(|attachment| {
+            !attachment.is_embedded_message
+                && smime::is_smime_attachment(
+                    &attachment.filename,
+                    attachment.content_type.as_deref(),
+                )
+        })
+        .ok_or_else(|| smime_error("邮件没有 S/MIME 附件"))?;
+    let data = read_attachment_data(file_data, attachment.index)?;
+    let payload = smime::read_payload(&data, attachment.content_type.as_deref())
+        .ok_or_else(|| smime_error("无法读取 S/MIME 附件的内容"))?;
+    verify_payload(&payload)
+}
+
+fn smime_error(reason: &str) -> MsgParseError {
+    MsgParseError::SmimeError {
+        reason: reason.to_string(),
+    }
+}
+
+fn verify_payload(payload: &Payload) -> Result<SignatureVerification, MsgParseError> {
+    let (kind, detached, der) = match payload {
+        Payload::ClearSigned(entity) => {
+            let parts = mime::multipart_parts(entity);
+            let [content, signature, ..] = parts[..] else {
+                return Err(smime_error("multipart/signed 缺少签名部分"));
+            };
+            let der = smime::decode_der(&mime::parse_entity(signature).body)
+                .ok_or_else(|| smime_error("无法读取签名部分"))?;
+            (SmimeKind::ClearSigned, Some(content.to_vec()), der)
+        }
+        Payload::Cms(der) => (SmimeKind::OpaqueSigned, None, der.clone()),
+    };
+    let parts = smime::signed_data_parts(&der)
+        .ok_or_else(|| smime_error("不是 SignedData，邮件可能已加密"))?;
+    let content = detached
+        .or(parts.content)
+        .ok_or_else(|| smime_error("签名中没有被签名的内容"))?;
+    let signer_info = parts
+        .signer_infos
+        .first()
+        .and_then(parse_signer_info)
+        .ok_or_else(|| smime_error("签名中没有签名者信息"))?;
+
+    let (certificates, fields): (Vec<ParsedCertificate>, Vec<CertificateFields>) = parts
+        .certificates
+        .iter()
+        .filter_map(|&cert| Some((smime::parse_certificate(cert)?, certificate_fields(cert)?)))
+        .unzip();
+    let signer = parts
+        .signer_infos
+        .first()
+        .and_then(|signer_info| smime::find_signer(&certificates, signer_info));
+
+    let mut warnings = Vec::new();
+    let digest = DigestAlgorithm::from_oid(&signer_info.digest_algorithm);
+    let scheme = digest.and_then(|digest| {
+        SignatureScheme::from_oid(&signer_info.signature_algorithm, Some(digest))
+    });
+    if signer.is_none() {
+        warnings.push("签名中没有签名者的证书，无法校验签名".to_string());
+    }
+    if scheme.is_none() {
+        warnings.push(format!(
+            "不支持的签名算法 {} / {}",
+            signer_info.digest_algorithm, signer_info.signature_algorithm
+        ));
+    }
+    // 明文签名按规范应以 CRLF 换行计算摘要，Outlook 保存的实体偶尔只有 LF
+    let candidates = content_candidates(&content);
+    let verify = |message: &[u8], warnings: &mut Vec<String>| match (signer, scheme) {
+        (Some(signer), Some((scheme, digest))) => check(
+            verify_signature(
+                &fields[signer].spki,
+                scheme,
+                digest,
+                message,
+                &signer_info.signature,
+            ),
+            warnings,
+        ),
+        _ => false,
+    };
+
+    let (digest_valid, signature_valid) = match (digest, &signer_info.signed_attributes) {
+        (None, _) => (false, false),
+        (Some(digest), Some(attributes)) => {
+            let digest_valid = match &attributes.message_digest {
+                Some(expected) => candidates
+                    .iter()
+                    .any(|content| &digest.digest(content) == expected),
+                None => {
+                    warnings.push("签名属性中没有 messageDigest".to_string());
+                    false
+                }
+            };
+            (digest_valid, verify(&attributes.encoded, &mut warnings))
+        }
+        (Some(_), None) => {
+            let valid = candidates
+                .iter()
+                .any(|content| verify(content, &mut warnings));
+            (valid, valid)
+        }
+    };
+
+    let chain = signer
+        .map(|signer| build_chain(&certificates, &fields, signer, &mut warnings))
+        .unwrap_or_default();
+    Ok(SignatureVerification {
+        kind,
+        digest_algorithm: digest.map_or_else(
+            || signer_info.digest_algorithm.clone(),
+            |digest| digest.name().to_string(),
+        ),
+        digest_valid,
+        signature_valid,
+        signing_time: signer_info
+            .signed_attributes
+            .and_then(|attributes| attributes.signing_time),
+        signer: signer.map(|signer| certificates[signer].info.clone()),
+        chain,
+        warnings,
+    })
+}
+
+/// 原样内容，以及含单独 LF 时换成 CRLF 的内容
+fn content_candidates(content: &[u8]) -> Vec<Vec<u8>> {
+    let mut candidates = vec![content.to_vec()];
+    let has_bare_lf = content
+        .iter()
+        .enumerate()
+        .any(|(i, &b)| b == b'\n' && (i == 0 || content[i - 1] != b'\r'));
+    if has_bare_lf {
+        let mut canonical = Vec::with_capacity(content.len() + content.len() / 32);
+        for (i, &b) in content.iter().enumerate() {
+            if b == b'\n' && (i == 0 || content[i - 1] != b'\r') {
+                canonical.push(b'\r');
+            }
+            canonical.push(b);
+        }
+        candidates.push(canonical);
+    }
+    candidates
+}
+
+/// 校验出错（如不支持的公钥）时记录原因并视为无效
+fn check(result: Result<bool, String>, warnings: &mut Vec<String>) -> bool {
+    result.unwrap_or_else(|reason| {
+        if !warnings.contains(&reason) {
+            warnings.push(reason);
+        }
+        false
+    })
+}
+
+/// 从签名者证书起沿颁发者向上，每张证书用颁发者的公钥校验
+fn build_chain(
+    certificates: &[ParsedCertificate],
+    fields: &[CertificateFields],
+    signer: usize,
+    warnings: &mut Vec<String>,
+) -> Vec<ChainCertificate> {
+    let mut chain = Vec::new();
+    let mut visited = Vec::new();
+    let mut current = Some(signer);
+    while let Some(index) = current.filter(|index| !visited.contains(index)) {
+        visited.push(index);
+        let cert = &certificates[index];
+        let self_signed = cert.issuer_der == cert.subject_der;
+        let issuer = if self_signed {
+            Some(index)
+        } else {
+            certificates
+                .iter()
+                .position(|issuer| issuer.subject_der == cert.issuer_der)
+        };
+        let signature_valid = issuer.map(|issuer| {
+            let cert = &fields[index];
+            let result = match SignatureScheme::from_oid(&cert.signature_algorithm, None) {
+                Some((scheme, digest)) => verify_signature(
+                    &fields[issuer].spki,
+                    scheme,
+                    digest,
+                    cert.tbs,
+                    cert.signature,
+                ),
+                None => Err(format!("不支持的证书签名算法 {}", cert.signature_algorithm)),
+            };
+            check(result, warnings)
+        });
+        chain.push(ChainCertificate {
+            certificate: cert.info.clone(),
+            signature_valid,
+            self_signed,
+        });
+        current = if self_signed { None } else { issuer };
+    }
+    chain
+}
+
+#[derive(Clone, Copy)]
+enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    fn from_oid(oid: &str) -> Option<Self> {
+        match oid {
+            "1.3.14.3.2.26" => Some(DigestAlgorithm::Sha1),
+            "2.16.840.1.101.3.4.2.1" => Some(DigestAlgorithm::Sha256),
+            "2.16.840.1.101.3.4.2.2" => Some(DigestAlgorithm::Sha384),
+            "2.16.840.1.101.3.4.2.3" => Some(DigestAlgorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha1 => "sha1",
+            DigestAlgorithm::Sha256 => "sha256",
+            DigestAlgorithm::Sha384 => "sha384",
+            DigestAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            DigestAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            DigestAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            DigestAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            DigestAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+
+    fn pkcs1v15(self) -> Pkcs1v15Sign {
+        match self {
+            DigestAlgorithm::Sha1 => Pkcs1v15Sign::new::<Sha1>(),
+            DigestAlgorithm::Sha256 => Pkcs1v15Sign::new::<Sha256>(),
+            DigestAlgorithm::Sha384 => Pkcs1v15Sign::new::<Sha384>(),
+            DigestAlgorithm::Sha512 => Pkcs1v15Sign::new::<Sha512>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SignatureScheme {
+    /// RSASSA-PKCS1-v1_5
+    Rsa,
+    Ecdsa,
+}
+
+impl SignatureScheme {
+    /// 签名算法 OID 对应的方案和摘要算法。CMS 中签名算法常只写公钥算法，
+    /// 此时摘要算法取 SignerInfo 的 `digest`
+    fn from_oid(oid: &str, digest: Option<DigestAlgorithm>) -> Option<(Self, DigestAlgorithm)> {
+        use DigestAlgorithm::*;
+        match oid {
+            "1.2.840.113549.1.1.1" => Some((SignatureScheme::Rsa, digest?)),
+            "1.2.840.113549.1.1.5" => Some((SignatureScheme::Rsa, Sha1)),
+            "1.2.840.113549.1.1.11" => Some((SignatureScheme::Rsa, Sha256)),
+            "1.2.840.113549.1.1.12" => Some((SignatureScheme::Rsa, Sha384)),
+            "1.2.840.113549.1.1.13" => Some((SignatureScheme::Rsa, Sha512)),
+            OID_EC_PUBLIC_KEY => Some((SignatureScheme::Ecdsa, digest?)),
+            "1.2.840.10045.4.1" => Some((SignatureScheme::Ecdsa, Sha1)),
+            "1.2.840.10045.4.3.2" => Some((SignatureScheme::Ecdsa, Sha256)),
+            "1.2.840.10045.4.3.3" => Some((SignatureScheme::Ecdsa, Sha384)),
+            "1.2.840.10045.4.3.4" => Some((SignatureScheme::Ecdsa, Sha512)),
+            _ => None,
+        }
+    }
+}
+
+/// 用 SubjectPublicKeyInfo 中的公钥校验 `message` 上的签名
+fn verify_signature(
+    spki: &Tlv,
+    scheme: SignatureScheme,
+    digest: DigestAlgorithm,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<bool, String> {
+    // SubjectPublicKeyInfo ::= SEQUENCE { algorithm AlgorithmIdentifier, subjectPublicKey BIT STRING }
+    let mut items = spki.children();
+    let mut algorithm = items
+        .next()
+        .and_then(|algorithm| algorithm.expect(TAG_SEQUENCE))
+        .ok_or("证书公钥格式错误")?
+        .children();
+    let key = items
+        .next()
+        .and_then(|key| key.expect(TAG_BIT_STRING))
+        .and_then(|key| key.body.get(1..))
+        .ok_or("证书公钥格式错误")?;
+    let hash = digest.digest(message);
+
+    match scheme {
+        SignatureScheme::Rsa => {
+            let key = RsaPublicKey::from_pkcs1_der(key).map_err(|_| "无法读取 RSA 公钥")?;
+            Ok(key.verify(digest.pkcs1v15(), &hash, signature).is_ok())
+        }
+        SignatureScheme::Ecdsa => {
+            let curve = algorithm
+                .nth(1)
+                .and_then(|curve| curve.expect(TAG_OID))
+                .map(|curve| format_oid(curve.body))
+                .unwrap_or_default();
+            match curve.as_str() {
+                OID_P256 => {
+                    let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                        .map_err(|_| "无法读取 P-256 公钥")?;
+                    Ok(p256::ecdsa::Signature::from_der(signature)
+                        .is_ok_and(|signature| key.verify_prehash(&hash, &signature).is_ok()))
+                }
+                OID_P384 => {
+                    let key = p384::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                        .map_err(|_| "无法读取 P-384 公钥")?;
+                    Ok(p384::ecdsa::Signature::from_der(signature)
+                        .is_ok_and(|signature| key.verify_prehash(&hash, &signature).is_ok()))
+                }
+                _ => Err(format!("不支持的椭圆曲线 {}", curve)),
+            }
+        }
+    }
+}
+
+/// 校验证书签名所需的字段
+struct CertificateFields<'a> {
+    /// tbsCertificate 的完整编码
+    tbs: &'a [u8],
+    spki: Tlv<'a>,
+    signature_algorithm: String,
+    signature: &'a [u8],
+}
+
+/// Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signature BIT STRING }
+fn certificate_fields(cert: Tlv) -> Option<CertificateFields> {
+    let mut items = cert.children();
+    let tbs = items.next()?.expect(TAG_SEQUENCE)?;
+    let signature_algorithm = algorithm_oid(items.next()?)?;
+    let signature = items.next()?.expect(TAG_BIT_STRING)?.body.get(1..)?;
+
+    let mut fields = tbs.children().peekable();
+    if fields.peek()?.tag == TAG_CONTEXT_0 {
+        fields.next();
+    }
+    // serialNumber、signature、issuer、validity、subject 之后是 subjectPublicKeyInfo
+    let spki = fields.nth(5)?.expect(TAG_SEQUENCE)?;
+    Some(CertificateFields {
+        tbs: tbs.encoded(),
+        spki,
+        signature_algorithm,
+        signature,
+    })
+}
+
+/// AlgorithmIdentifier ::= SEQUENCE { algorithm OID, parameters ANY OPTIONAL }
+fn algorithm_oid(algorithm: Tlv) -> Option<String> {
+    let oid = algorithm.expect(TAG_SEQUENCE)?.children().next()?;
+    Some(format_oid(oid.expect(TAG_OID)?.body))
+}
+
+struct SignerInfo {
+    digest_algorithm: String,
+    signed_attributes: Option<SignedAttributes>,
+    signature_algorithm: String,
+    signature: Vec<u8>,
+}
+
+struct SignedAttributes {
+    /// 签名针对的是以 SET OF 标签重新编码的签名属性
+    encoded: Vec<u8>,
+    message_digest: Option<Vec<u8>>,
+    signing_time: Option<String>,
+}
+
+/// SignerInfo ::= SEQUENCE { version, sid, digestAlgorithm, [0] IMPLICIT signedAttrs OPTIONAL,
+///     signatureAlgorithm, signature OCTET STRING, [1] IMPLICIT unsignedAttrs OPTIONAL }
+fn parse_signer_info(signer_info: &Tlv) -> Option<SignerInfo> {
+    let mut items = signer_info.expect(TAG_SEQUENCE)?.children();
+    items.next()?.expect(TAG_INTEGER)?;
+    items.next()?;
+    let digest_algorithm = algorithm_oid(items.next()?)?;
+    let mut item = items.next()?;
+    let signed_attributes = if item.tag == TAG_CONTEXT_0 {
+        let attributes = item;
+        item = items.next()?;
+        Some(parse_signed_attributes(attributes))
+    } else {
+        None
+    };
+    Some(SignerInfo {
+        digest_algorithm,
+        signed_attributes,
+        signature_algorithm: algorithm_oid(item)?,
+        signature: items.next()?.octets()?,
+    })
+}
+
+fn parse_signed_attributes(attributes: Tlv) -> SignedAttributes {
+    let mut encoded = attributes.encoded().to_vec();
+    encoded[0] = TAG_SET;
+    let mut parsed = SignedAttributes {
+        encoded,
+        message_digest: None,
+        signing_time: None,
+    };
+    // Attribute ::= SEQUENCE { attrType OID, attrValues SET OF AttributeValue }
+    for attribute in attributes.children() {
+        let mut parts = attribute.children();
+        let (Some(oid), Some(values)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Some(value) = values.children().next() else {
+            continue;
+        };
+        match format_oid(oid.body).as_str() {
+            OID_MESSAGE_DIGEST => parsed.message_digest = value.octets(),
+            OID_SIGNING_TIME => parsed.signing_time = smime::parse_time(&value),
+            _ => {}
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::der::TAG_OCTET_STRING;
+    use p256::ecdsa::signature::hazmat::PrehashSigner;
+    use p256::ecdsa::SigningKey;
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        match content.len() {
+            len if len < 0x80 => out.push(len as u8),
+            len if len < 0x100 => out.extend_from_slice(&[0x81, len as u8]),
+            len => out.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    fn sign(key: &SigningKey, message: &[u8]) -> Vec<u8> {
+        let signature: p256::ecdsa::Signature = key.sign_prehash(&Sha256::digest(message)).unwrap();
+        signature.to_der().as_bytes().to_vec()
+    }
+
+    /// 自签名的 P-256 证书
+    fn certificate(key: &SigningKey) -> Vec<u8> {
+        let ecdsa_sha256 = tlv(
+            TAG_SEQUENCE,
+            &tlv(TAG_OID, &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02]),
+        );
+        let name = tlv(
+            TAG_SEQUENCE,
+            &tlv(
+                TAG_SET,
+                &tlv(
+                    TAG_SEQUENCE,
+                    &[tlv(TAG_OID, &[0x55, 0x04, 0x03]), tlv(0x0C, b"Alice")].concat(),
+                ),
+            ),
+        );
+        let point = key.verifying_key().to_encoded_point(false);
+        let spki = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(
+                    TAG_SEQUENCE,
+                    &[
+                        tlv(TAG_OID, &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01]),
+                        tlv(TAG_OID, &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07]),
+                    ]
+                    .concat(),
+                ),
+                tlv(TAG_BIT_STRING, &[&[0][..], point.as_bytes()].concat()),
+            ]
+            .concat(),
+        );
+        let tbs = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_CONTEXT_0, &tlv(TAG_INTEGER, &[2])),
+                tlv(TAG_INTEGER, &[1]),
+                ecdsa_sha256.clone(),
+                name.clone(),
+                tlv(
+                    TAG_SEQUENCE,
+                    &[tlv(0x17, b"240101000000Z"), tlv(0x17, b"341231235959Z")].concat(),
+                ),
+                name,
+                spki,
+            ]
+            .concat(),
+        );
+        let signature = sign(key, &tbs);
+        tlv(
+            TAG_SEQUENCE,
+            &[
+                tbs,
+                ecdsa_sha256,
+                tlv(TAG_BIT_STRING, &[&[0][..], &signature].concat()),
+            ]
+            .concat(),
+        )
+    }
+
+    /// 带签名属性的 SignedData，`content` 为空时是明文签名的分离签名
+    fn signed_data(key: &SigningKey, content: &[u8], detached: bool) -> Vec<u8> {
+        let sha256 = tlv(
+            TAG_SEQUENCE,
+            &tlv(
+                TAG_OID,
+                &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01],
+            ),
+        );
+        let id_data = [0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01];
+        let cert = certificate(key);
+
+        let attributes = [
+            tlv(
+                TAG_SEQUENCE,
+                &[
+                    tlv(
+                        TAG_OID,
+                        &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04],
+                    ),
+                    tlv(TAG_SET, &tlv(TAG_OCTET_STRING, &Sha256::digest(content))),
+                ]
+                .concat(),
+            ),
+            tlv(
+                TAG_SEQUENCE,
+                &[
+                    tlv(
+                        TAG_OID,
+                        &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x05],
+                    ),
+                    tlv(TAG_SET, &tlv(0x17, b"260102030405Z")),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+        let signature = sign(key, &tlv(TAG_SET, &attributes));
+        let issuer_serial = tlv(
+            TAG_SEQUENCE,
+            &[smime_name(&cert), tlv(TAG_INTEGER, &[1])].concat(),
+        );
+        let signer_info = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[1]),
+                issuer_serial,
+                sha256.clone(),
+                tlv(TAG_CONTEXT_0, &attributes),
+                tlv(
+                    TAG_SEQUENCE,
+                    &tlv(TAG_OID, &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02]),
+                ),
+                tlv(TAG_OCTET_STRING, &signature),
+            ]
+            .concat(),
+        );
+
+        let mut encap = tlv(TAG_OID, &id_data);
+        if !detached {
+            encap.extend(tlv(TAG_CONTEXT_0, &tlv(TAG_OCTET_STRING, content)));
+        }
+        let signed = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[1]),
+                tlv(TAG_SET, &sha256),
+                tlv(TAG_SEQUENCE, &encap),
+                tlv(TAG_CONTEXT_0, &cert),
+                tlv(TAG_SET, &signer_info),
+            ]
+            .concat(),
+        );
+        tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(
+                    TAG_OID,
+                    &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02],
+                ),
+                tlv(TAG_CONTEXT_0, &signed),
+            ]
+            .concat(),
+        )
+    }
+
+    /// 证书中的主题 DN（自签名证书的颁发者与之相同）
+    fn smime_name(cert: &[u8]) -> Vec<u8> {
+        let cert = Tlv::read(cert).unwrap();
+        let tbs = cert.children().next().unwrap();
+        tbs.children().nth(3).unwrap().encoded().to_vec()
+    }
+
+    #[test]
+    fn test_verify_opaque_signed() {
+        let key = SigningKey::from_bytes(&[7; 32].into()).unwrap();
+        let content = b"Content-Type: text/plain\r\n\r\nHello";
+        let result = verify_payload(&Payload::Cms(signed_data(&key, content, false))).unwrap();
+        assert_eq!(result.kind, SmimeKind::OpaqueSigned);
+        assert_eq!(result.digest_algorithm, "sha256");
+        assert!(result.digest_valid);
+        assert!(result.signature_valid);
+        assert_eq!(result.signing_time.as_deref(), Some("2026-01-02T03:04:05Z"));
+        assert_eq!(result.signer.unwrap().subject, "CN=Alice");
+        assert_eq!(result.chain.len(), 1);
+        assert!(result.chain[0].self_signed);
+        assert_eq!(result.chain[0].signature_valid, Some(true));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_verify_clear_signed() {
+        let key = SigningKey::from_bytes(&[7; 32].into()).unwrap();
+        let content = b"Content-Type: text/plain\r\n\r\nHello";
+        let entity = |content: &[u8]| {
+            let signature = crate::eml::base64(&signed_data(
+                &key,
+                b"Content-Type: text/plain\r\n\r\nHello",
+                true,
+            ));
+            let mut entity =
+                b"Content-Type: multipart/signed; boundary=\"s\"\r\n\r\n--s\r\n".to_vec();
+            entity.extend_from_slice(content);
+            entity.extend_from_slice(b"\r\n--s\r\nContent-Type: application/pkcs7-signature\r\nContent-Transfer-Encoding: base64\r\n\r\n");
+            entity.extend_from_slice(signature.as_bytes());
+            entity.extend_from_slice(b"\r\n--s--\r\n");
+            entity
+        };
+
+        let result = verify_payload(&Payload::ClearSigned(entity(content))).unwrap();
+        assert_eq!(result.kind, SmimeKind::ClearSigned);
+        assert!(result.digest_valid && result.signature_valid);
+
+        // 只有 LF 换行时按 CRLF 计算摘要
+        let result = verify_payload(&Payload::ClearSigned(entity(
+            b"Content-Type: text/plain\n\nHello",
+        )))
+        .unwrap();
+        assert!(result.digest_valid);
+
+        // 内容被改动：摘要不符，签名属性上的签名仍然有效
+        let result = verify_payload(&Payload::ClearSigned(entity(
+            b"Content-Type: text/plain\r\n\r\nHellO",
+        )))
+        .unwrap();
+        assert!(!result.digest_valid);
+        assert!(result.signature_valid);
+    }
+}
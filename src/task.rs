@@ -0,0 +1,168 @@
+//! 任务（IPM.Task）及任务分配消息（IPM.TaskRequest.*）
+//!
+//! 任务分配消息本身只是个载体，真正的任务以嵌入消息的形式放在附件中；
+//! 分配状态（分配、接受、拒绝、更新）由载体的消息类型决定。
+
+use crate::named::{NameIdMap, PSETID_COMMON, PSETID_TASK};
+use crate::props::{self, PropertyStore, PropertyValue};
+use crate::quirks;
+use cfb::CompoundFile;
+use serde::Serialize;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+// PSETID_Task 下的 LID
+const LID_TASK_STATUS: u32 = 0x8101;
+const LID_PERCENT_COMPLETE: u32 = 0x8102;
+const LID_TASK_START_DATE: u32 = 0x8104;
+const LID_TASK_DUE_DATE: u32 = 0x8105;
+const LID_TASK_COMPLETE: u32 = 0x811C;
+const LID_TASK_OWNER: u32 = 0x811F;
+
+// PSETID_Common 下的 LID
+const LID_REMINDER_TIME: u32 = 0x8502;
+const LID_REMINDER_SET: u32 = 0x8503;
+
+/// 附件中嵌入消息的存储名 (PR_ATTACH_DATA_OBJ)
+pub(crate) const EMBEDDED_MESSAGE_STORAGE: &str = "__substg1.0_3701000D";
+
+/// 任务状态 (PidLidTaskStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    NotStarted,
+    InProgress,
+    Complete,
+    Waiting,
+    Deferred,
+}
+
+impl TaskStatus {
+    fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(TaskStatus::NotStarted),
+            1 => Some(TaskStatus::InProgress),
+            2 => Some(TaskStatus::Complete),
+            3 => Some(TaskStatus::Waiting),
+            4 => Some(TaskStatus::Deferred),
+            _ => None,
+        }
+    }
+}
+
+/// 任务信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Task {
+    pub status: Option<TaskStatus>,
+    /// 完成百分比，0.0 ~ 1.0
+    pub percent_complete: Option<f64>,
+    pub start_date: Option<String>,
+    pub due_date: Option<String>,
+    pub complete: bool,
+    pub owner: Option<String>,
+    /// 提醒时间，未设置提醒时为 `None`
+    pub reminder_time: Option<String>,
+}
+
+/// 任务分配状态，由载体消息的类型决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskAssignmentState {
+    /// IPM.TaskRequest
+    Assigned,
+    /// IPM.TaskRequest.Accept
+    Accepted,
+    /// IPM.TaskRequest.Decline
+    Declined,
+    /// IPM.TaskRequest.Update
+    Updated,
+}
+
+/// 任务分配消息：分配状态加上嵌入的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRequest {
+    pub state: TaskAssignmentState,
+    /// 附件中嵌入的任务，缺失或无法读取时为 `None`
+    pub task: Option<Task>,
+}
+
+/// 是否为任务本身
+pub(crate) fn is_task_class(message_class: &str) -> bool {
+    let class = message_class.to_ascii_uppercase();
+    class == "IPM.TASK" || class.starts_with("IPM.TASK.")
+}
+
+/// 任务分配消息的状态，不是任务分配消息时返回 `None`
+pub(crate) fn assignment_state(message_class: &str) -> Option<TaskAssignmentState> {
+    match message_class.to_ascii_uppercase().as_str() {
+        "IPM.TASKREQUEST" => Some(TaskAssignmentState::Assigned),
+        "IPM.TASKREQUEST.ACCEPT" => Some(TaskAssignmentState::Accepted),
+        "IPM.TASKREQUEST.DECLINE" => Some(TaskAssignmentState::Declined),
+        "IPM.TASKREQUEST.UPDATE" => Some(TaskAssignmentState::Updated),
+        _ => None,
+    }
+}
+
+/// 从属性集合中读取任务信息
+pub(crate) fn read_task(store: &PropertyStore, names: &NameIdMap) -> Task {
+    let task = |lid| names.get_lid(store, PSETID_TASK, lid);
+    let text = |value: Option<PropertyValue>| value.and_then(|v| v.as_str().map(str::to_string));
+
+    Task {
+        status: task(LID_TASK_STATUS)
+            .and_then(|v| v.as_i32())
+            .and_then(TaskStatus::from_i32),
+        percent_complete: match task(LID_PERCENT_COMPLETE) {
+            Some(PropertyValue::Double(v)) => Some(v),
+            _ => None,
+        },
+        start_date: text(task(LID_TASK_START_DATE)),
+        due_date: text(task(LID_TASK_DUE_DATE)),
+        complete: task(LID_TASK_COMPLETE)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        owner: text(task(LID_TASK_OWNER)).filter(|s| !s.is_empty()),
+        reminder_time: names
+            .get_lid(store, PSETID_COMMON, LID_REMINDER_SET)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+            .then(|| text(names.get_lid(store, PSETID_COMMON, LID_REMINDER_TIME)))
+            .flatten(),
+    }
+}
+
+/// 在附件中找到嵌入的任务并读取
+///
+/// 嵌入消息沿用顶层消息的命名属性映射。
+pub(crate) fn read_embedded_task<R: Read + Seek>(
+    comp: &mut CompoundFile<R>,
+    attachment_paths: &[&Path],
+    names: &NameIdMap,
+    quirks: &mut quirks::Quirks,
+) -> Option<Task> {
+    attachment_paths.iter().find_map(|path| {
+        let embedded = crate::embedded_message_storage(comp, path, quirks)?;
+        let store = props::read_store(comp, &embedded, props::EMBEDDED_HEADER_LEN, quirks).ok()?;
+        Some(read_task(&store, names))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assignment_state() {
+        assert_eq!(
+            assignment_state("IPM.TaskRequest"),
+            Some(TaskAssignmentState::Assigned)
+        );
+        assert_eq!(
+            assignment_state("IPM.TaskRequest.Decline"),
+            Some(TaskAssignmentState::Declined)
+        );
+        assert_eq!(assignment_state("IPM.Task"), None);
+        assert!(is_task_class("IPM.Task"));
+        assert!(!is_task_class("IPM.TaskRequest"));
+    }
+}
@@ -0,0 +1,141 @@
+//! 发件人时区推断
+//!
+//! 发件客户端写入的 Date 头和发件方第一跳服务器写入的 Received 时间戳都带有 UTC 偏移，
+//! 调查人员常用它们来佐证发件人声称的所在地。Date 头可以被伪造，因此两者都保留，
+//! 以便比对是否一致。
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// 推断结果的依据
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimezoneSource {
+    /// 发件客户端写入的 Date 头
+    DateHeader,
+    /// 最早一跳（发件方）Received 头的时间戳
+    Received,
+}
+
+/// 推断的发件人时区
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SenderTimezone {
+    /// 推断的 UTC 偏移（分钟），东八区为 480
+    pub offset_minutes: i32,
+    /// `+08:00` 形式的偏移
+    pub offset: String,
+    pub source: TimezoneSource,
+    /// Date 头中的偏移
+    pub date_offset_minutes: Option<i32>,
+    /// 最早一跳 Received 时间戳中的偏移
+    pub received_offset_minutes: Option<i32>,
+}
+
+/// 根据传输头推断发件人时区
+///
+/// 优先使用 Date 头；Date 头缺失或为 `-0000`（RFC 5322 中表示时区未知）时，
+/// 使用最早一跳 Received 头。`headers` 为 [`parse_header_block`] 的结果。
+///
+/// [`parse_header_block`]: crate::headers::parse_header_block
+pub(crate) fn infer_sender_timezone(
+    headers: &BTreeMap<String, Vec<String>>,
+) -> Option<SenderTimezone> {
+    let date_offset = headers
+        .get("date")
+        .and_then(|values| values.first())
+        .and_then(|date| zone_offset(date));
+    // Received 头按从新到旧的顺序添加在顶部，最后一个是离发件人最近的一跳
+    let received_offset = headers.get("received").and_then(|values| {
+        values.iter().rev().find_map(|value| {
+            let (_, date) = value.rsplit_once(';')?;
+            zone_offset(date)
+        })
+    });
+
+    let (offset_minutes, source) = match (date_offset, received_offset) {
+        (Some(offset), _) => (offset, TimezoneSource::DateHeader),
+        (None, Some(offset)) => (offset, TimezoneSource::Received),
+        (None, None) => return None,
+    };
+    Some(SenderTimezone {
+        offset_minutes,
+        offset: format_offset(offset_minutes),
+        source,
+        date_offset_minutes: date_offset,
+        received_offset_minutes: received_offset,
+    })
+}
+
+/// 取 RFC 5322 日期末尾的时区：`+0800` 或 `GMT`、`EST` 等旧式缩写；`-0000` 表示未知
+pub(crate) fn zone_offset(date: &str) -> Option<i32> {
+    // 去掉末尾的注释，如 "+0800 (CST)"
+    let date = match date.find('(') {
+        Some(comment) => &date[..comment],
+        None => date,
+    };
+    let zone = date.split_whitespace().last()?;
+    if zone == "-0000" {
+        return None;
+    }
+
+    if let (Some(sign @ ('+' | '-')), Some(digits)) = (zone.chars().next(), zone.get(1..)) {
+        if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let hours: i32 = digits[..2].parse().ok()?;
+        let minutes: i32 = digits[2..].parse().ok()?;
+        if minutes >= 60 {
+            return None;
+        }
+        let offset = hours * 60 + minutes;
+        return Some(if sign == '-' { -offset } else { offset });
+    }
+
+    let hours = match zone.to_ascii_uppercase().as_str() {
+        "UT" | "UTC" | "GMT" | "Z" => 0,
+        "EDT" => -4,
+        "EST" | "CDT" => -5,
+        "CST" | "MDT" => -6,
+        "MST" | "PDT" => -7,
+        "PST" => -8,
+        _ => return None,
+    };
+    Some(hours * 60)
+}
+
+fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headers::parse_header_block;
+
+    #[test]
+    fn test_infer_sender_timezone() {
+        let headers = parse_header_block(
+            "Received: from relay.example.net by mx.example.org; Mon, 1 Jan 2024 02:00:05 +0000\r\n\
+             Received: from [10.0.0.5] by smtp.example.com;\r\n Mon, 1 Jan 2024 07:30:01 +0530 (IST)\r\n\
+             Date: Mon, 1 Jan 2024 10:00:00 +0800\r\n",
+        );
+        let tz = infer_sender_timezone(&headers).unwrap();
+        assert_eq!(tz.offset, "+08:00");
+        assert_eq!(tz.source, TimezoneSource::DateHeader);
+        assert_eq!(tz.received_offset_minutes, Some(330));
+
+        let headers = parse_header_block(
+            "Received: by smtp.example.com; Sun, 31 Dec 2023 21:00:00 EST\r\n\
+             Date: Mon, 1 Jan 2024 02:00:00 -0000\r\n",
+        );
+        let tz = infer_sender_timezone(&headers).unwrap();
+        assert_eq!(tz.offset_minutes, -300);
+        assert_eq!(tz.offset, "-05:00");
+        assert_eq!(tz.source, TimezoneSource::Received);
+        assert_eq!(tz.date_offset_minutes, None);
+
+        assert_eq!(infer_sender_timezone(&BTreeMap::new()), None);
+    }
+}
@@ -0,0 +1,602 @@
+//! TNEF (winmail.dat) 解码 (MS-OXTNEF)
+//!
+//! Outlook/Exchange 向不认识 MAPI 的收件方发送 RTF 邮件时，把正文和附件打包成
+//! application/ms-tnef 附件 winmail.dat。这类邮件再存成 .msg 后，真正的附件和 RTF 正文
+//! 都在 winmail.dat 中。TNEF 流是一串属性：级别(1) + 属性 ID(4) + 长度(4) + 数据 + 校验和(2)，
+//! 附件级属性从 attAttachRendData 开始属于一个新附件；attMsgProps、attAttachment
+//! 中是 MAPI 属性列表。
+
+use crate::locale::encoding_for_codepage;
+use crate::props::decode_utf16;
+use crate::{
+    push_unwrapped_attachment, Attachment, MsgEmail, ParseOptions, DEFAULT_MAX_EMBEDDED_DEPTH,
+};
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+
+const TNEF_SIGNATURE: u32 = 0x223E_9F78;
+
+const LEVEL_MESSAGE: u8 = 1;
+const LEVEL_ATTACHMENT: u8 = 2;
+
+// TNEF 属性 ID（高 16 位为类型）
+const ATT_SUBJECT: u32 = 0x0001_8004;
+const ATT_BODY: u32 = 0x0002_800C;
+const ATT_ATTACH_DATA: u32 = 0x0006_800F;
+const ATT_ATTACH_TITLE: u32 = 0x0001_8010;
+const ATT_ATTACH_REND_DATA: u32 = 0x0006_9002;
+const ATT_MSG_PROPS: u32 = 0x0006_9003;
+const ATT_ATTACHMENT: u32 = 0x0006_9005;
+const ATT_OEM_CODEPAGE: u32 = 0x0006_9007;
+
+// MAPI 属性 ID
+const PROP_SUBJECT: u16 = 0x0037;
+const PROP_BODY: u16 = 0x1000;
+const PROP_RTF_COMPRESSED: u16 = 0x1009;
+const PROP_BODY_HTML: u16 = 0x1013;
+const PROP_INTERNET_CPID: u16 = 0x3FDE;
+const PROP_ATTACH_DATA: u16 = 0x3701;
+const PROP_ATTACH_FILENAME: u16 = 0x3704;
+const PROP_ATTACH_LONG_FILENAME: u16 = 0x3707;
+const PROP_ATTACH_MIME_TAG: u16 = 0x370E;
+const PROP_ATTACH_CONTENT_ID: u16 = 0x3712;
+
+// MAPI 属性类型
+const PT_I2: u16 = 0x0002;
+const PT_LONG: u16 = 0x0003;
+const PT_BOOLEAN: u16 = 0x000B;
+const PT_OBJECT: u16 = 0x000D;
+const PT_STRING8: u16 = 0x001E;
+const PT_UNICODE: u16 = 0x001F;
+const PT_CLSID: u16 = 0x0048;
+const PT_BINARY: u16 = 0x0102;
+const MV_FLAG: u16 = 0x1000;
+
+/// IID_IMessage，附件数据为嵌入消息
+const IID_IMESSAGE: [u8; 16] = [
+    0x07, 0x03, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+/// 从 TNEF 流中解出的内容
+#[derive(Debug, Default)]
+pub(crate) struct TnefContent {
+    pub subject: Option<String>,
+    pub body_text: Option<String>,
+    pub body_html: Option<String>,
+    /// 解压后的 RTF 正文
+    pub body_rtf: Option<String>,
+    pub attachments: Vec<Attachment>,
+}
+
+/// 是否为 TNEF 附件：文件名为 winmail.dat 或 MIME 类型为 application/ms-tnef，且数据以 TNEF 签名开头
+pub(crate) fn is_tnef_attachment(attachment: &Attachment) -> bool {
+    let content_type = attachment
+        .content_type
+        .as_deref()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    (attachment.filename.eq_ignore_ascii_case("winmail.dat")
+        || content_type == "application/ms-tnef"
+        || content_type == "application/vnd.ms-tnef")
+        && has_signature(&attachment.data)
+}
+
+fn has_signature(data: &[u8]) -> bool {
+    data.get(..4)
+        .is_some_and(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) == TNEF_SIGNATURE)
+}
+
+/// 解码邮件中的 winmail.dat：其中的附件替换 winmail.dat，同样经过附件过滤和
+/// `max_attachment_size` 限制；正文只在邮件自身没有时填入，并遵循 `skip_html`、`skip_rtf`
+/// 选项。`depth` 为邮件自身的嵌套层数，嵌入消息的解析层数受 `max_embedded_depth` 限制。
+/// 返回是否解出了内容
+pub(crate) fn unwrap_tnef(email: &mut MsgEmail, options: &ParseOptions, depth: usize) -> bool {
+    let Some(index) = email.attachments.iter().position(is_tnef_attachment) else {
+        return false;
+    };
+    let max_depth = options
+        .max_embedded_depth
+        .unwrap_or(DEFAULT_MAX_EMBEDDED_DEPTH);
+    let Some(content) = decode_tnef(&email.attachments[index].data, depth, max_depth) else {
+        return false;
+    };
+    email.attachments.remove(index);
+    push_attachments(email, content.attachments, options);
+    let is_empty = |body: &Option<String>| body.as_deref().is_none_or(|b| b.trim().is_empty());
+    if is_empty(&email.body_text) && content.body_text.is_some() {
+        email.body_text = content.body_text;
+    }
+    if !options.skip_html && is_empty(&email.body_html) && content.body_html.is_some() {
+        email.body_html = content.body_html;
+    }
+    if !options.skip_rtf && email.body_rtf.is_none() {
+        email.body_rtf = content.body_rtf;
+    }
+    true
+}
+
+/// 附件经过过滤和大小限制后放入邮件，嵌入消息中的附件同样处理
+fn push_attachments(email: &mut MsgEmail, attachments: Vec<Attachment>, options: &ParseOptions) {
+    for mut attachment in attachments {
+        if let Some(embedded) = &mut attachment.embedded_message {
+            let nested = std::mem::take(&mut embedded.attachments);
+            push_attachments(embedded, nested, options);
+        }
+        push_unwrapped_attachment(email, attachment, options);
+    }
+}
+
+/// 解码 TNEF 流，签名不符时返回 `None`；流在中途损坏时保留已解出的内容。
+/// 嵌入消息只在 `depth < max_depth` 时解析
+pub(crate) fn decode_tnef(data: &[u8], depth: usize, max_depth: usize) -> Option<TnefContent> {
+    if !has_signature(data) {
+        return None;
+    }
+    let mut content = TnefContent::default();
+    let mut codepage = None;
+    let mut reader = Reader { data, pos: 6 };
+    while let Some((level, id, value)) = reader.attribute() {
+        match (level, id) {
+            (LEVEL_MESSAGE, ATT_OEM_CODEPAGE) => {
+                codepage = value
+                    .get(..4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+            }
+            (LEVEL_MESSAGE, ATT_SUBJECT) => {
+                content.subject = Some(decode_ansi(value, codepage)).filter(|s| !s.is_empty());
+            }
+            (LEVEL_MESSAGE, ATT_BODY) => {
+                content.body_text = Some(decode_ansi(value, codepage)).filter(|s| !s.is_empty());
+            }
+            (LEVEL_MESSAGE, ATT_MSG_PROPS) => {
+                apply_message_props(&mut content, &parse_props(value, codepage));
+            }
+            (LEVEL_ATTACHMENT, ATT_ATTACH_REND_DATA) => {
+                content.attachments.push(Attachment::default());
+            }
+            (LEVEL_ATTACHMENT, _) => {
+                let Some(attachment) = content.attachments.last_mut() else {
+                    continue;
+                };
+                match id {
+                    ATT_ATTACH_TITLE if attachment.filename.is_empty() => {
+                        attachment.filename = decode_ansi(value, codepage);
+                    }
+                    ATT_ATTACH_DATA => attachment.data = value.to_vec(),
+                    ATT_ATTACHMENT => {
+                        let props = parse_props(value, codepage);
+                        apply_attachment_props(attachment, &props, depth, max_depth);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for attachment in &mut content.attachments {
+        if attachment.filename.is_empty() {
+            attachment.filename = match &attachment.embedded_message {
+                Some(_) => "message.msg".to_string(),
+                None => "未命名附件".to_string(),
+            };
+        }
+        attachment.size = attachment.data.len() as u64;
+    }
+    // 只有渲染信息、没有数据的条目（如 OLE 对象的占位）不算附件
+    content
+        .attachments
+        .retain(|attachment| !attachment.data.is_empty() || attachment.embedded_message.is_some());
+    Some(content)
+}
+
+fn apply_message_props(content: &mut TnefContent, props: &[MapiProp]) {
+    let internet_cpid = find(props, PROP_INTERNET_CPID).and_then(MapiProp::as_u32);
+    if let Some(subject) = find(props, PROP_SUBJECT).and_then(MapiProp::as_string) {
+        content.subject = Some(subject);
+    }
+    if let Some(body) = find(props, PROP_BODY).and_then(MapiProp::as_string) {
+        content.body_text = Some(body);
+    }
+    if let Some(html) = find(props, PROP_BODY_HTML) {
+        // PR_HTML 通常是二进制，字符集由 PR_INTERNET_CPID 给出
+        content.body_html = match html.prop_type {
+            PT_BINARY => {
+                let encoding = internet_cpid
+                    .and_then(encoding_for_codepage)
+                    .unwrap_or(UTF_8);
+                Some(
+                    encoding
+                        .decode_without_bom_handling(&html.value)
+                        .0
+                        .into_owned(),
+                )
+            }
+            _ => html.as_string(),
+        }
+        .filter(|html| !html.trim().is_empty());
+    }
+    if let Some(rtf) = find(props, PROP_RTF_COMPRESSED).filter(|rtf| rtf.value.len() >= 16) {
+        content.body_rtf = compressed_rtf::decompress_rtf(&rtf.value)
+            .ok()
+            .filter(|rtf| !rtf.trim().is_empty());
+    }
+}
+
+fn apply_attachment_props(
+    attachment: &mut Attachment,
+    props: &[MapiProp],
+    depth: usize,
+    max_depth: usize,
+) {
+    let filename = find(props, PROP_ATTACH_LONG_FILENAME)
+        .or_else(|| find(props, PROP_ATTACH_FILENAME))
+        .and_then(MapiProp::as_string)
+        .filter(|name| !name.is_empty());
+    if let Some(filename) = filename {
+        attachment.filename = filename;
+    }
+    attachment.content_type = find(props, PROP_ATTACH_MIME_TAG)
+        .and_then(MapiProp::as_string)
+        .filter(|s| !s.is_empty());
+    attachment.content_id = find(props, PROP_ATTACH_CONTENT_ID)
+        .and_then(MapiProp::as_string)
+        .map(|id| id.trim_matches(['<', '>']).to_string())
+        .filter(|id| !id.is_empty());
+
+    let Some(data) = find(props, PROP_ATTACH_DATA) else {
+        return;
+    };
+    match data.prop_type {
+        // PtypObject 以接口 IID 开头，嵌入消息的内容是又一个 TNEF 流
+        PT_OBJECT => {
+            let (iid, object) = data.value.split_at(data.value.len().min(16));
+            if iid == IID_IMESSAGE && depth < max_depth {
+                attachment.embedded_message =
+                    decode_tnef(object, depth + 1, max_depth).map(|content| {
+                        Box::new(MsgEmail {
+                            subject: content.subject,
+                            body_text: content.body_text,
+                            body_html: content.body_html,
+                            body_rtf: content.body_rtf,
+                            attachments: content.attachments,
+                            ..Default::default()
+                        })
+                    });
+            } else if attachment.data.is_empty() {
+                attachment.data = object.to_vec();
+            }
+        }
+        _ if attachment.data.is_empty() => attachment.data = data.value.clone(),
+        _ => {}
+    }
+}
+
+/// MAPI 属性列表中的一个属性，多值属性只保留第一个值
+struct MapiProp {
+    id: u16,
+    prop_type: u16,
+    value: Vec<u8>,
+    /// 8 位字符串使用的字符集
+    encoding: &'static Encoding,
+}
+
+impl MapiProp {
+    fn as_string(&self) -> Option<String> {
+        match self.prop_type {
+            PT_UNICODE => Some(decode_utf16(&self.value)),
+            PT_STRING8 => {
+                let end = self
+                    .value
+                    .iter()
+                    .position(|&b| b == 0)
+                    .unwrap_or(self.value.len());
+                Some(
+                    self.encoding
+                        .decode_without_bom_handling(&self.value[..end])
+                        .0
+                        .into_owned(),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    fn as_u32(&self) -> Option<u32> {
+        let b = self.value.get(..4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+fn find(props: &[MapiProp], id: u16) -> Option<&MapiProp> {
+    props.iter().find(|prop| prop.id == id)
+}
+
+/// 解析 MAPI 属性列表：个数(4)，每个属性为类型(2) + ID(2)、命名属性的名称、值。
+/// 变长类型和多值属性先给出值的个数，每个值为长度(4) + 数据，按 4 字节对齐
+fn parse_props(data: &[u8], codepage: Option<u32>) -> Vec<MapiProp> {
+    let encoding = codepage
+        .and_then(encoding_for_codepage)
+        .unwrap_or(WINDOWS_1252);
+    let mut reader = Reader { data, pos: 0 };
+    let Some(count) = reader.u32() else {
+        return Vec::new();
+    };
+    let mut props = Vec::new();
+    for _ in 0..count {
+        let Some(prop) = read_prop(&mut reader, encoding) else {
+            break;
+        };
+        props.push(prop);
+    }
+    props
+}
+
+fn read_prop(reader: &mut Reader, encoding: &'static Encoding) -> Option<MapiProp> {
+    let prop_type = reader.u16()?;
+    let id = reader.u16()?;
+    if id >= 0x8000 {
+        // 属性集 GUID + 种类，种类 0 为 LID，1 为 UTF-16 名称
+        reader.take(16)?;
+        match reader.u32()? {
+            0 => {
+                reader.u32()?;
+            }
+            _ => {
+                let len = reader.u32()? as usize;
+                reader.take(len.next_multiple_of(4))?;
+            }
+        }
+    }
+
+    let base_type = prop_type & !MV_FLAG;
+    let variable = matches!(base_type, PT_STRING8 | PT_UNICODE | PT_BINARY | PT_OBJECT);
+    let count = if variable || prop_type & MV_FLAG != 0 {
+        reader.u32()? as usize
+    } else {
+        1
+    };
+    let mut first = None;
+    for _ in 0..count {
+        let value = if variable {
+            let len = reader.u32()? as usize;
+            let value = reader.take(len)?.to_vec();
+            reader.take(len.next_multiple_of(4) - len)?;
+            value
+        } else {
+            let size = fixed_size(base_type)?;
+            let value = reader.take(size)?.to_vec();
+            reader.take(size.next_multiple_of(4) - size)?;
+            value
+        };
+        first.get_or_insert(value);
+    }
+    Some(MapiProp {
+        id,
+        prop_type: base_type,
+        value: first.unwrap_or_default(),
+        encoding,
+    })
+}
+
+/// 定长类型的字节数，未知类型无法跳过，返回 `None`
+fn fixed_size(prop_type: u16) -> Option<usize> {
+    match prop_type {
+        PT_I2 | PT_BOOLEAN => Some(2),
+        PT_LONG | 0x0001 | 0x0004 | 0x000A => Some(4),
+        0x0005 | 0x0006 | 0x0007 | 0x0014 | 0x0040 => Some(8),
+        PT_CLSID => Some(16),
+        _ => None,
+    }
+}
+
+/// ANSI 字符串属性，去掉结尾的 NUL
+fn decode_ansi(data: &[u8], codepage: Option<u32>) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    codepage
+        .and_then(encoding_for_codepage)
+        .unwrap_or(WINDOWS_1252)
+        .decode_without_bom_handling(&data[..end])
+        .0
+        .into_owned()
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.take(2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.take(4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// 读取一个 TNEF 属性，返回 (级别, 属性 ID, 数据)；不校验校验和
+    fn attribute(&mut self) -> Option<(u8, u32, &'a [u8])> {
+        let level = *self.take(1)?.first()?;
+        let id = self.u32()?;
+        let len = self.u32()? as usize;
+        let value = self.take(len)?;
+        self.take(2)?;
+        Some((level, id, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(level: u8, id: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = vec![level];
+        out.extend_from_slice(&id.to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        let checksum = data.iter().map(|&b| b as u32).sum::<u32>() as u16;
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out
+    }
+
+    /// 单值属性列表，值为 (ID, 类型, 数据)
+    fn props(props: &[(u16, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut out = (props.len() as u32).to_le_bytes().to_vec();
+        for (id, prop_type, value) in props {
+            out.extend_from_slice(&prop_type.to_le_bytes());
+            out.extend_from_slice(&id.to_le_bytes());
+            if matches!(*prop_type, PT_STRING8 | PT_UNICODE | PT_BINARY | PT_OBJECT) {
+                out.extend_from_slice(&1u32.to_le_bytes());
+                out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            }
+            out.extend_from_slice(value);
+            out.resize(out.len().next_multiple_of(4), 0);
+        }
+        out
+    }
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    fn sample_tnef() -> Vec<u8> {
+        let rtf = compressed_rtf::compress_rtf("{\\rtf1\\ansi Hello from RTF}").unwrap();
+        let mut data = TNEF_SIGNATURE.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0x01, 0x00]);
+        data.extend(attribute(
+            LEVEL_MESSAGE,
+            ATT_OEM_CODEPAGE,
+            &1252u32.to_le_bytes(),
+        ));
+        data.extend(attribute(LEVEL_MESSAGE, ATT_SUBJECT, b"Caf\xE9\0"));
+        data.extend(attribute(
+            LEVEL_MESSAGE,
+            ATT_MSG_PROPS,
+            &props(&[
+                (PROP_INTERNET_CPID, PT_LONG, 65001u32.to_le_bytes().to_vec()),
+                (PROP_RTF_COMPRESSED, PT_BINARY, rtf),
+                (
+                    PROP_BODY_HTML,
+                    PT_BINARY,
+                    "<p>Grüße</p>".as_bytes().to_vec(),
+                ),
+            ]),
+        ));
+
+        data.extend(attribute(LEVEL_ATTACHMENT, ATT_ATTACH_REND_DATA, &[0; 14]));
+        data.extend(attribute(
+            LEVEL_ATTACHMENT,
+            ATT_ATTACH_TITLE,
+            b"REPORT~1.PDF\0",
+        ));
+        data.extend(attribute(LEVEL_ATTACHMENT, ATT_ATTACH_DATA, b"%PDF-1.4"));
+        data.extend(attribute(
+            LEVEL_ATTACHMENT,
+            ATT_ATTACHMENT,
+            &props(&[
+                (PT_I2, PT_I2, vec![1, 0]),
+                (
+                    PROP_ATTACH_LONG_FILENAME,
+                    PT_UNICODE,
+                    utf16("Quarterly report.pdf"),
+                ),
+                (
+                    PROP_ATTACH_MIME_TAG,
+                    PT_STRING8,
+                    b"application/pdf\0".to_vec(),
+                ),
+            ]),
+        ));
+
+        // 嵌入消息
+        let mut embedded = TNEF_SIGNATURE.to_le_bytes().to_vec();
+        embedded.extend_from_slice(&[0x00, 0x00]);
+        embedded.extend(attribute(LEVEL_MESSAGE, ATT_BODY, b"Inner body\0"));
+        data.extend(attribute(LEVEL_ATTACHMENT, ATT_ATTACH_REND_DATA, &[0; 14]));
+        data.extend(attribute(
+            LEVEL_ATTACHMENT,
+            ATT_ATTACHMENT,
+            &props(&[(
+                PROP_ATTACH_DATA,
+                PT_OBJECT,
+                [&IID_IMESSAGE[..], &embedded].concat(),
+            )]),
+        ));
+        data
+    }
+
+    #[test]
+    fn test_decode_tnef() {
+        let content = decode_tnef(&sample_tnef(), 0, 8).unwrap();
+        assert_eq!(content.subject.as_deref(), Some("Café"));
+        assert_eq!(content.body_html.as_deref(), Some("<p>Grüße</p>"));
+        assert!(content.body_rtf.unwrap().contains("Hello from RTF"));
+
+        assert_eq!(content.attachments.len(), 2);
+        let pdf = &content.attachments[0];
+        assert_eq!(pdf.filename, "Quarterly report.pdf");
+        assert_eq!(pdf.content_type.as_deref(), Some("application/pdf"));
+        assert_eq!(pdf.data, b"%PDF-1.4");
+        assert_eq!(pdf.size, 8);
+        let inner = content.attachments[1].embedded_message.as_ref().unwrap();
+        assert_eq!(inner.body_text.as_deref(), Some("Inner body"));
+
+        // 截断的流保留已解出的内容
+        let data = sample_tnef();
+        assert!(decode_tnef(&data[..data.len() - 40], 0, 8).is_some());
+        assert!(decode_tnef(b"not tnef", 0, 8).is_none());
+    }
+
+    #[test]
+    fn test_unwrap_tnef() {
+        let winmail = || MsgEmail {
+            body_text: Some("Plain body".to_string()),
+            attachments: vec![Attachment {
+                filename: "winmail.dat".to_string(),
+                content_type: Some("application/ms-tnef".to_string()),
+                data: sample_tnef(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let mut email = winmail();
+        assert!(unwrap_tnef(&mut email, &ParseOptions::default(), 0));
+        assert_eq!(email.body_text.as_deref(), Some("Plain body"));
+        assert_eq!(email.body_html.as_deref(), Some("<p>Grüße</p>"));
+        assert!(email.body_rtf.is_some());
+        let names: Vec<&str> = email
+            .attachments
+            .iter()
+            .map(|a| a.filename.as_str())
+            .collect();
+        assert_eq!(names, ["Quarterly report.pdf", "message.msg"]);
+        assert!(email.attachments[1].embedded_message.is_some());
+
+        // 解出的附件同样经过过滤和大小限制，嵌入消息遵循 max_embedded_depth
+        let options = ParseOptions {
+            attachment_deny: vec![".pdf".to_string()],
+            max_attachment_size: Some(4),
+            max_embedded_depth: Some(0),
+            ..Default::default()
+        };
+        let mut email = winmail();
+        assert!(unwrap_tnef(&mut email, &options, 0));
+        assert_eq!(email.attachments.len(), 1);
+        let object = &email.attachments[0];
+        assert!(object.embedded_message.is_none());
+        assert!(object.data.is_empty());
+        assert!(object.size > 4);
+        assert_eq!(email.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,271 @@
+//! 正文后处理流水线
+//!
+//! 解析完成后按顺序对正文执行一系列处理步骤。内置步骤可以通过
+//! [`ParseOptions::body_pipeline`] 或 JS 选项对象的 `body_pipeline` 配置，
+//! 例如 `["sanitize", "inline_cid_images", "linkify", { "truncate": 500 }]`；
+//! Rust 宿主还可以实现 [`BodyTransform`] 加入自定义步骤，见 [`parse_msg_with_pipeline`]。
+//!
+//! [`ParseOptions::body_pipeline`]: crate::ParseOptions::body_pipeline
+
+use crate::{csp, parse_msg_with_options, truncate_chars, MsgEmail, MsgParseError, ParseOptions};
+use serde::Deserialize;
+
+/// 正文处理步骤
+pub trait BodyTransform {
+    /// 处理邮件正文；此时附件等其他字段均已解析完毕
+    fn apply(&self, email: &mut MsgEmail);
+}
+
+/// 内置的正文处理步骤
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyStep {
+    /// 清理 HTML：去掉脚本、事件属性和远程资源，样式限定在 `.msg-body` 容器内，
+    /// 同 [`ParseOptions::csp_safe_html`]，但 `cid:` 引用保留给后续步骤
+    Sanitize,
+    /// 把 HTML 中 `cid:` 引用的内嵌图片换成 data URI
+    InlineCidImages,
+    /// 把 HTML 正文中未加链接的 `http(s)://`、`www.` 地址转为 `<a>` 链接
+    Linkify,
+    /// 把文本和 HTML 正文截断到指定字符数，并设置 `MsgEmail::truncated`
+    Truncate(usize),
+}
+
+impl BodyTransform for BodyStep {
+    fn apply(&self, email: &mut MsgEmail) {
+        match self {
+            BodyStep::Sanitize => {
+                if let Some(body) = &mut email.body_html {
+                    *body = csp::sanitize_keep_cid(body);
+                }
+            }
+            BodyStep::InlineCidImages => {
+                if let Some(body) = &mut email.body_html {
+                    *body = csp::inline_cid_images(body, &email.attachments);
+                }
+            }
+            BodyStep::Linkify => {
+                if let Some(body) = &mut email.body_html {
+                    *body = linkify(body);
+                }
+            }
+            BodyStep::Truncate(max_chars) => {
+                for body in [&mut email.body_text, &mut email.body_html]
+                    .into_iter()
+                    .flatten()
+                {
+                    email.truncated |= truncate_chars(body, *max_chars);
+                }
+            }
+        }
+    }
+}
+
+/// 有序的正文处理流水线
+#[derive(Default)]
+pub struct BodyPipeline {
+    steps: Vec<Box<dyn BodyTransform>>,
+}
+
+impl BodyPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 由内置步骤组成的流水线
+    pub fn from_steps(steps: &[BodyStep]) -> Self {
+        let mut pipeline = Self::new();
+        for step in steps {
+            pipeline = pipeline.then(step.clone());
+        }
+        pipeline
+    }
+
+    /// 在末尾追加一个步骤
+    pub fn then(mut self, step: impl BodyTransform + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// 按顺序执行全部步骤
+    pub fn apply(&self, email: &mut MsgEmail) {
+        for step in &self.steps {
+            step.apply(email);
+        }
+    }
+}
+
+/// 按选项解析 MSG 文件，再依次执行 `pipeline` 中的步骤
+///
+/// `options.body_pipeline` 中的内置步骤先执行，`pipeline` 在其后。
+pub fn parse_msg_with_pipeline(
+    file_data: &[u8],
+    options: &ParseOptions,
+    pipeline: &BodyPipeline,
+) -> Result<MsgEmail, MsgParseError> {
+    let mut email = parse_msg_with_options(file_data, options)?;
+    pipeline.apply(&mut email);
+    Ok(email)
+}
+
+/// 文本内容中不做处理的元素
+const SKIPPED_ELEMENTS: &[&str] = &["a", "script", "style", "textarea", "title", "head"];
+
+/// 把 HTML 文本节点中的裸地址转为链接，标签、注释和 `<a>` 等元素内部不处理
+fn linkify(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut pos = 0;
+    while pos < html.len() {
+        let next = html[pos..]
+            .find('<')
+            .map_or(html.len(), |offset| pos + offset);
+        linkify_text(&html[pos..next], &mut out);
+        if next == html.len() {
+            break;
+        }
+
+        let end = if html[next..].starts_with("<!--") {
+            html[next..]
+                .find("-->")
+                .map_or(html.len(), |end| next + end + 3)
+        } else {
+            match csp::parse_tag(html, next) {
+                Some((tag, end))
+                    if !tag.closing && SKIPPED_ELEMENTS.contains(&tag.name.as_str()) =>
+                {
+                    csp::find_close(html, end, &tag.name)
+                        .and_then(|close| html[close..].find('>').map(|len| close + len + 1))
+                        .unwrap_or(html.len())
+                }
+                Some((_, end)) => end,
+                None => next + 1,
+            }
+        };
+        out.push_str(&html[next..end]);
+        pos = end;
+    }
+    out
+}
+
+fn linkify_text(text: &str, out: &mut String) {
+    let lower = text.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some((start, prefix_len)) = find_url_start(&lower, pos) {
+        let len = text[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\''))
+            .unwrap_or(text.len() - start);
+        // 句末标点和不成对的右括号不算地址的一部分
+        let mut url = &text[start..start + len];
+        while let Some(last) = url.chars().last() {
+            let unbalanced = last == ')' && url.matches('(').count() < url.matches(')').count();
+            if matches!(last, '.' | ',' | ';' | ':' | '!' | '?') || unbalanced {
+                url = &url[..url.len() - 1];
+            } else {
+                break;
+            }
+        }
+        if url.len() <= prefix_len {
+            out.push_str(&text[pos..start + len]);
+            pos = start + len;
+            continue;
+        }
+
+        out.push_str(&text[pos..start]);
+        let href = if url[..4].eq_ignore_ascii_case("www.") {
+            format!("http://{}", url)
+        } else {
+            url.to_string()
+        };
+        out.push_str("<a href=\"");
+        out.push_str(&href.replace('"', "&quot;"));
+        out.push_str("\">");
+        out.push_str(url);
+        out.push_str("</a>");
+        pos = start + url.len();
+    }
+    out.push_str(&text[pos..]);
+}
+
+/// 在 `from` 之后查找地址开头（须位于词首），返回位置和前缀长度
+fn find_url_start(lower: &str, from: usize) -> Option<(usize, usize)> {
+    ["https://", "http://", "www."]
+        .iter()
+        .filter_map(|prefix| {
+            let mut search = from;
+            while let Some(offset) = lower[search..].find(prefix) {
+                let start = search + offset;
+                let at_word_start = lower[..start]
+                    .chars()
+                    .last()
+                    .is_none_or(|c| !c.is_alphanumeric() && c != '/' && c != '.');
+                if at_word_start {
+                    return Some((start, prefix.len()));
+                }
+                search = start + prefix.len();
+            }
+            None
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attachment;
+
+    #[test]
+    fn test_linkify() {
+        assert_eq!(
+            linkify("<p>See https://example.com/a(1). Or www.example.org, thanks</p>"),
+            concat!(
+                "<p>See <a href=\"https://example.com/a(1)\">https://example.com/a(1)</a>. ",
+                "Or <a href=\"http://www.example.org\">www.example.org</a>, thanks</p>"
+            )
+        );
+        let linked = "<a href=\"https://x.com\">https://x.com</a><style>a{}/* http://y */</style>";
+        assert_eq!(linkify(linked), linked);
+    }
+
+    #[test]
+    fn test_body_pipeline() {
+        struct Shout;
+        impl BodyTransform for Shout {
+            fn apply(&self, email: &mut MsgEmail) {
+                if let Some(body) = &mut email.body_html {
+                    *body = body.to_uppercase();
+                }
+            }
+        }
+
+        let mut email = MsgEmail {
+            body_html: Some(
+                "<p onclick=\"x()\">see www.a.com <img src=\"cid:logo\"></p>".to_string(),
+            ),
+            attachments: vec![Attachment {
+                content_type: Some("image/png".to_string()),
+                content_id: Some("<logo>".to_string()),
+                data: vec![1, 2, 3],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        BodyPipeline::from_steps(&[
+            BodyStep::Sanitize,
+            BodyStep::InlineCidImages,
+            BodyStep::Linkify,
+        ])
+        .then(Shout)
+        .apply(&mut email);
+        assert_eq!(
+            email.body_html.as_deref(),
+            Some(concat!(
+                "<DIV CLASS=\"MSG-BODY\"><P>SEE <A HREF=\"HTTP://WWW.A.COM\">WWW.A.COM</A> ",
+                "<IMG SRC=\"DATA:IMAGE/PNG;BASE64,AQID\"></P></DIV>"
+            ))
+        );
+
+        BodyStep::Truncate(5).apply(&mut email);
+        assert!(email.truncated);
+        assert_eq!(email.body_html.as_deref(), Some("<DIV "));
+    }
+}
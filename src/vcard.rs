@@ -0,0 +1,215 @@
+//! vCard 4.0 (RFC 6350) 导出
+//!
+//! 把联系人条目转为 .vcf，姓名、单位、电话、电子邮件、地址和照片都写成标准属性，
+//! 照片以 data: URI 内嵌。换行、折行和转义规则与 iCalendar 相同。
+
+use crate::ical::{escape_text, push_line};
+use crate::{datetime, eml, parse_msg_to_struct, Contact, MsgParseError, PostalAddress};
+use wasm_bindgen::prelude::*;
+
+/// WASM 导出接口
+/// 把联系人 MSG 文件转为 vCard 4.0 文本
+#[wasm_bindgen]
+pub fn convert_msg_to_vcard(file_data: &[u8]) -> Result<String, JsValue> {
+    msg_to_vcard(file_data).map_err(|e| e.to_js())
+}
+
+/// 把联系人 MSG 文件转为 vCard 4.0 文本，见 [`convert_msg_to_vcard`]
+pub fn msg_to_vcard(file_data: &[u8]) -> Result<String, MsgParseError> {
+    let email = parse_msg_to_struct(file_data)?;
+    let contact = email.contact.as_ref().ok_or(MsgParseError::WrongItemKind {
+        expected: "联系人",
+    })?;
+    Ok(build_vcard(contact))
+}
+
+/// 生成 vCard 文本
+pub(crate) fn build_vcard(contact: &Contact) -> String {
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCARD");
+    push_line(&mut out, "VERSION:4.0");
+
+    // FN 是必需属性，没有显示名时用姓名拼出
+    let name_parts = [
+        &contact.name_prefix,
+        &contact.given_name,
+        &contact.middle_name,
+        &contact.surname,
+        &contact.name_suffix,
+    ];
+    let full_name = contact.display_name.clone().unwrap_or_else(|| {
+        name_parts
+            .iter()
+            .filter_map(|part| part.as_deref())
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    push_line(&mut out, &format!("FN:{}", escape_text(&full_name)));
+    push_line(
+        &mut out,
+        &format!(
+            "N:{}",
+            structured(&[
+                &contact.surname,
+                &contact.given_name,
+                &contact.middle_name,
+                &contact.name_prefix,
+                &contact.name_suffix,
+            ])
+        ),
+    );
+    if let Some(nickname) = &contact.nickname {
+        push_line(&mut out, &format!("NICKNAME:{}", escape_text(nickname)));
+    }
+    if contact.company.is_some() || contact.department.is_some() {
+        push_line(
+            &mut out,
+            &format!(
+                "ORG:{}",
+                structured(&[&contact.company, &contact.department])
+            ),
+        );
+    }
+    if let Some(title) = &contact.job_title {
+        push_line(&mut out, &format!("TITLE:{}", escape_text(title)));
+    }
+    for (i, address) in contact.emails.iter().enumerate() {
+        push_line(&mut out, &format!("EMAIL;PREF={}:{}", i + 1, address));
+    }
+    for (phone, kind) in [
+        (&contact.business_phone, "work,voice"),
+        (&contact.home_phone, "home,voice"),
+        (&contact.mobile_phone, "cell"),
+        (&contact.business_fax, "work,fax"),
+    ] {
+        if let Some(phone) = phone {
+            push_line(
+                &mut out,
+                &format!("TEL;VALUE=text;TYPE=\"{}\":{}", kind, escape_text(phone)),
+            );
+        }
+    }
+    for (address, kind) in [
+        (&contact.work_address, ";TYPE=work"),
+        (&contact.home_address, ";TYPE=home"),
+        (&contact.other_address, ""),
+    ] {
+        if let Some(address) = address {
+            push_line(&mut out, &format!("ADR{}:{}", kind, adr(address)));
+        }
+    }
+    // Outlook 的即时通讯地址多为 SIP 地址，且常常省略 scheme
+    if let Some(im) = &contact.im_address {
+        let uri = if im.contains(':') {
+            im.clone()
+        } else {
+            format!("sip:{}", im)
+        };
+        push_line(&mut out, &format!("IMPP:{}", uri));
+    }
+    if let Some(date) = contact.birthday.as_deref().and_then(datetime::all_day_date) {
+        push_line(&mut out, &format!("BDAY:{}", date));
+    }
+    if let Some(date) = contact
+        .anniversary
+        .as_deref()
+        .and_then(datetime::all_day_date)
+    {
+        push_line(&mut out, &format!("ANNIVERSARY:{}", date));
+    }
+    if let Some(photo) = contact.photo.as_ref().filter(|p| !p.data.is_empty()) {
+        let media_type = photo
+            .content_type
+            .as_deref()
+            .filter(|t| t.starts_with("image/"))
+            .unwrap_or("image/jpeg");
+        push_line(
+            &mut out,
+            &format!(
+                "PHOTO:data:{};base64,{}",
+                media_type,
+                eml::base64(&photo.data)
+            ),
+        );
+    }
+    push_line(&mut out, "END:VCARD");
+    out
+}
+
+/// 以分号分隔的结构化值，各部分内的逗号、分号需转义
+fn structured(parts: &[&Option<String>]) -> String {
+    parts
+        .iter()
+        .map(|part| part.as_deref().map(escape_text).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// ADR：邮政信箱;扩展地址;街道;城市;省/州;邮编;国家
+fn adr(address: &PostalAddress) -> String {
+    format!(
+        ";;{}",
+        structured(&[
+            &address.street,
+            &address.city,
+            &address.state,
+            &address.postal_code,
+            &address.country,
+        ])
+    )
+}
+
+/// Outlook 把生日等日期存为当地午夜对应的 UTC 时间，取最近的午夜作为日期，输出 `YYYYMMDD`
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attachment;
+
+    #[test]
+    fn test_build_vcard() {
+        let contact = Contact {
+            given_name: Some("Bob".to_string()),
+            surname: Some("Smith".to_string()),
+            name_suffix: Some("Jr.".to_string()),
+            emails: vec![
+                "bob@work.example".to_string(),
+                "bob@home.example".to_string(),
+            ],
+            mobile_phone: Some("+49 170 000000".to_string()),
+            company: Some("Example, GmbH".to_string()),
+            work_address: Some(PostalAddress {
+                street: Some("Hauptstr. 1".to_string()),
+                city: Some("Berlin".to_string()),
+                ..Default::default()
+            }),
+            im_address: Some("bob@im.example".to_string()),
+            // 东八区 1990-05-15 零点
+            birthday: Some("1965-05-14T16:00:00Z".to_string()),
+            photo: Some(Attachment {
+                content_type: Some("image/png".to_string()),
+                data: b"png".to_vec(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let vcard = build_vcard(&contact);
+        let lines: Vec<&str> = vcard.split("\r\n").collect();
+        assert_eq!(&lines[..2], ["BEGIN:VCARD", "VERSION:4.0"]);
+        for expected in [
+            "FN:Bob Smith Jr.",
+            "N:Smith;Bob;;;Jr.",
+            "ORG:Example\\, GmbH;",
+            "EMAIL;PREF=1:bob@work.example",
+            "EMAIL;PREF=2:bob@home.example",
+            "TEL;VALUE=text;TYPE=\"cell\":+49 170 000000",
+            "ADR;TYPE=work:;;Hauptstr. 1;Berlin;;;",
+            "IMPP:sip:bob@im.example",
+            "BDAY:19650515",
+            "PHOTO:data:image/png;base64,cG5n",
+            "END:VCARD",
+        ] {
+            assert!(lines.contains(&expected), "{}", expected);
+        }
+    }
+}
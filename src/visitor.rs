@@ -0,0 +1,163 @@
+//! 属性访问者
+//!
+//! 复用本 crate 的存储遍历和属性解码逻辑，让调用方实现自己的提取规则，例如
+//! “只收集 0x8000 以上的命名属性”。[`PropertyVisitor::wants`] 在读取流之前调用，
+//! 不需要的属性（例如附件二进制数据）根本不会被读入内存。
+
+use crate::{detect, props, quirks, MsgParseError, PropertyValue};
+use cfb::CompoundFile;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// 属性所在的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyLocation {
+    /// 顶层消息
+    Message,
+    /// 第 n 个收件人存储
+    Recipient(usize),
+    /// 第 n 个附件存储
+    Attachment(usize),
+}
+
+/// 由 [`walk_properties`] 驱动的属性访问者
+pub trait PropertyVisitor {
+    /// 是否需要某个属性，返回 `false` 时不会读取该属性的数据
+    fn wants(&mut self, _location: PropertyLocation, _tag: u32) -> bool {
+        true
+    }
+
+    /// 访问一个已解码的属性，`tag` 为完整属性标签（属性 ID << 16 | 属性类型）
+    fn visit(&mut self, location: PropertyLocation, tag: u32, value: PropertyValue);
+}
+
+/// 遍历 MSG 文件中的全部属性：先顶层消息，再按存储顺序遍历收件人和附件
+pub fn walk_properties<V: PropertyVisitor + ?Sized>(
+    file_data: &[u8],
+    visitor: &mut V,
+) -> Result<(), MsgParseError> {
+    let mut comp = detect::open_msg(file_data)?;
+
+    let mut quirks = quirks::Quirks::new(true);
+    let mut storages: Vec<(PropertyLocation, PathBuf)> =
+        vec![(PropertyLocation::Message, PathBuf::from("/"))];
+    let (mut recipients, mut attachments) = (0, 0);
+    for entry in comp.read_root_storage() {
+        let name = quirks.canonical_name(entry.name());
+        if name.starts_with("__recip_version1.0_") {
+            storages.push((
+                PropertyLocation::Recipient(recipients),
+                entry.path().to_path_buf(),
+            ));
+            recipients += 1;
+        } else if name.starts_with("__attach_version1.0_") {
+            storages.push((
+                PropertyLocation::Attachment(attachments),
+                entry.path().to_path_buf(),
+            ));
+            attachments += 1;
+        }
+    }
+
+    for (location, path) in storages {
+        let store = read_store(&mut comp, &path, location, &mut quirks, visitor)?;
+        for (tag, value) in store.iter() {
+            visitor.visit(location, tag, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取某个存储下访问者需要的属性
+fn read_store<V: PropertyVisitor + ?Sized>(
+    comp: &mut CompoundFile<Cursor<&[u8]>>,
+    path: &Path,
+    location: PropertyLocation,
+    quirks: &mut quirks::Quirks,
+    visitor: &mut V,
+) -> Result<props::PropertyStore, MsgParseError> {
+    let mut store = props::PropertyStore::default();
+    let mut streams = Vec::new();
+    let mut properties_path = None;
+    let entries = comp
+        .read_storage(path)
+        .map_err(|e| MsgParseError::corrupt_stream(path, e))?;
+    for entry in entries {
+        if !entry.is_stream() {
+            continue;
+        }
+        let name = quirks.canonical_name(entry.name());
+        if name == props::PROPERTIES_STREAM {
+            properties_path = Some(entry.path().to_path_buf());
+        } else if let Some(tag) = name
+            .strip_prefix("__substg1.0_")
+            .and_then(|rest| u32::from_str_radix(rest.get(..8)?, 16).ok())
+        {
+            if visitor.wants(location, tag) {
+                streams.push((name, entry.path().to_path_buf()));
+            }
+        }
+    }
+
+    if let Some(properties_path) = properties_path {
+        let data = props::read_stream(comp, &properties_path)
+            .map_err(|e| MsgParseError::corrupt_stream(&properties_path, e))?;
+        let expected = match location {
+            PropertyLocation::Message => props::TOP_LEVEL_HEADER_LEN,
+            _ => props::CHILD_HEADER_LEN,
+        };
+        let header_len = quirks.property_header_len(data.len(), expected);
+        let fixed = props::parse_property_stream(&data, header_len)
+            .into_iter()
+            .filter(|prop| prop.is_fixed_width() && visitor.wants(location, prop.tag))
+            .collect();
+        store.set_fixed(fixed);
+    }
+
+    for (name, path) in streams {
+        let data =
+            props::read_stream(comp, &path).map_err(|e| MsgParseError::corrupt_stream(&path, e))?;
+        store.insert_stream(&name, data);
+    }
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 只收集附件存储中的文件名，并记录被询问过的附件数据流
+    #[derive(Default)]
+    struct FilenameCollector {
+        filenames: Vec<String>,
+        data_requested: bool,
+    }
+
+    impl PropertyVisitor for FilenameCollector {
+        fn wants(&mut self, location: PropertyLocation, tag: u32) -> bool {
+            if tag >> 16 == 0x3701 {
+                self.data_requested = true;
+            }
+            matches!(location, PropertyLocation::Attachment(_)) && tag >> 16 == 0x3707
+        }
+
+        fn visit(&mut self, _location: PropertyLocation, _tag: u32, value: PropertyValue) {
+            if let PropertyValue::String(name) = value {
+                self.filenames.push(name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_walk_properties() {
+        let file_data = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let mut visitor = FilenameCollector::default();
+
+        walk_properties(file_data, &mut visitor).unwrap();
+
+        assert_eq!(visitor.filenames.len(), 1);
+        assert!(visitor.data_requested);
+    }
+}
@@ -0,0 +1,195 @@
+//! 投票按钮 (MS-OXOMSG §2.2.1.12)
+//!
+//! 发出投票的邮件在 PidLidVerbStream 中保存全部操作：回复、全部回复、转发等标准操作
+//! 以及自定义的投票选项，只有类型为投票的操作才是按钮。每个操作的显示名先以 ANSI 存一遍，
+//! 流末尾再以 UTF-16 存一遍。收件人投票后的答复消息在 PidLidVerbResponse 中记下所选选项。
+
+use crate::named::{NameIdMap, PSETID_COMMON};
+use crate::props::{PropertyStore, PropertyValue};
+use encoding_rs::WINDOWS_1252;
+use serde::Serialize;
+
+const LID_VERB_STREAM: u32 = 0x8520;
+const LID_VERB_RESPONSE: u32 = 0x8524;
+
+/// 投票操作的 VerbType
+const VERB_TYPE_VOTE: u32 = 4;
+
+/// 投票选项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VotingOption {
+    /// 操作 ID，答复中以此区分选项
+    pub id: u32,
+    pub name: String,
+}
+
+/// 投票按钮及投票结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Voting {
+    /// 发起投票的邮件中的选项，如 "Approve"、"Reject"
+    pub options: Vec<VotingOption>,
+    /// 答复消息中收件人所选的选项 (PidLidVerbResponse)
+    pub response: Option<String>,
+}
+
+/// 读取投票信息，既没有投票选项也没有投票结果时返回 `None`
+pub(crate) fn read_voting(store: &PropertyStore, names: &NameIdMap) -> Option<Voting> {
+    let options = match names.get_lid(store, PSETID_COMMON, LID_VERB_STREAM) {
+        Some(PropertyValue::Binary(data)) => parse_verb_stream(&data),
+        _ => Vec::new(),
+    };
+    let response = names
+        .get_lid(store, PSETID_COMMON, LID_VERB_RESPONSE)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|s| !s.is_empty());
+    (!options.is_empty() || response.is_some()).then_some(Voting { options, response })
+}
+
+/// 解析 VoteRequest 结构，数据不完整时返回已解析出的投票选项
+fn parse_verb_stream(data: &[u8]) -> Vec<VotingOption> {
+    let mut reader = Reader { data, pos: 2 };
+    let Some(count) = reader.u32() else {
+        return Vec::new();
+    };
+
+    // (VerbType, ID, ANSI 显示名)
+    let mut verbs = Vec::new();
+    for _ in 0..count {
+        let Some(verb) = read_vote_option(&mut reader) else {
+            break;
+        };
+        verbs.push(verb);
+    }
+    // Version2 之后按同样顺序给出 UTF-16 显示名
+    let mut unicode_names = Vec::new();
+    if verbs.len() == count as usize && reader.skip(2).is_some() {
+        for _ in 0..count {
+            // DisplayName 与 DisplayNameRepeat 相同，取前者
+            let Some(name) = reader.utf16_string() else {
+                break;
+            };
+            if reader.utf16_string().is_none() {
+                break;
+            }
+            unicode_names.push(name);
+        }
+    }
+
+    verbs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (verb_type, _, _))| *verb_type == VERB_TYPE_VOTE)
+        .map(|(i, (_, id, ansi_name))| VotingOption {
+            id,
+            name: unicode_names.get(i).cloned().unwrap_or(ansi_name),
+        })
+        .collect()
+}
+
+/// 读取一个 VoteOption，返回 (VerbType, ID, ANSI 显示名)
+fn read_vote_option(reader: &mut Reader) -> Option<(u32, u32, String)> {
+    let verb_type = reader.u32()?;
+    let name = reader.ansi_string()?;
+    // MsgClsName、Internal1String、DisplayNameRepeat
+    for _ in 0..3 {
+        reader.ansi_string()?;
+    }
+    // Internal2 (4)、Internal3 (1)、fUseUSHeaders (4)、Internal4 (4)、SendBehavior (4)、Internal5 (4)
+    reader.skip(21)?;
+    let id = reader.u32()?;
+    // Internal6
+    reader.skip(4)?;
+    Some((verb_type, id, name))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Option<&[u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.take(4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// 1 字节长度 + ANSI 字符串
+    fn ansi_string(&mut self) -> Option<String> {
+        let len = *self.take(1)?.first()? as usize;
+        let (text, _) = WINDOWS_1252.decode_without_bom_handling(self.take(len)?);
+        Some(text.into_owned())
+    }
+
+    /// 1 字节字符数 + UTF-16LE 字符串
+    fn utf16_string(&mut self) -> Option<String> {
+        let len = *self.take(1)?.first()? as usize;
+        let units: Vec<u16> = self
+            .take(len * 2)?
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Some(String::from_utf16_lossy(&units))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 VoteRequest 格式生成操作流
+    fn verb_stream(verbs: &[(u32, u32, &str)]) -> Vec<u8> {
+        let mut data = vec![0x02, 0x01];
+        data.extend_from_slice(&(verbs.len() as u32).to_le_bytes());
+        for &(verb_type, id, name) in verbs {
+            data.extend_from_slice(&verb_type.to_le_bytes());
+            for s in [name, "IPM.Note", "", name] {
+                data.push(s.len() as u8);
+                data.extend_from_slice(s.as_bytes());
+            }
+            data.extend_from_slice(&[0; 21]);
+            data.extend_from_slice(&id.to_le_bytes());
+            data.extend_from_slice(&[0; 4]);
+        }
+        data.extend_from_slice(&[0x04, 0x01]);
+        for &(_, _, name) in verbs {
+            for _ in 0..2 {
+                data.push(name.encode_utf16().count() as u8);
+                data.extend(name.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_verb_stream() {
+        let data = verb_stream(&[(1, 1, "Reply"), (4, 5, "Approve"), (4, 6, "Reject")]);
+        assert_eq!(
+            parse_verb_stream(&data),
+            [
+                VotingOption {
+                    id: 5,
+                    name: "Approve".to_string()
+                },
+                VotingOption {
+                    id: 6,
+                    name: "Reject".to_string()
+                },
+            ]
+        );
+
+        // 缺少 UTF-16 部分时使用 ANSI 显示名
+        let truncated = &data[..data.len() - 10];
+        assert_eq!(parse_verb_stream(truncated).len(), 2);
+        assert_eq!(parse_verb_stream(&data[..30]), []);
+    }
+}
@@ -0,0 +1,209 @@
+//! WMF 预览图转 PNG（`wmf-preview` feature）
+//!
+//! OLE 附件（嵌入的 Word/Excel 对象、Package 等）的 PR_ATTACH_RENDERING 是一张 WMF 图元文件，
+//! 浏览器无法直接显示。这类预览几乎都只是用 META_STRETCHDIB 等记录画出的一张位图
+//! （对象图标或内容截图），这里取出第一张位图转为 PNG；纯矢量绘制的 WMF 不做渲染。
+
+/// 可放置 WMF 头部的魔数
+const PLACEABLE_KEY: u32 = 0x9AC6_CDD7;
+const PLACEABLE_HEADER_LEN: usize = 22;
+const META_HEADER_LEN: usize = 18;
+
+// 携带位图的记录及其参数区长度（记录头 6 字节之后、DIB 之前）
+const META_DIBBITBLT: u16 = 0x0940;
+const META_DIBSTRETCHBLT: u16 = 0x0B41;
+const META_STRETCHDIB: u16 = 0x0F43;
+const META_EOF: u16 = 0x0000;
+
+const BITMAPINFOHEADER_LEN: usize = 40;
+const BI_RGB: u32 = 0;
+
+/// 把 WMF 中的第一张位图转为 PNG，没有可用位图时返回 `None`
+pub(crate) fn wmf_to_png(data: &[u8]) -> Option<Vec<u8>> {
+    let (width, height, rgba) = decode_dib(find_dib(data)?)?;
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().ok()?.write_image_data(&rgba).ok()?;
+    Some(png)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// 遍历 WMF 记录，返回第一个位图记录中的 DIB 数据
+fn find_dib(data: &[u8]) -> Option<&[u8]> {
+    let mut offset = if u32_at(data, 0)? == PLACEABLE_KEY {
+        PLACEABLE_HEADER_LEN
+    } else {
+        0
+    };
+    // META_HEADER 的 HeaderSize 以 16 位字为单位
+    let header_words = u16_at(data, offset + 2)? as usize;
+    offset += (header_words * 2).max(META_HEADER_LEN);
+
+    while offset + 6 <= data.len() {
+        let record_len = (u32_at(data, offset)? as usize).checked_mul(2)?;
+        let function = u16_at(data, offset + 4)?;
+        if record_len < 6 || function == META_EOF {
+            return None;
+        }
+        let record = data.get(offset..offset.checked_add(record_len)?)?;
+        let params_len = match function {
+            META_STRETCHDIB => Some(22),
+            // 不带位图的变体多一个保留字段，长度正好为 6 + 20
+            META_DIBSTRETCHBLT if record_len > 6 + 20 => Some(20),
+            META_DIBBITBLT if record_len > 6 + 16 => Some(16),
+            _ => None,
+        };
+        if let Some(params_len) = params_len {
+            return record.get(6 + params_len..);
+        }
+        offset += record_len;
+    }
+    None
+}
+
+/// 把 BI_RGB 编码的 DIB 解码为 RGBA，返回宽、高和像素
+fn decode_dib(dib: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let header_len = u32_at(dib, 0)? as usize;
+    if header_len < BITMAPINFOHEADER_LEN {
+        return None;
+    }
+    let width = i32::from_le_bytes(dib.get(4..8)?.try_into().ok()?);
+    let height = i32::from_le_bytes(dib.get(8..12)?.try_into().ok()?);
+    let bit_count = u16_at(dib, 14)?;
+    let compression = u32_at(dib, 16)?;
+    let colors_used = u32_at(dib, 32)? as usize;
+    if compression != BI_RGB || width <= 0 || height == 0 {
+        return None;
+    }
+
+    let palette_len = match bit_count {
+        1 | 4 | 8 if colors_used == 0 => 1usize << bit_count,
+        1 | 4 | 8 => colors_used,
+        24 | 32 => 0,
+        _ => return None,
+    };
+    // 宽度、调色板大小都来自文件，wasm32 上 usize 只有 32 位，运算一律检查溢出
+    let pixels_start = palette_len.checked_mul(4)?.checked_add(header_len)?;
+    let palette = dib.get(header_len..pixels_start)?;
+    let pixels = &dib[pixels_start..];
+
+    let (width, rows) = (width as usize, height.unsigned_abs() as usize);
+    // 每行按 4 字节对齐
+    let stride = width
+        .checked_mul(bit_count as usize)?
+        .div_ceil(32)
+        .checked_mul(4)?;
+    if pixels.len() < stride.checked_mul(rows)? {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width.checked_mul(rows)?.checked_mul(4)?);
+    for y in 0..rows {
+        // 高度为正时是自下而上存储
+        let src_row = if height > 0 { rows - 1 - y } else { y };
+        let row = &pixels[src_row * stride..(src_row + 1) * stride];
+        for x in 0..width {
+            let (b, g, r) = match bit_count {
+                24 | 32 => {
+                    let p = x * bit_count as usize / 8;
+                    (row[p], row[p + 1], row[p + 2])
+                }
+                _ => {
+                    let bit = x * bit_count as usize;
+                    let byte = row[bit / 8];
+                    let shift = 8 - bit_count as usize - bit % 8;
+                    let index = ((byte >> shift) & ((1u8 << bit_count) - 1)) as usize;
+                    let entry = palette.get(index * 4..index * 4 + 3)?;
+                    (entry[0], entry[1], entry[2])
+                }
+            };
+            rgba.extend_from_slice(&[r, g, b, 0xFF]);
+        }
+    }
+
+    Some((width as u32, rows as u32, rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 构造一个只包含 META_STRETCHDIB 记录的 WMF，位图为 2x1、24 位
+    fn sample_wmf() -> Vec<u8> {
+        let mut dib = Vec::new();
+        dib.extend_from_slice(&40u32.to_le_bytes());
+        dib.extend_from_slice(&2i32.to_le_bytes());
+        dib.extend_from_slice(&1i32.to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&24u16.to_le_bytes());
+        dib.extend_from_slice(&[0u8; 24]);
+        // 蓝、红两个像素（BGR），行补齐到 8 字节
+        dib.extend_from_slice(&[0xFF, 0, 0, 0, 0, 0xFF, 0, 0]);
+
+        let mut wmf = Vec::new();
+        wmf.extend_from_slice(&1u16.to_le_bytes());
+        wmf.extend_from_slice(&9u16.to_le_bytes());
+        wmf.extend_from_slice(&[0u8; 14]);
+        let record_len = 6 + 22 + dib.len();
+        wmf.extend_from_slice(&((record_len / 2) as u32).to_le_bytes());
+        wmf.extend_from_slice(&META_STRETCHDIB.to_le_bytes());
+        wmf.extend_from_slice(&[0u8; 22]);
+        wmf.extend_from_slice(&dib);
+        wmf.extend_from_slice(&3u32.to_le_bytes());
+        wmf.extend_from_slice(&META_EOF.to_le_bytes());
+        wmf
+    }
+
+    #[test]
+    fn test_decode_dib_from_wmf() {
+        let wmf = sample_wmf();
+        let (width, height, rgba) = decode_dib(find_dib(&wmf).unwrap()).unwrap();
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(rgba, [0, 0, 0xFF, 0xFF, 0xFF, 0, 0, 0xFF]);
+
+        let png = wmf_to_png(&wmf).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn test_oversized_dib() {
+        let dib = |width: i32, bit_count: u16, colors_used: u32| {
+            let mut dib = 40u32.to_le_bytes().to_vec();
+            dib.extend_from_slice(&width.to_le_bytes());
+            dib.extend_from_slice(&1i32.to_le_bytes());
+            dib.extend_from_slice(&1u16.to_le_bytes());
+            dib.extend_from_slice(&bit_count.to_le_bytes());
+            dib.extend_from_slice(&[0u8; 16]);
+            dib.extend_from_slice(&colors_used.to_le_bytes());
+            dib.extend_from_slice(&[0u8; 12]);
+            dib
+        };
+        // 32 位时 0x0800_0001 * 32 在 32 位 usize 上回绕为 32，行宽只剩 4 字节
+        assert_eq!(decode_dib(&dib(0x0800_0001, 32, 0)), None);
+        assert_eq!(decode_dib(&dib(i32::MAX, 32, 0)), None);
+        assert_eq!(decode_dib(&dib(1, 8, u32::MAX)), None);
+    }
+
+    #[test]
+    fn test_vector_only_wmf() {
+        let mut wmf = sample_wmf();
+        wmf.truncate(META_HEADER_LEN);
+        wmf.extend_from_slice(&3u32.to_le_bytes());
+        wmf.extend_from_slice(&META_EOF.to_le_bytes());
+        assert_eq!(wmf_to_png(&wmf), None);
+    }
+}
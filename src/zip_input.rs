@@ -0,0 +1,145 @@
+//! 解析 ZIP 压缩包中的 MSG 文件（`zip-input` feature）
+//!
+//! 用户经常把一批 Outlook 导出的邮件打包成一个 zip 发过来。这里一次调用找出包内全部
+//! `.msg` 条目并逐个解析，单个条目失败只记录错误，不影响其他条目。
+
+use crate::{parse_msg_to_struct, MsgEmail, MsgParseError};
+use serde::Serialize;
+use std::io::{Cursor, Read};
+use wasm_bindgen::prelude::*;
+
+/// 单个条目解压后的大小上限，防止压缩炸弹
+const MAX_ENTRY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// 压缩包中一个 `.msg` 条目的解析结果，`email` 与 `error` 二者有一
+#[derive(Debug, Serialize)]
+pub struct ZipMsgEntry {
+    /// 条目在压缩包中的路径
+    pub path: String,
+    pub email: Option<MsgEmail>,
+    pub error: Option<String>,
+}
+
+/// WASM 导出接口
+/// 解析 ZIP 压缩包中的全部 `.msg` 文件，返回 `{ path, email, error }[]`；
+/// 压缩包本身无法读取时报错
+#[wasm_bindgen]
+pub fn parse_msg_zip(zip_data: &[u8]) -> Result<JsValue, JsValue> {
+    let entries = parse_msgs_in_zip(zip_data).map_err(|e| e.to_js())?;
+
+    serde_wasm_bindgen::to_value(&entries)
+        .map_err(|e| JsValue::from_str(&format!("序列化失败: {}", e)))
+}
+
+/// 解析 ZIP 压缩包中的全部 `.msg` 文件，见 [`parse_msg_zip`]
+///
+/// 条目按压缩包中的顺序返回；macOS 打包时附带的 `__MACOSX/` 资源文件会被忽略。
+pub fn parse_msgs_in_zip(zip_data: &[u8]) -> Result<Vec<ZipMsgEntry>, MsgParseError> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(zip_data)).map_err(|e| MsgParseError::NotZipFile {
+            reason: e.to_string(),
+        })?;
+
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let (path, is_dir) = match archive.by_index_raw(index) {
+            Ok(file) => (file.name().to_string(), file.is_dir()),
+            Err(e) => {
+                entries.push(ZipMsgEntry {
+                    path: format!("#{}", index),
+                    email: None,
+                    error: Some(e.to_string()),
+                });
+                continue;
+            }
+        };
+        if is_dir || !is_msg_entry(&path) {
+            continue;
+        }
+        let (email, error) = match read_entry(&mut archive, index) {
+            Ok(data) => match parse_msg_to_struct(&data) {
+                Ok(email) => (Some(email), None),
+                Err(e) => (None, Some(e.to_string())),
+            },
+            Err(e) => (None, Some(e)),
+        };
+        entries.push(ZipMsgEntry { path, email, error });
+    }
+    Ok(entries)
+}
+
+fn is_msg_entry(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    !path.starts_with("__MACOSX/")
+        && !name.starts_with("._")
+        && name.len() > 4
+        && name.as_bytes()[name.len() - 4..].eq_ignore_ascii_case(b".msg")
+}
+
+fn read_entry(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    index: usize,
+) -> Result<Vec<u8>, String> {
+    let file = archive.by_index(index).map_err(|e| match e {
+        zip::result::ZipError::UnsupportedArchive(msg) => format!("不支持的压缩格式: {}", msg),
+        e => e.to_string(),
+    })?;
+    if file.encrypted() {
+        return Err("条目已加密".to_string());
+    }
+    if file.size() > MAX_ENTRY_SIZE {
+        return Err(format!("条目解压后大小 {} 字节超出限制", file.size()));
+    }
+
+    // 声明的大小可能是伪造的，读取时再限制一次
+    let mut data = Vec::with_capacity(file.size() as usize);
+    file.take(MAX_ENTRY_SIZE + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| e.to_string())?;
+    if data.len() as u64 > MAX_ENTRY_SIZE {
+        return Err("条目解压后大小超出限制".to_string());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_parse_msgs_in_zip() {
+        let msg = include_bytes!("../target/e990525095f52ef1fadf5cef4fc4864c.msg");
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        for (name, data) in [
+            ("export/report.MSG", &msg[..]),
+            ("export/readme.txt", b"hi"),
+            ("export/季度报告", b"hi"),
+            ("x中中", b"hi"),
+            ("export/broken.msg", b"not a msg"),
+            ("__MACOSX/export/._report.MSG", b"\0"),
+            ("归档/季度报告.msg", &msg[..]),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        let data = zip.finish().unwrap().into_inner();
+
+        let entries = parse_msgs_in_zip(&data).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "export/report.MSG");
+        assert_eq!(
+            entries[0].email.as_ref().unwrap().subject.as_deref(),
+            Some("Quarterly report")
+        );
+        assert_eq!(entries[1].path, "export/broken.msg");
+        assert!(entries[1].error.is_some());
+        assert_eq!(entries[2].path, "归档/季度报告.msg");
+        assert!(entries[2].email.is_some());
+
+        let err = parse_msgs_in_zip(b"not a zip").unwrap_err();
+        assert_eq!(err.code(), "not_zip_file");
+    }
+}
@@ -0,0 +1,19 @@
+// Deno 示例：deno run --allow-read test/deno.ts path/to/mail.msg
+// 先执行 wasm-pack build --target deno --out-dir pkg/deno
+import { parse_msg_file } from "../pkg/deno/msg_parser_wasm.js";
+
+const path = Deno.args[0];
+if (!path) {
+  console.error("用法: deno run --allow-read test/deno.ts <file.msg>");
+  Deno.exit(1);
+}
+
+try {
+  const emailData = parse_msg_file(await Deno.readFile(path));
+  console.log("主题:", emailData.subject);
+  console.log("附件数:", emailData.attachments.length);
+} catch (err) {
+  // 解析错误是 { code, message } 对象，不是 Error 实例
+  console.error(`解析失败 (${err.code}): ${err.message}`);
+  Deno.exit(1);
+}
@@ -0,0 +1,28 @@
+// Cloudflare Workers 示例：POST 一个 .msg 文件，返回解析后的主题和附件列表
+// 先执行 wasm-pack build --target web --out-dir pkg/web，Wrangler 会把 .wasm 作为 WebAssembly.Module 导入
+import { initSync, parse_msg_file } from "../pkg/web/msg_parser_wasm.js";
+import wasmModule from "../pkg/web/msg_parser_wasm_bg.wasm";
+
+initSync({ module: wasmModule });
+
+export default {
+  async fetch(request) {
+    if (request.method !== "POST") {
+      return new Response("请 POST 一个 .msg 文件", { status: 405 });
+    }
+
+    try {
+      const emailData = parse_msg_file(new Uint8Array(await request.arrayBuffer()));
+      return Response.json({
+        subject: emailData.subject,
+        attachments: emailData.attachments.map((a) => ({
+          filename: a.filename,
+          size: a.data.length,
+        })),
+      });
+    } catch (err) {
+      // 解析错误是 { code, message } 对象，不是 Error 实例
+      return Response.json({ code: err.code, message: err.message }, { status: 400 });
+    }
+  },
+};